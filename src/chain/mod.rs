@@ -0,0 +1,211 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, bail};
+use bc_components::{Digest, XID};
+use bc_ur::UREncodable;
+use provenance_mark::ProvenanceMark;
+use serde::{Deserialize, Serialize};
+
+use crate::{io, output};
+
+/// How long to wait for another process to release a chain file's lock.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The chain so far, written by `edition sequence --write-chain` and
+/// extended by `edition compose --chain`, so a new edition's mark can be
+/// checked against the recorded tip without passing `--previous`.
+///
+/// ```json
+/// { "club": "ur:xid/hdcx…", "chain_id": "…", "editions": [ { "seq": 0, "mark": "ur:provenance/…", "digest": "…" } ] }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub struct ChainFile {
+    /// Club XID UR.
+    pub club: String,
+    /// Provenance chain id, hex.
+    pub chain_id: String,
+    /// The chain's editions in seq order.
+    pub editions: Vec<ChainEntry>,
+}
+
+/// One link of a chain file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub struct ChainEntry {
+    pub seq: u32,
+    /// Provenance mark UR.
+    pub mark: String,
+    /// Edition digest, hex; absent for a bare mark standing in for an
+    /// edition held elsewhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+}
+
+impl ChainFile {
+    /// An empty chain for `club` whose marks come from `chain_id`.
+    pub fn new(club: XID, chain_id: &[u8]) -> Self {
+        Self {
+            club: club.ur_string(),
+            chain_id: hex::encode(chain_id),
+            editions: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).with_context(|| {
+            format!("failed to read chain file '{}'", path.display())
+        })?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("invalid chain file '{}'", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            output::ensure_dir(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .context("failed to serialize chain file")?;
+        output::write_atomic(path, format!("{json}\n").as_bytes())
+    }
+
+    /// Record `mark`, and the digest of the edition it belongs to, at the
+    /// tip.
+    pub fn push(&mut self, mark: &ProvenanceMark, digest: Option<&Digest>) {
+        self.editions.push(ChainEntry {
+            seq: mark.seq(),
+            mark: mark.ur_string(),
+            digest: digest.map(Digest::hex),
+        });
+    }
+
+    /// The mark of the latest edition recorded.
+    pub fn tip(&self) -> Result<ProvenanceMark> {
+        let entry = self.editions.last().context("chain file is empty")?;
+        io::parse_provenance_mark(&entry.mark).with_context(|| {
+            format!(
+                "invalid provenance mark for seq {} in chain file",
+                entry.seq
+            )
+        })
+    }
+
+    /// Fail unless an edition of `club` carrying `mark` extends the
+    /// recorded chain: same club, and the tip's mark precedes `mark`.
+    /// Returns the tip's mark.
+    pub fn check_extends(
+        &self,
+        club: XID,
+        mark: &ProvenanceMark,
+    ) -> Result<ProvenanceMark> {
+        if self.club != club.ur_string() {
+            bail!(
+                "chain file records club {}, but the edition is for club {}",
+                self.club,
+                club.ur_string()
+            );
+        }
+        let tip = self.tip()?;
+        if hex::encode(mark.chain_id()) != self.chain_id {
+            bail!(
+                "provenance mark comes from another chain than the chain file's"
+            );
+        }
+        if !links(&tip, mark) {
+            bail!(
+                "provenance mark seq {} does not extend the chain file's tip at seq {}",
+                mark.seq(),
+                tip.seq()
+            );
+        }
+        Ok(tip)
+    }
+}
+
+/// Exclusive use of a chain file, held through a `<file>.lock` sibling
+/// that is removed on drop.
+#[derive(Debug)]
+pub struct ChainLock {
+    path: PathBuf,
+}
+
+impl ChainLock {
+    /// Take the lock on the chain file at `chain`, waiting a while for
+    /// another holder to release it.
+    pub fn acquire(chain: &Path) -> Result<Self> {
+        let mut name = chain.as_os_str().to_owned();
+        name.push(".lock");
+        let path = PathBuf::from(name);
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            output::ensure_dir(parent)?;
+        }
+        let started = Instant::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = writeln!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(err)
+                    if err.kind() == ErrorKind::AlreadyExists
+                        && started.elapsed() < LOCK_TIMEOUT =>
+                {
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => bail!(
+                    "chain file '{}' is locked by another process; remove '{}' if it is stale",
+                    chain.display(),
+                    path.display()
+                ),
+                Err(err) => {
+                    return Err(err).with_context(|| {
+                        format!(
+                            "failed to create lock file '{}'",
+                            path.display()
+                        )
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ChainLock {
+    fn drop(&mut self) { let _ = fs::remove_file(&self.path); }
+}
+
+/// Whether `next` is the mark after `previous` on the same chain: it has
+/// the next seq and reveals the key that generated `previous`'s hash. This
+/// is `ProvenanceMark::precedes` without its date check, which callers
+/// make themselves so `--allow-date-regression` can waive it.
+pub fn links(previous: &ProvenanceMark, next: &ProvenanceMark) -> bool {
+    if previous.precedes(next) {
+        return true;
+    }
+    if previous.seq().checked_add(1) != Some(next.seq())
+        || next.chain_id() != previous.chain_id()
+        || next.key() == next.chain_id()
+    {
+        return false;
+    }
+    ProvenanceMark::new(
+        previous.res(),
+        previous.key().to_vec(),
+        next.key().to_vec(),
+        previous.chain_id().to_vec(),
+        previous.seq(),
+        previous.date(),
+        previous.info(),
+    )
+    .is_ok_and(|rebuilt| rebuilt.hash() == previous.hash())
+}
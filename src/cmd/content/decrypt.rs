@@ -1,12 +1,18 @@
+use std::{collections::HashMap, io::Write, path::PathBuf};
+
 use anyhow::{Context, Result, anyhow, bail};
-use bc_components::{PrivateKeys, SymmetricKey};
-use bc_envelope::prelude::Envelope;
-use bc_ur::UREncodable;
-use clap::Args;
-use clubs::edition::Edition;
-use dcbor::{CBORTaggedDecodable, prelude::CBOR};
+use bc_components::{
+    Digest, DigestProvider, EncapsulationPrivateKey, EncapsulationScheme,
+    EncryptedKey, PrivateKeys, SealedMessage, SymmetricKey,
+};
+use bc_envelope::prelude::{Envelope, known_values};
+use clap::{Args, ValueEnum};
+use dcbor::{
+    CBORTaggedDecodable, CBORTaggedEncodable, Date,
+    prelude::{CBOR, CBORCase},
+};
 
-use crate::io;
+use crate::{cmd::edition, io, output};
 
 /// Decrypt edition content using permits, SSKR shards, or raw keys.
 #[derive(Debug, Args)]
@@ -14,7 +20,8 @@ pub struct CommandArgs {
     /// Edition UR containing the encrypted content.
     #[arg(long, value_name = "UR")]
     pub edition: String,
-    /// Permit URs capable of unwrapping the content key.
+    /// Permit URs capable of unwrapping the content key. Without any, the
+    /// edition's own permits are tried against `--identity`.
     #[arg(long = "permit", value_name = "UR")]
     pub permits: Vec<String>,
     /// SSKR share URs for recovering the content key.
@@ -30,12 +37,200 @@ pub struct CommandArgs {
     /// private-keys UR).
     #[arg(long = "identity", value_name = "UR", aliases = ["prvkeys", "private-keys"])]
     pub identities: Vec<String>,
-    /// Emit decrypted envelope UR to stdout.
+    /// Prompt for a password to unlock the edition's password permits.
+    #[arg(long, conflicts_with = "password_env")]
+    pub password: bool,
+    /// Read the password for the edition's password permits from this
+    /// environment variable instead of prompting.
+    #[arg(long, value_name = "VAR")]
+    pub password_env: Option<String>,
+    /// Use permits even after the expiry date recorded on the edition.
+    #[arg(long)]
+    pub ignore_expiry: bool,
+    #[command(flatten)]
+    pub passphrase: io::PassphraseArgs,
+    /// Print the decrypted envelope UR to stdout. This is the default unless
+    /// `--text`, `--raw`, or `--out` is given; with `--out` it prints the UR
+    /// as well.
     #[arg(long)]
     pub emit_ur: bool,
+    /// Print nothing on success, for checks that only need the content key
+    /// recovered; the exit status reports the outcome.
+    #[arg(long, conflicts_with_all = ["emit_ur", "text", "raw"])]
+    pub quiet: bool,
+    /// Print the content's text subject to stdout.
+    #[arg(long, conflicts_with_all = ["emit_ur", "raw"])]
+    pub text: bool,
+    /// Write the content's byte-string subject to stdout as raw bytes, for
+    /// piping into other tools.
+    #[arg(long, conflicts_with = "emit_ur")]
+    pub raw: bool,
+    /// Write the decrypted content to PATH: a byte-string subject as its
+    /// raw bytes, a text subject as UTF-8, and anything else as the
+    /// envelope UR.
+    #[arg(long, value_name = "PATH")]
+    pub out: Option<PathBuf>,
+    /// With `--out`, write this representation whatever the subject is.
+    #[arg(long, value_enum, value_name = "FORMAT", requires = "out")]
+    pub out_format: Option<OutFormat>,
+    /// With `--out`, overwrite an existing file.
+    #[arg(long, requires = "out")]
+    pub force: bool,
+}
+
+/// How `--out` writes the decrypted content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutFormat {
+    /// The subject's byte string, as raw bytes.
+    Bytes,
+    /// The subject's text, as UTF-8.
+    Text,
+    /// The envelope UR, on one line.
+    Ur,
+    /// The envelope's tagged CBOR.
+    Cbor,
+}
+
+impl OutFormat {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Bytes => "bytes",
+            Self::Text => "text",
+            Self::Ur => "ur",
+            Self::Cbor => "cbor",
+        }
+    }
 }
 
 pub fn exec(args: CommandArgs) -> Result<()> {
+    if let Some(path) = args.out.as_ref()
+        && !args.force
+        && path.exists()
+    {
+        bail!(
+            "'{}' already exists; pass --force to overwrite it",
+            path.display()
+        );
+    }
+    let content_envelope = run(&args)?;
+    let out = args
+        .out
+        .as_ref()
+        .map(|path| {
+            content_bytes(&content_envelope, args.out_format)
+                .map(|(bytes, format)| (path, bytes, format))
+        })
+        .transpose()?;
+    let mut stdout = std::io::stdout().lock();
+    write_stdout(&args, &content_envelope, &mut stdout)?;
+    if let Some((path, bytes, format)) = out {
+        output::write_atomic(path, &bytes)?;
+        if !args.quiet {
+            eprintln!(
+                "Wrote {} byte(s) of {} content to {}",
+                bytes.len(),
+                format.label(),
+                path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Write what `args` asks for on stdout: the content envelope's UR unless
+/// another output is chosen, or its text or raw bytes.
+fn write_stdout(
+    args: &CommandArgs,
+    content: &Envelope,
+    stdout: &mut impl Write,
+) -> Result<()> {
+    let other_output =
+        args.quiet || args.text || args.raw || args.out.is_some();
+    if args.emit_ur || !other_output {
+        writeln!(stdout, "{}", io::envelope_ur_string(content))
+            .context("failed to write content to stdout")?;
+    }
+    if args.text || args.raw {
+        let format = if args.text {
+            OutFormat::Text
+        } else {
+            OutFormat::Bytes
+        };
+        let (mut bytes, _) = content_bytes(content, Some(format))?;
+        if args.text && !bytes.ends_with(b"\n") {
+            bytes.push(b'\n');
+        }
+        stdout
+            .write_all(&bytes)
+            .context("failed to write content to stdout")?;
+    }
+    stdout.flush().context("failed to write content to stdout")
+}
+
+/// `content` as `--out` writes it, in `format` or, by default, chosen from
+/// its subject, and the format used. Wrapped content is unwrapped to reach
+/// a byte string or text. Content that is neither falls back to its UR,
+/// with a note.
+fn content_bytes(
+    content: &Envelope,
+    format: Option<OutFormat>,
+) -> Result<(Vec<u8>, OutFormat)> {
+    let subject = if content.is_wrapped() {
+        content.try_unwrap().context("failed to unwrap content")?
+    } else {
+        content.clone()
+    };
+    // Only a bare leaf is plain bytes or text; assertions would be lost.
+    let leaf = subject.as_leaf();
+    let (bytes, text) = match leaf.as_ref().map(CBOR::as_case) {
+        Some(CBORCase::ByteString(bytes)) => (Some(bytes.data()), None),
+        Some(CBORCase::Text(text)) => (None, Some(text.as_str())),
+        _ => (None, None),
+    };
+    let format = format.unwrap_or_else(|| {
+        if bytes.is_some() {
+            OutFormat::Bytes
+        } else if text.is_some() {
+            OutFormat::Text
+        } else {
+            eprintln!(
+                "note: the content subject is neither a byte string nor text; writing the envelope UR"
+            );
+            OutFormat::Ur
+        }
+    });
+    let written = match format {
+        OutFormat::Bytes => bytes
+            .with_context(|| match text {
+                Some(_) => "the content subject is text, not a byte string; use --text to print it",
+                None => "the content subject is not a byte string; use --emit-ur to see the content envelope",
+            })?
+            .to_vec(),
+        OutFormat::Text => text
+            .with_context(|| match bytes {
+                Some(_) => "the content subject is a byte string, not text; use --raw or --out to write its bytes",
+                None => "the content subject is not text; use --emit-ur to see the content envelope",
+            })?
+            .as_bytes()
+            .to_vec(),
+        OutFormat::Ur => {
+            format!("{}\n", io::envelope_ur_string(content)).into_bytes()
+        }
+        OutFormat::Cbor => content.tagged_cbor().to_cbor_data(),
+    };
+    Ok((written, format))
+}
+
+/// The edition's content, decrypted or joined from SSKR shares.
+pub fn run(args: &CommandArgs) -> Result<Envelope> {
+    decrypt_content(args, read_password(args)?.as_deref())
+}
+
+/// [`run`], with the password for password permits already read.
+fn decrypt_content(
+    args: &CommandArgs,
+    password: Option<&str>,
+) -> Result<Envelope> {
     let edition_env =
         io::parse_envelope(&args.edition).context("failed to parse edition")?;
 
@@ -58,7 +253,7 @@ pub fn exec(args: CommandArgs) -> Result<()> {
         edition_env.clone().try_unwrap()?
     };
 
-    let edition = Edition::try_from(inner_envelope.clone())
+    let edition = edition::decode_edition(&inner_envelope)
         .context("edition payload is not a valid club edition")?;
 
     if let Some(descriptor) = verifier_descriptor.as_ref()
@@ -72,17 +267,29 @@ pub fn exec(args: CommandArgs) -> Result<()> {
         );
     }
 
-    let sealed_permits = parse_permits(&args.permits)?;
+    let expiries = edition::permit_expiries(&inner_envelope)?;
+    // With identities but no explicit permits, try the edition's own.
+    let embedded = args.permits.is_empty() && !args.identities.is_empty();
+    let permits = if embedded {
+        edition::permits::sealed_permits(&edition)
+            .into_iter()
+            .map(|(sealed, _)| sealed.clone())
+            .collect()
+    } else {
+        parse_permits(&args.permits)?
+    };
+    let sealed_permits =
+        usable_permits(permits, &expiries, &Date::now(), args.ignore_expiry)?;
     let share_envelopes = parse_shards(&args.shards)?;
 
-    let private_keys = parse_private_keys(&args.identities)?;
+    let private_keys = parse_private_keys(&args.identities, &args.passphrase)?;
 
-    let mut symmetric_key: Option<SymmetricKey> = None;
+    let mut symmetric_key: Option<(SymmetricKey, &str)> = None;
 
     if let Some(key_spec) = args.key.as_ref() {
         let key = io::parse_symmetric_key(key_spec)
             .context("failed to parse symmetric key input")?;
-        symmetric_key = Some(key);
+        symmetric_key = Some((key, "--key"));
     }
 
     if !sealed_permits.is_empty() {
@@ -91,30 +298,27 @@ pub fn exec(args: CommandArgs) -> Result<()> {
                 "private keys are required to decrypt permits; supply --identity"
             );
         }
-        let permit_key =
-            recover_key_from_permits(&sealed_permits, &private_keys)?;
-        if let Some(existing) = symmetric_key.as_ref() {
-            if existing != &permit_key {
-                bail!(
-                    "conflicting symmetric keys recovered from --key and --permit inputs"
-                );
-            }
+        let recovered =
+            recover_key_from_permits(&sealed_permits, &private_keys);
+        let permit_key = if embedded {
+            recovered.context(
+                "no permit on the edition opens with the supplied identities",
+            )?
         } else {
-            symmetric_key = Some(permit_key);
-        }
+            recovered?
+        };
+        merge_key(&mut symmetric_key, permit_key, "--permit")?;
+    }
+
+    if let Some(password) = password {
+        let permits = password_permits(&inner_envelope)?;
+        let password_key = recover_key_from_password(&permits, password)?;
+        merge_key(&mut symmetric_key, password_key, "--password")?;
     }
+    let symmetric_key = symmetric_key.map(|(key, _)| key);
 
     let sskr_content = if !share_envelopes.is_empty() {
-        let refs: Vec<&Envelope> = share_envelopes.iter().collect();
-        let joined =
-            Envelope::sskr_join(&refs).context("failed to join SSKR shares")?;
-        Some(if joined.is_wrapped() {
-            joined
-                .try_unwrap()
-                .context("failed to unwrap joined SSKR content")?
-        } else {
-            joined
-        })
+        Some(join_shards(&edition.content, &share_envelopes)?)
     } else {
         None
     };
@@ -156,16 +360,88 @@ pub fn exec(args: CommandArgs) -> Result<()> {
         }
     };
 
-    if args.emit_ur {
-        println!("{}", content_envelope.ur_string());
+    Ok(content_envelope)
+}
+
+/// Keep `key`, recovered from `source`, unless a key recovered from an
+/// earlier source differs from it.
+fn merge_key<'a>(
+    recovered: &mut Option<(SymmetricKey, &'a str)>,
+    key: SymmetricKey,
+    source: &'a str,
+) -> Result<()> {
+    match recovered {
+        Some((existing, earlier)) if *existing != key => bail!(
+            "conflicting symmetric keys recovered from {earlier} and {source} inputs"
+        ),
+        Some(_) => Ok(()),
+        None => {
+            *recovered = Some((key, source));
+            Ok(())
+        }
     }
+}
 
-    Ok(())
+/// The password from `--password-env` or an interactive prompt; `None`
+/// without either flag.
+fn read_password(args: &CommandArgs) -> Result<Option<String>> {
+    let password = match args.password_env.as_ref() {
+        Some(var) => std::env::var(var).with_context(|| {
+            format!("password environment variable '{var}' is not set")
+        })?,
+        None if args.password => rpassword::prompt_password("Password: ")
+            .context("failed to read password")?,
+        None => return Ok(None),
+    };
+    if password.is_empty() {
+        bail!("password must not be empty");
+    }
+    Ok(Some(password))
 }
 
-fn parse_permits(
-    inputs: &[String],
-) -> Result<Vec<bc_components::SealedMessage>> {
+/// The edition's password permits: `'hasSecret'` assertions on the
+/// unwrapped `inner` edition, each holding the content key locked under a
+/// key derived from a password with the salt and parameters it records.
+fn password_permits(inner: &Envelope) -> Result<Vec<EncryptedKey>> {
+    inner
+        .objects_for_predicate(known_values::HAS_SECRET)
+        .iter()
+        .map(|permit| {
+            permit
+                .extract_subject::<EncryptedKey>()
+                .context("password permit is not an encrypted key")
+        })
+        .collect()
+}
+
+/// The content key unlocked from `permits` with `password`. Every permit is
+/// tried, and those it unlocks must agree.
+pub fn recover_key_from_password(
+    permits: &[EncryptedKey],
+    password: &str,
+) -> Result<SymmetricKey> {
+    if permits.is_empty() {
+        bail!(
+            "the edition carries no password permit for --password to unlock"
+        );
+    }
+    let mut recovered: Option<SymmetricKey> = None;
+    for permit in permits {
+        let Ok(key) = permit.unlock(password.as_bytes()) else {
+            continue;
+        };
+        match recovered.as_ref() {
+            Some(existing) if *existing != key => bail!(
+                "different password permits yielded conflicting symmetric keys"
+            ),
+            Some(_) => {}
+            None => recovered = Some(key),
+        }
+    }
+    recovered.context("passphrase did not unlock any permit")
+}
+
+fn parse_permits(inputs: &[String]) -> Result<Vec<SealedMessage>> {
     let mut permits = Vec::with_capacity(inputs.len());
     for permit in inputs {
         let sealed = io::parse_sealed_message(permit)
@@ -175,7 +451,36 @@ fn parse_permits(
     Ok(permits)
 }
 
-fn parse_shards(inputs: &[String]) -> Result<Vec<Envelope>> {
+/// Drop permits whose recorded expiry has passed, warning about each one,
+/// unless `ignore_expiry` is set. Fails when every supplied permit expired.
+fn usable_permits(
+    permits: Vec<SealedMessage>,
+    expiries: &HashMap<Digest, Date>,
+    now: &Date,
+    ignore_expiry: bool,
+) -> Result<Vec<SealedMessage>> {
+    if ignore_expiry {
+        return Ok(permits);
+    }
+    let supplied = permits.len();
+    let mut usable = Vec::with_capacity(supplied);
+    for permit in permits {
+        match expiries.get(&edition::sealed_digest(&permit)) {
+            Some(expires) if edition::is_expired(expires, now) => {
+                eprintln!("warning: skipping permit that expired on {expires}");
+            }
+            _ => usable.push(permit),
+        }
+    }
+    if supplied > 0 && usable.is_empty() {
+        bail!(
+            "all {supplied} supplied permits have expired; pass --ignore-expiry to use them anyway"
+        );
+    }
+    Ok(usable)
+}
+
+pub fn parse_shards(inputs: &[String]) -> Result<Vec<Envelope>> {
     let mut shares = Vec::with_capacity(inputs.len());
     for shard in inputs {
         let envelope = io::parse_envelope(shard)
@@ -185,19 +490,51 @@ fn parse_shards(inputs: &[String]) -> Result<Vec<Envelope>> {
     Ok(shares)
 }
 
-fn parse_private_keys(inputs: &[String]) -> Result<Vec<PrivateKeys>> {
+/// The edition `content` decrypted with the key joined from SSKR `shares`,
+/// unwrapped. Shares name the content by its digest and carry only their
+/// part of the key, so the ciphertext comes from the edition.
+pub fn join_shards(
+    content: &Envelope,
+    shares: &[Envelope],
+) -> Result<Envelope> {
+    if shares
+        .iter()
+        .any(|share| share.subject().digest() != content.digest())
+    {
+        bail!("SSKR shares were not split from this edition's content");
+    }
+    // `sskr_join` decrypts the subject of the first envelope it is given.
+    let refs: Vec<&Envelope> =
+        std::iter::once(content).chain(shares).collect();
+    let joined =
+        Envelope::sskr_join(&refs).context("failed to join SSKR shares")?;
+    if joined.is_wrapped() {
+        joined
+            .try_unwrap()
+            .context("failed to unwrap joined SSKR content")
+    } else {
+        Ok(joined)
+    }
+}
+
+pub fn parse_private_keys(
+    inputs: &[String],
+    passphrase: &io::PassphraseArgs,
+) -> Result<Vec<PrivateKeys>> {
     let mut keys = Vec::with_capacity(inputs.len());
     for identity in inputs {
-        let parsed = io::parse_private_keys(identity).with_context(|| {
-            format!("failed to parse private keys from '{identity}'")
-        })?;
+        let parsed = io::parse_private_keys(identity, passphrase)
+            .with_context(|| {
+                format!("failed to parse private keys from '{identity}'")
+            })?;
         keys.push(parsed);
     }
     Ok(keys)
 }
 
-fn recover_key_from_permits(
-    permits: &[bc_components::SealedMessage],
+/// The content key sealed in `permits`, opened with any of `private_keys`.
+pub fn recover_key_from_permits(
+    permits: &[SealedMessage],
     private_keys: &[PrivateKeys],
 ) -> Result<SymmetricKey> {
     let mut recovered: Option<SymmetricKey> = None;
@@ -239,9 +576,406 @@ fn recover_key_from_permits(
         }
     }
 
-    recovered.ok_or_else(|| {
-        anyhow!(
-            "none of the provided permits could be decrypted with the supplied identities"
+    if let Some(key) = recovered {
+        return Ok(key);
+    }
+
+    // Permits can only be opened by identities of the same key scheme; say
+    // so when none of them line up.
+    let identity_schemes: Vec<EncapsulationScheme> = private_keys
+        .iter()
+        .map(|keys| {
+            AsRef::<EncapsulationPrivateKey>::as_ref(keys)
+                .encapsulation_scheme()
+        })
+        .collect();
+    if !permits
+        .iter()
+        .any(|permit| identity_schemes.contains(&permit.encapsulation_scheme()))
+    {
+        return Err(anyhow!(
+            "no permit is sealed to the identities' key scheme ({}); permits use {}",
+            scheme_list(identity_schemes),
+            scheme_list(
+                permits.iter().map(|permit| permit.encapsulation_scheme())
+            )
+        ));
+    }
+
+    Err(anyhow!(
+        "none of the provided permits could be decrypted with the supplied identities"
+    ))
+}
+
+/// Whether `keys` open `permit` to a content key, decoded as
+/// `recover_key_from_permits` does. The key itself is dropped unused.
+pub fn opens_permit(permit: &SealedMessage, keys: &PrivateKeys) -> bool {
+    permit
+        .decrypt(keys)
+        .ok()
+        .and_then(|data| CBOR::try_from_data(data).ok())
+        .is_some_and(|cbor| {
+            <SymmetricKey as CBORTaggedDecodable>::from_tagged_cbor(cbor)
+                .is_ok()
+        })
+}
+
+fn scheme_list(
+    schemes: impl IntoIterator<Item = EncapsulationScheme>,
+) -> String {
+    let mut labels: Vec<&str> = Vec::new();
+    for scheme in schemes {
+        let label = io::encapsulation_scheme_label(scheme);
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    labels.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::{
+        KeyDerivationMethod, PrivateKeyBase, PrivateKeysProvider, PublicKeys,
+        PublicKeysProvider, SignatureScheme, XIDProvider, keypair_opt,
+    };
+    use bc_envelope::prelude::SignatureMetadata;
+    use bc_ur::UREncodable;
+    use bc_xid::XIDDocument;
+    use clubs::edition::Edition;
+    use dcbor::prelude::*;
+
+    use super::*;
+    use crate::{
+        cmd::edition::compose,
+        test_support::{
+            compose_for, genesis_mark, parse, private_ur, register_tags,
+            try_parse, xid_document,
+        },
+    };
+
+    fn seal(key: &SymmetricKey, recipient: &PublicKeys) -> SealedMessage {
+        SealedMessage::new(key.tagged_cbor().to_cbor_data(), recipient)
+    }
+
+    #[test]
+    fn recovers_key_from_mixed_scheme_permits() {
+        let key = SymmetricKey::new();
+        let classic = PrivateKeyBase::new();
+        let (pq_private, pq_public) = keypair_opt(
+            SignatureScheme::MLDSA65,
+            EncapsulationScheme::MLKEM768,
+        );
+        let permits =
+            vec![seal(&key, &classic.public_keys()), seal(&key, &pq_public)];
+
+        let recovered =
+            recover_key_from_permits(&permits, &[pq_private]).unwrap();
+        assert_eq!(recovered, key);
+        let recovered =
+            recover_key_from_permits(&permits, &[classic.private_keys()])
+                .unwrap();
+        assert_eq!(recovered, key);
+    }
+
+    #[test]
+    fn reports_scheme_mismatch() {
+        let key = SymmetricKey::new();
+        let (_, pq_public) = keypair_opt(
+            SignatureScheme::MLDSA65,
+            EncapsulationScheme::MLKEM1024,
+        );
+        let permits = vec![seal(&key, &pq_public)];
+        let err = recover_key_from_permits(
+            &permits,
+            &[PrivateKeyBase::new().private_keys()],
         )
-    })
+        .unwrap_err();
+        assert!(err.to_string().contains("ML-KEM-1024"));
+    }
+
+    #[test]
+    fn skips_expired_permits() {
+        let key = SymmetricKey::new();
+        let expired_holder = PrivateKeyBase::new();
+        let valid_holder = PrivateKeyBase::new();
+        let expired = seal(&key, &expired_holder.public_keys());
+        let valid = seal(&key, &valid_holder.public_keys());
+        let now = Date::from_string("2025-06-01T00:00:00Z").unwrap();
+        let expiries = HashMap::from([
+            (
+                edition::sealed_digest(&expired),
+                Date::from_string("2025-01-01").unwrap(),
+            ),
+            (
+                edition::sealed_digest(&valid),
+                Date::from_string("2026-01-01").unwrap(),
+            ),
+        ]);
+        let identities =
+            [expired_holder.private_keys(), valid_holder.private_keys()];
+
+        let permits = usable_permits(
+            vec![expired.clone(), valid],
+            &expiries,
+            &now,
+            false,
+        )
+        .unwrap();
+        assert_eq!(permits.len(), 1);
+        assert_eq!(
+            recover_key_from_permits(&permits, &identities).unwrap(),
+            key
+        );
+
+        assert!(
+            usable_permits(vec![expired.clone()], &expiries, &now, false)
+                .is_err()
+        );
+        let permits =
+            usable_permits(vec![expired], &expiries, &now, true).unwrap();
+        assert_eq!(
+            recover_key_from_permits(&permits, &identities).unwrap(),
+            key
+        );
+    }
+
+    #[test]
+    fn decrypts_with_the_editions_own_permits() {
+        let (publisher, member, stranger) =
+            (xid_document(), xid_document(), xid_document());
+        let content = Envelope::new("issue one").ur_string();
+        let edition = compose_for(
+            &publisher,
+            &genesis_mark(),
+            &[&member],
+            &["--content", &content],
+        );
+
+        let decrypt = |identity: &XIDDocument| {
+            let identity = private_ur(identity);
+            run(&parse::<CommandArgs>(&[
+                "clubs",
+                "--edition",
+                &edition,
+                "--identity",
+                &identity,
+            ]))
+        };
+        let content = decrypt(&member).unwrap();
+        assert_eq!(content.extract_subject::<String>().unwrap(), "issue one");
+        let err = decrypt(&stranger).err().unwrap();
+        assert!(
+            err.to_string().contains("no permit on the edition opens"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn writes_a_binary_payload_back_to_identical_bytes() {
+        let payload: Vec<u8> = (0..=255).collect();
+        let (publisher, member) = (xid_document(), xid_document());
+        let content = Envelope::new(CBOR::to_byte_string(&payload)).ur_string();
+        let edition = compose_for(
+            &publisher,
+            &genesis_mark(),
+            &[&member],
+            &["--content", &content],
+        );
+
+        let identity = private_ur(&member);
+        let args = parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &edition,
+            "--identity",
+            &identity,
+        ]);
+        let decrypted = run(&args).unwrap();
+        let (bytes, format) = content_bytes(&decrypted, None).unwrap();
+        assert_eq!(format, OutFormat::Bytes);
+        assert_eq!(bytes, payload);
+        assert!(content_bytes(&decrypted, Some(OutFormat::Text)).is_err());
+        let (ur, _) = content_bytes(&decrypted, Some(OutFormat::Ur)).unwrap();
+        assert_eq!(
+            String::from_utf8(ur).unwrap().trim(),
+            decrypted.ur_string()
+        );
+
+        let (text, format) =
+            content_bytes(&Envelope::new("issue one"), None).unwrap();
+        assert_eq!(
+            (text.as_slice(), format),
+            (&b"issue one"[..], OutFormat::Text)
+        );
+
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-decrypt-out-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let out = dir.join("payload.bin");
+        let path = out.display().to_string();
+        let write = |extra: &[&str]| {
+            let mut argv = vec![
+                "clubs",
+                "--edition",
+                &edition,
+                "--identity",
+                &identity,
+                "--out",
+                &path,
+            ];
+            argv.extend(extra);
+            exec(parse::<CommandArgs>(&argv))
+        };
+        write(&[]).unwrap();
+        assert_eq!(std::fs::read(&out).unwrap(), payload);
+        let err = write(&[]).err().unwrap();
+        assert!(err.to_string().contains("pass --force"), "{err}");
+        let err = exec(parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            "ur:envelope/not-an-edition",
+            "--identity",
+            &identity,
+            "--out",
+            &path,
+        ]))
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("pass --force"), "{err}");
+        assert_eq!(std::fs::read(&out).unwrap(), payload);
+        write(&["--force", "--out-format", "cbor"]).unwrap();
+        assert_eq!(
+            std::fs::read(&out).unwrap(),
+            decrypted.tagged_cbor().to_cbor_data()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn extracts_text_and_bytes_and_refuses_structured_subjects() {
+        let text = Envelope::new("meeting moved to Friday").wrap();
+        let (printed, _) = content_bytes(&text, Some(OutFormat::Text)).unwrap();
+        assert_eq!(printed, b"meeting moved to Friday");
+        let err = content_bytes(&text, Some(OutFormat::Bytes)).err().unwrap();
+        assert!(err.to_string().contains("use --text"), "{err}");
+
+        let bytes = Envelope::new(CBOR::to_byte_string([0, 159, 255]));
+        let (raw, _) = content_bytes(&bytes, Some(OutFormat::Bytes)).unwrap();
+        assert_eq!(raw, [0, 159, 255]);
+        let err = content_bytes(&bytes, Some(OutFormat::Text)).err().unwrap();
+        assert!(err.to_string().contains("use --raw"), "{err}");
+
+        let structured =
+            Envelope::new("announcement").add_assertion("title", "Agenda");
+        let err = content_bytes(&structured, Some(OutFormat::Text))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("is not text"), "{err}");
+
+        assert!(
+            try_parse::<CommandArgs>(&[
+                "clubs",
+                "--edition",
+                "ur:x",
+                "--text",
+                "--emit-ur"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn unlocks_password_permits_alongside_public_key_permits() {
+        let publisher = PrivateKeyBase::new();
+        let member = xid_document();
+        let key = SymmetricKey::new();
+        let content = Envelope::new("issue one")
+            .wrap()
+            .encrypt_subject(&key)
+            .unwrap();
+        let inner = Envelope::from(
+            Edition::new(member.xid(), genesis_mark(), content).unwrap(),
+        )
+        .add_assertion(
+            known_values::HAS_RECIPIENT,
+            seal(&key, member.inception_key().unwrap().public_keys()),
+        )
+        .add_assertion(
+            known_values::HAS_SECRET,
+            EncryptedKey::lock(KeyDerivationMethod::HKDF, "open sesame", &key)
+                .unwrap(),
+        );
+        let edition = compose::sign_edition(
+            &inner,
+            &publisher.private_keys(),
+            SignatureMetadata::new(),
+        )
+        .ur_string();
+
+        let decrypt = |extra: &[&str], password: Option<&str>| {
+            let mut argv = vec!["clubs", "--edition", &edition];
+            argv.extend(extra);
+            let args = parse::<CommandArgs>(&argv);
+            decrypt_content(&args, password)
+        };
+        let content = decrypt(&[], Some("open sesame")).unwrap();
+        assert_eq!(content.extract_subject::<String>().unwrap(), "issue one");
+        let err = decrypt(&[], Some("open barley")).err().unwrap();
+        assert_eq!(err.to_string(), "passphrase did not unlock any permit");
+
+        let identity = private_ur(&member);
+        assert!(
+            decrypt(&["--identity", &identity], Some("open sesame")).is_ok()
+        );
+        let other = SymmetricKey::new().ur_string();
+        let err = decrypt(&["--key", &other], Some("open sesame"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "conflicting symmetric keys recovered from --key and --password inputs"
+        );
+
+        let unprotected = Envelope::new("no permits");
+        assert!(
+            recover_key_from_password(
+                &password_permits(&unprotected).unwrap(),
+                "open sesame"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn prints_the_content_ur_unless_quiet() {
+        register_tags();
+        let content = Envelope::new("issue one");
+        let printed = |extra: &[&str]| {
+            let mut argv = vec!["clubs", "--edition", "ur:envelope/x"];
+            argv.extend(extra);
+            let args = parse::<CommandArgs>(&argv);
+            let mut stdout = Vec::new();
+            write_stdout(&args, &content, &mut stdout).unwrap();
+            String::from_utf8(stdout).unwrap()
+        };
+        let ur = format!("{}\n", content.ur_string());
+        assert_eq!(printed(&[]), ur);
+        assert_eq!(printed(&["--emit-ur"]), ur);
+        assert_eq!(printed(&["--quiet"]), "");
+        assert_eq!(printed(&["--text"]), "issue one\n");
+        assert_eq!(printed(&["--out", "content.txt"]), "");
+        assert_eq!(printed(&["--out", "content.txt", "--emit-ur"]), ur);
+        assert!(
+            try_parse::<CommandArgs>(&[
+                "clubs",
+                "--edition",
+                "ur:envelope/x",
+                "--quiet",
+                "--emit-ur"
+            ])
+            .is_err()
+        );
+    }
 }
@@ -12,6 +12,11 @@ pub struct CommandArgs {
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// Decrypt edition content using permits, SSKR shards, or raw keys.
+    ///
+    /// The content envelope's UR is printed to stdout unless `--text`,
+    /// `--raw`, or `--out` chooses another output. Earlier versions printed
+    /// nothing without `--emit-ur`, which is now the default; pass `--quiet`
+    /// for that silence.
     Decrypt(decrypt::CommandArgs),
 }
 
@@ -0,0 +1,376 @@
+use anyhow::{Context, Result, bail};
+use bc_components::{ReferenceProvider, XID};
+use bc_envelope::prelude::*;
+use bc_ur::UREncodable;
+use clap::Args;
+
+use super::{compose, inspect};
+use crate::{
+    cmd::VerificationFailed,
+    io::{self, PassphraseArgs},
+};
+
+/// Seal an edition's content key to more recipients and re-sign it.
+#[derive(Debug, Args)]
+pub struct CommandArgs {
+    /// Signed edition UR to add permits to.
+    #[arg(long, value_name = "UR")]
+    pub edition: String,
+    /// Recipient to grant access (XID document or public-keys UR); an XID
+    /// document's XID is recorded as the permit holder. Repeatable.
+    #[arg(long = "recipient", value_name = "UR", required = true)]
+    pub recipients: Vec<String>,
+    /// Symmetric key UR the edition's content is encrypted under.
+    #[arg(long, value_name = "UR")]
+    pub content_key: String,
+    /// Publisher's XID document UR (must include signing keys); the edition
+    /// must verify against it before it is re-signed.
+    #[arg(long, value_name = "UR")]
+    pub publisher: String,
+    /// Sign with the publisher key whose reference matches, as for
+    /// `edition compose`.
+    #[arg(long, value_name = "REF")]
+    pub signing_key_ref: Option<String>,
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
+}
+
+pub fn exec(args: CommandArgs) -> Result<()> {
+    let edition_ur = run(&args)?;
+    eprintln!(
+        "warning: adding permits changes the edition's digest; copies already distributed no longer match it"
+    );
+    println!("{edition_ur}");
+    Ok(())
+}
+
+/// The re-signed edition UR with a permit for each of `args.recipients`.
+pub fn run(args: &CommandArgs) -> Result<String> {
+    let publisher_doc =
+        io::parse_xid_document(&args.publisher, &args.passphrase)
+            .context("failed to load publisher XID document")?;
+    let (signing_keys, signing_key_ref) = compose::select_signing_keys(
+        &publisher_doc,
+        args.signing_key_ref.as_deref(),
+    )?;
+    let verifier = publisher_doc
+        .keys()
+        .iter()
+        .find(|key| key.public_keys().reference() == signing_key_ref)
+        .map(|key| key.public_keys().clone())
+        .context("signing key is missing from the publisher document")?;
+
+    let edition_env =
+        io::parse_envelope(&args.edition).context("failed to parse edition")?;
+    let inner = edition_env.verify(&verifier).map_err(|err| {
+        VerificationFailed(format!(
+            "failed to verify edition signature against the publisher; no permit added: {err}"
+        ))
+    })?;
+    let edition = super::decode_edition(&inner)
+        .context("edition payload is not a valid club edition")?;
+
+    let key = io::parse_symmetric_key(&args.content_key)
+        .context("failed to parse --content-key")?;
+    if !edition.content.is_encrypted() {
+        bail!("edition content is not encrypted; permits would grant nothing");
+    }
+    edition
+        .content
+        .decrypt(&key)
+        .context("--content-key does not decrypt the edition's content")?;
+
+    // Sealed under the edition id, like the permits already there.
+    let edition_id = super::edition_id(&edition);
+    let annotate_refs = inner
+        .objects_for_predicate(known_values::HAS_RECIPIENT)
+        .iter()
+        .any(|permit| {
+            permit
+                .optional_object_for_predicate(io::PERMIT_RECIPIENT_KEY)
+                .is_ok_and(|object| object.is_some())
+        });
+    let holders: Vec<XID> = super::permits::sealed_permits(&edition)
+        .into_iter()
+        .filter_map(|(_, holder)| holder)
+        .collect();
+    let recorded = super::permit_recipient_keys(&inner)?;
+
+    let mut reissued = inner;
+    for spec in &args.recipients {
+        let descriptor = io::parse_recipient_descriptor(spec)
+            .with_context(|| format!("failed to parse recipient '{spec}'"))?;
+        let reference = descriptor.public_keys().reference();
+        if let Some(holder) = descriptor.member_xid()
+            && holders.contains(&holder)
+        {
+            bail!("{holder} already holds a permit for this edition");
+        }
+        if recorded.values().any(|recorded| *recorded == reference) {
+            bail!(
+                "recipient {reference} already holds a permit for this edition"
+            );
+        }
+        reissued = reissued.add_assertion(
+            known_values::HAS_RECIPIENT,
+            super::recipient_permit(
+                &descriptor,
+                &key,
+                edition_id.data(),
+                None,
+                annotate_refs,
+            ),
+        );
+    }
+
+    let mut metadata = SignatureMetadata::new();
+    let signatures = inspect::signature_metadata(&edition_env);
+    if let Some(first) = signatures.first() {
+        for assertion in first.assertions() {
+            if let (Some(predicate), Some(object)) =
+                (assertion.as_predicate(), assertion.as_object())
+            {
+                metadata = metadata.with_assertion(predicate, object);
+            }
+        }
+    }
+    if edition_env
+        .objects_for_predicate(known_values::SIGNED)
+        .len()
+        > 1
+    {
+        eprintln!(
+            "warning: the edition's other signatures are dropped; cosigners must sign the re-issued edition again"
+        );
+    }
+    let signed = compose::sign_edition(&reissued, &signing_keys, metadata);
+    Ok(signed.ur_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::{
+        PrivateKeyBase, PrivateKeysProvider, PublicKeysProvider, SealedMessage,
+        SymmetricKey, XIDProvider,
+    };
+
+    use clubs::{edition::Edition, public_key_permit::PublicKeyPermit};
+
+    use super::*;
+    use crate::{
+        cmd::{content::decrypt, edition::permits},
+        test_support::{
+            Club, compose_edition, compose_for, genesis_mark, parse, public_ur,
+            xid_document_with,
+        },
+    };
+
+    #[test]
+    fn grants_a_new_member_access_and_re_signs() {
+        let publisher = Club::new();
+        let (old_keys, new_keys) =
+            (PrivateKeyBase::new(), PrivateKeyBase::new());
+        let (old_member, new_member) =
+            (xid_document_with(&old_keys), xid_document_with(&new_keys));
+        let key = SymmetricKey::new();
+        let signer = publisher.private_ur();
+        let content = Envelope::new("issue one")
+            .wrap()
+            .encrypt_subject(&key)
+            .unwrap()
+            .ur_string();
+        let key_ur = key.ur_string();
+        let edition = compose_for(
+            &publisher.doc,
+            &genesis_mark(),
+            &[&old_member],
+            &[
+                "--content",
+                &content,
+                "--pre-encrypted",
+                "--content-key",
+                &key_ur,
+                "--title",
+                "Issue 1",
+            ],
+        );
+
+        let new_ur = public_ur(&new_member);
+        let add = |content_key: &str| {
+            run(&parse::<CommandArgs>(&[
+                "clubs",
+                "--edition",
+                &edition,
+                "--recipient",
+                &new_ur,
+                "--content-key",
+                content_key,
+                "--publisher",
+                &signer,
+            ]))
+        };
+        let reissued = add(&key_ur).unwrap();
+
+        let reissued_env = io::parse_envelope(&reissued).unwrap();
+        let inner = reissued_env.verify(&publisher.keys.public_keys()).unwrap();
+        assert_eq!(
+            inspect::signature_metadata(&reissued_env)[0]
+                .extract_object_for_predicate::<String>(known_values::NAME)
+                .unwrap(),
+            "Issue 1"
+        );
+        let edition = super::super::decode_edition(&inner).unwrap();
+        let sealed: Vec<SealedMessage> = permits::sealed_permits(&edition)
+            .into_iter()
+            .map(|(sealed, _)| sealed.clone())
+            .collect();
+        assert_eq!(sealed.len(), 2);
+        for member in [&old_keys, &new_keys] {
+            let recovered = decrypt::recover_key_from_permits(
+                &sealed,
+                &[member.private_keys()],
+            )
+            .unwrap();
+            let content = edition.content.decrypt(&recovered).unwrap();
+            assert_eq!(
+                content.extract_subject::<String>().unwrap(),
+                "issue one"
+            );
+        }
+        assert!(
+            permits::sealed_permits(&edition)
+                .iter()
+                .any(|(_, holder)| *holder == Some(new_member.xid()))
+        );
+
+        let err = add(&SymmetricKey::new().ur_string()).unwrap_err();
+        assert!(format!("{err:#}").contains("does not decrypt"), "{err:#}");
+    }
+
+    #[test]
+    fn first_permit_is_sealed_under_the_edition_id() {
+        let publisher = Club::new();
+        let member_keys = PrivateKeyBase::new();
+        let member = xid_document_with(&member_keys);
+        let key = SymmetricKey::new();
+        let authored = Envelope::new("issue one");
+        let content =
+            authored.wrap().encrypt_subject(&key).unwrap().ur_string();
+        let key_ur = key.ur_string();
+        let edition = compose_edition(
+            &publisher.doc,
+            &genesis_mark(),
+            &[
+                "--content",
+                &content,
+                "--pre-encrypted",
+                "--content-key",
+                &key_ur,
+            ],
+        );
+
+        let reissued = run(&parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &edition,
+            "--recipient",
+            &public_ur(&member),
+            "--content-key",
+            &key_ur,
+            "--publisher",
+            &publisher.private_ur(),
+        ]))
+        .unwrap();
+
+        let inner = io::parse_envelope(&reissued)
+            .unwrap()
+            .verify(&publisher.keys.public_keys())
+            .unwrap();
+        let edition = super::super::decode_edition(&inner).unwrap();
+        let sealed: Vec<SealedMessage> = permits::sealed_permits(&edition)
+            .into_iter()
+            .map(|(sealed, _)| sealed.clone())
+            .collect();
+        assert_eq!(sealed.len(), 1);
+        assert_eq!(
+            crate::cmd::edition::sealed_aad(&sealed[0]),
+            super::super::edition_id(&edition).data()
+        );
+        let recovered = decrypt::recover_key_from_permits(
+            &sealed,
+            &[member_keys.private_keys()],
+        )
+        .unwrap();
+        assert_eq!(
+            edition.content.decrypt(&recovered).unwrap().digest(),
+            authored.digest()
+        );
+    }
+
+    #[test]
+    fn appends_to_an_edition_sealed_by_the_library() {
+        let publisher = Club::new();
+        let (old_keys, new_keys) =
+            (PrivateKeyBase::new(), PrivateKeyBase::new());
+        let (sealed, _) = Edition::new(
+            publisher.doc.xid(),
+            genesis_mark(),
+            Envelope::new("issue one"),
+        )
+        .unwrap()
+        .seal_with_permits(
+            &[PublicKeyPermit::new(old_keys.public_keys())],
+            None,
+            &publisher.keys,
+        )
+        .unwrap();
+        let original =
+            Edition::unseal(sealed.clone(), &publisher.keys.public_keys())
+                .unwrap();
+        let original_permits: Vec<SealedMessage> =
+            permits::sealed_permits(&original)
+                .into_iter()
+                .map(|(sealed, _)| sealed.clone())
+                .collect();
+        let key = decrypt::recover_key_from_permits(
+            &original_permits,
+            &[old_keys.private_keys()],
+        )
+        .unwrap();
+
+        let reissued = run(&parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &sealed.ur_string(),
+            "--recipient",
+            &new_keys.public_keys().ur_string(),
+            "--content-key",
+            &key.ur_string(),
+            "--publisher",
+            &publisher.private_ur(),
+        ]))
+        .unwrap();
+
+        let inner = io::parse_envelope(&reissued)
+            .unwrap()
+            .verify(&publisher.keys.public_keys())
+            .unwrap();
+        let edition = super::super::decode_edition(&inner).unwrap();
+        let sealed: Vec<SealedMessage> = permits::sealed_permits(&edition)
+            .into_iter()
+            .map(|(sealed, _)| sealed.clone())
+            .collect();
+        assert_eq!(sealed.len(), 2);
+        let library_aad =
+            crate::cmd::edition::sealed_aad(&original_permits[0]);
+        for permit in &sealed {
+            assert_eq!(crate::cmd::edition::sealed_aad(permit), library_aad);
+        }
+        let recovered = decrypt::recover_key_from_permits(
+            &sealed,
+            &[new_keys.private_keys()],
+        )
+        .unwrap();
+        assert_eq!(recovered, key);
+    }
+}
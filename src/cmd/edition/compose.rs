@@ -1,29 +1,67 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
 use anyhow::{Context, Result, anyhow, bail};
 use bc_components::{
-    PrivateKeys, ReferenceProvider, SSKRGroupSpec, SSKRSpec, XIDProvider,
+    Digest, DigestProvider, EncapsulationScheme, PrivateKeys, PublicKeys,
+    Reference, ReferenceProvider, SSKRGroupSpec, SSKRSpec, SealedMessage,
+    SignatureScheme, SymmetricKey, XID, XIDProvider, keypair_opt,
 };
+use bc_envelope::prelude::*;
 use bc_ur::UREncodable;
-use bc_xid::XIDDocument;
+use bc_xid::{HasPermissions, Privilege, XIDDocument};
 use clap::Args;
-use clubs::{
-    edition::Edition, provenance_mark_provider::ProvenanceMarkProvider,
-    public_key_permit::PublicKeyPermit,
-};
+use clubs::{edition::Edition, public_key_permit::PublicKeyPermit};
+use serde::Serialize;
 
-use crate::io::{self, RecipientDescriptor};
+use super::{
+    DigestBinding, manifest,
+    permits::{self, PermitId},
+    receipt::{self, ReceiptRecipient},
+    size::EditionSize,
+};
+use crate::{
+    chain::{self, ChainFile, ChainLock},
+    io::{self, PassphraseArgs, RecipientDescriptor},
+    output::{
+        self, FragmentArgs,
+        qr::{self, QrArgs},
+    },
+    workspace::Workspace,
+};
 
 /// Arguments for composing and signing a club edition.
 #[derive(Debug, Args)]
 pub struct CommandArgs {
+    /// TOML manifest supplying any of these options; explicit flags take
+    /// precedence over its values.
+    #[arg(long, value_name = "PATH")]
+    pub manifest: Option<PathBuf>,
+    /// Club workspace created by `clubs init --out-dir`; its `club.toml`
+    /// supplies the publisher, previous edition, and carried recipients, and
+    /// the new edition is recorded under its `editions/` directory.
+    #[arg(long, value_name = "DIR")]
+    pub club: Option<PathBuf>,
     /// Publisher's XID document UR (must include signing keys).
     #[arg(long, value_name = "UR", global = true)]
-    pub publisher: String,
+    pub publisher: Option<String>,
     /// Content envelope UR for this edition.
     #[arg(long, value_name = "UR")]
-    pub content: String,
+    pub content: Option<String>,
     /// Provenance mark UR bound to this edition.
     #[arg(long, value_name = "UR")]
-    pub provenance: String,
+    pub provenance: Option<String>,
+    /// Provenance generator state (e.g. `provenance-state.ur` from `clubs
+    /// init`). The edition's mark is minted from it with the content digest
+    /// as its info, and the file is advanced once the edition is composed.
+    #[arg(long, value_name = "PATH", conflicts_with = "provenance")]
+    pub provenance_state: Option<PathBuf>,
+    /// Date of the mark minted from `--provenance-state` (or by `clubs
+    /// init`): an RFC 3339 date or `now`, the default.
+    #[arg(long, value_name = "DATE", conflicts_with = "provenance")]
+    pub date: Option<String>,
     /// Permit descriptors (XID or public-keys UR).
     #[arg(long = "permit", value_name = "UR")]
     pub permits: Vec<String>,
@@ -33,122 +71,1254 @@ pub struct CommandArgs {
     /// Previous edition UR to enforce provenance ordering.
     #[arg(long, value_name = "UR")]
     pub previous: Option<String>,
+    /// Chain file written by `edition sequence --write-chain`. The
+    /// provenance mark must extend its tip, and the edition is appended to
+    /// it once composed; a genesis edition starts a missing file.
+    #[arg(long, value_name = "PATH")]
+    pub chain: Option<PathBuf>,
+    /// Content is already encrypted; seal `--content-key` into the permits
+    /// instead of encrypting again.
+    #[arg(long, requires = "content_key")]
+    pub pre_encrypted: bool,
+    /// Symmetric key UR that decrypts pre-encrypted content.
+    #[arg(long, value_name = "UR", requires = "pre_encrypted")]
+    pub content_key: Option<String>,
+    /// Require the provenance mark's info field to hold the content digest,
+    /// committing the mark to this edition's content. A mark whose info
+    /// holds some other digest is refused either way.
+    #[arg(long, overrides_with = "no_bind_digest")]
+    pub bind_digest: bool,
+    /// Turn off `bind-digest` when the manifest turns it on.
+    #[arg(long, overrides_with = "bind_digest")]
+    pub no_bind_digest: bool,
+    /// Confirm that a genesis provenance mark is meant to start a new chain.
+    #[arg(long, overrides_with = "no_genesis")]
+    pub genesis: bool,
+    /// Turn off `genesis` when the manifest turns it on.
+    #[arg(long, overrides_with = "genesis")]
+    pub no_genesis: bool,
+    /// Fail instead of warning when provenance ordering cannot be checked.
+    #[arg(long, overrides_with = "no_strict")]
+    pub strict: bool,
+    /// Turn off `strict` when the manifest turns it on.
+    #[arg(long, overrides_with = "strict")]
+    pub no_strict: bool,
+    /// Accept a provenance mark dated earlier than the previous edition's
+    /// mark (e.g. to tolerate clock skew).
+    #[arg(long, overrides_with = "no_allow_date_regression")]
+    pub allow_date_regression: bool,
+    /// Turn off `allow-date-regression` when the manifest turns it on.
+    #[arg(long, overrides_with = "allow_date_regression")]
+    pub no_allow_date_regression: bool,
+    /// Also issue a permit to the publisher's own encryption keys.
+    #[arg(long, overrides_with = "no_self_permit")]
+    pub self_permit: bool,
+    /// Turn off `self-permit` when the manifest or workspace turns it on.
+    #[arg(long, overrides_with = "self_permit")]
+    pub no_self_permit: bool,
+    /// RFC 3339 date after which decrypters stop honoring the permits. It is
+    /// recorded next to each permit, outside the sealed message, and covered
+    /// by the signature. Expiries carried by permit descriptors take
+    /// precedence.
+    #[arg(long, value_name = "DATE")]
+    pub permit_expiry: Option<String>,
+    /// Pad the edition with decoy permits until it carries this many. Decoys
+    /// name no holder, so they hide how many recipients are anonymous: a
+    /// permit that names its holder is still known to be real.
+    #[arg(long, value_name = "N")]
+    pub pad_permits: Option<usize>,
+    /// Sign with the publisher key whose reference matches (full or short
+    /// hex, or bytewords) instead of the first available private key.
+    #[arg(long, value_name = "REF")]
+    pub signing_key_ref: Option<String>,
+    /// Title recorded in the signature metadata (visible without decrypting).
+    #[arg(long, value_name = "TEXT")]
+    pub title: Option<String>,
+    /// Free-form note recorded in the signature metadata; repeatable.
+    #[arg(long = "note", value_name = "TEXT")]
+    pub notes: Vec<String>,
+    /// Record the signing key's public keys in the signature metadata, so
+    /// `edition inspect --genesis` can verify the chain from its genesis
+    /// edition alone.
+    #[arg(long, overrides_with = "no_embed_publisher_keys")]
+    pub embed_publisher_keys: bool,
+    /// Turn off `embed-publisher-keys` when the manifest turns it on.
+    #[arg(long, overrides_with = "embed_publisher_keys")]
+    pub no_embed_publisher_keys: bool,
+    /// Record each permit's recipient key reference as a public assertion,
+    /// so `edition permits --recipients-file` can tell whom an unannotated
+    /// permit was sealed to. Anyone holding the recipients' public keys can
+    /// then do the same.
+    #[arg(long, overrides_with = "no_annotate_recipient_refs")]
+    pub annotate_recipient_refs: bool,
+    /// Turn off `annotate-recipient-refs` when the manifest turns it on.
+    #[arg(long, overrides_with = "annotate_recipient_refs")]
+    pub no_annotate_recipient_refs: bool,
+    /// Recipients manifest written by a previous compose run (see
+    /// `--out-dir`); permits are regenerated for the same recipients.
+    #[arg(long, value_name = "PATH")]
+    pub carry_permits: Option<PathBuf>,
+    /// Directory to write the edition, SSKR shares, recipients manifest, and
+    /// permit manifest.
+    #[arg(long, value_name = "DIR")]
+    pub out_dir: Option<PathBuf>,
+    /// Write a publisher-signed recipients receipt to this path (also
+    /// written as `receipt.ur` under `--out-dir`).
+    #[arg(long, value_name = "PATH")]
+    pub receipt: Option<PathBuf>,
+    /// Fail if the signed edition's CBOR exceeds this many bytes.
+    #[arg(long, value_name = "BYTES")]
+    pub max_size: Option<usize>,
+    /// Refuse content whose envelope CBOR is larger than this many bytes;
+    /// a single-part UR is measured before it is decoded.
+    #[arg(long, value_name = "BYTES")]
+    pub max_content_size: Option<usize>,
+    /// Emit a human-readable summary to stderr.
+    #[arg(long)]
+    pub summary: bool,
+    /// Validate inputs and compose the edition without emitting or writing
+    /// anything; implies `--summary`.
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Keep emitted URs off stdout; set by callers that report them
+    /// themselves, such as `clubs init --format json`.
+    #[arg(skip)]
+    pub quiet: bool,
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
+    #[command(flatten)]
+    pub fragments: FragmentArgs,
+    #[command(flatten)]
+    pub qr: QrArgs,
+}
+
+/// File name of the recipients manifest written under `--out-dir`.
+pub const RECIPIENTS_FILE: &str = "recipients.ur";
+
+/// File name of the permit manifest written under `--out-dir`.
+pub const PERMITS_FILE: &str = "permits.json";
+
+/// What a compose run produced, for callers that file the results away.
+pub struct Composed {
+    pub club_xid: XID,
+    pub seq: u32,
+    pub edition_ur: String,
+    /// SSKR share URs by group, with their file stems.
+    pub shares: Vec<(String, String)>,
+    /// Permit descriptor URs for the edition's recipients, as written to
+    /// `recipients.ur`.
+    pub recipients: Vec<String>,
+    /// The edition's sealed permits, as written to `permits.json`.
+    pub permits: Vec<ComposedPermit>,
+}
+
+/// One sealed permit of a composed edition, identified as `edition inspect`
+/// and `edition permits` identify it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ComposedPermit {
+    #[serde(flatten)]
+    pub id: PermitId,
+    /// Recipient key reference, with `--annotate-recipient-refs`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient_key: Option<String>,
+    /// Whether the permit is a `--pad-permits` decoy.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub decoy: bool,
 }
 
 pub fn exec(args: CommandArgs) -> Result<()> {
+    let args = manifest::apply(args)?;
+    let workspace = match args.club.as_ref() {
+        Some(dir) => Some(Workspace::open(dir)?),
+        None => None,
+    };
+    let args = match workspace.as_ref() {
+        Some(workspace) => apply_workspace(workspace, args)?,
+        None => args,
+    };
+    let dry_run = args.dry_run;
+    let composed = run(args)?;
+    if let Some(workspace) = workspace
+        && !dry_run
+    {
+        let path = workspace.record_edition(&composed)?;
+        eprintln!("Recorded edition in {}", path.display());
+    }
+    Ok(())
+}
+
+/// Fill publisher, previous edition, carried recipients, and the
+/// self-permit setting from a club workspace's `club.toml` where the command
+/// line left them unset.
+fn apply_workspace(
+    workspace: &Workspace,
+    mut args: CommandArgs,
+) -> Result<CommandArgs> {
+    let defaults = &workspace.config().defaults;
+    if args.publisher.is_none() {
+        let path = defaults.publisher.as_ref().ok_or_else(|| {
+            anyhow!("club.toml names no publisher; pass --publisher")
+        })?;
+        args.publisher = Some(format!("@{}", workspace.path(path).display()));
+    }
+    if args.provenance.is_none()
+        && args.provenance_state.is_none()
+        && let Some(path) = defaults.provenance_state.as_ref()
+    {
+        args.provenance_state = Some(workspace.path(path));
+    }
+    if args.previous.is_none()
+        && let Some(path) = workspace.latest_edition()?
+    {
+        args.previous = Some(format!("@{}", path.display()));
+    }
+    if args.carry_permits.is_none()
+        && let Some(path) = defaults.recipients.as_ref()
+        && workspace.path(path).exists()
+    {
+        args.carry_permits = Some(workspace.path(path));
+    }
+    if args.sskr.is_empty() {
+        args.sskr = defaults.sskr.clone();
+    }
+    default_switch(
+        &mut args.self_permit,
+        &mut args.no_self_permit,
+        Some(defaults.self_permit),
+    );
+    Ok(args)
+}
+
+/// Set the switch `on`, whose `--no-` form is `off`, to `value` unless the
+/// command line turned it either way.
+pub fn default_switch(on: &mut bool, off: &mut bool, value: Option<bool>) {
+    if *on || *off {
+        return;
+    }
+    match value {
+        Some(true) => *on = true,
+        Some(false) => *off = true,
+        None => {}
+    }
+}
+
+/// Compose, sign, and emit an edition, returning what was produced.
+pub fn run(args: CommandArgs) -> Result<Composed> {
     let CommandArgs {
+        manifest: _,
+        club: _,
         publisher,
         content,
         provenance,
+        provenance_state,
+        date,
         permits,
         sskr,
         previous,
+        chain,
+        pre_encrypted,
+        content_key,
+        bind_digest,
+        no_bind_digest: _,
+        genesis,
+        no_genesis: _,
+        strict,
+        no_strict: _,
+        allow_date_regression,
+        no_allow_date_regression: _,
+        self_permit,
+        no_self_permit: _,
+        permit_expiry,
+        pad_permits,
+        signing_key_ref,
+        title,
+        notes,
+        embed_publisher_keys,
+        no_embed_publisher_keys: _,
+        annotate_recipient_refs,
+        no_annotate_recipient_refs: _,
+        carry_permits,
+        out_dir,
+        receipt,
+        max_size,
+        max_content_size,
+        summary,
+        dry_run,
+        quiet,
+        passphrase,
+        fragments,
+        qr,
     } = args;
+    qr.format()?;
+    let summary = summary || dry_run;
+    let publisher = required(publisher, "publisher")?;
+    let content = required(content, "content")?;
+    if provenance.is_none() && provenance_state.is_none() {
+        bail!(
+            "--provenance or --provenance-state is required (pass one or set it in --manifest)"
+        );
+    }
 
-    let publisher_doc = io::parse_xid_document(&publisher)
+    let publisher_doc = io::parse_xid_document(&publisher, &passphrase)
         .context("failed to load publisher XID document")?;
-    let signing_keys = extract_signing_keys(&publisher_doc)?;
+    let (signing_keys, signing_key_ref) =
+        select_signing_keys(&publisher_doc, signing_key_ref.as_deref())?;
     let club_xid = publisher_doc.xid();
 
-    let content_env = io::parse_envelope(&content)
-        .context("failed to load edition content envelope")?;
+    let mut content_env =
+        io::parse_envelope_limited(&content, max_content_size)
+            .context("failed to load edition content envelope")?;
+    drop(content);
     if content_env.has_assertions() {
         bail!(
             "content envelope still has assertions; supply a subject-only envelope (wrap the content so assertions are removed) to keep the digest stable"
         );
     }
-    let provenance_mark = io::parse_provenance_mark(&provenance)
-        .context("failed to parse provenance mark")?;
+    let supplied_key = match content_key.as_ref() {
+        Some(spec) => Some(
+            io::parse_symmetric_key(spec)
+                .context("failed to parse --content-key")?,
+        ),
+        None => None,
+    };
+    if content_env.is_encrypted() {
+        let Some(key) = supplied_key.as_ref() else {
+            bail!(
+                "content envelope is already encrypted; permits could not unlock it. Supply plaintext content, or pass --pre-encrypted --content-key <ur:crypto-key> to seal the existing key"
+            );
+        };
+        content_env.decrypt(key).context(
+            "--content-key does not decrypt the pre-encrypted content",
+        )?;
+    } else if pre_encrypted {
+        bail!("--pre-encrypted was given but the content is not encrypted");
+    }
+    let (provenance_mark, advanced_state) = match provenance.as_ref() {
+        Some(spec) => (
+            io::parse_provenance_mark(spec)
+                .context("failed to parse provenance mark")?,
+            None,
+        ),
+        None => {
+            let path = provenance_state
+                .as_ref()
+                .expect("provenance state is present without a mark");
+            let (mut generator, secret) = io::parse_provenance_state(
+                &format!("@{}", path.display()),
+                &passphrase,
+            )
+            .context("failed to load --provenance-state")?;
+            let date = super::parse_mark_date(date.as_deref())
+                .context("failed to parse --date")?;
+            let mark =
+                super::mint_bound_mark(&mut generator, &content_env, date)?;
+            (mark, Some((generator, secret)))
+        }
+    };
+    if bind_digest {
+        match DigestBinding::check(&provenance_mark, &content_env) {
+            DigestBinding::Match => {}
+            DigestBinding::Mismatch { bound, content } => bail!(
+                "--bind-digest: provenance mark info digest {} does not match content digest {}",
+                bound.hex(),
+                content.hex()
+            ),
+            DigestBinding::Absent => bail!(
+                "--bind-digest: provenance mark info does not hold the content digest {}",
+                content_env.digest().hex()
+            ),
+        }
+    }
+    let permit_expiry = match permit_expiry.as_ref() {
+        Some(spec) => Some(
+            io::parse_date(spec).context("failed to parse --permit-expiry")?,
+        ),
+        None => None,
+    };
+
+    // Held until the edition is appended, so concurrent runs cannot both
+    // extend the same tip.
+    let chain_lock = match chain.as_ref() {
+        Some(path) if !dry_run => Some(ChainLock::acquire(path)?),
+        _ => None,
+    };
+    let mut chain_file = match chain.as_ref() {
+        Some(path) if path.exists() || !provenance_mark.is_genesis() => {
+            let file = ChainFile::load(path)?;
+            let tip = file.check_extends(club_xid, &provenance_mark)?;
+            if provenance_mark.date() < tip.date() && !allow_date_regression {
+                bail!(
+                    "provenance mark date {} precedes the chain file's tip date {}; pass --allow-date-regression to override",
+                    provenance_mark.date(),
+                    tip.date()
+                );
+            }
+            Some(file)
+        }
+        Some(_) => Some(ChainFile::new(club_xid, provenance_mark.chain_id())),
+        None => None,
+    };
+
+    if previous.is_none()
+        && chain_file
+            .as_ref()
+            .is_none_or(|file| file.editions.is_empty())
+    {
+        if provenance_mark.is_genesis() {
+            if !genesis {
+                bail!(
+                    "provenance mark is a genesis mark, which starts a new chain; pass --genesis to confirm, or supply the current chain's next mark together with --previous <edition UR>"
+                );
+            }
+        } else {
+            let message = format!(
+                "provenance ordering was not checked for seq {}; supply the prior edition with --previous <edition UR> to confirm this mark follows it",
+                provenance_mark.seq()
+            );
+            if strict {
+                bail!(message);
+            }
+            eprintln!("warning: {message}");
+        }
+    }
 
     if let Some(previous_str) = previous.as_ref() {
         let previous_env = io::parse_envelope(previous_str)
             .context("failed to parse previous edition")?;
-        let previous_edition = Edition::try_from(previous_env)
+        let (previous_inner, _) = super::inspect::edition_payload(&previous_env)
+            .context("previous edition input is not a valid club edition")?;
+        let previous_edition = super::decode_edition(&previous_inner)
             .context("previous edition input is not a valid club edition")?;
-        if !previous_edition.precedes(&provenance_mark) {
+        if !chain::links(&previous_edition.provenance, &provenance_mark) {
             bail!(
                 "provided provenance mark does not follow the previous edition's provenance mark"
             );
         }
+        let previous_date = previous_edition.provenance.date();
+        if provenance_mark.date() < previous_date && !allow_date_regression {
+            bail!(
+                "provenance mark date {} precedes the previous edition's mark date {}; pass --allow-date-regression to override",
+                provenance_mark.date(),
+                previous_date
+            );
+        }
     }
 
-    let mut recipient_permits: Vec<PublicKeyPermit> = Vec::new();
+    let mut recipients: Vec<RecipientDescriptor> = Vec::new();
+    let mut carried = 0usize;
+    if let Some(path) = carry_permits.as_ref() {
+        let descriptors = io::parse_recipients_file(path)
+            .context("failed to load carried recipients")?;
+        for descriptor in descriptors {
+            if push_unique(&mut recipients, descriptor) {
+                carried += 1;
+            }
+        }
+    }
+
+    let mut added = 0usize;
     for permit_input in permits.iter() {
         let descriptor = io::parse_recipient_descriptor(permit_input)
             .with_context(|| {
                 format!("failed to parse permit input '{permit_input}'")
             })?;
-        let (permit, _label) = permit_from_descriptor(descriptor);
-        recipient_permits.push(permit);
+        if push_unique(&mut recipients, descriptor) {
+            added += 1;
+        }
+    }
+
+    if self_permit {
+        let publisher_keys = publisher_encryption_keys(&publisher_doc)?;
+        let descriptor = RecipientDescriptor::from_public_keys(
+            publisher_keys,
+            Some(club_xid),
+        );
+        if push_unique(&mut recipients, descriptor) {
+            added += 1;
+        } else {
+            eprintln!(
+                "note: publisher is already a recipient; --self-permit adds nothing"
+            );
+        }
+    }
+
+    let recipient_labels: Vec<String> =
+        recipients.iter().map(recipient_label).collect();
+
+    let sskr_policy = parse_sskr_policy(&sskr)?;
+    let sskr_spec = match sskr_policy.as_ref() {
+        Some(policy) => Some(policy.to_spec()?),
+        None => None,
+    };
+
+    if supplied_key.is_some() && sskr_spec.is_some() {
+        bail!("--sskr cannot be combined with --pre-encrypted content");
+    }
+    // The mark binds the authored content, which `Edition::new` checks;
+    // sealing then swaps in the ciphertext, as `seal_with_permits` does.
+    let mut edition =
+        Edition::new(club_xid, provenance_mark.clone(), content_env.clone())
+            .context("the provenance mark does not bind the content digest")?;
+    // Pre-encrypted content keeps its key; otherwise content is encrypted
+    // under a fresh key whenever permits or shares must unlock it.
+    let content_key = match supplied_key.as_ref() {
+        Some(key) => Some(key.clone()),
+        None if recipients.is_empty() && sskr_spec.is_none() => None,
+        None => Some(SymmetricKey::new()),
+    };
+    let mut share_groups = None;
+    if let Some(key) = content_key.as_ref()
+        && !content_env.is_encrypted()
+    {
+        content_env = content_env
+            .wrap()
+            .encrypt_subject(key)
+            .context("failed to encrypt the content")?;
+        // Shares split only the content key. They name the content by its
+        // elided digest rather than each carrying the ciphertext, which
+        // `decrypt` takes from the edition.
+        if let Some(spec) = sskr_spec.as_ref() {
+            share_groups = Some(
+                content_env
+                    .elide()
+                    .sskr_split(spec, key)
+                    .context("failed to split the content into SSKR shares")?,
+            );
+        }
+    }
+    edition.content = content_env;
+    let mut unsigned_edition = Envelope::from(edition);
+    // Permits are bound to the edition before any of them is added, the
+    // AAD `Edition::seal_with_permits` uses.
+    let edition_id = unsigned_edition.digest();
+    if let Some(key) = content_key.as_ref() {
+        for descriptor in &recipients {
+            unsigned_edition = unsigned_edition.add_assertion(
+                known_values::HAS_RECIPIENT,
+                super::recipient_permit(
+                    descriptor,
+                    key,
+                    edition_id.data(),
+                    permit_expiry.as_ref(),
+                    annotate_recipient_refs,
+                ),
+            );
+        }
+    }
+
+    let mut decoys = 0usize;
+    let mut decoy_digests: HashSet<Digest> = HashSet::new();
+    if let Some(target) = pad_permits {
+        if recipients.len() > target {
+            bail!(
+                "--pad-permits {target} is smaller than the {} real permits",
+                recipients.len()
+            );
+        }
+        let sealed_edition = super::decode_edition(&unsigned_edition)
+            .context("composed edition could not be re-read for padding")?;
+        let real_permits: Vec<&SealedMessage> = sealed_edition
+            .permits
+            .iter()
+            .filter_map(|permit| match permit {
+                PublicKeyPermit::Decode { sealed, .. } => Some(sealed),
+                _ => None,
+            })
+            .collect();
+        // Mirror the real permits' key schemes so decoys cannot be told
+        // apart by their encapsulation.
+        let schemes: Vec<EncapsulationScheme> = if real_permits.is_empty() {
+            vec![EncapsulationScheme::default()]
+        } else {
+            real_permits
+                .iter()
+                .map(|sealed| sealed.encapsulation_scheme())
+                .collect()
+        };
+        if !unsigned_edition.subject().is_encrypted() {
+            bail!(
+                "--pad-permits requires encrypted content; add a --permit or --sskr"
+            );
+        }
+        decoys = target - recipients.len();
+        let named = recipients
+            .iter()
+            .filter(|descriptor| descriptor.member_xid().is_some())
+            .count();
+        if decoys > 0 && named > 0 {
+            eprintln!(
+                "note: {named} permit(s) name their holder and decoys name none; the padding hides only how many recipients are anonymous"
+            );
+        }
+        for index in 0..decoys {
+            let scheme = schemes[index % schemes.len()];
+            let (decoy, reference) = decoy_permit(edition_id.data(), scheme);
+            decoy_digests.insert(super::sealed_digest(&decoy));
+            // Like an anonymous permit, with the default expiry.
+            let permit = super::permit_object(
+                decoy,
+                None,
+                permit_expiry.as_ref(),
+                annotate_recipient_refs.then_some(reference),
+            );
+            unsigned_edition = unsigned_edition
+                .add_assertion(known_values::HAS_RECIPIENT, permit);
+        }
     }
 
-    let sskr_spec = parse_sskr_spec(&sskr)?;
+    if let Some(policy) = sskr_policy.as_ref() {
+        unsigned_edition = unsigned_edition
+            .add_assertion(super::SSKR_POLICY, policy.to_envelope());
+    }
 
-    let edition = Edition::new(club_xid, provenance_mark.clone(), content_env)
-        .context("content envelope must not contain assertions")?;
-    let (signed_edition, share_groups) = edition
-        .seal_with_permits(&recipient_permits, sskr_spec.clone(), &signing_keys)
-        .context("failed to compose edition")?;
+    let mut metadata = SignatureMetadata::new();
+    if let Some(title) = title.as_ref() {
+        metadata = metadata.with_assertion(known_values::NAME, title.as_str());
+    }
+    for note in &notes {
+        metadata = metadata.with_assertion(known_values::NOTE, note.as_str());
+    }
+    if embed_publisher_keys {
+        let public_keys = publisher_doc
+            .keys()
+            .iter()
+            .find(|key| key.public_keys().reference() == signing_key_ref)
+            .map(|key| key.public_keys().clone())
+            .context("signing key is missing from the publisher document")?;
+        metadata = metadata.with_assertion(super::PUBLISHER_KEYS, public_keys);
+    }
+    let signed_edition =
+        sign_edition(&unsigned_edition, &signing_keys, metadata);
+    let composed_permits = composed_permits(&unsigned_edition, &decoy_digests)?;
+
+    let edition_size = EditionSize::measure(&signed_edition);
+    if let Some(limit) = max_size
+        && edition_size.cbor_bytes > limit
+    {
+        edition_size.print();
+        bail!(
+            "edition is {} bytes, exceeding --max-size {limit}",
+            edition_size.cbor_bytes
+        );
+    }
 
-    let edition_ur = signed_edition.ur_string();
-    println!("{}", edition_ur);
+    let edition_ur = io::envelope_ur_string(&signed_edition);
+    let edition_digest = signed_edition.digest();
+    if !dry_run && !quiet {
+        for line in fragments.lines(&edition_ur)? {
+            println!("{}", line);
+        }
+    }
 
+    let mut share_urs: Vec<Vec<String>> = Vec::new();
     if let Some(groups) = share_groups {
-        for group in groups {
-            for share in group {
-                let ur = share.ur_string();
-                println!("{}", ur);
+        for (group_index, group) in groups.into_iter().enumerate() {
+            let name = sskr_policy
+                .as_ref()
+                .and_then(|policy| policy.groups.get(group_index))
+                .and_then(|group| group.name.as_deref());
+            let urs: Vec<String> = group
+                .into_iter()
+                .map(|share| match name {
+                    Some(name) => share.add_assertion(known_values::NAME, name),
+                    None => share,
+                })
+                .map(|share| share.ur_string())
+                .collect();
+            if !dry_run && !quiet {
+                for ur in &urs {
+                    println!("{}", ur);
+                }
             }
+            share_urs.push(urs);
         }
     }
 
-    Ok(())
+    let receipt_ur = if receipt.is_some() || out_dir.is_some() {
+        let sealed_edition = super::decode_edition(&unsigned_edition)
+            .context("composed edition could not be re-read for the receipt")?;
+        let receipt_recipients: Vec<ReceiptRecipient> = recipients
+            .iter()
+            .map(|descriptor| match descriptor.member_xid() {
+                Some(xid) => ReceiptRecipient::Member(xid),
+                None => ReceiptRecipient::Keys(
+                    descriptor.public_keys().reference().to_string(),
+                ),
+            })
+            .collect();
+        Some(
+            receipt::build_receipt(
+                &sealed_edition,
+                &receipt_recipients,
+                decoys,
+                &signing_keys,
+            )
+            .ur_string(),
+        )
+    } else {
+        None
+    };
+    if !dry_run {
+        if let (Some(path), Some(ur)) = (receipt.as_ref(), receipt_ur.as_ref())
+        {
+            output::write_lines(path, [ur])?;
+        }
+
+        qr::emit(&qr, &fragments, "edition", &edition_ur)?;
+        for (group_index, group) in share_urs.iter().enumerate() {
+            for (share_index, ur) in group.iter().enumerate() {
+                qr::emit(
+                    &qr,
+                    &fragments,
+                    &share_name(group_index, share_index),
+                    ur,
+                )?;
+            }
+        }
+
+        if let Some(dir) = out_dir.as_ref() {
+            write_out_dir(dir, &edition_ur, &share_urs, &recipients)?;
+            let json = serde_json::to_string_pretty(&composed_permits)
+                .context("failed to serialize permit manifest")?;
+            output::write_atomic(
+                &dir.join(PERMITS_FILE),
+                format!("{json}\n").as_bytes(),
+            )?;
+            if let Some(ur) = receipt_ur.as_ref() {
+                output::write_lines(&dir.join("receipt.ur"), [ur])?;
+            }
+        }
+    }
+
+    if summary {
+        eprintln!(
+            "Edition: seq {} for club {}",
+            provenance_mark.seq(),
+            club_xid
+        );
+        eprintln!(
+            "Provenance: {} resolution, dated {}",
+            super::resolution_label(provenance_mark.res()),
+            provenance_mark.date()
+        );
+        eprintln!("Signing key: {signing_key_ref}");
+        if decoys > 0 {
+            eprintln!("Decoy permits: {decoys}");
+        }
+        if let Some(date) = permit_expiry.as_ref() {
+            eprintln!("Permits expire: {date}");
+        }
+        if recipient_labels.is_empty() {
+            eprintln!("Recipients: none");
+        } else {
+            eprintln!(
+                "Recipients: {} ({} carried, {} new)",
+                recipient_labels.len(),
+                carried,
+                added
+            );
+            for label in &recipient_labels {
+                eprintln!("  - {label}");
+            }
+        }
+        for permit in &composed_permits {
+            let decoy = if permit.decoy { " (decoy)" } else { "" };
+            eprintln!("Permit {}{decoy}", permit.id.describe());
+        }
+        if let Some(title) = title.as_ref() {
+            eprintln!("Title: {title}");
+        }
+        for note in &notes {
+            eprintln!("Note: {note}");
+        }
+        let content_state = if supplied_key.is_some() {
+            "pre-encrypted (sealed the supplied content key)"
+        } else if recipient_labels.is_empty() && sskr_spec.is_none() {
+            "cleartext"
+        } else {
+            "encrypted"
+        };
+        eprintln!("Content: {content_state}");
+        match sskr_policy.as_ref() {
+            Some(policy) => eprintln!(
+                "SSKR: {} ({} share(s))",
+                policy.describe(),
+                share_urs.iter().map(Vec::len).sum::<usize>()
+            ),
+            None => eprintln!("SSKR: none"),
+        }
+        edition_size.print();
+        if dry_run {
+            eprintln!("Dry run: nothing emitted or written");
+        } else if let Some(dir) = out_dir.as_ref() {
+            eprintln!("Output: {}", dir.display());
+        }
+    }
+
+    if let (Some(path), Some((generator, secret))) =
+        (provenance_state.as_ref(), advanced_state.as_ref())
+        && !dry_run
+    {
+        let mut state = io::provenance_state_ur(generator)?;
+        if let Some(secret) = secret {
+            state = io::lock_ur(&state, secret)?;
+        }
+        output::write_secret(path, format!("{state}\n").as_bytes())
+            .context("failed to advance --provenance-state")?;
+    }
+    if let (Some(path), Some(file)) = (chain.as_ref(), chain_file.as_mut())
+        && !dry_run
+    {
+        file.push(&provenance_mark, Some(&edition_digest));
+        file.save(path)?;
+    }
+    drop(chain_lock);
+
+    let shares = share_urs
+        .into_iter()
+        .enumerate()
+        .flat_map(|(group_index, group)| {
+            group.into_iter().enumerate().map(move |(share_index, ur)| {
+                (share_name(group_index, share_index), ur)
+            })
+        })
+        .collect();
+    Ok(Composed {
+        club_xid,
+        seq: provenance_mark.seq(),
+        edition_ur,
+        shares,
+        recipients: recipients_manifest(&recipients),
+        permits: composed_permits,
+    })
+}
+
+/// The sealed permits of the unwrapped `inner` edition with their
+/// identifiers, marking those whose sealed digest is in `decoys`.
+fn composed_permits(
+    inner: &Envelope,
+    decoys: &HashSet<Digest>,
+) -> Result<Vec<ComposedPermit>> {
+    let recorded = super::permit_recipient_keys(inner)?;
+    let edition = super::decode_edition(inner)
+        .context("composed edition could not be re-read for its permits")?;
+    Ok(permits::sourced_permits(&edition)
+        .into_iter()
+        .map(|permit| {
+            let digest = super::sealed_digest(permit.sealed);
+            ComposedPermit {
+                id: PermitId::new(
+                    permit.position,
+                    permit.sealed,
+                    permit.holder,
+                ),
+                recipient_key: recorded.get(&digest).map(Reference::ref_hex),
+                decoy: decoys.contains(&digest),
+            }
+        })
+        .collect())
+}
+
+/// Append `descriptor` unless a recipient with the same public keys is
+/// already present. Returns whether it was added.
+fn push_unique(
+    recipients: &mut Vec<RecipientDescriptor>,
+    descriptor: RecipientDescriptor,
+) -> bool {
+    let reference = descriptor.public_keys().reference();
+    if recipients
+        .iter()
+        .any(|existing| existing.public_keys().reference() == reference)
+    {
+        return false;
+    }
+    recipients.push(descriptor);
+    true
+}
+
+fn write_out_dir(
+    dir: &Path,
+    edition_ur: &str,
+    share_urs: &[Vec<String>],
+    recipients: &[RecipientDescriptor],
+) -> Result<()> {
+    output::ensure_dir(dir)?;
+    output::write_lines(&dir.join("edition.ur"), [edition_ur])?;
+
+    for (group_index, group) in share_urs.iter().enumerate() {
+        for (share_index, ur) in group.iter().enumerate() {
+            let name = format!("{}.ur", share_name(group_index, share_index));
+            output::write_lines(&dir.join(name), [ur])?;
+        }
+    }
+
+    output::write_lines(
+        &dir.join(RECIPIENTS_FILE),
+        recipients_manifest(recipients),
+    )
+}
+
+/// Permit descriptor URs, one per recipient, for `--carry-permits`.
+fn recipients_manifest(recipients: &[RecipientDescriptor]) -> Vec<String> {
+    recipients
+        .iter()
+        .map(|descriptor| {
+            io::permit_descriptor_envelope(
+                descriptor.public_keys(),
+                descriptor.member_xid(),
+                descriptor.expires(),
+            )
+            .ur_string()
+        })
+        .collect()
+}
+
+/// Wrap and sign the inner edition envelope, attaching `metadata` to the
+/// signature when it carries any assertions so it is covered by the signer.
+pub fn sign_edition(
+    inner: &Envelope,
+    signing_keys: &PrivateKeys,
+    metadata: SignatureMetadata,
+) -> Envelope {
+    let metadata = metadata.has_assertions().then_some(metadata);
+    inner.wrap().add_signature_opt(signing_keys, None, metadata)
+}
+
+/// A sealed message indistinguishable from a real anonymous permit: a
+/// random key of the same size sealed to a throwaway recipient of the given
+/// scheme under the same AAD. Like an anonymous permit it names no holder. The
+/// throwaway recipient's key reference is returned so the decoy can carry a
+/// recipient key like a real permit.
+fn decoy_permit(
+    aad: &[u8],
+    scheme: EncapsulationScheme,
+) -> (SealedMessage, Reference) {
+    let (_, recipient) = keypair_opt(SignatureScheme::default(), scheme);
+    let payload = SymmetricKey::new().tagged_cbor().to_cbor_data();
+    (
+        SealedMessage::new_with_aad(payload, &recipient, Some(aad)),
+        recipient.reference(),
+    )
+}
+
+fn required(value: Option<String>, name: &str) -> Result<String> {
+    value.ok_or_else(|| {
+        anyhow!("--{name} is required (pass it or set '{name}' in --manifest)")
+    })
 }
 
-fn extract_signing_keys(doc: &XIDDocument) -> Result<PrivateKeys> {
-    if let Some(keys) = doc
-        .inception_key()
-        .and_then(|key| key.private_keys().cloned())
+pub fn share_name(group_index: usize, share_index: usize) -> String {
+    format!("sskr-group{}-share{}", group_index + 1, share_index + 1)
+}
+
+/// Pick the publisher's signing keys. With `key_ref`, only the key whose
+/// reference matches (full hex, short hex, or bytewords) is accepted;
+/// otherwise the inception key is preferred, then the first private key.
+pub fn select_signing_keys(
+    doc: &XIDDocument,
+    key_ref: Option<&str>,
+) -> Result<(PrivateKeys, Reference)> {
+    if let Some(wanted) = key_ref {
+        let wanted = normalize_reference(wanted);
+        let mut available = Vec::new();
+        for key in doc.keys() {
+            let Some(private_keys) = key.private_keys() else {
+                continue;
+            };
+            let reference = key.public_keys().reference();
+            if reference_matches(&reference, &wanted) {
+                return Ok((private_keys.clone(), reference));
+            }
+            available.push(reference.ref_hex_short());
+        }
+        if available.is_empty() {
+            bail!(
+                "publisher XID document must include private keys for signing"
+            );
+        }
+        bail!(
+            "no publisher signing key matches --signing-key-ref; available: {}",
+            available.join(", ")
+        );
+    }
+
+    if let Some(key) = doc.inception_key()
+        && let Some(keys) = key.private_keys()
     {
-        return Ok(keys);
+        return Ok((keys.clone(), key.public_keys().reference()));
     }
 
     for key in doc.keys() {
         if let Some(private_keys) = key.private_keys() {
-            return Ok(private_keys.clone());
+            return Ok((private_keys.clone(), key.public_keys().reference()));
         }
     }
 
     bail!("publisher XID document must include private keys for signing");
 }
 
-fn permit_from_descriptor(
-    descriptor: RecipientDescriptor,
-) -> (PublicKeyPermit, String) {
-    if let Some(member_xid) = descriptor.member_xid() {
-        let permit =
-            PublicKeyPermit::for_member(member_xid, descriptor.public_keys());
-        let label = member_xid.to_string();
-        (permit, label)
-    } else {
-        let public_keys = descriptor.public_keys();
-        let reference = public_keys.reference();
-        let permit = PublicKeyPermit::for_recipient(public_keys);
-        let label = reference.to_string();
-        (permit, label)
+fn normalize_reference(input: &str) -> String {
+    let lower = input.trim().to_ascii_lowercase();
+    let inner = lower
+        .strip_prefix("reference(")
+        .and_then(|rest| rest.strip_suffix(')'))
+        .unwrap_or(&lower);
+    inner
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn reference_matches(reference: &Reference, wanted: &str) -> bool {
+    wanted == reference.ref_hex()
+        || wanted == reference.ref_hex_short()
+        || wanted == normalize_reference(&reference.bytewords_identifier(None))
+}
+
+fn publisher_encryption_keys(doc: &XIDDocument) -> Result<PublicKeys> {
+    doc.keys()
+        .iter()
+        .find(|key| {
+            key.permissions().allow().iter().any(|privilege| {
+                matches!(privilege, Privilege::All | Privilege::Encrypt)
+            })
+        })
+        .map(|key| key.public_keys().clone())
+        .ok_or_else(|| {
+            anyhow!(
+                "publisher XID document has no key allowed to encrypt; --self-permit needs a key with the 'Encrypt' or 'All' permission"
+            )
+        })
+}
+
+/// How the summary names a recipient: its holder XID, or its key
+/// reference, with the key scheme.
+fn recipient_label(descriptor: &RecipientDescriptor) -> String {
+    let public_keys = descriptor.public_keys();
+    let scheme = io::recipient_key_scheme(public_keys);
+    match descriptor.member_xid() {
+        Some(member_xid) => format!("{member_xid} ({scheme})"),
+        None => format!("{} ({scheme})", public_keys.reference()),
     }
 }
 
-fn parse_sskr_spec(values: &[String]) -> Result<Option<SSKRSpec>> {
+/// Maximum number of groups, and of members per group, SSKR supports.
+const SSKR_MAX_SHARES: usize = 16;
+
+/// One group of an SSKR policy, optionally named.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct SskrGroupPolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub threshold: usize,
+    pub count: usize,
+}
+
+/// An SSKR policy as interpreted from the `--sskr` arguments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct SskrPolicy {
+    pub group_threshold: usize,
+    pub groups: Vec<SskrGroupPolicy>,
+}
+
+impl SskrPolicy {
+    /// Restate the interpreted policy, e.g. `2 of [board: 2of3, 3of5]`.
+    fn describe(&self) -> String {
+        let groups: Vec<String> = self
+            .groups
+            .iter()
+            .map(|group| match group.name.as_ref() {
+                Some(name) => {
+                    format!("{name}: {}of{}", group.threshold, group.count)
+                }
+                None => format!("{}of{}", group.threshold, group.count),
+            })
+            .collect();
+        format!("{} of [{}]", self.group_threshold, groups.join(", "))
+    }
+
+    /// Plain summary, e.g. `1 group, 2-of-3` or
+    /// `2 of 3 groups: board 2-of-3, 3-of-5, 1-of-1`.
+    pub fn summary(&self) -> String {
+        let groups: Vec<String> = self
+            .groups
+            .iter()
+            .map(|group| match group.name.as_ref() {
+                Some(name) => {
+                    format!("{name} {}-of-{}", group.threshold, group.count)
+                }
+                None => format!("{}-of-{}", group.threshold, group.count),
+            })
+            .collect();
+        match groups.as_slice() {
+            [group] => format!("1 group, {group}"),
+            _ => format!(
+                "{} of {} groups: {}",
+                self.group_threshold,
+                groups.len(),
+                groups.join(", ")
+            ),
+        }
+    }
+
+    /// The policy as recorded on the edition under `"sskr"`: the group
+    /// threshold, with a `"group"` assertion per group giving its 1-based
+    /// position, threshold, count, and `'name'` if it has one.
+    pub fn to_envelope(&self) -> Envelope {
+        self.groups.iter().enumerate().fold(
+            Envelope::new(self.group_threshold as u64),
+            |policy, (index, group)| {
+                let group = Envelope::new((index + 1) as u64)
+                    .add_assertion("threshold", group.threshold as u64)
+                    .add_assertion("count", group.count as u64)
+                    .add_optional_assertion(
+                        known_values::NAME,
+                        group.name.clone(),
+                    );
+                policy.add_assertion("group", group)
+            },
+        )
+    }
+
+    /// Read a policy recorded by `to_envelope`.
+    pub fn from_envelope(policy: &Envelope) -> Result<Self> {
+        let group_threshold = policy
+            .extract_subject::<u64>()
+            .context("SSKR policy has no group threshold")?
+            as usize;
+        let mut groups = Vec::new();
+        for group in policy.objects_for_predicate("group") {
+            let position = group
+                .extract_subject::<u64>()
+                .context("SSKR policy group has no position")?;
+            let threshold = group
+                .extract_object_for_predicate::<u64>("threshold")
+                .context("SSKR policy group has no threshold")?
+                as usize;
+            let count = group
+                .extract_object_for_predicate::<u64>("count")
+                .context("SSKR policy group has no count")?
+                as usize;
+            let name = group
+                .extract_optional_object_for_predicate::<String>(
+                    known_values::NAME,
+                )
+                .context("SSKR policy group name must be text")?;
+            groups.push((position, SskrGroupPolicy { name, threshold, count }));
+        }
+        groups.sort_by_key(|(position, _)| *position);
+        let policy = Self {
+            group_threshold,
+            groups: groups.into_iter().map(|(_, group)| group).collect(),
+        };
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let described = self.describe();
+        for (index, group) in self.groups.iter().enumerate() {
+            let label = match group.name.as_ref() {
+                Some(name) => format!("'{name}'"),
+                None => format!("{}", index + 1),
+            };
+            if group.count == 0 {
+                bail!(
+                    "SSKR group {label} has zero members (interpreted spec: {described})"
+                );
+            }
+            if group.threshold == 0 {
+                bail!(
+                    "SSKR group {label} has a zero threshold (interpreted spec: {described})"
+                );
+            }
+            if group.threshold > group.count {
+                bail!(
+                    "SSKR group {label} threshold {} exceeds its {} members (interpreted spec: {described})",
+                    group.threshold,
+                    group.count
+                );
+            }
+            if group.count > SSKR_MAX_SHARES {
+                bail!(
+                    "SSKR group {label} has {} members but at most {SSKR_MAX_SHARES} are supported (interpreted spec: {described})",
+                    group.count
+                );
+            }
+            if let Some(name) = group.name.as_ref()
+                && self.groups[..index]
+                    .iter()
+                    .any(|other| other.name.as_ref() == Some(name))
+            {
+                bail!(
+                    "SSKR group name '{name}' is used more than once (interpreted spec: {described})"
+                );
+            }
+        }
+        if self.groups.len() > SSKR_MAX_SHARES {
+            bail!(
+                "{} SSKR groups were given but at most {SSKR_MAX_SHARES} are supported (interpreted spec: {described})",
+                self.groups.len()
+            );
+        }
+        if self.group_threshold == 0 {
+            bail!(
+                "SSKR group threshold must be at least 1 (interpreted spec: {described})"
+            );
+        }
+        if self.group_threshold > self.groups.len() {
+            bail!(
+                "group threshold {} exceeds the {} groups provided (interpreted spec: {described})",
+                self.group_threshold,
+                self.groups.len()
+            );
+        }
+        Ok(())
+    }
+
+    fn to_spec(&self) -> Result<SSKRSpec> {
+        let mut group_specs = Vec::with_capacity(self.groups.len());
+        for group in &self.groups {
+            let spec = SSKRGroupSpec::new(group.threshold, group.count)
+                .map_err(|err| {
+                    anyhow!(
+                        "invalid SSKR group {}of{}: {err}",
+                        group.threshold,
+                        group.count
+                    )
+                })?;
+            group_specs.push(spec);
+        }
+        SSKRSpec::new(self.group_threshold, group_specs).map_err(|err| {
+            anyhow!("invalid SSKR specification {}: {err}", self.describe())
+        })
+    }
+}
+
+/// Parse `--sskr` values. Each value is a comma-separated list of groups
+/// (`2of3`, optionally named as `board:2of3`) and may end with `@N` to set
+/// the group threshold; `threshold=N` is accepted as an entry as well.
+fn parse_sskr_policy(values: &[String]) -> Result<Option<SskrPolicy>> {
     if values.is_empty() {
         return Ok(None);
     }
 
-    let mut group_specs: Vec<SSKRGroupSpec> = Vec::new();
+    let mut groups: Vec<SskrGroupPolicy> = Vec::new();
     let mut group_threshold: Option<usize> = None;
 
     for value in values {
-        for part in value.split(',') {
+        let mut body = value.trim();
+        if let Some((rest, suffix)) = body.rsplit_once('@') {
+            let threshold = parse_group_threshold(suffix)?;
+            set_group_threshold(&mut group_threshold, threshold)?;
+            body = rest;
+        }
+
+        for part in body.split(',') {
             let entry = part.trim();
             if entry.is_empty() {
                 continue;
@@ -156,52 +1326,819 @@ fn parse_sskr_spec(values: &[String]) -> Result<Option<SSKRSpec>> {
 
             if let Some((key, value)) = entry.split_once('=') {
                 let key = key.trim().to_ascii_lowercase();
-                let threshold_value = value.trim();
                 if matches!(
                     key.as_str(),
                     "threshold" | "group-threshold" | "group_threshold"
                 ) {
-                    let parsed = threshold_value
-                        .parse::<usize>()
-                        .map_err(|err| anyhow!("invalid SSKR group threshold '{threshold_value}': {err}"))?;
-                    group_threshold = Some(parsed);
+                    let threshold = parse_group_threshold(value)?;
+                    set_group_threshold(&mut group_threshold, threshold)?;
                 } else {
                     bail!("unrecognized SSKR option '{key}'");
                 }
                 continue;
             }
 
-            let spec = parse_group_spec(entry)?;
-            group_specs.push(spec);
+            groups.push(parse_group_spec(entry)?);
         }
     }
 
-    if group_specs.is_empty() {
+    if groups.is_empty() {
         bail!(
             "at least one SSKR group specification is required when --sskr is provided"
         );
     }
 
-    let threshold = group_threshold.unwrap_or(1);
-    let spec = SSKRSpec::new(threshold, group_specs)
-        .map_err(|err| anyhow!("invalid SSKR specification: {err}"))?;
-    Ok(Some(spec))
+    let policy = SskrPolicy {
+        group_threshold: group_threshold.unwrap_or(1),
+        groups,
+    };
+    policy.validate()?;
+    Ok(Some(policy))
+}
+
+fn parse_group_threshold(value: &str) -> Result<usize> {
+    let value = value.trim();
+    value
+        .parse::<usize>()
+        .map_err(|err| anyhow!("invalid SSKR group threshold '{value}': {err}"))
 }
 
-fn parse_group_spec(input: &str) -> Result<SSKRGroupSpec> {
-    let cleaned = input.replace(' ', "").to_ascii_lowercase();
+fn set_group_threshold(slot: &mut Option<usize>, value: usize) -> Result<()> {
+    if let Some(existing) = slot {
+        bail!(
+            "SSKR group threshold given more than once ({existing} and {value})"
+        );
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn parse_group_spec(input: &str) -> Result<SskrGroupPolicy> {
+    let (name, body) = match input.split_once(':') {
+        Some((name, body)) => {
+            let name = name.trim();
+            if name.is_empty() {
+                bail!("SSKR group '{input}' has an empty name before ':'");
+            }
+            (Some(name.to_owned()), body)
+        }
+        None => (None, input),
+    };
+
+    let cleaned = body.replace(' ', "").to_ascii_lowercase();
     let (threshold_str, count_str) =
         cleaned.split_once("of").ok_or_else(|| {
-            anyhow!("SSKR group spec '{input}' must be in the form MofN")
+            anyhow!("SSKR group spec '{input}' must be in the form MofN or NAME:MofN")
         })?;
-    let member_threshold = threshold_str.parse::<usize>().map_err(|err| {
+    let threshold = threshold_str.parse::<usize>().map_err(|err| {
         anyhow!("invalid SSKR group threshold '{threshold_str}': {err}")
     })?;
-    let member_count = count_str.parse::<usize>().map_err(|err| {
+    let count = count_str.parse::<usize>().map_err(|err| {
         anyhow!("invalid SSKR group count '{count_str}': {err}")
     })?;
 
-    let spec = SSKRGroupSpec::new(member_threshold, member_count)
-        .map_err(|err| anyhow!("invalid SSKR group spec '{input}': {err}"))?;
-    Ok(spec)
+    Ok(SskrGroupPolicy { name, threshold, count })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dcbor::Date;
+
+    use super::*;
+    use crate::{
+        cmd::content::decrypt,
+        test_support::{
+            self, compose_args, compose_for, marks, private_ur, xid_document,
+        },
+    };
+
+    fn parse(values: &[&str]) -> Result<SskrPolicy> {
+        let values: Vec<String> =
+            values.iter().map(|value| value.to_string()).collect();
+        Ok(parse_sskr_policy(&values)?.expect("policy"))
+    }
+
+    fn group(
+        name: Option<&str>,
+        threshold: usize,
+        count: usize,
+    ) -> SskrGroupPolicy {
+        SskrGroupPolicy { name: name.map(str::to_owned), threshold, count }
+    }
+
+    #[test]
+    fn parses_plain_groups() {
+        let policy = parse(&["2of3", "3of5"]).unwrap();
+        assert_eq!(policy.group_threshold, 1);
+        assert_eq!(policy.groups, vec![group(None, 2, 3), group(None, 3, 5)]);
+    }
+
+    #[test]
+    fn parses_named_groups_with_suffix_threshold() {
+        let policy = parse(&[" board : 2 OF 3 , staff:3of5 @ 2 "]).unwrap();
+        assert_eq!(policy.group_threshold, 2);
+        assert_eq!(
+            policy.groups,
+            vec![group(Some("board"), 2, 3), group(Some("staff"), 3, 5)]
+        );
+        assert_eq!(policy.describe(), "2 of [board: 2of3, staff: 3of5]");
+    }
+
+    #[test]
+    fn parses_threshold_option() {
+        let policy = parse(&["2of3,2of3,Threshold=2"]).unwrap();
+        assert_eq!(policy.group_threshold, 2);
+        assert_eq!(policy.groups.len(), 2);
+    }
+
+    #[test]
+    fn rejects_duplicate_threshold() {
+        let err = parse(&["2of3,2of3,threshold=1", "threshold=2"]).unwrap_err();
+        assert!(err.to_string().contains("more than once"));
+        assert!(parse(&["2of3,2of3@1,threshold=2"]).is_err());
+    }
+
+    #[test]
+    fn rejects_excessive_group_threshold() {
+        let err = parse(&["2of3,3of5,threshold=5"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "group threshold 5 exceeds the 2 groups provided (interpreted spec: 5 of [2of3, 3of5])"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_groups() {
+        assert!(
+            parse(&["0of0"])
+                .unwrap_err()
+                .to_string()
+                .contains("zero members")
+        );
+        assert!(
+            parse(&["0of3"])
+                .unwrap_err()
+                .to_string()
+                .contains("zero threshold")
+        );
+        assert!(parse(&["4of3"]).is_err());
+        assert!(parse(&["2of17"]).is_err());
+        assert!(parse(&["a:2of3,a:2of3"]).is_err());
+        assert!(parse(&[":2of3"]).is_err());
+        assert!(parse(&["two-of-three"]).is_err());
+        assert!(parse(&["threshold=2"]).is_err());
+    }
+
+    fn two_key_document() -> (XIDDocument, PublicKeys, PublicKeys) {
+        use bc_components::{PrivateKeyBase, PublicKeysProvider};
+        use bc_xid::{Key, XIDGenesisMarkOptions, XIDInceptionKeyOptions};
+
+        let current = PrivateKeyBase::new();
+        let standby = PrivateKeyBase::new();
+        let current_public = current.public_keys();
+        let standby_public = standby.public_keys();
+        let mut doc = XIDDocument::new(
+            XIDInceptionKeyOptions::PrivateKeyBase(current),
+            XIDGenesisMarkOptions::None,
+        );
+        doc.add_key(Key::new_with_private_key_base(standby))
+            .unwrap();
+        (doc, current_public, standby_public)
+    }
+
+    #[test]
+    fn signing_key_ref_selects_the_matching_key() {
+        let (doc, current, standby) = two_key_document();
+        let wanted = standby.reference();
+        for spec in [
+            wanted.ref_hex(),
+            wanted.ref_hex_short().to_uppercase(),
+            wanted.bytewords_identifier(None).replace(' ', "-"),
+            wanted.to_string(),
+        ] {
+            let (keys, reference) =
+                select_signing_keys(&doc, Some(&spec)).unwrap();
+            assert_eq!(reference, wanted);
+            let signed = Envelope::new("edition").sign(&keys);
+            assert!(signed.verify(&standby).is_ok());
+            assert!(signed.verify(&current).is_err());
+        }
+    }
+
+    #[test]
+    fn signing_key_ref_lists_available_keys() {
+        let (doc, current, standby) = two_key_document();
+        let err = select_signing_keys(&doc, Some("00000000")).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains(&current.reference().ref_hex_short()));
+        assert!(message.contains(&standby.reference().ref_hex_short()));
+
+        let (_, reference) = select_signing_keys(&doc, None).unwrap();
+        assert_eq!(reference, current.reference());
+    }
+
+    #[test]
+    fn carried_permits_open_the_next_edition() {
+        let (publisher, alice, bob) =
+            (xid_document(), xid_document(), xid_document());
+        let marks = marks(2);
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-compose-carry-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let out_dir = dir.display().to_string();
+        let recipients = dir.join(RECIPIENTS_FILE).display().to_string();
+
+        let genesis = compose_for(
+            &publisher,
+            &marks[0],
+            &[&alice],
+            &["--out-dir", &out_dir],
+        );
+        let next = compose_for(
+            &publisher,
+            &marks[1],
+            &[&bob],
+            &["--previous", &genesis, "--carry-permits", &recipients],
+        );
+
+        let decrypt = |edition: &str, identity: &XIDDocument| {
+            let identity = private_ur(identity);
+            decrypt::run(&test_support::parse::<decrypt::CommandArgs>(&[
+                "clubs",
+                "--edition",
+                edition,
+                "--identity",
+                &identity,
+            ]))
+            .map(|content| content.extract_subject::<String>().unwrap())
+        };
+        assert_eq!(decrypt(&genesis, &alice).unwrap(), "issue 0");
+        assert_eq!(decrypt(&next, &alice).unwrap(), "issue 1");
+        assert_eq!(decrypt(&next, &bob).unwrap(), "issue 1");
+        assert!(decrypt(&genesis, &bob).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_a_mark_dated_before_the_previous_edition() {
+        use provenance_mark::{
+            ProvenanceMarkGenerator, ProvenanceMarkResolution,
+        };
+
+        let publisher = xid_document();
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        let genesis_mark = generator.next(
+            Date::from_string("2025-06-02T00:00:00Z").unwrap(),
+            None::<String>,
+        );
+        let earlier_mark = generator.next(
+            Date::from_string("2025-06-01T00:00:00Z").unwrap(),
+            None::<String>,
+        );
+        let genesis = compose_for(&publisher, &genesis_mark, &[], &[]);
+
+        let compose = |extra: &[&str]| {
+            let mut argv = vec!["--previous", genesis.as_str()];
+            argv.extend(extra);
+            run(compose_args(&publisher, &earlier_mark, &argv))
+        };
+        let err = compose(&[]).err().unwrap();
+        assert!(
+            err.to_string().contains("pass --allow-date-regression"),
+            "{err:#}"
+        );
+        let composed = compose(&["--allow-date-regression"]).unwrap();
+        assert_eq!(composed.seq, 1);
+    }
+
+    #[test]
+    fn pre_encrypted_content_opens_with_the_sealed_permits() {
+        use bc_components::{
+            PrivateKeyBase, PrivateKeysProvider, PublicKeysProvider,
+        };
+
+        let (publisher, member) = (xid_document(), xid_document());
+        let anonymous = PrivateKeyBase::new();
+        let key = SymmetricKey::new();
+        let content = Envelope::new("issue one")
+            .wrap()
+            .encrypt_subject(&key)
+            .unwrap()
+            .ur_string();
+        let key_ur = key.ur_string();
+        let anonymous_ur = anonymous.public_keys().ur_string();
+        let edition = compose_for(
+            &publisher,
+            &test_support::genesis_mark(),
+            &[&member],
+            &[
+                "--permit",
+                &anonymous_ur,
+                "--content",
+                &content,
+                "--pre-encrypted",
+                "--content-key",
+                &key_ur,
+            ],
+        );
+
+        let decrypt = |identity: &str| {
+            decrypt::run(&test_support::parse::<decrypt::CommandArgs>(&[
+                "clubs",
+                "--edition",
+                &edition,
+                "--identity",
+                identity,
+            ]))
+            .unwrap()
+            .extract_subject::<String>()
+            .unwrap()
+        };
+        assert_eq!(decrypt(&private_ur(&member)), "issue one");
+        assert_eq!(decrypt(&anonymous.private_keys().ur_string()), "issue one");
+
+        let inner = io::parse_envelope(&edition).unwrap().try_unwrap().unwrap();
+        let holders: Vec<Option<XID>> =
+            permits::sealed_permits(
+                &crate::cmd::edition::decode_edition(&inner).unwrap(),
+            )
+                .into_iter()
+                .map(|(_, holder)| holder)
+                .collect();
+        assert_eq!(holders.len(), 2);
+        assert!(holders.contains(&Some(member.xid())));
+        assert!(holders.contains(&None));
+    }
+
+    #[test]
+    fn self_permit_lets_the_publisher_decrypt() {
+        let (publisher, member) = (xid_document(), xid_document());
+        let edition = compose_for(
+            &publisher,
+            &test_support::genesis_mark(),
+            &[&member],
+            &["--self-permit"],
+        );
+        let identity = private_ur(&publisher);
+        let content =
+            decrypt::run(&test_support::parse::<decrypt::CommandArgs>(&[
+                "clubs",
+                "--edition",
+                &edition,
+                "--identity",
+                &identity,
+            ]))
+            .unwrap();
+        assert_eq!(content.extract_subject::<String>().unwrap(), "issue 0");
+    }
+
+    #[test]
+    fn sskr_shares_carry_only_the_content_key() {
+        let publisher = xid_document();
+        let marks = marks(2);
+        let [first, second] = [&marks[0], &marks[1]].map(|mark| {
+            run(compose_args(&publisher, mark, &["--sskr", "2of3"])).unwrap()
+        });
+        assert_eq!(first.shares.len(), 3);
+        for (_, share) in &first.shares {
+            let share = Envelope::from_ur_string(share).unwrap();
+            assert!(share.subject().is_elided());
+        }
+        let open = |edition: &str, shares: &[(String, String)]| {
+            let mut argv = vec!["clubs", "--edition", edition];
+            for (_, share) in shares.iter().take(2) {
+                argv.extend(["--sskr", share.as_str()]);
+            }
+            decrypt::run(&test_support::parse::<decrypt::CommandArgs>(&argv))
+        };
+
+        let content = open(&first.edition_ur, &first.shares).unwrap();
+        assert_eq!(content.extract_subject::<String>().unwrap(), "issue 0");
+        let err = open(&first.edition_ur, &second.shares).err().unwrap();
+        assert!(
+            err.to_string().contains("not split from this edition's content"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn content_under_the_size_limit_round_trips_through_decrypt() {
+        let (publisher, member) = (xid_document(), xid_document());
+        let payload: Vec<u8> = (0..1 << 20).map(|i| (i % 251) as u8).collect();
+        let envelope = Envelope::new(CBOR::to_byte_string(&payload));
+        let content = envelope.ur_string();
+        let compose = |limit: &str| {
+            let member = test_support::public_ur(&member);
+            run(compose_args(
+                &publisher,
+                &test_support::genesis_mark(),
+                &[
+                    "--content",
+                    &content,
+                    "--permit",
+                    &member,
+                    "--max-content-size",
+                    limit,
+                ],
+            ))
+        };
+
+        let err = compose("1000000").err().unwrap();
+        assert!(
+            format!("{err:#}").contains("exceeding the limit of 1000000 bytes"),
+            "{err:#}"
+        );
+        let composed = compose("2000000").unwrap();
+        let identity = private_ur(&member);
+        let decrypted =
+            decrypt::run(&test_support::parse::<decrypt::CommandArgs>(&[
+                "clubs",
+                "--edition",
+                &composed.edition_ur,
+                "--identity",
+                &identity,
+            ]))
+            .unwrap();
+        assert!(decrypted.is_identical_to(&envelope));
+    }
+
+    /// Synthetic content of `size` bytes, written as an envelope UR to a file
+    /// so compose reads it from `--content @path` as it would a real video.
+    fn large_content(size: usize, name: &str) -> (Envelope, PathBuf) {
+        let payload: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+        let envelope = Envelope::new(CBOR::to_byte_string(&payload));
+        let path = std::env::temp_dir()
+            .join(format!("clubs-{name}-{}.ur", std::process::id()));
+        std::fs::write(&path, envelope.ur_string()).unwrap();
+        (envelope, path)
+    }
+
+    /// Compose `content` for `member` with a 2-of-3 SSKR split, keeping the
+    /// edition UR off stdout.
+    fn compose_large(
+        publisher: &XIDDocument,
+        member: &XIDDocument,
+        content: &Path,
+    ) -> Composed {
+        let content = format!("@{}", content.display());
+        let member = test_support::public_ur(member);
+        let mut args = compose_args(
+            publisher,
+            &test_support::genesis_mark(),
+            &["--content", &content, "--permit", &member, "--sskr", "2of3"],
+        );
+        args.quiet = true;
+        run(args).unwrap()
+    }
+
+    #[test]
+    #[ignore = "composes 256 MiB of content; run with --release -- --ignored"]
+    fn large_content_round_trips_through_decrypt() {
+        test_support::register_tags();
+        let (publisher, member) = (xid_document(), xid_document());
+        let (envelope, path) = large_content(256 << 20, "large-round-trip");
+        let composed = compose_large(&publisher, &member, &path);
+        std::fs::remove_file(&path).unwrap();
+
+        let identity = private_ur(&member);
+        let mut by_shares = vec!["clubs", "--edition", &composed.edition_ur];
+        for (_, share) in composed.shares.iter().take(2) {
+            by_shares.extend(["--sskr", share.as_str()]);
+        }
+        let by_identity = [
+            "clubs",
+            "--edition",
+            &composed.edition_ur,
+            "--identity",
+            &identity,
+        ];
+        for argv in [&by_shares[..], &by_identity[..]] {
+            let decrypted =
+                decrypt::run(&test_support::parse::<decrypt::CommandArgs>(argv))
+                    .unwrap();
+            assert!(decrypted.is_identical_to(&envelope));
+        }
+    }
+
+    /// This process's peak resident set size in MiB, from `VmHWM`.
+    #[cfg(target_os = "linux")]
+    fn peak_rss_mib() -> u64 {
+        let status = std::fs::read_to_string("/proc/self/status").unwrap();
+        let kib: u64 = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmHWM:"))
+            .and_then(|value| value.trim_end_matches("kB").trim().parse().ok())
+            .unwrap();
+        kib >> 10
+    }
+
+    /// Peak memory while composing 256 MiB of content with a permit and a
+    /// 2-of-3 SSKR split. Run it alone so no other test shares the process:
+    /// `cargo test --release peak_rss -- --ignored --nocapture`.
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[ignore = "benchmark; run alone with --release -- --ignored --nocapture"]
+    fn peak_rss_composing_large_content() {
+        const CONTENT_MIB: usize = 256;
+        test_support::register_tags();
+        let (publisher, member) = (xid_document(), xid_document());
+        let (envelope, path) = large_content(CONTENT_MIB << 20, "peak-rss");
+        drop(envelope);
+        // Reset the peak so it covers compose alone.
+        std::fs::write("/proc/self/clear_refs", "5").unwrap();
+        let before = peak_rss_mib();
+        let composed = compose_large(&publisher, &member, &path);
+        let peak = peak_rss_mib();
+        std::fs::remove_file(&path).unwrap();
+        let shares: usize =
+            composed.shares.iter().map(|(_, share)| share.len()).sum();
+        println!(
+            "composing {CONTENT_MIB} MiB: peak RSS {peak} MiB ({} MiB above the baseline), edition UR {} MiB, SSKR shares {shares} bytes",
+            peak - before,
+            composed.edition_ur.len() >> 20,
+        );
+        // The content, its CBOR, and the edition UR text at twice the size,
+        // with room for the allocator.
+        assert!(peak - before < 6 * CONTENT_MIB as u64);
+    }
+
+    #[test]
+    fn workspace_defaults_turn_on_self_permit() {
+        use crate::workspace::ClubConfig;
+
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-compose-self-permit-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut config =
+            ClubConfig::new(xid_document().xid(), "2025-01-01".into(), false);
+        config.defaults.self_permit = true;
+        let workspace = Workspace::create(&dir, config).unwrap();
+
+        let args: CommandArgs = test_support::parse(&["clubs"]);
+        assert!(!args.self_permit && !args.no_self_permit);
+        let args = apply_workspace(&workspace, args).unwrap();
+        assert!(args.self_permit);
+        let args: CommandArgs =
+            test_support::parse(&["clubs", "--no-self-permit"]);
+        let args = apply_workspace(&workspace, args).unwrap();
+        assert!(!args.self_permit);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn permits_carry_holder_expiry_and_recipient_key_as_sealed() {
+        use bc_components::{PrivateKeyBase, PublicKeysProvider};
+
+        let publisher = xid_document();
+        let (member_keys, anonymous_keys, lapsed_keys) = (
+            PrivateKeyBase::new(),
+            PrivateKeyBase::new(),
+            PrivateKeyBase::new(),
+        );
+        let member = test_support::xid_document_with(&member_keys);
+        let member_expiry = io::parse_date("2026-06-01T00:00:00Z").unwrap();
+        let lapsed_expiry = io::parse_date("2025-01-01T00:00:00Z").unwrap();
+        let descriptor = |keys: &PrivateKeyBase, holder, expires| {
+            io::permit_descriptor_envelope(&keys.public_keys(), holder, expires)
+                .ur_string()
+        };
+        let member_permit =
+            descriptor(&member_keys, Some(member.xid()), Some(&member_expiry));
+        let anonymous_permit = descriptor(&anonymous_keys, None, None);
+        // An expiry no longer needs a holder to be matched by.
+        let lapsed_permit =
+            descriptor(&lapsed_keys, None, Some(&lapsed_expiry));
+        let composed = run(compose_args(
+            &publisher,
+            &test_support::genesis_mark(),
+            &[
+                "--permit",
+                &member_permit,
+                "--permit",
+                &anonymous_permit,
+                "--permit",
+                &lapsed_permit,
+                "--permit-expiry",
+                "2027-01-01T00:00:00Z",
+                "--annotate-recipient-refs",
+            ],
+        ))
+        .unwrap();
+
+        let inner = io::parse_envelope(&composed.edition_ur)
+            .unwrap()
+            .try_unwrap()
+            .unwrap();
+        let recorded =
+            crate::cmd::edition::permit_recipient_keys(&inner).unwrap();
+        let mut found: Vec<(Option<XID>, String, String)> = inner
+            .objects_for_predicate(known_values::HAS_RECIPIENT)
+            .into_iter()
+            .map(|permit| {
+                let sealed: SealedMessage = permit.extract_subject().unwrap();
+                let holder = permit
+                    .extract_optional_object_for_predicate::<XID>(
+                        known_values::HOLDER,
+                    )
+                    .unwrap();
+                let expires = permit
+                    .extract_object_for_predicate::<Date>(io::PERMIT_EXPIRES)
+                    .unwrap();
+                let reference = recorded
+                    [&crate::cmd::edition::sealed_digest(&sealed)]
+                    .ref_hex();
+                (holder, expires.to_string(), reference)
+            })
+            .collect();
+        found.sort_by_key(|(_, expires, _)| expires.clone());
+        assert_eq!(
+            found,
+            [
+                (
+                    None,
+                    lapsed_expiry.to_string(),
+                    lapsed_keys.public_keys().reference().ref_hex()
+                ),
+                (
+                    Some(member.xid()),
+                    member_expiry.to_string(),
+                    member_keys.public_keys().reference().ref_hex()
+                ),
+                (
+                    None,
+                    io::parse_date("2027-01-01T00:00:00Z").unwrap().to_string(),
+                    anonymous_keys.public_keys().reference().ref_hex()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn permits_are_sealed_under_the_library_edition_id() {
+        use bc_components::{PrivateKeyBase, PublicKeysProvider};
+
+        let publisher = xid_document();
+        let member = PrivateKeyBase::new().public_keys();
+        let mark = test_support::genesis_mark();
+        let composed = run(compose_args(
+            &publisher,
+            &mark,
+            &["--permit", &member.ur_string(), "--pad-permits", "2"],
+        ))
+        .unwrap();
+        let inner = io::parse_envelope(&composed.edition_ur)
+            .unwrap()
+            .try_unwrap()
+            .unwrap();
+        let edition = crate::cmd::edition::decode_edition(&inner).unwrap();
+
+        let (signer, _) = select_signing_keys(&publisher, None).unwrap();
+        let (sealed, _) =
+            Edition::new(publisher.xid(), mark, Envelope::new("issue 0"))
+                .unwrap()
+                .seal_with_permits(
+                    &[PublicKeyPermit::new(member)],
+                    None,
+                    &signer,
+                )
+                .unwrap();
+        let library = crate::cmd::edition::decode_edition(
+            &sealed.try_unwrap().unwrap(),
+        )
+        .unwrap();
+        let expected = crate::cmd::edition::sealed_aad(
+            permits::sealed_permits(&library)[0].0,
+        );
+        let permits = permits::sealed_permits(&edition);
+        assert_eq!(permits.len(), 2);
+        for (permit, _) in permits {
+            assert_eq!(crate::cmd::edition::sealed_aad(permit), expected);
+        }
+    }
+
+    #[test]
+    fn decoys_look_like_anonymous_permits() {
+        use bc_components::{PrivateKeyBase, PublicKeysProvider};
+
+        let (publisher, member) = (xid_document(), xid_document());
+        let anonymous = PrivateKeyBase::new().public_keys().ur_string();
+        let compose = |pad: &str| {
+            let member = test_support::public_ur(&member);
+            run(compose_args(
+                &publisher,
+                &test_support::genesis_mark(),
+                &[
+                    "--permit",
+                    &member,
+                    "--permit",
+                    &anonymous,
+                    "--pad-permits",
+                    pad,
+                ],
+            ))
+        };
+        let err = compose("1").err().unwrap();
+        assert!(err.to_string().contains("smaller than the 2 real permits"));
+
+        let composed = compose("5").unwrap();
+        let inner = io::parse_envelope(&composed.edition_ur)
+            .unwrap()
+            .try_unwrap()
+            .unwrap();
+        let edition = crate::cmd::edition::decode_edition(&inner).unwrap();
+        let sealed: HashMap<String, (SealedMessage, Option<XID>)> =
+            permits::sealed_permits(&edition)
+                .into_iter()
+                .map(|(sealed, holder)| {
+                    (
+                        crate::cmd::edition::sealed_digest(sealed).hex(),
+                        (sealed.clone(), holder),
+                    )
+                })
+                .collect();
+        assert_eq!(sealed.len(), 5);
+        let (decoys, real): (Vec<_>, Vec<_>) =
+            composed.permits.iter().partition(|permit| permit.decoy);
+        assert_eq!((decoys.len(), real.len()), (3, 2));
+
+        let anonymous_real = real
+            .iter()
+            .map(|permit| &sealed[&permit.id.sealed_digest])
+            .find(|(_, holder)| holder.is_none())
+            .map(|(sealed, _)| sealed)
+            .unwrap();
+        for decoy in &decoys {
+            let (message, holder) = &sealed[&decoy.id.sealed_digest];
+            assert_eq!(*holder, None);
+            assert_eq!(decoy.id.holder_xid, None);
+            assert_eq!(
+                message.encapsulation_scheme(),
+                anonymous_real.encapsulation_scheme()
+            );
+            assert_eq!(
+                crate::cmd::edition::sealed_aad(message),
+                crate::cmd::edition::sealed_aad(anonymous_real)
+            );
+            assert_eq!(
+                message.to_cbor_data().len(),
+                anonymous_real.to_cbor_data().len()
+            );
+        }
+
+        let identity = private_ur(&member);
+        let content =
+            decrypt::run(&test_support::parse::<decrypt::CommandArgs>(&[
+                "clubs",
+                "--edition",
+                &composed.edition_ur,
+                "--identity",
+                &identity,
+            ]))
+            .unwrap();
+        assert_eq!(content.extract_subject::<String>().unwrap(), "issue 0");
+    }
+
+    #[test]
+    fn genesis_and_previous_combinations() {
+        let publisher = xid_document();
+        let marks = marks(3);
+        let genesis = compose_for(&publisher, &marks[0], &[], &[]);
+        let other_genesis =
+            compose_for(&publisher, &test_support::genesis_mark(), &[], &[]);
+        // (mark, --genesis, --previous, --strict, accepted)
+        let cases: [(usize, bool, Option<&str>, bool, bool); 9] = [
+            (0, false, None, false, false),
+            (0, true, None, false, true),
+            (0, true, None, true, true),
+            (0, true, Some(&other_genesis), false, false),
+            (1, false, None, false, true),
+            (1, false, None, true, false),
+            (1, false, Some(&genesis), false, true),
+            (1, false, Some(&genesis), true, true),
+            (2, false, Some(&genesis), false, false),
+        ];
+        for (index, genesis_flag, previous, strict, accepted) in cases {
+            let mut extra = Vec::new();
+            if let Some(previous) = previous {
+                extra.extend(["--previous", previous]);
+            }
+            if strict {
+                extra.push("--strict");
+            }
+            let mut args = compose_args(&publisher, &marks[index], &extra);
+            args.genesis = genesis_flag;
+            let outcome = run(args);
+            assert_eq!(
+                outcome.is_ok(),
+                accepted,
+                "seq {index}, --genesis {genesis_flag}, --previous {}, --strict {strict}: {:?}",
+                previous.is_some(),
+                outcome.err()
+            );
+        }
+    }
 }
@@ -0,0 +1,377 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result};
+use bc_components::XID;
+use bc_envelope::prelude::*;
+use clap::Args;
+use clubs::edition::Edition;
+use serde::Serialize;
+
+use super::inspect;
+use crate::{
+    cmd::{ExpectationFailed, VerificationFailed},
+    directory::MemberDirectory,
+    io::{self, RecipientDescriptor},
+    output::{self, OutputFormat},
+};
+
+/// Compare two editions structurally.
+#[derive(Debug, Args)]
+pub struct CommandArgs {
+    /// Edition to compare against, typically the previous one.
+    #[arg(long, value_name = "UR")]
+    pub base: String,
+    /// Edition to compare.
+    #[arg(long, value_name = "UR")]
+    pub new: String,
+    /// Publisher descriptor (XID document or public-keys UR); when given,
+    /// both editions must verify against it (exit status 4 otherwise).
+    #[arg(long, value_name = "UR")]
+    pub publisher: Option<String>,
+    /// Fail (exit status 3) when the editions differ, instead of when they
+    /// cannot be compared.
+    #[arg(long)]
+    pub fail_on_change: bool,
+    /// Report format. `json` prints the diff as one object on stdout.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+pub fn exec(args: CommandArgs) -> Result<()> {
+    let verifier = match args.publisher.as_ref() {
+        Some(spec) => Some(
+            io::parse_recipient_descriptor(spec)
+                .context("failed to parse publisher input")?,
+        ),
+        None => None,
+    };
+    let base = LoadedEdition::load(&args.base, "base", verifier.as_ref())?;
+    let new = LoadedEdition::load(&args.new, "new", verifier.as_ref())?;
+    let diff = EditionDiff::between(&base, &new);
+
+    match args.format {
+        OutputFormat::Text => diff.print(&base, &new),
+        OutputFormat::Json => output::print_json(&diff)?,
+    }
+    if args.fail_on_change && diff.changed {
+        return Err(ExpectationFailed("editions differ".to_string()).into());
+    }
+    Ok(())
+}
+
+/// An edition together with the envelope it arrived in, whose signatures
+/// carry the metadata.
+struct LoadedEdition {
+    envelope: Envelope,
+    edition: Edition,
+}
+
+impl LoadedEdition {
+    fn load(
+        spec: &str,
+        label: &str,
+        verifier: Option<&RecipientDescriptor>,
+    ) -> Result<Self> {
+        let envelope = io::parse_envelope(spec)
+            .with_context(|| format!("failed to parse {label} edition"))?;
+        let inner = match verifier {
+            Some(descriptor) => {
+                envelope.verify(descriptor.public_keys()).map_err(|err| {
+                    VerificationFailed(format!(
+                        "failed to verify {label} edition signature: {err}"
+                    ))
+                })?
+            }
+            None => inspect::edition_payload(&envelope)?.0,
+        };
+        let edition = super::decode_edition(&inner).with_context(|| {
+            format!("{label} edition is not a valid club edition")
+        })?;
+        Ok(Self { envelope, edition })
+    }
+
+    /// Permits keyed by holder XID when annotated, otherwise by sealed
+    /// digest; re-sealed anonymous permits therefore always differ.
+    fn permits(&self) -> BTreeMap<String, PermitChange> {
+        super::permits::sealed_permits(&self.edition)
+            .into_iter()
+            .map(|(sealed, holder)| {
+                let change = PermitChange {
+                    holder,
+                    sealed_digest: super::sealed_digest(sealed),
+                };
+                (change.key(), change)
+            })
+            .collect()
+    }
+
+    /// Signature metadata assertions in envelope notation.
+    fn metadata(&self) -> BTreeSet<String> {
+        inspect::signature_metadata(&self.envelope)
+            .iter()
+            .flat_map(|meta| meta.assertions())
+            .map(|assertion| assertion.format_flat())
+            .collect()
+    }
+}
+
+/// Structural differences between a base edition and a new one.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct EditionDiff {
+    changed: bool,
+    club_changed: bool,
+    content_changed: bool,
+    same_chain: bool,
+    /// New seq minus base seq.
+    seq_delta: i64,
+    permits_added: Vec<PermitChange>,
+    permits_removed: Vec<PermitChange>,
+    permits_kept: usize,
+    metadata_added: Vec<String>,
+    metadata_removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct PermitChange {
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_holder"
+    )]
+    holder: Option<XID>,
+    #[serde(serialize_with = "serialize_digest")]
+    sealed_digest: Digest,
+}
+
+fn serialize_holder<S: serde::Serializer>(
+    holder: &Option<XID>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&holder.expect("skipped when absent"))
+}
+
+fn serialize_digest<S: serde::Serializer>(
+    digest: &Digest,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&digest.hex())
+}
+
+impl PermitChange {
+    fn key(&self) -> String {
+        match self.holder {
+            Some(xid) => format!("xid:{xid}"),
+            None => format!("sealed:{}", self.sealed_digest.hex()),
+        }
+    }
+
+    fn label(&self) -> String {
+        MemberDirectory::holder_label(
+            None,
+            self.holder.as_ref(),
+            &self.sealed_digest,
+        )
+    }
+}
+
+impl EditionDiff {
+    fn between(base: &LoadedEdition, new: &LoadedEdition) -> Self {
+        let (old_mark, new_mark) =
+            (&base.edition.provenance, &new.edition.provenance);
+        let old_permits = base.permits();
+        let new_permits = new.permits();
+        let old_metadata = base.metadata();
+        let new_metadata = new.metadata();
+
+        let mut diff = Self {
+            changed: false,
+            club_changed: base.edition.club_xid != new.edition.club_xid,
+            content_changed: base.edition.content.digest()
+                != new.edition.content.digest(),
+            same_chain: old_mark.chain_id() == new_mark.chain_id(),
+            seq_delta: i64::from(new_mark.seq()) - i64::from(old_mark.seq()),
+            permits_added: new_permits
+                .iter()
+                .filter(|(key, _)| !old_permits.contains_key(*key))
+                .map(|(_, permit)| permit.clone())
+                .collect(),
+            permits_removed: old_permits
+                .iter()
+                .filter(|(key, _)| !new_permits.contains_key(*key))
+                .map(|(_, permit)| permit.clone())
+                .collect(),
+            permits_kept: new_permits
+                .keys()
+                .filter(|key| old_permits.contains_key(*key))
+                .count(),
+            metadata_added: new_metadata
+                .difference(&old_metadata)
+                .cloned()
+                .collect(),
+            metadata_removed: old_metadata
+                .difference(&new_metadata)
+                .cloned()
+                .collect(),
+        };
+        diff.changed = diff.club_changed
+            || diff.content_changed
+            || !diff.same_chain
+            || diff.seq_delta != 0
+            || !diff.permits_added.is_empty()
+            || !diff.permits_removed.is_empty()
+            || !diff.metadata_added.is_empty()
+            || !diff.metadata_removed.is_empty();
+        diff
+    }
+
+    /// Print the human-readable diff to stderr.
+    fn print(&self, base: &LoadedEdition, new: &LoadedEdition) {
+        if !self.changed {
+            eprintln!("Editions are identical");
+            return;
+        }
+        eprintln!("Editions differ");
+        if self.club_changed {
+            eprintln!(
+                "  club: {} -> {}",
+                base.edition.club_xid, new.edition.club_xid
+            );
+        }
+        let chain = if self.same_chain {
+            ""
+        } else {
+            " (different chain)"
+        };
+        eprintln!(
+            "  provenance: seq {} -> {} ({:+}){chain}",
+            base.edition.provenance.seq(),
+            new.edition.provenance.seq(),
+            self.seq_delta
+        );
+        if self.content_changed {
+            eprintln!(
+                "  content: {} -> {}",
+                base.edition.content.digest(),
+                new.edition.content.digest()
+            );
+        } else {
+            eprintln!("  content: unchanged");
+        }
+        for permit in &self.permits_added {
+            eprintln!("  + permit {}", permit.label());
+        }
+        for permit in &self.permits_removed {
+            eprintln!("  - permit {}", permit.label());
+        }
+        eprintln!("  permits kept: {}", self.permits_kept);
+        for assertion in &self.metadata_added {
+            eprintln!("  + metadata {assertion}");
+        }
+        for assertion in &self.metadata_removed {
+            eprintln!("  - metadata {assertion}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::XIDProvider;
+    use bc_xid::XIDDocument;
+    use provenance_mark::ProvenanceMark;
+
+    use super::*;
+    use crate::test_support::{compose_for, marks, xid_document};
+
+    fn compose(
+        publisher: &XIDDocument,
+        content: &str,
+        mark: &ProvenanceMark,
+        members: &[&XIDDocument],
+    ) -> LoadedEdition {
+        let content = Envelope::new(content).ur_string();
+        let edition =
+            compose_for(publisher, mark, members, &["--content", &content]);
+        LoadedEdition::load(&edition, "test", None).unwrap()
+    }
+
+    fn holders(permits: &[PermitChange]) -> Vec<Option<XID>> {
+        permits.iter().map(|permit| permit.holder).collect()
+    }
+
+    #[test]
+    fn reports_permit_content_and_provenance_changes() {
+        let publisher = xid_document();
+        let (alice, bob) = (xid_document(), xid_document());
+        let marks = marks(2);
+        let (first, second) = (&marks[0], &marks[1]);
+
+        let base = compose(&publisher, "issue one", first, &[&alice]);
+        let unchanged = EditionDiff::between(&base, &base);
+        assert!(!unchanged.changed);
+        assert_eq!(unchanged.permits_kept, 1);
+
+        let added = compose(&publisher, "issue one", second, &[&alice, &bob]);
+        let diff = EditionDiff::between(&base, &added);
+        assert!(diff.changed);
+        assert!(!diff.content_changed);
+        assert_eq!(diff.seq_delta, 1);
+        assert_eq!(holders(&diff.permits_added), [Some(bob.xid())]);
+        assert!(diff.permits_removed.is_empty());
+
+        let removed = EditionDiff::between(&added, &base);
+        assert_eq!(holders(&removed.permits_removed), [Some(bob.xid())]);
+        assert_eq!(removed.seq_delta, -1);
+
+        let rewritten = compose(&publisher, "issue two", second, &[&alice]);
+        let diff = EditionDiff::between(&base, &rewritten);
+        assert!(diff.content_changed);
+        assert!(
+            diff.permits_added.is_empty() && diff.permits_removed.is_empty()
+        );
+        assert_eq!(diff.permits_kept, 1);
+    }
+
+    #[test]
+    fn exit_status_tells_a_change_from_a_failed_signature() {
+        use std::process::ExitCode;
+
+        use crate::{
+            cmd::{self, EXPECTATION_FAILED_EXIT, VERIFICATION_FAILED_EXIT},
+            test_support::{parse, public_ur},
+        };
+
+        let publisher = xid_document();
+        let marks = marks(2);
+        let base = compose_for(&publisher, &marks[0], &[], &[]);
+        let new = compose_for(&publisher, &marks[1], &[], &[]);
+        let verifier = public_ur(&publisher);
+        let outsider = public_ur(&xid_document());
+        let diff = |new: &str, publisher: &str| {
+            exec(parse::<CommandArgs>(&[
+                "clubs",
+                "--base",
+                &base,
+                "--new",
+                new,
+                "--publisher",
+                publisher,
+                "--fail-on-change",
+                "--format",
+                "json",
+            ]))
+        };
+
+        diff(&base, &verifier).unwrap();
+        let err = diff(&new, &verifier).unwrap_err();
+        assert_eq!(
+            cmd::exit_code(&err),
+            ExitCode::from(EXPECTATION_FAILED_EXIT)
+        );
+        let err = diff(&base, &outsider).unwrap_err();
+        assert_eq!(
+            cmd::exit_code(&err),
+            ExitCode::from(VERIFICATION_FAILED_EXIT)
+        );
+    }
+}
@@ -0,0 +1,2948 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use bc_components::{
+    Digest, PrivateKeys, PublicKeys, ReferenceProvider, Verifier, XID,
+};
+use bc_envelope::prelude::*;
+use bc_ur::UREncodable;
+use clap::Args;
+use clubs::{
+    edition::Edition, provenance_mark_provider::ProvenanceMarkProvider,
+    public_key_permit::PublicKeyPermit,
+};
+use dcbor::Date;
+use known_values::{
+    DATE, HAS_RECIPIENT_RAW, IS_A_RAW, KnownValue, NAME, NOTE, PROVENANCE_RAW,
+    SIGNED,
+};
+use provenance_mark::ProvenanceMark;
+use serde::Serialize;
+
+use super::{
+    DigestBinding,
+    compose::SskrPolicy,
+    obscured::{ObscuredNodes, is_obscured_assertion, strip_obscured},
+    permits::{self, PermitId},
+    receipt::{self, ReceiptCheck},
+    size::EditionSize,
+};
+use crate::{
+    cmd::{ExpectationFailed, VerificationFailed},
+    directory::MemberDirectory,
+    io::{self, EditionOrMark, RecipientDescriptor},
+    output::{self, FragmentArgs, OutputFormat, SummaryTarget, SummaryWriter},
+    trust::TrustArgs,
+    workspace::edition_stem,
+};
+
+/// Arguments for inspecting an edition.
+#[derive(Debug, Args)]
+pub struct CommandArgs {
+    /// Edition UR to inspect; repeatable. Several editions are summarized
+    /// in provenance order and checked as a chain.
+    #[arg(long = "edition", value_name = "UR")]
+    pub editions: Vec<String>,
+    /// Inspect every `.ur` file in this directory as an edition.
+    #[arg(long, value_name = "DIR")]
+    pub edition_dir: Option<PathBuf>,
+    /// Stop at the first edition that fails instead of reporting the
+    /// failure and continuing.
+    #[arg(long)]
+    pub fail_fast: bool,
+    /// Publisher descriptor (XID document or public-keys UR) used for
+    /// signature verification. Repeat for co-signed editions or across a
+    /// key rotation: a signature from any of them verifies the edition, and
+    /// the first that matches is the club's publisher for the club,
+    /// previous-edition, and receipt checks. An edition none of them
+    /// signed is reported as unverified, and fails `--check`.
+    /// When omitted, the keys pinned for the edition's club in the trust
+    /// store, if any, verify it.
+    #[arg(long = "publisher", value_name = "UR", alias = "verifier")]
+    pub publishers: Vec<String>,
+    /// Genesis edition of the chain, composed with `--embed-publisher-keys`.
+    /// The publisher keys it carries (and is signed with) verify the
+    /// edition in place of `--publisher`.
+    #[arg(long, value_name = "UR", conflicts_with = "publishers")]
+    pub genesis: Option<String>,
+    /// Directory of trusted publisher XID documents named by club XID
+    /// (`<xid>.ur`, or a unique prefix of the XID hex); the document for
+    /// the club the edition names verifies it in place of `--publisher`.
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["publishers", "genesis"]
+    )]
+    pub publisher_dir: Option<PathBuf>,
+    /// Optional previous edition UR for provenance validation. A bare
+    /// provenance mark (`ur:provenance`) is checked as with
+    /// `--previous-mark`.
+    #[arg(long, value_name = "UR")]
+    pub previous: Option<String>,
+    /// Bare provenance mark expected immediately before this edition's.
+    #[arg(long, value_name = "UR")]
+    pub previous_mark: Option<String>,
+    /// Bare provenance mark expected immediately after this edition's.
+    #[arg(long, value_name = "UR")]
+    pub next_mark: Option<String>,
+    /// Fail unless the edition carries a genesis provenance mark.
+    #[arg(long)]
+    pub expect_genesis: bool,
+    /// Fail unless the edition's provenance mark has this sequence number.
+    #[arg(long, value_name = "N")]
+    pub expect_seq: Option<u32>,
+    /// Recipients receipt (from `edition compose --receipt`) to check
+    /// against this edition.
+    #[arg(long, value_name = "UR")]
+    pub receipt: Option<String>,
+    /// Member directory (TOML mapping XIDs to names) used to show permit
+    /// holders by name.
+    #[arg(long, value_name = "PATH")]
+    pub directory: Option<PathBuf>,
+    /// Private keys (XID document or private-keys UR) to test against each
+    /// permit; repeatable. Recovered content keys are discarded unused.
+    #[arg(long = "identity", value_name = "UR")]
+    pub identities: Vec<String>,
+    #[command(flatten)]
+    pub passphrase: io::PassphraseArgs,
+    /// Fail when the provenance mark binds a digest other than the
+    /// content's, when any assertion is elided or otherwise obscured, or
+    /// when the edition or its signed wrapper carries structure inspect does
+    /// not recognize. All problems are reported together.
+    #[arg(long)]
+    pub strict: bool,
+    /// Emit sealed permit URs after the edition UR (under `--format json`,
+    /// as each permit's `ur` field).
+    #[arg(long)]
+    pub emit_permits: bool,
+    /// Print the unsigned inner edition envelope's UR instead of the input
+    /// edition's, so the output is the same however the edition was signed.
+    #[arg(long)]
+    pub emit_unsigned: bool,
+    /// Write each sealed permit to its own file in this directory (see
+    /// `edition permits --permits-dir`). With several editions, each gets a
+    /// subdirectory named after its provenance seq.
+    #[arg(long, value_name = "DIR")]
+    pub permits_dir: Option<PathBuf>,
+    /// Report the edition's size and where its bytes go.
+    #[arg(long)]
+    pub stats: bool,
+    /// Show cleartext content: text up to N characters (default 256), a hex
+    /// preview of bytes, or envelope notation for structured content.
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "256"
+    )]
+    pub preview: Option<usize>,
+    /// Print the envelope notation tree of the signed edition to stderr.
+    #[arg(long)]
+    pub tree: bool,
+    /// With `--tree`, also print the tree of the unwrapped edition.
+    #[arg(short, long)]
+    pub verbose: bool,
+    /// Report format. `json` prints one object on stdout, with the edition
+    /// UR in its `edition-ur` field, instead of the UR and a text summary.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// Where the text summary goes: `stderr`, `stdout`, or a file written
+    /// atomically. With `stdout`, the edition UR is printed only with
+    /// `--emit-ur`.
+    #[arg(long, value_name = "stderr|stdout|PATH", default_value = "stderr")]
+    pub summary_to: SummaryTarget,
+    /// Print the edition UR even when `--summary-to stdout` shares stdout.
+    #[arg(long)]
+    pub emit_ur: bool,
+    /// Run as a gate: print nothing (the summary goes to stderr with `-v`)
+    /// and fail on any check the other flags imply. Exit status 4 means a
+    /// signature or provenance check failed, 3 a structural or expectation
+    /// check, and 1 unreadable input.
+    #[arg(
+        long,
+        conflicts_with_all = ["emit_permits", "emit_unsigned", "emit_ur"]
+    )]
+    pub check: bool,
+    #[command(flatten)]
+    pub fragments: FragmentArgs,
+    #[command(flatten)]
+    pub trust: TrustArgs,
+}
+
+enum SignatureStatus {
+    Verified {
+        metadata_assertions: usize,
+    },
+    /// Signed, but by none of the supplied publishers. `tampered` when the
+    /// content also no longer matches the mark's digest binding.
+    Unverified {
+        tampered: bool,
+    },
+    NotChecked,
+    /// The input carries no signatures: a wrapped edition, or the bare
+    /// edition envelope itself.
+    Unsigned {
+        wrapped: bool,
+    },
+}
+
+pub fn exec(args: CommandArgs) -> Result<()> {
+    let mut specs = args.editions.clone();
+    if let Some(dir) = args.edition_dir.as_ref() {
+        specs.extend(
+            io::ur_files_in(dir)?
+                .into_iter()
+                .map(|path| format!("@{}", path.display())),
+        );
+    }
+    if args.format == OutputFormat::Json
+        && args.summary_to != SummaryTarget::Stderr
+    {
+        bail!("--summary-to applies to text output, not --format json");
+    }
+    if args.check {
+        if args.format == OutputFormat::Json {
+            bail!(
+                "--check prints nothing and cannot be combined with --format json"
+            );
+        }
+        if args.summary_to == SummaryTarget::Stdout {
+            bail!(
+                "--check never writes to stdout; use --summary-to stderr or a file"
+            );
+        }
+    }
+    let mut summary = SummaryWriter::new(if args.check && !args.verbose {
+        SummaryTarget::Discard
+    } else {
+        args.summary_to.clone()
+    });
+    match specs.as_slice() {
+        [] => bail!("supply --edition or --edition-dir"),
+        [spec] => {
+            let findings = examine(&args, spec)?;
+            check_expectations(&args, &findings.edition.provenance)?;
+            render(
+                &args,
+                &findings,
+                args.permits_dir.as_deref(),
+                &mut summary,
+            )?;
+            if args.format == OutputFormat::Json {
+                output::print_json(&findings.report(args.emit_permits))?;
+            }
+            summary.finish()?;
+            if args.check {
+                check_findings(&findings)?;
+            }
+            Ok(())
+        }
+        specs => {
+            if args.previous.is_some()
+                || args.previous_mark.is_some()
+                || args.next_mark.is_some()
+            {
+                bail!(
+                    "--previous, --previous-mark, and --next-mark apply to a single edition"
+                );
+            }
+            if args.expect_genesis || args.expect_seq.is_some() {
+                bail!(
+                    "--expect-genesis and --expect-seq apply to a single edition"
+                );
+            }
+            let outcomes = examine_all(&args, specs)?;
+            let count = outcomes.len();
+            let mut records = Vec::with_capacity(count);
+            for (index, outcome) in outcomes.iter().enumerate() {
+                if args.format == OutputFormat::Text {
+                    summary.line(&format!(
+                        "== Edition {} of {count}: {} ==",
+                        index + 1,
+                        outcome.source
+                    ));
+                }
+                match outcome.result.as_ref() {
+                    Ok(findings) => {
+                        let permits_dir =
+                            args.permits_dir.as_ref().map(|dir| {
+                                dir.join(edition_stem(
+                                    findings.edition.provenance.seq(),
+                                ))
+                            });
+                        render(
+                            &args,
+                            findings,
+                            permits_dir.as_deref(),
+                            &mut summary,
+                        )?;
+                        if let (OutputFormat::Text, Some(chain)) =
+                            (args.format, outcome.chain.as_ref())
+                        {
+                            summary
+                                .line(&format!("Chain: {}", chain.describe()));
+                        }
+                    }
+                    Err(err) if args.format == OutputFormat::Text => {
+                        summary.line(&format!("Error: {err:#}"));
+                    }
+                    Err(_) => {}
+                }
+                records.push(outcome.record(args.emit_permits));
+            }
+            if args.format == OutputFormat::Json {
+                output::print_json(&records)?;
+            }
+            summary.finish()?;
+            if args.check {
+                check_outcomes(outcomes)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Print one edition's tree, URs, and text summary, and write its permit
+/// files, as `args` requests. In JSON mode only the tree (on stderr) is
+/// printed here.
+fn render(
+    args: &CommandArgs,
+    findings: &Findings,
+    permits_dir: Option<&Path>,
+    summary: &mut SummaryWriter,
+) -> Result<()> {
+    if let Some(dir) = permits_dir {
+        permits::write_permit_files(
+            dir,
+            &permits::sourced_permits(&findings.edition),
+            Some(findings.edition.provenance.seq()),
+            true,
+        )?;
+    }
+    if args.tree {
+        eprint!("{}", findings.trees(args.verbose));
+    }
+    if args.format == OutputFormat::Json {
+        return Ok(());
+    }
+
+    if prints_edition_ur(args) {
+        for line in args.fragments.lines(&emitted_ur(args, findings))? {
+            println!("{}", line);
+        }
+    }
+    if args.emit_permits {
+        for permit in &findings.edition.permits {
+            if let PublicKeyPermit::Decode { sealed, .. } = permit {
+                println!("{}", sealed.ur_string());
+            }
+        }
+    }
+    for line in summary_lines(findings) {
+        summary.line(&line);
+    }
+    if let Some(stats) = findings.stats.as_ref() {
+        for line in stats.lines() {
+            summary.line(&line);
+        }
+    }
+    Ok(())
+}
+
+/// The UR printed for the edition: the input as given, or with
+/// `--emit-unsigned` the inner edition without its signature wrapper.
+fn emitted_ur(args: &CommandArgs, findings: &Findings) -> String {
+    if args.emit_unsigned {
+        findings.inner_env.ur_string()
+    } else {
+        findings.edition_env.ur_string()
+    }
+}
+
+/// Whether the edition UR goes to stdout: always, unless the summary is
+/// there too, in which case only with `--emit-ur`.
+fn prints_edition_ur(args: &CommandArgs) -> bool {
+    !args.check && (args.summary_to != SummaryTarget::Stdout || args.emit_ur)
+}
+
+/// Under `--check`, fail on any finding that inspect otherwise only
+/// reports.
+fn check_findings(findings: &Findings) -> Result<()> {
+    let mut failures = Vec::new();
+    if let SignatureStatus::Unverified { tampered } = findings.signature {
+        failures.push(if tampered {
+            "failed to verify edition signature; the content digest also differs from the one bound in the provenance mark, so the content was likely altered after signing"
+        } else {
+            "failed to verify edition signature"
+        }
+        .to_string());
+    }
+    if findings.previous_ok == Some(false) {
+        failures
+            .push("previous edition does not precede this edition".to_string());
+    }
+    if let Some(link) = findings.previous_mark
+        && link != MarkLink::Adjacent
+    {
+        failures.push(format!("previous mark {}", link.describe("precedes")));
+    }
+    if let Some(link) = findings.next_mark
+        && link != MarkLink::Adjacent
+    {
+        failures.push(format!("next mark {}", link.describe("follows")));
+    }
+    if let DigestBinding::Mismatch { .. } = findings.binding {
+        failures
+            .push(format!("digest binding: {}", findings.binding.describe()));
+    }
+    if !failures.is_empty() {
+        return Err(VerificationFailed(failures.join("; ")).into());
+    }
+    if findings.identities_can_decrypt() == Some(false) {
+        return Err(ExpectationFailed(
+            "no --identity can decrypt this edition".to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Under `--check` with several editions, fail on the first edition that
+/// could not be inspected or failed its checks, or on a break in the chain.
+fn check_outcomes(outcomes: Vec<Outcome>) -> Result<()> {
+    for outcome in outcomes {
+        let source = outcome.source;
+        let findings = outcome
+            .result
+            .with_context(|| format!("failed to inspect {source}"))?;
+        check_findings(&findings)
+            .with_context(|| format!("check failed for {source}"))?;
+        if outcome.chain.and_then(|chain| chain.follows_previous) == Some(false)
+        {
+            return Err(VerificationFailed(format!(
+                "{source} does not follow the previous edition"
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// The result of inspecting one of several editions.
+struct Outcome {
+    source: String,
+    result: Result<Findings>,
+    chain: Option<ChainPosition>,
+}
+
+/// Where an edition sits among the successfully inspected editions, sorted
+/// by provenance seq.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct ChainPosition {
+    position: usize,
+    of: usize,
+    /// Whether this edition's mark follows the previous one's; absent for
+    /// the first edition.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    follows_previous: Option<bool>,
+}
+
+impl ChainPosition {
+    fn describe(&self) -> String {
+        let follows = match self.follows_previous {
+            Some(true) => ", follows the previous edition",
+            Some(false) => ", does NOT follow the previous edition",
+            None => "",
+        };
+        format!("position {} of {}{follows}", self.position, self.of)
+    }
+}
+
+/// One entry of the JSON array printed for several editions.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+struct Record {
+    source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain: Option<ChainPosition>,
+    #[serde(flatten)]
+    report: Option<Report>,
+}
+
+impl Outcome {
+    fn record(&self, with_permit_urs: bool) -> Record {
+        Record {
+            source: self.source.clone(),
+            error: self.result.as_ref().err().map(|err| format!("{err:#}")),
+            chain: self.chain.clone(),
+            report: self
+                .result
+                .as_ref()
+                .ok()
+                .map(|findings| findings.report(with_permit_urs)),
+        }
+    }
+}
+
+/// Inspect each edition, then order the successful ones by provenance seq
+/// and check consecutive pairs as `edition sequence` does. Failed editions
+/// follow, in input order.
+fn examine_all(args: &CommandArgs, specs: &[String]) -> Result<Vec<Outcome>> {
+    let mut inspected = Vec::new();
+    let mut failed = Vec::new();
+    for (index, spec) in specs.iter().enumerate() {
+        let source = match spec.strip_prefix('@') {
+            Some(path) => path.trim().to_string(),
+            None => format!("--edition #{}", index + 1),
+        };
+        match examine(args, spec) {
+            Ok(findings) => inspected.push((source, findings)),
+            Err(err) if args.fail_fast => {
+                return Err(err.context(format!("failed to inspect {source}")));
+            }
+            Err(err) => {
+                failed.push(Outcome { source, result: Err(err), chain: None })
+            }
+        }
+    }
+
+    inspected.sort_by_key(|(_, findings)| findings.edition.provenance.seq());
+    let of = inspected.len();
+    let mut outcomes = Vec::with_capacity(specs.len());
+    let mut previous: Option<ProvenanceMark> = None;
+    for (index, (source, findings)) in inspected.into_iter().enumerate() {
+        let mark = findings.edition.provenance.clone();
+        let chain = ChainPosition {
+            position: index + 1,
+            of,
+            follows_previous: previous
+                .as_ref()
+                .map(|prev| prev.precedes(&mark)),
+        };
+        previous = Some(mark);
+        outcomes.push(Outcome {
+            source,
+            result: Ok(findings),
+            chain: Some(chain),
+        });
+    }
+    outcomes.extend(failed);
+    Ok(outcomes)
+}
+
+/// How an edition envelope was presented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditionShape {
+    /// Wrapped and signed, as `edition compose` publishes it.
+    Signed,
+    /// Wrapped, but without signatures.
+    Wrapped,
+    /// The `Edition` envelope itself, neither wrapped nor signed.
+    Bare,
+}
+
+/// How a detached provenance mark relates to the edition's mark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MarkLink {
+    /// The earlier mark immediately precedes the later one.
+    Adjacent,
+    /// Same chain, but not immediately adjacent in this order.
+    OutOfOrder,
+    /// The marks belong to different chains.
+    OtherChain,
+}
+
+impl MarkLink {
+    fn between(earlier: &ProvenanceMark, later: &ProvenanceMark) -> Self {
+        if earlier.chain_id() != later.chain_id() {
+            Self::OtherChain
+        } else if earlier.precedes(later) {
+            Self::Adjacent
+        } else {
+            Self::OutOfOrder
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Adjacent => "adjacent",
+            Self::OutOfOrder => "out-of-order",
+            Self::OtherChain => "other-chain",
+        }
+    }
+
+    /// Summary text, where `relation` is how the detached mark should
+    /// relate to this edition (`precedes` or `follows`).
+    fn describe(self, relation: &str) -> String {
+        match self {
+            Self::Adjacent => format!("{relation} this edition"),
+            Self::OutOfOrder => {
+                format!(
+                    "does NOT immediately {} this edition",
+                    relation.trim_end_matches('s')
+                )
+            }
+            Self::OtherChain => "is from a different chain".to_string(),
+        }
+    }
+}
+
+/// Find the `Edition` envelope in `envelope`, which may be signed, merely
+/// wrapped, or the edition itself.
+pub fn edition_payload(
+    envelope: &Envelope,
+) -> Result<(Envelope, EditionShape)> {
+    if envelope.check_type("Edition").is_ok() {
+        return Ok((envelope.clone(), EditionShape::Bare));
+    }
+    let shape = if envelope.objects_for_predicate(SIGNED).is_empty() {
+        EditionShape::Wrapped
+    } else {
+        EditionShape::Signed
+    };
+    let payload = envelope.try_unwrap().context(
+        "edition envelope is neither an Edition nor a wrapped Edition",
+    )?;
+    Ok((payload, shape))
+}
+
+/// Structure that `decode_edition` tolerates but `--strict` rejects:
+/// unknown assertions on the edition or its signed wrapper, unexpected
+/// signature metadata, repeated club or provenance assertions, a club
+/// object that is not an XID, and malformed permits. Obscured assertions
+/// are reported separately.
+fn structure_problems(
+    wrapper: &Envelope,
+    inner: &Envelope,
+    shape: EditionShape,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    let (mut clubs, mut marks) = (0, 0);
+    for assertion in inner.assertions() {
+        if is_obscured_assertion(&assertion) {
+            continue;
+        }
+        let (Some(predicate), Some(object)) =
+            (assertion.as_predicate(), assertion.as_object())
+        else {
+            continue;
+        };
+        if predicate == Envelope::new(super::SSKR_POLICY) {
+            continue;
+        }
+        if predicate == Envelope::new("club") {
+            clubs += 1;
+            if object.extract_subject::<XID>().is_err() {
+                problems.push("club assertion's object is not an XID".into());
+            }
+            continue;
+        }
+        match predicate.as_known_value().map(KnownValue::value) {
+            Some(PROVENANCE_RAW) => marks += 1,
+            Some(HAS_RECIPIENT_RAW) => {}
+            Some(IS_A_RAW)
+                if object
+                    .extract_subject::<String>()
+                    .is_ok_and(|kind| kind == "Edition") => {}
+            _ => problems.push(format!(
+                "unexpected assertion {} on the edition",
+                assertion.format_flat()
+            )),
+        }
+    }
+    if clubs > 1 {
+        problems.push(format!("{clubs} club assertions"));
+    }
+    if marks > 1 {
+        problems.push(format!("{marks} provenance assertions"));
+    }
+    problems.extend(permits::permit_problems(inner));
+
+    if shape == EditionShape::Signed {
+        for assertion in wrapper.assertions() {
+            let signed = assertion
+                .as_predicate()
+                .and_then(|predicate| predicate.as_known_value().cloned())
+                .is_some_and(|value| value == SIGNED);
+            if !signed {
+                problems.push(format!(
+                    "unexpected assertion {} on the signed wrapper",
+                    assertion.format_flat()
+                ));
+            }
+        }
+        for meta in signature_metadata(wrapper) {
+            for assertion in meta.assertions() {
+                let known = assertion
+                    .as_predicate()
+                    .and_then(|predicate| predicate.as_known_value().cloned())
+                    .is_some_and(|value| [NAME, NOTE, DATE].contains(&value))
+                    || assertion.as_predicate().is_some_and(|predicate| {
+                        predicate == Envelope::new(super::PUBLISHER_KEYS)
+                    });
+                if !known {
+                    problems.push(format!(
+                        "unexpected signature metadata {}",
+                        assertion.format_flat()
+                    ));
+                }
+            }
+        }
+    }
+    problems
+}
+
+/// Recover the publisher from a genesis edition composed with
+/// `--embed-publisher-keys`. The embedded keys must have signed the genesis
+/// edition itself, and its mark must start a chain.
+fn genesis_publisher(
+    spec: &str,
+) -> Result<(RecipientDescriptor, ProvenanceMark)> {
+    let envelope =
+        io::parse_envelope(spec).context("failed to parse genesis edition")?;
+    let Some(keys) = signature_metadata(&envelope).iter().find_map(|meta| {
+        meta.extract_object_for_predicate::<PublicKeys>(super::PUBLISHER_KEYS)
+            .ok()
+    }) else {
+        bail!(
+            "genesis edition carries no publisher keys in its signature metadata; compose it with --embed-publisher-keys, or verify with --publisher instead"
+        );
+    };
+    let inner = envelope.verify(&keys).context(
+        "genesis edition is not signed by the publisher keys it embeds",
+    )?;
+    let genesis = super::decode_edition(&inner)
+        .context("genesis edition is not a valid club edition")?;
+    if !genesis.provenance.is_genesis() {
+        bail!(
+            "--genesis edition has provenance seq {} and does not start a chain",
+            genesis.provenance.seq()
+        );
+    }
+    Ok((
+        RecipientDescriptor::from_public_keys(keys, Some(genesis.club_xid)),
+        genesis.provenance,
+    ))
+}
+
+/// Compare `mark` with `--expect-genesis` and `--expect-seq`, failing with
+/// what was actually found.
+fn check_expectations(args: &CommandArgs, mark: &ProvenanceMark) -> Result<()> {
+    let mut unmet = Vec::new();
+    if args.expect_genesis && !mark.is_genesis() {
+        if mark.seq() == 0 {
+            unmet.push(
+                "expected the genesis edition, but its seq 0 mark's key does not match its chain ID"
+                    .to_string(),
+            );
+        } else {
+            unmet.push(format!(
+                "expected the genesis edition, but found seq {}",
+                mark.seq()
+            ));
+        }
+    }
+    if let Some(expected) = args.expect_seq
+        && mark.seq() != expected
+    {
+        unmet.push(format!(
+            "expected seq {expected}, but found seq {}",
+            mark.seq()
+        ));
+    }
+    if unmet.is_empty() {
+        Ok(())
+    } else {
+        Err(ExpectationFailed(unmet.join("; ")).into())
+    }
+}
+
+/// Parse, verify, and cross-check the edition at `spec` as `args` requests.
+fn examine(args: &CommandArgs, spec: &str) -> Result<Findings> {
+    let edition_env =
+        io::parse_envelope(spec).context("failed to parse edition")?;
+
+    let mut verifiers = Vec::with_capacity(args.publishers.len());
+    for spec in &args.publishers {
+        verifiers.push(
+            io::parse_recipient_descriptor(spec)
+                .context("failed to parse verifier input")?,
+        );
+    }
+    let genesis_mark = match args.genesis.as_ref() {
+        Some(spec) => {
+            let (descriptor, mark) = genesis_publisher(spec)?;
+            verifiers.push(descriptor);
+            Some(mark)
+        }
+        None => None,
+    };
+
+    // Compare the content with the mark's digest binding on the unverified
+    // payload first, so content altered after signing is reported as such
+    // rather than as a bare signature failure.
+    let (payload, shape) = edition_payload(&edition_env)?;
+    let tampered = super::decode_edition(&payload).is_ok_and(|edition| {
+        matches!(
+            DigestBinding::check(&edition.provenance, &edition.content),
+            DigestBinding::Mismatch { .. }
+        )
+    });
+
+    // Without --publisher, use the keys pinned for the club the edition
+    // claims; the signature check below decides whether the claim holds.
+    let claimed_club = super::decode_edition(&payload)
+        .ok()
+        .map(|edition| edition.club_xid);
+    if let Some(dir) = &args.publisher_dir {
+        let Some(club) = claimed_club else {
+            bail!(
+                "cannot look up the club's publisher document: edition payload is not a valid club edition"
+            );
+        };
+        verifiers.push(io::publisher_in_dir(dir, &club, &args.passphrase)?);
+    }
+    if args.genesis.is_none()
+        && let Some(club) = claimed_club
+    {
+        verifiers = args.trust.resolve(club, verifiers)?;
+    }
+
+    let (inner_envelope, signature, matched) =
+        match (shape, verifiers.is_empty()) {
+            (EditionShape::Signed, false) => {
+                let verified = verifiers.iter().enumerate().find_map(
+                    |(index, descriptor)| {
+                        edition_env
+                            .verify_returning_metadata(descriptor.public_keys())
+                            .ok()
+                            .map(|(inner, metadata)| (inner, metadata, index))
+                    },
+                );
+                // None of the keys made a signature: report the signatures as
+                // unverified and read the payload as given; `--check` fails.
+                match verified {
+                    Some((inner, metadata, index)) => {
+                        let status = SignatureStatus::Verified {
+                            metadata_assertions: metadata.assertions().len(),
+                        };
+                        (inner, status, Some(index))
+                    }
+                    None => (
+                        payload,
+                        SignatureStatus::Unverified { tampered },
+                        None,
+                    ),
+                }
+            }
+            (EditionShape::Signed, true) => {
+                (payload, SignatureStatus::NotChecked, None)
+            }
+            (_, false) => bail!("cannot verify: edition is unsigned"),
+            (shape, true) => {
+                let wrapped = shape == EditionShape::Wrapped;
+                (payload, SignatureStatus::Unsigned { wrapped }, None)
+            }
+        };
+    // The publisher whose key verified the edition is the one its club,
+    // previous-edition, and receipt checks are made against.
+    let verifier_descriptor = matched.map(|index| &verifiers[index]);
+
+    // Selectively disclosed editions may hide assertions; read what is left
+    // unless the caller asked for a fully visible edition.
+    let obscured = ObscuredNodes::survey(&inner_envelope);
+    let mut strict_problems = Vec::new();
+    if args.strict {
+        if obscured.any() {
+            strict_problems.push(format!(
+                "edition has obscured assertions: {}",
+                obscured.describe()
+            ));
+        }
+        strict_problems.extend(structure_problems(
+            &edition_env,
+            &inner_envelope,
+            shape,
+        ));
+    }
+    if obscured.blocks_chain_validation() {
+        bail!(
+            "edition hides {}, so it cannot be placed in its provenance chain",
+            if obscured.club {
+                "its club assertion"
+            } else {
+                "its provenance assertion"
+            }
+        );
+    }
+    let readable = strip_obscured(&inner_envelope);
+    let parsed = super::decode_edition(&readable);
+    let binding = match parsed.as_ref() {
+        Ok(edition) => {
+            DigestBinding::check(&edition.provenance, &edition.content)
+        }
+        Err(_) => DigestBinding::Absent,
+    };
+    if args.strict && matches!(binding, DigestBinding::Mismatch { .. }) {
+        strict_problems.push(format!("digest binding: {}", binding.describe()));
+    }
+    if !strict_problems.is_empty() {
+        return Err(ExpectationFailed(format!(
+            "strict checks failed:\n  - {}",
+            strict_problems.join("\n  - ")
+        ))
+        .into());
+    }
+    let edition =
+        parsed.context("edition payload is not a valid club edition")?;
+    let expiries = super::permit_expiries(&readable)?;
+    let sskr = readable
+        .optional_object_for_predicate(super::SSKR_POLICY)
+        .context("edition records more than one SSKR policy")?
+        .map(|policy| SskrPolicy::from_envelope(&policy))
+        .transpose()
+        .context("edition's SSKR policy is malformed")?;
+
+    if let Some(descriptor) = verifier_descriptor
+        && let Some(expected_xid) = descriptor.member_xid()
+        && edition.club_xid != expected_xid
+    {
+        return Err(VerificationFailed(format!(
+            "edition references club XID {} but verifier is {}",
+            edition.club_xid, expected_xid
+        ))
+        .into());
+    }
+    if let Some(descriptor) = verifier_descriptor
+        && args
+            .trust
+            .record(edition.club_xid, descriptor.public_keys())?
+    {
+        eprintln!("Pinned the publisher keys for club {}", edition.club_xid);
+    }
+    if let Some(genesis) = genesis_mark.as_ref()
+        && edition.provenance.chain_id() != genesis.chain_id()
+    {
+        return Err(VerificationFailed(
+            "edition is not on the provenance chain started by --genesis"
+                .to_string(),
+        )
+        .into());
+    }
+
+    let previous = match args.previous.as_ref() {
+        Some(prev_spec) => {
+            Some(io::parse_edition_or_mark(prev_spec).context(
+                "failed to parse previous edition or provenance mark",
+            )?)
+        }
+        None => None,
+    };
+    let (previous_ok, previous_provenance, mut previous_mark) = match previous {
+        Some(EditionOrMark::Edition(prev_env)) => {
+            let prev_inner = match verifier_descriptor {
+                // The previous edition may predate a key rotation.
+                Some(_) => verifiers
+                    .iter()
+                    .find_map(|descriptor| {
+                        prev_env.verify(descriptor.public_keys()).ok()
+                    })
+                    .ok_or_else(|| {
+                        VerificationFailed(
+                            "failed to verify previous edition signature"
+                                .to_string(),
+                        )
+                    })?,
+                None => {
+                    edition_payload(&prev_env)
+                        .context("previous edition is not readable")?
+                        .0
+                }
+            };
+            let prev_edition = super::decode_edition(&prev_inner)
+                .context("previous edition is not a valid club edition")?;
+            (
+                Some(prev_edition.precedes(&edition)),
+                Some(prev_edition.provenance),
+                None,
+            )
+        }
+        // A bare mark has no signature; only its place in the chain is
+        // checked, and reported as the previous mark.
+        Some(EditionOrMark::Mark(mark)) => {
+            if args.previous_mark.is_some() {
+                bail!(
+                    "--previous is a bare provenance mark; pass it or --previous-mark, not both"
+                );
+            }
+            let link = MarkLink::between(&mark, &edition.provenance);
+            (None, Some(mark), Some(link))
+        }
+        None => (None, None, None),
+    };
+
+    if let Some(spec) = args.previous_mark.as_ref() {
+        let mark = io::parse_provenance_mark(spec)
+            .context("failed to parse --previous-mark")?;
+        previous_mark = Some(MarkLink::between(&mark, &edition.provenance));
+    }
+    let next_mark = match args.next_mark.as_ref() {
+        Some(spec) => {
+            let mark = io::parse_provenance_mark(spec)
+                .context("failed to parse --next-mark")?;
+            Some(MarkLink::between(&edition.provenance, &mark))
+        }
+        None => None,
+    };
+
+    let receipt_check = match args.receipt.as_ref() {
+        Some(spec) => {
+            let receipt_env =
+                io::parse_envelope(spec).context("failed to parse receipt")?;
+            let verifier =
+                verifier_descriptor.map(|descriptor| descriptor.public_keys());
+            Some(
+                receipt::check_receipt(&receipt_env, &edition, verifier)
+                    .context("receipt does not match the edition")?,
+            )
+        }
+        None => None,
+    };
+
+    let profile = match verifier_descriptor
+        .and_then(|descriptor| descriptor.xid_document())
+    {
+        Some(doc) => io::ClubProfile::from_document(doc)
+            .context("failed to read the publisher's club profile")?,
+        None => None,
+    };
+
+    let directory = match args.directory.as_ref() {
+        Some(path) => Some(MemberDirectory::load(path)?),
+        None => None,
+    };
+
+    let openers = if args.identities.is_empty() {
+        None
+    } else {
+        let mut identities = Vec::with_capacity(args.identities.len());
+        for identity in &args.identities {
+            identities.push(
+                io::parse_private_keys(identity, &args.passphrase)
+                    .with_context(|| {
+                        format!("failed to parse identity '{identity}'")
+                    })?,
+            );
+        }
+        Some(permit_openers(&edition, &identities))
+    };
+
+    let stats = args.stats.then(|| EditionSize::measure(&edition_env));
+    let preview = args
+        .preview
+        .map(|limit| content_preview(&edition.content, limit));
+    let metadata = signature_metadata(&edition_env);
+    let keys: Vec<&PublicKeys> = verifiers
+        .iter()
+        .map(|descriptor| descriptor.public_keys())
+        .collect();
+    let signatures = signature_details(&edition_env, &keys);
+    Ok(Findings {
+        edition_env,
+        inner_env: inner_envelope,
+        edition,
+        profile,
+        verifier: matched.and_then(|index| verifiers.into_iter().nth(index)),
+        signature,
+        metadata,
+        signatures,
+        previous_ok,
+        previous_provenance,
+        previous_mark,
+        next_mark,
+        receipt_check,
+        expiries,
+        binding,
+        obscured,
+        directory,
+        openers,
+        stats,
+        preview,
+        sskr,
+    })
+}
+
+/// Everything inspect learned about an edition, rendered as the text
+/// summary or the JSON report.
+struct Findings {
+    edition_env: Envelope,
+    inner_env: Envelope,
+    edition: Edition,
+    profile: Option<io::ClubProfile>,
+    verifier: Option<RecipientDescriptor>,
+    signature: SignatureStatus,
+    metadata: Vec<Envelope>,
+    signatures: Vec<SignatureDetail>,
+    previous_ok: Option<bool>,
+    previous_provenance: Option<ProvenanceMark>,
+    previous_mark: Option<MarkLink>,
+    next_mark: Option<MarkLink>,
+    receipt_check: Option<ReceiptCheck>,
+    expiries: HashMap<Digest, Date>,
+    binding: DigestBinding,
+    obscured: ObscuredNodes,
+    directory: Option<MemberDirectory>,
+    /// For each permit (by sealed digest), the 1-based positions of the
+    /// `--identity` inputs that open it; `None` without `--identity`.
+    openers: Option<HashMap<Digest, Vec<usize>>>,
+    stats: Option<EditionSize>,
+    /// `--preview` rendering of the content.
+    preview: Option<String>,
+    /// SSKR policy recorded by compose, if any.
+    sskr: Option<SskrPolicy>,
+}
+
+/// Which identities open each sealed permit of `edition`. The decrypted
+/// content key is dropped as soon as the attempt succeeds.
+fn permit_openers(
+    edition: &Edition,
+    identities: &[PrivateKeys],
+) -> HashMap<Digest, Vec<usize>> {
+    let mut openers = HashMap::new();
+    for permit in &edition.permits {
+        if let PublicKeyPermit::Decode { sealed, .. } = permit {
+            let positions = identities
+                .iter()
+                .enumerate()
+                .filter(|(_, keys)| sealed.decrypt(*keys).is_ok())
+                .map(|(index, _)| index + 1)
+                .collect();
+            openers.insert(super::sealed_digest(sealed), positions);
+        }
+    }
+    openers
+}
+
+impl Findings {
+    /// Encrypted content with no permits can only be recovered from SSKR
+    /// shares, yet no policy says how they were split.
+    fn sskr_policy_missing(&self) -> bool {
+        self.sskr.is_none()
+            && self.edition.content.is_encrypted()
+            && self.edition.permits.is_empty()
+    }
+
+    /// Whether any `--identity` opens any permit; `None` without
+    /// `--identity`.
+    fn identities_can_decrypt(&self) -> Option<bool> {
+        self.openers.as_ref().map(|openers| {
+            openers.values().any(|positions| !positions.is_empty())
+        })
+    }
+
+    /// Envelope notation of the signed edition and, if `verbose`, of the
+    /// unwrapped edition. Encrypted and elided nodes show as such.
+    fn trees(&self, verbose: bool) -> String {
+        let label = match self.signature {
+            SignatureStatus::Unsigned { .. } => "Edition",
+            _ => "Signed edition",
+        };
+        let mut out = format!("{label}:\n{}\n", self.edition_env.format());
+        if verbose {
+            out.push_str(&format!(
+                "Unwrapped edition:\n{}\n",
+                self.inner_env.format()
+            ));
+        }
+        out
+    }
+
+    /// How many signatures the `--publisher` keys made, once checked.
+    fn verified_signatures(&self) -> Option<usize> {
+        match self.signature {
+            SignatureStatus::Verified { .. }
+            | SignatureStatus::Unverified { .. } => Some(
+                self.signatures
+                    .iter()
+                    .filter(|detail| detail.verified == Some(true))
+                    .count(),
+            ),
+            SignatureStatus::NotChecked | SignatureStatus::Unsigned { .. } => {
+                None
+            }
+        }
+    }
+
+    /// The string values of `predicate` across every signature's metadata,
+    /// each listed once: cosigners that repeat the publisher's title or
+    /// notes do not duplicate them.
+    fn metadata_strings(&self, predicate: KnownValue) -> Vec<String> {
+        let mut strings = Vec::new();
+        for value in self.metadata.iter().flat_map(|meta| {
+            meta.extract_objects_for_predicate::<String>(predicate.clone())
+                .unwrap_or_default()
+        }) {
+            if !strings.contains(&value) {
+                strings.push(value);
+            }
+        }
+        strings
+    }
+
+    fn report(&self, with_permit_urs: bool) -> Report {
+        let edition = &self.edition;
+        let edition_env = &self.edition_env;
+        let mark = &edition.provenance;
+        let now = Date::now();
+        let permits = permits::sealed_permits(edition)
+            .into_iter()
+            .enumerate()
+            .map(|(index, (sealed, member_xid))| {
+                let digest = super::sealed_digest(sealed);
+                let expires = self.expiries.get(&digest);
+                PermitReport {
+                    id: PermitId::new(index + 1, sealed, member_xid),
+                    holder_name: member_xid.and_then(|xid| {
+                        self.directory
+                            .as_ref()
+                            .and_then(|directory| directory.name(&xid))
+                            .map(str::to_owned)
+                    }),
+                    scheme: io::encapsulation_scheme_label(
+                        sealed.encapsulation_scheme(),
+                    )
+                    .to_string(),
+                    expires: expires.map(ToString::to_string),
+                    expired: expires
+                        .is_some_and(|date| super::is_expired(date, &now)),
+                    ur: with_permit_urs.then(|| sealed.ur_string()),
+                    decryptable_by: self.openers.as_ref().map(|openers| {
+                        openers.get(&digest).cloned().unwrap_or_default()
+                    }),
+                }
+            })
+            .collect();
+        Report {
+            edition_ur: edition_env.ur_string(),
+            edition_digest: edition_env.digest().hex(),
+            club_xid: edition.club_xid.to_string(),
+            club_name: self
+                .profile
+                .as_ref()
+                .map(|profile| profile.name.clone()),
+            provenance: ProvenanceReport::new(mark),
+            digest_binding: self.binding.label(),
+            signature: SignatureReport {
+                status: match self.signature {
+                    SignatureStatus::Verified { .. } => "verified",
+                    SignatureStatus::Unverified { .. } => "unverified",
+                    SignatureStatus::NotChecked => "not-checked",
+                    SignatureStatus::Unsigned { .. } => "unsigned",
+                },
+                verifier: self.verifier.as_ref().map(verifier_label),
+                metadata_assertions: match self.signature {
+                    SignatureStatus::Verified { metadata_assertions } => {
+                        Some(metadata_assertions)
+                    }
+                    SignatureStatus::Unverified { .. }
+                    | SignatureStatus::NotChecked
+                    | SignatureStatus::Unsigned { .. } => None,
+                },
+                verified_count: self.verified_signatures(),
+                signatures: self
+                    .signatures
+                    .iter()
+                    .map(|detail| SignatureEntry {
+                        status: detail.status(),
+                        verified_by: detail.verified_by,
+                        date: detail.date.as_ref().map(ToString::to_string),
+                        signer: detail.signer.clone(),
+                        notes: detail.notes.clone(),
+                        other_assertions: detail.other_predicates.clone(),
+                    })
+                    .collect(),
+            },
+            titles: self.metadata_strings(NAME),
+            notes: self.metadata_strings(NOTE),
+            content: content_state(&edition.content),
+            obscured: self.obscured.clone(),
+            previous_precedes: self.previous_ok,
+            previous_provenance: self
+                .previous_provenance
+                .as_ref()
+                .map(ProvenanceReport::new),
+            previous_mark: self.previous_mark.map(MarkLink::label),
+            next_mark: self.next_mark.map(MarkLink::label),
+            receipt: self.receipt_check.as_ref().map(|check| ReceiptReport {
+                recipients: check.recipients,
+                signature_verified: check.signature_verified,
+            }),
+            identities_can_decrypt: self.identities_can_decrypt(),
+            permits,
+            stats: self.stats.clone(),
+            content_preview: self.preview.clone(),
+            sskr: self.sskr.clone(),
+            sskr_policy_missing: self.sskr_policy_missing(),
+        }
+    }
+}
+
+/// The `--format json` report. Field names are part of the CLI's interface.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct Report {
+    edition_ur: String,
+    edition_digest: String,
+    club_xid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    club_name: Option<String>,
+    provenance: ProvenanceReport,
+    digest_binding: &'static str,
+    signature: SignatureReport,
+    titles: Vec<String>,
+    notes: Vec<String>,
+    content: &'static str,
+    obscured: ObscuredNodes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_precedes: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_provenance: Option<ProvenanceReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_mark: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_mark: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<ReceiptReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identities_can_decrypt: Option<bool>,
+    permits: Vec<PermitReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<EditionSize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_preview: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sskr: Option<SskrPolicy>,
+    /// The content key has no permits and no recorded SSKR policy, as with
+    /// editions composed with `--sskr` before the policy was recorded.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    sskr_policy_missing: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct ProvenanceReport {
+    seq: u32,
+    chain_id: String,
+    chain_id_bytewords: String,
+    date: String,
+    resolution: &'static str,
+    /// `digest` or `cbor`; absent when the mark has no info field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    info_kind: Option<&'static str>,
+    /// The digest in hex, or the CBOR in diagnostic notation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    info: Option<String>,
+    ur: String,
+}
+
+impl ProvenanceReport {
+    fn new(mark: &ProvenanceMark) -> Self {
+        let info = mark_info(mark);
+        Self {
+            seq: mark.seq(),
+            chain_id: hex::encode(mark.chain_id()),
+            chain_id_bytewords: chain_id_bytewords(mark),
+            date: mark.date().to_string(),
+            resolution: super::resolution_label(mark.res()),
+            info_kind: info.as_ref().map(|(kind, _)| *kind),
+            info: info.map(|(_, value)| value),
+            ur: mark.ur_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct SignatureReport {
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata_assertions: Option<usize>,
+    /// How many of `signatures` the supplied publishers made.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verified_count: Option<usize>,
+    signatures: Vec<SignatureEntry>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct SignatureEntry {
+    status: &'static str,
+    /// 1-based position of the `--publisher` that made the signature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verified_by: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signer: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
+    /// Predicates of metadata assertions inspect does not interpret.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    other_assertions: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct ReceiptReport {
+    recipients: usize,
+    signature_verified: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct PermitReport {
+    #[serde(flatten)]
+    id: PermitId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    holder_name: Option<String>,
+    scheme: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires: Option<String>,
+    expired: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ur: Option<String>,
+    /// 1-based positions of the `--identity` inputs that open the permit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decryptable_by: Option<Vec<usize>>,
+}
+
+/// How the verifier was identified: its XID, or its public keys reference.
+pub fn verifier_label(descriptor: &RecipientDescriptor) -> String {
+    match descriptor.member_xid() {
+        Some(xid) => format!("XID {xid}"),
+        None => {
+            format!("keys {}", descriptor.public_keys().reference().ref_hex())
+        }
+    }
+}
+
+/// The text summary of one edition, one entry per line.
+fn summary_lines(findings: &Findings) -> Vec<String> {
+    let edition = &findings.edition;
+    let mut lines = Vec::new();
+    match findings.profile.as_ref() {
+        Some(profile) => {
+            lines.push(format!("Club: {} ({})", edition.club_xid, profile.name))
+        }
+        None => lines.push(format!("Club: {}", edition.club_xid)),
+    }
+    for title in findings.metadata_strings(NAME) {
+        lines.push(format!("Title: {title}"));
+    }
+    let mark = &edition.provenance;
+    lines.push(format!("Provenance: {}", provenance_summary(mark)));
+    lines.push(format!(
+        "  chain id: {} ({})",
+        hex::encode(mark.chain_id()),
+        chain_id_bytewords(mark)
+    ));
+    lines.push(match mark_info(mark) {
+        Some((kind, value)) => format!("  info: {kind} {value}"),
+        None => "  info: none".to_string(),
+    });
+    lines.push(format!("  mark: {}", mark.ur_string()));
+    if let Some(previous) = findings.previous_provenance.as_ref() {
+        lines.push(format!(
+            "  previous: seq {}, dated {} (this: seq {}, dated {})",
+            previous.seq(),
+            previous.date(),
+            mark.seq(),
+            mark.date()
+        ));
+    }
+    lines.push(format!("Digest binding: {}", findings.binding.describe()));
+    match findings.signature {
+        SignatureStatus::Verified { .. } => lines.push(format!(
+            "Signature: verified by {} ({} of {} signatures verified by provided keys)",
+            findings
+                .verifier
+                .as_ref()
+                .map(verifier_label)
+                .unwrap_or_default(),
+            findings.verified_signatures().unwrap_or_default(),
+            findings.signatures.len()
+        )),
+        SignatureStatus::Unverified { tampered } => {
+            lines.push(format!(
+                "Signature: NOT verified (0 of {} signatures verified by provided keys)",
+                findings.signatures.len()
+            ));
+            if tampered {
+                lines.push(
+                    "  the content digest also differs from the one bound in the provenance mark, so the content was likely altered after signing".to_string(),
+                );
+            }
+        }
+        SignatureStatus::NotChecked => lines.push(
+            "Signature: not checked (no --publisher supplied)".to_string(),
+        ),
+        SignatureStatus::Unsigned { wrapped: true } => lines.push(
+            "Signature: unsigned (wrapped edition, no signatures)".to_string(),
+        ),
+        SignatureStatus::Unsigned { wrapped: false } => lines
+            .push("Signature: unsigned (bare edition envelope)".to_string()),
+    }
+    for (index, detail) in findings.signatures.iter().enumerate() {
+        let by = detail
+            .verified_by
+            .map(|position| format!(" by publisher {position}"))
+            .unwrap_or_default();
+        lines.push(format!(
+            "  - signature {}: {}{by}",
+            index + 1,
+            detail.status()
+        ));
+        if let Some(date) = detail.date.as_ref() {
+            lines.push(format!("      date: {date}"));
+        }
+        if let Some(signer) = detail.signer.as_ref() {
+            lines.push(format!("      signer: {signer}"));
+        }
+        for note in &detail.notes {
+            lines.push(format!("      note: {note}"));
+        }
+        if !detail.other_predicates.is_empty() {
+            lines.push(format!(
+                "      other assertions: {}",
+                detail.other_predicates.join(", ")
+            ));
+        }
+    }
+    lines.push(format!("Content: {}", content_state(&edition.content)));
+    if let Some(preview) = findings.preview.as_ref() {
+        lines.push(format!("Preview: {preview}"));
+    }
+    if let Some(policy) = findings.sskr.as_ref() {
+        lines.push(format!(
+            "SSKR: content key is also recoverable via SSKR: {}",
+            policy.summary()
+        ));
+    } else if findings.sskr_policy_missing() {
+        lines.push(
+            "SSKR: WARNING: encrypted content has no permits and no recorded SSKR policy; shares may exist (editions composed before the policy was recorded omit it)"
+                .to_string(),
+        );
+    }
+    lines.push(format!("Obscured: {}", findings.obscured.describe()));
+    match findings.previous_ok {
+        Some(true) => lines.push("Previous: precedes this edition".to_string()),
+        Some(false) => {
+            lines.push("Previous: does NOT precede this edition".to_string())
+        }
+        None => {}
+    }
+    if let Some(link) = findings.previous_mark {
+        lines.push(format!("Previous mark: {}", link.describe("precedes")));
+    }
+    if let Some(link) = findings.next_mark {
+        lines.push(format!("Next mark: {}", link.describe("follows")));
+    }
+    if let Some(check) = findings.receipt_check.as_ref() {
+        let signature = if check.signature_verified {
+            "signature verified"
+        } else {
+            "signature not checked"
+        };
+        lines.push(format!(
+            "Receipt: matches edition ({} recipients, {signature})",
+            check.recipients
+        ));
+    }
+
+    match findings.identities_can_decrypt() {
+        Some(true) => {
+            lines.push("Identity: CAN decrypt this edition".to_string())
+        }
+        Some(false) => {
+            lines.push("Identity: CANNOT decrypt this edition".to_string())
+        }
+        None => {}
+    }
+    if edition.permits.is_empty() {
+        lines.push("Permits: none".to_string());
+        return lines;
+    }
+    lines.push(format!("Permits: {}", edition.permits.len()));
+    let now = Date::now();
+    for (index, (sealed, member_xid)) in
+        permits::sealed_permits(edition).into_iter().enumerate()
+    {
+        let scheme =
+            io::encapsulation_scheme_label(sealed.encapsulation_scheme());
+        let digest = super::sealed_digest(sealed);
+        let expiry = match findings.expiries.get(&digest) {
+            Some(expires) if super::is_expired(expires, &now) => {
+                format!(", EXPIRED {expires}")
+            }
+            Some(expires) => format!(", expires {expires}"),
+            None => String::new(),
+        };
+        let holder = MemberDirectory::holder_label(
+            findings.directory.as_ref(),
+            member_xid.as_ref(),
+            &digest,
+        );
+        let opened = match findings.openers.as_ref() {
+            Some(openers) => match openers.get(&digest) {
+                Some(positions) if !positions.is_empty() => {
+                    let positions: Vec<String> =
+                        positions.iter().map(ToString::to_string).collect();
+                    format!(", opens with identity {}", positions.join(", "))
+                }
+                _ => ", no identity opens it".to_string(),
+            },
+            None => String::new(),
+        };
+        let id = PermitId::new(index + 1, sealed, member_xid);
+        lines.push(format!(
+            "  - #{} {holder} ({scheme}, sealed {}{expiry}{opened})",
+            id.index, id.sealed_digest_short
+        ));
+    }
+    lines
+}
+
+/// One-line description of a provenance mark's position, resolution, and
+/// date.
+pub fn provenance_summary(mark: &ProvenanceMark) -> String {
+    format!(
+        "seq {} ({}), {} resolution, dated {}",
+        mark.seq(),
+        mark,
+        super::resolution_label(mark.res()),
+        mark.date()
+    )
+}
+
+/// A mark's chain ID in standard bytewords.
+fn chain_id_bytewords(mark: &ProvenanceMark) -> String {
+    bc_ur::bytewords::encode(mark.chain_id(), bc_ur::bytewords::Style::Standard)
+}
+
+/// What a mark's info field holds: a digest (in hex) or other CBOR (in
+/// diagnostic notation).
+fn mark_info(mark: &ProvenanceMark) -> Option<(&'static str, String)> {
+    let info = mark.info()?;
+    Some(match Digest::try_from(info.clone()) {
+        Ok(digest) => ("digest", digest.hex()),
+        Err(_) => ("cbor", info.diagnostic_flat()),
+    })
+}
+
+/// Collect the metadata envelopes attached to the wrapper's signatures.
+/// Signatures without metadata contribute nothing.
+pub fn signature_metadata(edition_env: &Envelope) -> Vec<Envelope> {
+    edition_env
+        .objects_for_predicate(SIGNED)
+        .into_iter()
+        .filter(|object| object.subject().is_wrapped())
+        .filter_map(|object| object.try_unwrap().ok())
+        .collect()
+}
+
+/// One signature on the edition wrapper and what its metadata says.
+struct SignatureDetail {
+    /// Whether the signature is from one of the `--publisher` keys, if any
+    /// were supplied.
+    verified: Option<bool>,
+    /// 1-based position of the `--publisher` whose key made the signature.
+    verified_by: Option<usize>,
+    date: Option<Date>,
+    signer: Option<String>,
+    notes: Vec<String>,
+    other_predicates: Vec<String>,
+}
+
+impl SignatureDetail {
+    fn status(&self) -> &'static str {
+        match self.verified {
+            Some(true) => "verified",
+            Some(false) => "not-from-publisher",
+            None => "not-checked",
+        }
+    }
+}
+
+/// Describe each signature on the wrapper, checking it against `verifier`
+/// when one is given.
+fn signature_details(
+    edition_env: &Envelope,
+    verifiers: &[&PublicKeys],
+) -> Vec<SignatureDetail> {
+    let message = edition_env.subject().digest();
+    edition_env
+        .objects_for_predicate(SIGNED)
+        .into_iter()
+        .map(|object| {
+            let (signature, metadata) = super::signature_parts(&object);
+            let verified_by = signature.as_ref().and_then(|signature| {
+                verifiers
+                    .iter()
+                    .position(|keys| keys.verify(signature, message.data()))
+                    .map(|index| index + 1)
+            });
+            let verified =
+                (!verifiers.is_empty()).then_some(verified_by.is_some());
+            let Some(meta) = metadata else {
+                return SignatureDetail {
+                    verified,
+                    verified_by,
+                    date: None,
+                    signer: None,
+                    notes: Vec::new(),
+                    other_predicates: Vec::new(),
+                };
+            };
+            let other_predicates = meta
+                .assertions()
+                .iter()
+                .filter_map(|assertion| assertion.as_predicate())
+                .filter(|predicate| {
+                    !predicate.as_known_value().is_some_and(|value| {
+                        [DATE, NOTE, NAME].contains(value)
+                    }) && *predicate != Envelope::new(super::SIGNER)
+                })
+                .map(|predicate| predicate.format_flat())
+                .collect();
+            SignatureDetail {
+                verified,
+                verified_by,
+                date: meta.extract_object_for_predicate::<Date>(DATE).ok(),
+                signer: meta
+                    .object_for_predicate(super::SIGNER)
+                    .ok()
+                    .map(|signer| signer_label(&signer)),
+                notes: meta
+                    .extract_objects_for_predicate::<String>(NOTE)
+                    .unwrap_or_default(),
+                other_predicates,
+            }
+        })
+        .collect()
+}
+
+/// Render a `"signer"` metadata object: an XID, a public keys reference, or
+/// free text, falling back to envelope notation.
+fn signer_label(signer: &Envelope) -> String {
+    if let Ok(xid) = signer.extract_subject::<XID>() {
+        format!("XID {xid}")
+    } else if let Ok(keys) = signer.extract_subject::<PublicKeys>() {
+        format!("keys {}", keys.reference().ref_hex())
+    } else if let Ok(text) = signer.extract_subject::<String>() {
+        text
+    } else {
+        signer.format_flat()
+    }
+}
+
+fn content_state(content: &Envelope) -> &'static str {
+    if content.is_encrypted() {
+        "encrypted"
+    } else if content.is_elided() {
+        "elided"
+    } else if content.is_compressed() {
+        "compressed"
+    } else if content.is_wrapped() {
+        "wrapped cleartext"
+    } else {
+        "cleartext"
+    }
+}
+
+/// Render cleartext `content` for `--preview`: text up to `limit`
+/// characters, bytes as hex (at most `limit` digits) with their length, and
+/// anything else in envelope notation. Content that is not cleartext is
+/// only named.
+fn content_preview(content: &Envelope, limit: usize) -> String {
+    let state = content_state(content);
+    if !state.ends_with("cleartext") {
+        return format!("content is {state}; nothing to preview");
+    }
+    let content = content.try_unwrap().unwrap_or_else(|_| content.clone());
+    let subject = content.subject();
+    if content.assertions().is_empty() {
+        if let Ok(text) = subject.extract_subject::<String>() {
+            return truncate(&text, limit);
+        }
+        if let Some(bytes) = subject
+            .as_leaf()
+            .and_then(|cbor| cbor.try_into_byte_string().ok())
+        {
+            let shown = truncate(&hex::encode(&bytes), limit);
+            return format!("{} bytes: {shown}", bytes.len());
+        }
+    }
+    truncate(&content.format(), limit)
+}
+
+/// `text` cut to `limit` characters, marking how much was dropped.
+fn truncate(text: &str, limit: usize) -> String {
+    let total = text.chars().count();
+    if total <= limit {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(limit).collect();
+    format!("{kept}… [truncated, {total} characters]")
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::{
+        PrivateKeyBase, PrivateKeysProvider, PublicKeysProvider, SealedMessage,
+        XIDProvider,
+    };
+    use bc_ur::URDecodable;
+    use known_values::HAS_RECIPIENT;
+    use provenance_mark::{ProvenanceMarkGenerator, ProvenanceMarkResolution};
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        cmd::edition::compose,
+        test_support::{
+            compose_args, compose_edition, compose_for, genesis_mark, marks,
+            parse, public_ur, register_tags, try_parse, with_mark,
+            xid_document,
+        },
+    };
+
+    /// Dotted paths of every key in `value`; array elements add `[]`.
+    fn key_paths(value: &Value, prefix: &str, paths: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{prefix}.{key}")
+                    };
+                    paths.push(path.clone());
+                    key_paths(value, &path, paths);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    key_paths(item, &format!("{prefix}[]"), paths);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// A composed edition with one anonymous permit, the publisher's public
+    /// XID document, and the permit holder's keys.
+    fn composed_edition() -> (compose::Composed, String, PrivateKeyBase) {
+        bound_edition(None)
+    }
+
+    /// Like `composed_edition`, with the mark's info holding the digest of
+    /// `bound_to`. A mark binding other content is swapped in after
+    /// composing, which refuses it.
+    fn bound_edition(
+        bound_to: Option<&Envelope>,
+    ) -> (compose::Composed, String, PrivateKeyBase) {
+        let doc = xid_document();
+        let member_keys = PrivateKeyBase::new();
+        let member = member_keys.public_keys().ur_string();
+        let content = Envelope::new("issue one");
+        let info = bound_to.map(|envelope| envelope.digest());
+        let mark = |info: Option<Digest>| {
+            ProvenanceMarkGenerator::new_random(
+                ProvenanceMarkResolution::Quartile,
+            )
+            .next(Date::now(), info)
+        };
+        let misbound = info.is_some_and(|info| info != content.digest());
+        let composed_mark = mark(if misbound { None } else { info });
+        let content = content.ur_string();
+        let args = compose_args(
+            &doc,
+            &composed_mark,
+            &[
+                "--content",
+                &content,
+                "--permit",
+                &member,
+                "--title",
+                "Issue 1",
+            ],
+        );
+        let mut composed = compose::run(args).unwrap();
+        if misbound {
+            composed.edition_ur =
+                with_mark(&composed.edition_ur, &doc, &mark(info));
+        }
+        (composed, public_ur(&doc), member_keys)
+    }
+
+    #[test]
+    fn json_report_field_names_are_stable() {
+        let (composed, verifier, _) = composed_edition();
+        let args = parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &composed.edition_ur,
+            "--publisher",
+            &verifier,
+            "--format",
+            "json",
+        ]);
+        let report =
+            examine(&args, &composed.edition_ur).unwrap().report(false);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["edition_ur"], composed.edition_ur);
+        assert_eq!(json["club_xid"], composed.club_xid.to_string());
+
+        let mut paths = Vec::new();
+        key_paths(&json, "", &mut paths);
+        paths.sort();
+        paths.dedup();
+        assert_eq!(
+            paths,
+            [
+                "club_xid",
+                "content",
+                "digest_binding",
+                "edition_digest",
+                "edition_ur",
+                "notes",
+                "obscured",
+                "obscured.assertions",
+                "obscured.club",
+                "obscured.permits",
+                "obscured.provenance",
+                "permits",
+                "permits[].expired",
+                "permits[].holder_xid",
+                "permits[].index",
+                "permits[].scheme",
+                "permits[].sealed_digest",
+                "permits[].sealed_digest_short",
+                "provenance",
+                "provenance.chain_id",
+                "provenance.chain_id_bytewords",
+                "provenance.date",
+                "provenance.resolution",
+                "provenance.seq",
+                "provenance.ur",
+                "signature",
+                "signature.metadata_assertions",
+                "signature.signatures",
+                "signature.signatures[].status",
+                "signature.signatures[].verified_by",
+                "signature.status",
+                "signature.verified_count",
+                "signature.verifier",
+                "titles",
+            ]
+        );
+    }
+
+    #[test]
+    fn cosigned_title_and_notes_are_listed_once() {
+        let doc = xid_document();
+        let cosigner = PrivateKeyBase::new();
+        let edition = compose_edition(
+            &doc,
+            &genesis_mark(),
+            &["--title", "Issue 1", "--note", "draft", "--note", "errata"],
+        );
+        let metadata = SignatureMetadata::new()
+            .with_assertion(NAME, "Issue 1")
+            .with_assertion(NOTE, "errata")
+            .with_assertion(NOTE, "cosigned");
+        let cosigned = Envelope::from_ur_string(&edition)
+            .unwrap()
+            .add_signature_opt(&cosigner, None, Some(metadata))
+            .ur_string();
+
+        let publisher = public_ur(&doc);
+        let args = parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &cosigned,
+            "--publisher",
+            &publisher,
+            "--format",
+            "json",
+        ]);
+        let findings = examine(&args, &cosigned).unwrap();
+        assert_eq!(findings.metadata.len(), 2);
+        let json = serde_json::to_value(findings.report(false)).unwrap();
+        assert_eq!(json["titles"], serde_json::json!(["Issue 1"]));
+        let mut notes: Vec<&str> = json["notes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|note| note.as_str().unwrap())
+            .collect();
+        notes.sort();
+        assert_eq!(notes, ["cosigned", "draft", "errata"]);
+        assert_eq!(
+            json["signature"]["signatures"].as_array().unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn tree_shows_edition_structure_without_verification() {
+        let (composed, ..) = composed_edition();
+        let args = parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &composed.edition_ur,
+            "--tree",
+        ]);
+        let findings = examine(&args, &composed.edition_ur).unwrap();
+
+        let tree = findings.trees(false);
+        assert!(tree.contains("'provenance'"), "{tree}");
+        assert!(tree.contains("'hasRecipient'"), "{tree}");
+        assert!(tree.contains("ENCRYPTED"), "{tree}");
+        assert!(!tree.contains("Unwrapped edition"));
+
+        let verbose = findings.trees(true);
+        assert!(verbose.contains("Unwrapped edition"));
+    }
+
+    #[test]
+    fn identity_check_reports_which_keys_open_permits() {
+        let (composed, _, member_keys) = composed_edition();
+        let member = member_keys.private_keys().ur_string();
+        let stranger = PrivateKeyBase::new().private_keys().ur_string();
+
+        let args = parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &composed.edition_ur,
+            "--identity",
+            &stranger,
+            "--identity",
+            &member,
+        ]);
+        let findings = examine(&args, &composed.edition_ur).unwrap();
+        assert_eq!(findings.identities_can_decrypt(), Some(true));
+        let json = serde_json::to_value(findings.report(false)).unwrap();
+        assert_eq!(json["identities_can_decrypt"], true);
+        assert_eq!(
+            json["permits"][0]["decryptable_by"],
+            serde_json::json!([2])
+        );
+
+        let args = parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &composed.edition_ur,
+            "--identity",
+            &stranger,
+        ]);
+        let findings = examine(&args, &composed.edition_ur).unwrap();
+        assert_eq!(findings.identities_can_decrypt(), Some(false));
+    }
+
+    fn inspect(edition: &str, extra: &[&str]) -> Result<Findings> {
+        let mut argv = vec!["clubs", "--edition", edition];
+        argv.extend_from_slice(extra);
+        let args = parse::<CommandArgs>(&argv);
+        examine(&args, edition)
+    }
+
+    #[test]
+    fn reports_digest_binding_in_all_states() {
+        let content = Envelope::new("issue one");
+        let (bound, ..) = bound_edition(Some(&content));
+        let (unbound, ..) = bound_edition(None);
+        let (mismatched, ..) =
+            bound_edition(Some(&Envelope::new("another issue")));
+
+        let binding = |ur: &str| inspect(ur, &[]).unwrap().binding;
+        assert_eq!(binding(&bound.edition_ur), DigestBinding::Match);
+        assert_eq!(binding(&unbound.edition_ur), DigestBinding::Absent);
+        assert!(matches!(
+            binding(&mismatched.edition_ur),
+            DigestBinding::Mismatch { .. }
+        ));
+        assert_eq!(
+            inspect(&bound.edition_ur, &[])
+                .unwrap()
+                .report(false)
+                .digest_binding,
+            "match"
+        );
+        assert!(inspect(&mismatched.edition_ur, &["--strict"]).is_err());
+        assert!(inspect(&bound.edition_ur, &["--strict"]).is_ok());
+    }
+
+    #[test]
+    fn detects_content_replaced_after_signing() {
+        let content = Envelope::new("issue one");
+        let (composed, verifier, _) = bound_edition(Some(&content));
+        let signed = io::parse_envelope(&composed.edition_ur).unwrap();
+        let inner = signed.try_unwrap().unwrap();
+        let forged_inner = inner.assertions().into_iter().fold(
+            Envelope::new("forged issue"),
+            |envelope, assertion| {
+                envelope.add_assertion_envelope(assertion).unwrap()
+            },
+        );
+        let forged = signed
+            .assertions()
+            .into_iter()
+            .fold(forged_inner.wrap(), |envelope, assertion| {
+                envelope.add_assertion_envelope(assertion).unwrap()
+            })
+            .ur_string();
+
+        let findings = inspect(&forged, &[]).unwrap();
+        assert!(matches!(findings.binding, DigestBinding::Mismatch { .. }));
+        assert!(inspect(&forged, &["--strict"]).is_err());
+        let findings = inspect(&forged, &["--publisher", &verifier]).unwrap();
+        assert_eq!(findings.verified_signatures(), Some(0));
+        assert!(
+            summary_lines(&findings)
+                .iter()
+                .any(|line| line.contains("altered after signing"))
+        );
+        let err = check_findings(&findings).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("altered after signing"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn inspects_several_editions_and_reports_failures_per_record() {
+        let (composed, ..) = composed_edition();
+        let args = parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            "ur:envelope/corrupt",
+            "--edition",
+            &composed.edition_ur,
+        ]);
+        let outcomes = examine_all(&args, &args.editions).unwrap();
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].source, "--edition #2");
+        assert!(outcomes[0].result.is_ok());
+        assert_eq!(outcomes[0].chain.as_ref().unwrap().position, 1);
+        assert_eq!(outcomes[1].source, "--edition #1");
+        assert!(outcomes[1].result.is_err());
+
+        let records: Vec<_> = outcomes
+            .iter()
+            .map(|outcome| outcome.record(false))
+            .collect();
+        let json = serde_json::to_value(&records).unwrap();
+        assert_eq!(json[0]["club_xid"], composed.club_xid.to_string());
+        assert_eq!(json[0]["chain"]["of"], 1);
+        assert!(json[0].get("error").is_none());
+        assert!(json[1]["error"].as_str().unwrap().contains("parse edition"));
+        assert!(json[1].get("club_xid").is_none());
+
+        let args = parse::<CommandArgs>(&[
+            "clubs",
+            "--fail-fast",
+            "--edition",
+            "ur:envelope/corrupt",
+            "--edition",
+            &composed.edition_ur,
+        ]);
+        assert!(examine_all(&args, &args.editions).is_err());
+    }
+
+    #[test]
+    fn reports_each_signature_and_its_metadata() {
+        let (composed, verifier, _) = composed_edition();
+        let countersigner = PrivateKeyBase::new();
+        let signed = Envelope::from_ur_string(&composed.edition_ur).unwrap();
+        let date = Date::from_string("2025-03-01").unwrap();
+        let countersigned = signed
+            .add_signature_opt(
+                &countersigner,
+                None,
+                Some(
+                    SignatureMetadata::new()
+                        .with_assertion(DATE, date)
+                        .with_assertion(NOTE, "counter-signed")
+                        .with_assertion(super::super::SIGNER, "alice")
+                        .with_assertion("reviewer", "bob"),
+                ),
+            )
+            .ur_string();
+
+        let findings =
+            inspect(&countersigned, &["--publisher", &verifier]).unwrap();
+        assert_eq!(findings.signatures.len(), 2);
+        let counter = findings
+            .signatures
+            .iter()
+            .find(|detail| detail.date.is_some())
+            .unwrap();
+        assert_eq!(counter.status(), "not-from-publisher");
+        assert_eq!(counter.date, Some(date));
+        assert_eq!(counter.notes, ["counter-signed"]);
+        assert_eq!(counter.signer.as_deref(), Some("alice"));
+        assert_eq!(counter.other_predicates, ["\"reviewer\""]);
+        let publisher = findings
+            .signatures
+            .iter()
+            .find(|detail| detail.date.is_none())
+            .unwrap();
+        assert_eq!(publisher.status(), "verified");
+        assert!(publisher.other_predicates.is_empty());
+
+        let plain = signed
+            .try_unwrap()
+            .unwrap()
+            .wrap()
+            .add_signature(&countersigner)
+            .add_signature(&PrivateKeyBase::new())
+            .ur_string();
+        let findings = inspect(
+            &plain,
+            &["--publisher", &countersigner.public_keys().ur_string()],
+        )
+        .unwrap();
+        let mut statuses: Vec<_> = findings
+            .signatures
+            .iter()
+            .map(SignatureDetail::status)
+            .collect();
+        statuses.sort();
+        assert_eq!(statuses, ["not-from-publisher", "verified"]);
+        assert!(findings.signatures.iter().all(|detail| {
+            detail.date.is_none()
+                && detail.signer.is_none()
+                && detail.notes.is_empty()
+        }));
+    }
+
+    #[test]
+    fn trust_pins_the_publisher_and_later_inspections_use_it() {
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-inspect-trust-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store_arg = dir.join("trust.json").display().to_string();
+        let (composed, verifier, _) = composed_edition();
+        let edition = composed.edition_ur.as_str();
+        let inspect = |extra: &[&str]| {
+            let mut argv = vec![
+                "clubs",
+                "--edition",
+                edition,
+                "--trust-store",
+                &store_arg,
+            ];
+            argv.extend_from_slice(extra);
+            examine(&parse::<CommandArgs>(&argv), edition)
+        };
+
+        let findings = inspect(&[]).unwrap();
+        assert!(matches!(findings.signature, SignatureStatus::NotChecked));
+        inspect(&["--publisher", &verifier, "--trust"]).unwrap();
+
+        // The pinned keys verify the edition without --publisher.
+        let findings = inspect(&[]).unwrap();
+        assert!(matches!(
+            findings.signature,
+            SignatureStatus::Verified { .. }
+        ));
+
+        let impostor = PrivateKeyBase::new().public_keys().ur_string();
+        let err = inspect(&["--publisher", &impostor]).err().unwrap();
+        assert!(err.is::<VerificationFailed>());
+        let message = err.to_string();
+        assert!(message.contains("CONFLICT"));
+        assert!(message.contains(&format!(
+            "clubs trust remove --club {}",
+            composed.club_xid.ur_string()
+        )));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn expectations_compare_against_the_provenance_mark() {
+        let (composed, ..) = composed_edition();
+        let edition = composed.edition_ur.as_str();
+        let check = |extra: &[&str]| {
+            let mut argv = vec!["clubs", "--edition", edition];
+            argv.extend_from_slice(extra);
+            let args = parse::<CommandArgs>(&argv);
+            let findings = examine(&args, edition).unwrap();
+            check_expectations(&args, &findings.edition.provenance)
+        };
+        assert!(check(&["--expect-genesis"]).is_ok());
+        assert!(check(&["--expect-seq", "0"]).is_ok());
+        assert!(check(&["--expect-genesis", "--expect-seq", "0"]).is_ok());
+
+        let err = check(&["--expect-seq", "12"]).unwrap_err();
+        assert!(err.is::<ExpectationFailed>());
+        assert_eq!(err.to_string(), "expected seq 12, but found seq 0");
+
+        let err = check(&["--expect-genesis", "--expect-seq", "3"])
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "expected seq 3, but found seq 0");
+
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        generator.next(Date::now(), None::<String>);
+        let second = generator.next(Date::now(), None::<String>);
+        let args = parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            edition,
+            "--expect-genesis",
+            "--expect-seq",
+            "0",
+        ]);
+        let err = check_expectations(&args, &second).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "expected the genesis edition, but found seq 1; expected seq 0, but found seq 1"
+        );
+    }
+
+    #[test]
+    fn accepts_signed_wrapped_and_bare_editions() {
+        let (composed, verifier, _) = composed_edition();
+        let signed = Envelope::from_ur_string(&composed.edition_ur).unwrap();
+        let wrapped = signed.subject();
+        let bare = signed.try_unwrap().unwrap();
+
+        let findings = inspect(&signed.ur_string(), &[]).unwrap();
+        assert!(matches!(findings.signature, SignatureStatus::NotChecked));
+        let findings = inspect(&wrapped.ur_string(), &[]).unwrap();
+        assert!(matches!(
+            findings.signature,
+            SignatureStatus::Unsigned { wrapped: true }
+        ));
+        let findings = inspect(&bare.ur_string(), &[]).unwrap();
+        assert!(matches!(
+            findings.signature,
+            SignatureStatus::Unsigned { wrapped: false }
+        ));
+        assert_eq!(findings.edition.club_xid, composed.club_xid);
+        assert_eq!(findings.report(false).signature.status, "unsigned");
+
+        for unsigned in [&wrapped, &bare] {
+            let err =
+                inspect(&unsigned.ur_string(), &["--publisher", &verifier])
+                    .err()
+                    .unwrap();
+            assert_eq!(err.to_string(), "cannot verify: edition is unsigned");
+        }
+        assert!(inspect(&Envelope::new("nope").ur_string(), &[]).is_err());
+    }
+
+    #[test]
+    fn counts_obscured_assertions_instead_of_failing() {
+        let (composed, ..) = composed_edition();
+        let bare = Envelope::from_ur_string(&composed.edition_ur)
+            .unwrap()
+            .try_unwrap()
+            .unwrap();
+        let outsider = PrivateKeyBase::new().public_keys();
+        let extra_permit =
+            Envelope::new(SealedMessage::new(b"key", &outsider));
+        let note = Envelope::new_assertion(NOTE, "internal");
+        let disclosed = bare
+            .add_assertion(HAS_RECIPIENT, extra_permit.clone())
+            .add_assertion_envelope(note.clone())
+            .unwrap();
+        let elided = disclosed
+            .elide_removing_target(&extra_permit)
+            .elide_removing_target(&note)
+            .ur_string();
+
+        let findings = inspect(&elided, &[]).unwrap();
+        assert_eq!(
+            findings.obscured,
+            ObscuredNodes {
+                assertions: 2,
+                permits: 1,
+                club: false,
+                provenance: false,
+            }
+        );
+        assert_eq!(findings.obscured.describe(), "2 assertions (1 permit)");
+        assert_eq!(findings.edition.permits.len(), 1);
+        assert_eq!(findings.report(false).content, "encrypted");
+
+        let err = inspect(&elided, &["--strict"]).err().unwrap();
+        assert!(err.to_string().contains("obscured"), "{err}");
+    }
+
+    #[test]
+    fn summary_destination_moves_the_ur_off_a_shared_stdout() {
+        let args = |extra: &[&str]| {
+            let mut argv = vec!["clubs", "--edition", "ur:envelope/x"];
+            argv.extend_from_slice(extra);
+            parse::<CommandArgs>(&argv)
+        };
+        let default = args(&[]);
+        assert_eq!(default.summary_to, SummaryTarget::Stderr);
+        assert!(prints_edition_ur(&default));
+        let to_file = args(&["--summary-to", "audit.txt"]);
+        assert_eq!(to_file.summary_to, SummaryTarget::File("audit.txt".into()));
+        assert!(prints_edition_ur(&to_file));
+        assert!(!prints_edition_ur(&args(&["--summary-to", "stdout"])));
+        assert!(prints_edition_ur(&args(&[
+            "--summary-to",
+            "stdout",
+            "--emit-ur"
+        ])));
+
+        let err = exec(args(&["--summary-to", "stdout", "--format", "json"]))
+            .unwrap_err();
+        assert!(err.to_string().contains("--summary-to"), "{err}");
+
+        let (composed, ..) = composed_edition();
+        let path = std::env::temp_dir()
+            .join(format!("clubs-inspect-summary-{}.txt", std::process::id()));
+        let to_file = parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &composed.edition_ur,
+            "--summary-to",
+            path.to_str().unwrap(),
+        ]);
+        exec(to_file).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            written.starts_with(&format!("Club: {}\n", composed.club_xid)),
+            "{written}"
+        );
+        assert!(written.contains("\nPermits: 1\n"), "{written}");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn detached_marks_check_the_edition_from_either_side() {
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        let genesis = generator.next(Date::now(), None::<String>);
+        let edition = generator.next(Date::now(), None::<String>);
+        let next = generator.next(Date::now(), None::<String>);
+        let stranger = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        )
+        .next(Date::now(), None::<String>);
+
+        assert_eq!(MarkLink::between(&genesis, &edition), MarkLink::Adjacent);
+        assert_eq!(MarkLink::between(&edition, &next), MarkLink::Adjacent);
+        assert_eq!(MarkLink::between(&next, &edition), MarkLink::OutOfOrder);
+        assert_eq!(MarkLink::between(&genesis, &next), MarkLink::OutOfOrder);
+        assert_eq!(
+            MarkLink::between(&stranger, &edition),
+            MarkLink::OtherChain
+        );
+        assert_eq!(
+            MarkLink::OutOfOrder.describe("follows"),
+            "does NOT immediately follow this edition"
+        );
+
+        let (composed, ..) = composed_edition();
+        let findings = inspect(
+            &composed.edition_ur,
+            &[
+                "--next-mark",
+                &next.ur_string(),
+                "--previous-mark",
+                &genesis.ur_string(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(findings.next_mark, Some(MarkLink::OtherChain));
+        let report = findings.report(false);
+        assert_eq!(report.previous_mark, Some("other-chain"));
+        assert_eq!(report.next_mark, Some("other-chain"));
+    }
+
+    #[test]
+    fn detached_marks_from_the_same_chain_are_adjacent_or_out_of_order() {
+        let publisher = xid_document();
+        let chain = marks(4);
+        let edition = compose_edition(&publisher, &chain[1], &[]);
+        let with_marks = |previous: usize, next: usize| {
+            inspect(
+                &edition,
+                &[
+                    "--previous-mark",
+                    &chain[previous].ur_string(),
+                    "--next-mark",
+                    &chain[next].ur_string(),
+                ],
+            )
+            .unwrap()
+        };
+
+        let findings = with_marks(0, 2);
+        assert_eq!(findings.previous_mark, Some(MarkLink::Adjacent));
+        assert_eq!(findings.next_mark, Some(MarkLink::Adjacent));
+        let report = findings.report(false);
+        assert_eq!(report.previous_mark, Some("adjacent"));
+        assert_eq!(report.next_mark, Some("adjacent"));
+        check_findings(&findings).unwrap();
+
+        let findings = with_marks(2, 3);
+        assert_eq!(findings.previous_mark, Some(MarkLink::OutOfOrder));
+        assert_eq!(findings.next_mark, Some(MarkLink::OutOfOrder));
+        let report = findings.report(false);
+        assert_eq!(report.previous_mark, Some("out-of-order"));
+        assert_eq!(report.next_mark, Some("out-of-order"));
+        let err = check_findings(&findings).unwrap_err().to_string();
+        assert!(
+            err.contains("previous mark does NOT immediately precede"),
+            "{err}"
+        );
+        assert!(
+            err.contains("next mark does NOT immediately follow"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn stats_match_the_serialized_edition() {
+        let (composed, ..) = composed_edition();
+        let findings = inspect(&composed.edition_ur, &["--stats"]).unwrap();
+        let stats = findings.stats.as_ref().unwrap();
+        assert_eq!(stats.cbor_bytes, findings.edition_env.to_cbor_data().len());
+        assert_eq!(stats.ur_chars, composed.edition_ur.len());
+        assert_eq!(stats.permit_count, 1);
+        assert_eq!(stats.assertions_by_level[0], 1);
+        let json = serde_json::to_value(findings.report(false)).unwrap();
+        assert_eq!(json["stats"]["cbor_bytes"], stats.cbor_bytes);
+
+        let extra = Envelope::new_assertion(
+            HAS_RECIPIENT,
+            SealedMessage::new(
+                b"key",
+                &PrivateKeyBase::new().public_keys(),
+            ),
+        );
+        let bigger = findings
+            .inner_env
+            .add_assertion_envelope(extra.clone())
+            .unwrap();
+        let grown = EditionSize::measure(&bigger);
+        assert_eq!(grown.permit_count, 2);
+        assert_eq!(
+            grown.permit_bytes,
+            stats.permit_bytes + extra.to_cbor_data().len()
+        );
+    }
+
+    #[test]
+    fn unsigned_output_survives_re_signing() {
+        let (composed, ..) = composed_edition();
+        let signed = Envelope::from_ur_string(&composed.edition_ur).unwrap();
+        let countersigned = signed.add_signature(&PrivateKeyBase::new());
+        let emit = |edition: &str| {
+            let args = parse::<CommandArgs>(&[
+                "clubs",
+                "--edition",
+                edition,
+                "--emit-unsigned",
+            ]);
+            let findings = examine(&args, edition).unwrap();
+            emitted_ur(&args, &findings)
+        };
+        let unsigned = emit(&composed.edition_ur);
+        assert_eq!(emit(&countersigned.ur_string()), unsigned);
+
+        let inner = Envelope::from_ur_string(&unsigned).unwrap();
+        let resigned = inner
+            .wrap()
+            .add_signature(&PrivateKeyBase::new())
+            .ur_string();
+        assert_ne!(resigned, composed.edition_ur);
+        assert_eq!(emit(&resigned), unsigned);
+        let findings = inspect(&resigned, &[]).unwrap();
+        assert_eq!(
+            findings.inner_env.digest(),
+            signed.try_unwrap().unwrap().digest()
+        );
+    }
+
+    #[test]
+    fn provenance_report_details_the_mark() {
+        register_tags();
+        let content = Envelope::new("issue one");
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        let mark =
+            generator.next(Date::now(), Some(content.digest()));
+        let report =
+            serde_json::to_value(ProvenanceReport::new(&mark)).unwrap();
+        assert_eq!(report["chain_id"], hex::encode(generator.chain_id()));
+        assert_eq!(report["info_kind"], "digest");
+        assert_eq!(report["info"], content.digest().hex());
+        assert_eq!(report["ur"], mark.ur_string());
+        assert_eq!(
+            report["chain_id_bytewords"],
+            bc_ur::bytewords::encode(
+                generator.chain_id(),
+                bc_ur::bytewords::Style::Standard
+            )
+        );
+
+        // An edition composed at a mark of a generator held here reports
+        // that generator's chain id, not merely its own mark's.
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        let mark = generator.next(Date::now(), None::<String>);
+        let edition = compose_edition(&xid_document(), &mark, &[]);
+        let json =
+            serde_json::to_value(inspect(&edition, &[]).unwrap().report(false))
+                .unwrap();
+        assert_eq!(
+            json["provenance"]["chain_id"],
+            hex::encode(generator.chain_id())
+        );
+        assert_eq!(json["provenance"]["ur"], mark.ur_string());
+        assert!(json["provenance"].get("info_kind").is_none());
+    }
+
+    #[test]
+    fn strict_rejects_unrecognized_structure() {
+        let (composed, ..) = composed_edition();
+        let signed = Envelope::from_ur_string(&composed.edition_ur).unwrap();
+        let bogus = signed
+            .try_unwrap()
+            .unwrap()
+            .add_assertion("bogus", "extra")
+            .wrap()
+            .add_signature(&PrivateKeyBase::new())
+            .add_assertion("stamp", "unsigned")
+            .ur_string();
+
+        assert!(inspect(&composed.edition_ur, &["--strict"]).is_ok());
+        assert!(inspect(&bogus, &[]).is_ok());
+        let err = inspect(&bogus, &["--strict"]).err().unwrap().to_string();
+        for expected in [
+            "unexpected assertion \"bogus\": \"extra\" on the edition",
+            "unexpected assertion \"stamp\": \"unsigned\" on the signed wrapper",
+        ] {
+            assert!(err.contains(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn counts_signatures_verified_by_each_supplied_publisher() {
+        let (composed, verifier, _) = composed_edition();
+        let cosigner = PrivateKeyBase::new();
+        let cosigner_keys = cosigner.public_keys().ur_string();
+        let stranger = PrivateKeyBase::new().public_keys().ur_string();
+        let cosigned = Envelope::from_ur_string(&composed.edition_ur)
+            .unwrap()
+            .add_signature(&cosigner)
+            .ur_string();
+
+        let findings = inspect(&cosigned, &["--publisher", &verifier]).unwrap();
+        assert_eq!(findings.signatures.len(), 2);
+        assert_eq!(findings.verified_signatures(), Some(1));
+
+        let findings = inspect(
+            &cosigned,
+            &["--publisher", &verifier, "--publisher", &cosigner_keys],
+        )
+        .unwrap();
+        assert_eq!(findings.verified_signatures(), Some(2));
+        let mut by: Vec<_> = findings
+            .signatures
+            .iter()
+            .map(|detail| detail.verified_by)
+            .collect();
+        by.sort();
+        assert_eq!(by, [Some(1), Some(2)]);
+        let report = serde_json::to_value(findings.report(false)).unwrap();
+        assert_eq!(report["signature"]["verified_count"], 2);
+        assert_eq!(
+            report["signature"]["signatures"].as_array().unwrap().len(),
+            2
+        );
+
+        let findings = inspect(&cosigned, &["--publisher", &stranger]).unwrap();
+        assert_eq!(findings.verified_signatures(), Some(0));
+        assert!(findings.verifier.is_none());
+        assert!(summary_lines(&findings).contains(
+            &"Signature: NOT verified (0 of 2 signatures verified by provided keys)"
+                .to_string()
+        ));
+        let report = serde_json::to_value(findings.report(false)).unwrap();
+        assert_eq!(report["signature"]["status"], "unverified");
+        assert_eq!(report["signature"]["verified_count"], 0);
+        let err = check_findings(&findings).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("failed to verify edition signature"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn permit_identifiers_match_the_extracted_permits() {
+        let (composed, ..) = composed_edition();
+        let findings = inspect(&composed.edition_ur, &[]).unwrap();
+        let report = serde_json::to_value(findings.report(false)).unwrap();
+        let permit = &report["permits"][0];
+        assert_eq!(permit["index"], 1);
+
+        let extracted = permits::sealed_permits(&findings.edition);
+        let (sealed, holder) = extracted[0];
+        let reparsed = io::parse_sealed_message(&sealed.ur_string()).unwrap();
+        let digest = super::super::sealed_digest(&reparsed);
+        assert_eq!(permit["sealed_digest"], digest.hex());
+        assert_eq!(
+            permit["sealed_digest_short"],
+            hex::encode(&digest.data()[..4])
+        );
+        let id = PermitId::new(1, sealed, holder);
+        assert_eq!(
+            serde_json::to_value(&id).unwrap()["sealed_digest"],
+            digest.hex()
+        );
+
+        // `edition permits` writes files whose URs digest to the identifiers
+        // inspect prints, holder for holder.
+        let (alice, anonymous) = (xid_document(), PrivateKeyBase::new());
+        let anonymous = anonymous.public_keys().ur_string();
+        let edition = compose_for(
+            &xid_document(),
+            &genesis_mark(),
+            &[&alice],
+            &["--permit", &anonymous],
+        );
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-inspect-permit-ids-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        permits::exec(parse::<permits::CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &edition,
+            "--allow-unverified",
+            "--permits-dir",
+            dir.to_str().unwrap(),
+        ]))
+        .unwrap();
+        let index: Value = serde_json::from_str(
+            &std::fs::read_to_string(dir.join(permits::PERMIT_INDEX_JSON_FILE))
+                .unwrap(),
+        )
+        .unwrap();
+        let extracted: HashMap<String, Value> = index["permits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| {
+                let file = dir.join(entry["file"].as_str().unwrap());
+                let ur = std::fs::read_to_string(file).unwrap();
+                let sealed = io::parse_sealed_message(ur.trim()).unwrap();
+                let digest = super::super::sealed_digest(&sealed).hex();
+                (digest, entry["holder_xid"].clone())
+            })
+            .collect();
+        let report =
+            serde_json::to_value(inspect(&edition, &[]).unwrap().report(false))
+                .unwrap();
+        let listed = report["permits"].as_array().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(extracted.len(), 2);
+        for permit in listed {
+            let digest = permit["sealed_digest"].as_str().unwrap();
+            assert_eq!(
+                extracted.get(digest),
+                Some(&permit["holder_xid"]),
+                "{digest}"
+            );
+        }
+        assert!(
+            extracted
+                .values()
+                .any(|holder| *holder == alice.xid().to_string())
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // The compose permit manifest names the same permit the same way.
+        assert_eq!(composed.permits.len(), 1);
+        let manifest = serde_json::to_value(&composed.permits[0]).unwrap();
+        for key in [
+            "index",
+            "sealed_digest",
+            "sealed_digest_short",
+            "holder_xid",
+        ] {
+            assert_eq!(manifest[key], permit[key], "{key}");
+        }
+        assert!(!composed.permits[0].decoy);
+        assert!(composed.permits[0].recipient_key.is_none());
+    }
+
+    #[test]
+    fn verifies_with_publisher_keys_embedded_in_genesis() {
+        let doc = xid_document();
+        let marks = marks(2);
+        let (first, second) = (&marks[0], &marks[1]);
+        let compose_at = |mark: &ProvenanceMark, extra: &[&str]| {
+            compose_edition(&doc, mark, extra)
+        };
+        let genesis =
+            compose_at(first, &["--genesis", "--embed-publisher-keys"]);
+        let next = compose_at(second, &[]);
+
+        let findings = inspect(&next, &["--genesis", &genesis]).unwrap();
+        assert!(matches!(
+            findings.signature,
+            SignatureStatus::Verified { .. }
+        ));
+        assert!(
+            inspect(&genesis, &["--genesis", &genesis, "--strict"]).is_ok()
+        );
+
+        let bare_genesis = compose_at(first, &["--genesis"]);
+        let err = inspect(&next, &["--genesis", &bare_genesis]).err().unwrap();
+        assert!(
+            format!("{err:#}").contains("--embed-publisher-keys"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn previews_text_bytes_and_encrypted_content() {
+        let text = Envelope::new("issue one: the quick brown fox");
+        assert_eq!(
+            content_preview(&text, 256),
+            "issue one: the quick brown fox"
+        );
+        assert_eq!(
+            content_preview(&text.wrap(), 9),
+            "issue one… [truncated, 30 characters]"
+        );
+
+        let bytes =
+            Envelope::new(CBOR::to_byte_string([0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(content_preview(&bytes, 256), "4 bytes: deadbeef");
+        assert_eq!(
+            content_preview(&bytes, 4),
+            "4 bytes: dead… [truncated, 8 characters]"
+        );
+
+        let structured = text.add_assertion(known_values::NOTE, "draft");
+        assert_eq!(content_preview(&structured, 256), structured.format());
+
+        let encrypted = text
+            .encrypt_subject(&bc_components::SymmetricKey::new())
+            .unwrap();
+        assert_eq!(
+            content_preview(&encrypted, 256),
+            "content is encrypted; nothing to preview"
+        );
+    }
+
+    #[test]
+    fn reports_the_recorded_sskr_policy() {
+        let doc = xid_document();
+        let member = PrivateKeyBase::new().public_keys().ur_string();
+        let compose_with =
+            |extra: &[&str]| compose_edition(&doc, &genesis_mark(), extra);
+
+        let split =
+            inspect(&compose_with(&["--sskr", "2of3"]), &["--strict"]).unwrap();
+        assert_eq!(split.sskr.as_ref().unwrap().summary(), "1 group, 2-of-3");
+        assert!(summary_lines(&split).contains(
+            &"SSKR: content key is also recoverable via SSKR: 1 group, 2-of-3"
+                .to_string()
+        ));
+        let json = serde_json::to_value(split.report(false)).unwrap();
+        assert_eq!(json["sskr"]["groups"][0]["count"], 3);
+        assert!(json.get("sskr_policy_missing").is_none());
+
+        let named =
+            inspect(&compose_with(&["--sskr", "board:2of3,1of1@2"]), &[])
+                .unwrap();
+        assert_eq!(
+            named.sskr.unwrap().summary(),
+            "2 of 2 groups: board 2-of-3, 1-of-1"
+        );
+
+        let plain =
+            inspect(&compose_with(&["--permit", &member]), &[]).unwrap();
+        assert!(plain.sskr.is_none() && !plain.sskr_policy_missing());
+
+        // An edition from before the policy was recorded.
+        let recorded = split
+            .inner_env
+            .assertion_with_predicate(super::super::SSKR_POLICY)
+            .unwrap();
+        let older = split.inner_env.remove_assertion(recorded);
+        let older = inspect(&older.ur_string(), &[]).unwrap();
+        assert!(older.sskr.is_none() && older.sskr_policy_missing());
+        let json = serde_json::to_value(older.report(false)).unwrap();
+        assert_eq!(json["sskr_policy_missing"], true);
+    }
+
+    #[test]
+    fn check_mode_is_silent_and_classifies_failures() {
+        use std::process::ExitCode;
+
+        use crate::cmd::{self, VERIFICATION_FAILED_EXIT};
+
+        let (composed, verifier, _) = composed_edition();
+        let outsider = public_ur(&xid_document());
+        let check = |edition: &str, extra: &[&str]| {
+            let mut argv = vec!["clubs", "--edition", edition, "--check"];
+            argv.extend_from_slice(extra);
+            let args = parse::<CommandArgs>(&argv);
+            assert!(!prints_edition_ur(&args));
+            exec(args)
+        };
+
+        assert!(
+            check(&composed.edition_ur, &["--publisher", &verifier]).is_ok()
+        );
+
+        let err = check(&composed.edition_ur, &["--publisher", &outsider])
+            .unwrap_err();
+        assert_eq!(
+            cmd::exit_code(&err),
+            ExitCode::from(VERIFICATION_FAILED_EXIT)
+        );
+
+        let err = check("ur:envelope/not-an-edition", &[]).unwrap_err();
+        assert_eq!(cmd::exit_code(&err), ExitCode::FAILURE);
+
+        assert!(check(&composed.edition_ur, &["--format", "json"]).is_err());
+        assert!(
+            try_parse::<CommandArgs>(&[
+                "clubs",
+                "--edition",
+                &composed.edition_ur,
+                "--check",
+                "--emit-permits",
+            ])
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Deserialize;
+
+use super::compose::{self, CommandArgs};
+
+/// Declarative description of a compose run, read from TOML. Keys mirror
+/// the `edition compose` flags; UR-valued keys accept inline URs or `@path`
+/// indirections, and relative paths resolve against the manifest's
+/// directory.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Manifest {
+    publisher: Option<String>,
+    content: Option<String>,
+    provenance: Option<String>,
+    provenance_state: Option<PathBuf>,
+    previous: Option<String>,
+    #[serde(default)]
+    recipients: Vec<String>,
+    #[serde(default)]
+    sskr: Vec<String>,
+    title: Option<String>,
+    #[serde(default)]
+    notes: Vec<String>,
+    out_dir: Option<PathBuf>,
+    receipt: Option<PathBuf>,
+    carry_permits: Option<PathBuf>,
+    signing_key_ref: Option<String>,
+    permit_expiry: Option<String>,
+    pad_permits: Option<usize>,
+    max_size: Option<usize>,
+    max_content_size: Option<usize>,
+    self_permit: Option<bool>,
+    genesis: Option<bool>,
+    strict: Option<bool>,
+    bind_digest: Option<bool>,
+    allow_date_regression: Option<bool>,
+    embed_publisher_keys: Option<bool>,
+    annotate_recipient_refs: Option<bool>,
+}
+
+impl Manifest {
+    /// Read and validate a manifest file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).with_context(|| {
+            format!("failed to read manifest '{}'", path.display())
+        })?;
+        Self::parse(&text)
+            .with_context(|| format!("invalid manifest '{}'", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let manifest: Self =
+            toml::from_str(text).map_err(|err| anyhow!(err))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.provenance.is_some() && self.provenance_state.is_some() {
+            bail!("manifest sets both 'provenance' and 'provenance-state'");
+        }
+        let fields = [
+            ("publisher", &self.publisher),
+            ("content", &self.content),
+            ("provenance", &self.provenance),
+            ("previous", &self.previous),
+        ];
+        for (key, value) in fields {
+            if value.as_deref().is_some_and(|v| v.trim().is_empty()) {
+                bail!("manifest key '{key}' is empty");
+            }
+        }
+        if let Some(index) =
+            self.recipients.iter().position(|r| r.trim().is_empty())
+        {
+            bail!("manifest 'recipients' entry {} is empty", index + 1);
+        }
+        if self.pad_permits.is_some_and(|n| n < self.recipients.len()) {
+            bail!(
+                "manifest 'pad-permits' is smaller than its {} recipients",
+                self.recipients.len()
+            );
+        }
+        Ok(())
+    }
+
+    /// Fill every option not given on the command line from the manifest.
+    /// Explicit flags win, including `--no-flag` over a manifest `true`;
+    /// list flags replace the manifest's list.
+    fn merge_into(self, base: &Path, mut args: CommandArgs) -> CommandArgs {
+        let spec = |value: String| resolve_spec(base, value);
+        let path = |value: PathBuf| base.join(value);
+
+        args.publisher = args.publisher.or(self.publisher.map(spec));
+        args.content = args.content.or(self.content.map(spec));
+        if args.provenance.is_none() && args.provenance_state.is_none() {
+            args.provenance = self.provenance.map(spec);
+            args.provenance_state = self.provenance_state.map(path);
+        }
+        args.previous = args.previous.or(self.previous.map(spec));
+        if args.permits.is_empty() {
+            args.permits = self.recipients.into_iter().map(spec).collect();
+        }
+        if args.sskr.is_empty() {
+            args.sskr = self.sskr;
+        }
+        args.title = args.title.or(self.title);
+        if args.notes.is_empty() {
+            args.notes = self.notes;
+        }
+        args.out_dir = args.out_dir.or(self.out_dir.map(path));
+        args.receipt = args.receipt.or(self.receipt.map(path));
+        args.carry_permits =
+            args.carry_permits.or(self.carry_permits.map(path));
+        args.signing_key_ref = args.signing_key_ref.or(self.signing_key_ref);
+        args.permit_expiry = args.permit_expiry.or(self.permit_expiry);
+        args.pad_permits = args.pad_permits.or(self.pad_permits);
+        args.max_size = args.max_size.or(self.max_size);
+        args.max_content_size = args.max_content_size.or(self.max_content_size);
+        let switches = [
+            (&mut args.self_permit, &mut args.no_self_permit, self.self_permit),
+            (&mut args.genesis, &mut args.no_genesis, self.genesis),
+            (&mut args.strict, &mut args.no_strict, self.strict),
+            (
+                &mut args.bind_digest,
+                &mut args.no_bind_digest,
+                self.bind_digest,
+            ),
+            (
+                &mut args.allow_date_regression,
+                &mut args.no_allow_date_regression,
+                self.allow_date_regression,
+            ),
+            (
+                &mut args.embed_publisher_keys,
+                &mut args.no_embed_publisher_keys,
+                self.embed_publisher_keys,
+            ),
+            (
+                &mut args.annotate_recipient_refs,
+                &mut args.no_annotate_recipient_refs,
+                self.annotate_recipient_refs,
+            ),
+        ];
+        for (on, off, value) in switches {
+            compose::default_switch(on, off, value);
+        }
+        args
+    }
+}
+
+/// Apply `--manifest`, if given, to the parsed compose arguments.
+pub fn apply(mut args: CommandArgs) -> Result<CommandArgs> {
+    let Some(path) = args.manifest.take() else {
+        return Ok(args);
+    };
+    let manifest = Manifest::load(&path)?;
+    let base = path.parent().unwrap_or(Path::new("")).to_path_buf();
+    Ok(manifest.merge_into(&base, args))
+}
+
+/// Rebase a relative `@path` reference onto the manifest directory; inline
+/// URs and stdin (`@-`) pass through unchanged.
+fn resolve_spec(base: &Path, value: String) -> String {
+    match value.strip_prefix('@').map(str::trim) {
+        Some(path) if path != "-" && Path::new(path).is_relative() => {
+            format!("@{}", base.join(path).display())
+        }
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::parse;
+
+    const FIXTURE: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/manifest.toml");
+
+    fn parse_cli(extra: &[&str]) -> CommandArgs {
+        let mut argv = vec!["clubs", "--manifest", FIXTURE];
+        argv.extend_from_slice(extra);
+        apply(parse::<CommandArgs>(&argv)).unwrap()
+    }
+
+    #[test]
+    fn sample_manifest_fills_compose_arguments() {
+        let fixtures = Path::new(FIXTURE).parent().unwrap();
+        let args = parse_cli(&[]);
+        assert_eq!(
+            args.publisher,
+            Some(format!("@{}", fixtures.join("publisher.ur").display()))
+        );
+        assert_eq!(args.permits.len(), 2);
+        assert_eq!(
+            args.permits[1],
+            format!("@{}", fixtures.join("members/bob.ur").display())
+        );
+        assert_eq!(args.sskr, vec!["board:2of3", "staff:3of5"]);
+        assert_eq!(args.title.as_deref(), Some("Issue 7"));
+        assert_eq!(args.out_dir, Some(fixtures.join("out")));
+        assert!(args.bind_digest);
+        assert!(args.strict);
+        assert!(!args.self_permit && !args.no_self_permit);
+        assert!(args.manifest.is_none());
+    }
+
+    #[test]
+    fn explicit_flags_override_the_manifest() {
+        let args = parse_cli(&[
+            "--title",
+            "Special issue",
+            "--permit",
+            "@alice.ur",
+            "--out-dir",
+            "elsewhere",
+        ]);
+        assert_eq!(args.title.as_deref(), Some("Special issue"));
+        assert_eq!(args.permits, vec!["@alice.ur"]);
+        assert_eq!(args.out_dir, Some(PathBuf::from("elsewhere")));
+    }
+
+    #[test]
+    fn sample_manifest_composes_a_dry_run() {
+        use bc_envelope::prelude::*;
+        use provenance_mark::{
+            ProvenanceMarkGenerator, ProvenanceMarkResolution,
+        };
+
+        use crate::{
+            cmd::edition::{compose, mint_bound_mark},
+            test_support::{
+                compose_edition, private_ur, public_ur, xid_document,
+            },
+        };
+
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-manifest-sample-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for sub in ["marks", "editions", "members"] {
+            fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        let write =
+            |name: &str, ur: &str| fs::write(dir.join(name), ur).unwrap();
+
+        let publisher = xid_document();
+        let content = Envelope::new("issue 7");
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        let marks: Vec<_> = (0..7)
+            .map(|_| generator.next(dcbor::Date::now(), None::<String>))
+            .collect();
+        let bound =
+            mint_bound_mark(&mut generator, &content, dcbor::Date::now())
+                .unwrap();
+        write("publisher.ur", &private_ur(&publisher));
+        write("content.ur", &content.ur_string());
+        write("marks/0007.ur", &bound.ur_string());
+        write(
+            "editions/0006.ur",
+            &compose_edition(&publisher, &marks[6], &[]),
+        );
+        write("members/alice.ur", &public_ur(&xid_document()));
+        write("members/bob.ur", &public_ur(&xid_document()));
+        let manifest = dir.join("manifest.toml");
+        fs::copy(FIXTURE, &manifest).unwrap();
+
+        let manifest = manifest.display().to_string();
+        let mut args = apply(parse::<CommandArgs>(&[
+            "clubs",
+            "--manifest",
+            &manifest,
+            "--dry-run",
+        ]))
+        .unwrap();
+        args.quiet = true;
+        let composed = compose::run(args).unwrap();
+        assert_eq!(composed.seq, 7);
+        assert_eq!(composed.permits.len(), 8);
+        assert_eq!(composed.shares.len(), 8);
+        assert!(!dir.join("out").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn boolean_keys_can_be_switched_off() {
+        let args = parse_cli(&["--no-strict", "--self-permit"]);
+        assert!(!args.strict);
+        assert!(args.bind_digest);
+        assert!(args.self_permit);
+
+        let manifest =
+            Manifest::parse("self-permit = false\nstrict = true\n").unwrap();
+        let args = manifest.merge_into(
+            Path::new(""),
+            parse::<CommandArgs>(&["clubs", "--no-strict"]),
+        );
+        assert!(!args.self_permit && args.no_self_permit);
+        assert!(!args.strict);
+
+        // The later of a switch and its `--no-` form wins.
+        let args = parse::<CommandArgs>(&[
+            "clubs",
+            "--strict",
+            "--no-strict",
+            "--no-genesis",
+            "--genesis",
+        ]);
+        assert!(!args.strict && args.no_strict);
+        assert!(args.genesis && !args.no_genesis);
+        assert!(
+            crate::test_support::try_parse::<CommandArgs>(&[
+                "clubs",
+                "--strict=false"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys_with_location() {
+        let err = Manifest::parse("title = \"x\"\nrecipient = []\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("recipient"), "{err}");
+        assert!(err.contains("line 2"), "{err}");
+    }
+}
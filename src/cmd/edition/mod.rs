@@ -1,10 +1,36 @@
+pub mod add_permit;
 pub mod compose;
+pub mod diff;
+pub mod inspect;
+pub mod manifest;
+pub mod obscured;
+pub mod permit_stats;
 pub mod permits;
+pub mod receipt;
+pub mod redact;
 pub mod sequence;
+pub mod size;
 pub mod verify;
 
-use anyhow::Result;
-use clap::{Args, Subcommand};
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, bail};
+use bc_components::{
+    Digest, Reference, ReferenceProvider, SealedMessage, Signature,
+    SymmetricKey, XID,
+};
+use bc_envelope::prelude::*;
+use clap::{Args, Subcommand, ValueEnum};
+use clubs::edition::Edition;
+use provenance_mark::{
+    ProvenanceMark, ProvenanceMarkGenerator, ProvenanceMarkResolution,
+};
+use serde::Serialize;
+
+use crate::{
+    chain,
+    io::{self, PERMIT_EXPIRES, PERMIT_RECIPIENT_KEY, RecipientDescriptor},
+};
 
 #[derive(Debug, Args)]
 pub struct CommandArgs {
@@ -16,10 +42,14 @@ pub struct CommandArgs {
 pub enum Commands {
     /// Compose and sign an edition.
     Compose(compose::CommandArgs),
+    /// Inspect an edition and summarize its contents.
+    Inspect(inspect::CommandArgs),
     /// Verify the signature and provenance of an edition.
     Verify(verify::CommandArgs),
     /// Extract sealed permits from an edition.
     Permits(permits::CommandArgs),
+    /// Compare two editions structurally.
+    Diff(diff::CommandArgs),
     /// Validate a sequence of editions for provenance continuity.
     Sequence(sequence::CommandArgs),
 }
@@ -27,8 +57,511 @@ pub enum Commands {
 pub fn exec(args: CommandArgs) -> Result<()> {
     match args.command {
         Commands::Compose(args) => compose::exec(args),
+        Commands::Inspect(args) => inspect::exec(args),
         Commands::Verify(args) => verify::exec(args),
         Commands::Permits(args) => permits::exec(args),
+        Commands::Diff(args) => diff::exec(args),
         Commands::Sequence(args) => sequence::exec(args),
     }
 }
+
+/// Signature metadata predicate carrying the signer's public keys, for
+/// editions composed with `--embed-publisher-keys`.
+pub const PUBLISHER_KEYS: &str = "publisherKeys";
+
+/// Signature metadata predicate naming the signer: an XID, public keys, or
+/// free text. No known value is registered for it.
+pub const SIGNER: &str = "signer";
+
+/// Edition assertion recording the SSKR policy the content key was split
+/// under, for editions composed with `--sskr`.
+pub const SSKR_POLICY: &str = "sskr";
+
+/// The signature in the object of a `'signed'` assertion, and the
+/// metadata signed with it. A signature with metadata is wrapped together
+/// with its assertions and signed again; a plain one is a bare leaf.
+pub fn signature_parts(
+    object: &Envelope,
+) -> (Option<Signature>, Option<Envelope>) {
+    let metadata = if object.subject().is_wrapped() {
+        object.try_unwrap().ok()
+    } else {
+        None
+    };
+    let signature = metadata
+        .as_ref()
+        .unwrap_or(object)
+        .extract_subject::<Signature>()
+        .ok();
+    (signature, metadata)
+}
+
+/// Canonical identifier of a sealed permit: the digest of its tagged CBOR.
+pub fn sealed_digest(sealed: &SealedMessage) -> Digest {
+    Digest::from_image(sealed.tagged_cbor().to_cbor_data())
+}
+
+/// The additional authenticated data `sealed` was bound to. `SealedMessage`
+/// keeps its encrypted message private, so it is read back from the CBOR.
+#[cfg(test)]
+pub fn sealed_aad(sealed: &SealedMessage) -> Vec<u8> {
+    let message = sealed
+        .untagged_cbor()
+        .try_into_array()
+        .ok()
+        .and_then(|elements| elements.into_iter().next())
+        .and_then(|cbor| bc_components::EncryptedMessage::try_from(cbor).ok())
+        .expect("a sealed message encodes its encrypted message first");
+    message.aad().to_vec()
+}
+
+/// The digest permits are sealed under: `edition` without its permits,
+/// the edition id `Edition::seal_with_permits` binds them to.
+pub fn edition_id(edition: &Edition) -> Digest {
+    let mut core = edition.clone();
+    core.permits.clear();
+    Envelope::from(core).digest()
+}
+
+/// Decode the unwrapped edition `inner`. `Edition::try_from` refuses the
+/// assertions this CLI adds, such as the SSKR policy, so it is handed only
+/// the type, the first club and provenance assertions, and each readable
+/// permit reduced to its sealed message and holder. The holder is read
+/// from the permit object, where `permit_object` records it, or else from
+/// the permit assertion. `inspect --strict` reports what is left out.
+pub fn decode_edition(inner: &Envelope) -> Result<Edition> {
+    inner.check_type("Edition")?;
+    let mut core = inner.subject().add_type("Edition");
+    let first = |predicate: Envelope| {
+        inner.assertions_with_predicate(predicate).into_iter().next()
+    };
+    for assertion in [
+        first(Envelope::new("club")),
+        first(Envelope::new(known_values::PROVENANCE)),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        core = core.add_assertion_envelope(assertion)?;
+    }
+    let permits = inner.assertions_with_predicate(known_values::HAS_RECIPIENT);
+    for assertion in permits {
+        let Some(permit) = assertion.as_object() else {
+            continue;
+        };
+        let Ok(sealed) = permit.extract_subject::<SealedMessage>() else {
+            continue;
+        };
+        let holder = [&permit, &assertion].into_iter().find_map(|envelope| {
+            envelope
+                .extract_optional_object_for_predicate::<XID>(
+                    known_values::HOLDER,
+                )
+                .ok()
+                .flatten()
+        });
+        let mut reduced =
+            Envelope::new_assertion(known_values::HAS_RECIPIENT, sealed);
+        if let Some(holder) = holder {
+            reduced = reduced.add_assertion(known_values::HOLDER, holder);
+        }
+        core = core.add_assertion_envelope(reduced)?;
+    }
+    Ok(Edition::try_from(core)?)
+}
+
+/// Expiry dates attached to the permits of an (unwrapped) edition envelope,
+/// keyed by `sealed_digest`. Permits without an expiry are omitted.
+pub fn permit_expiries(inner: &Envelope) -> Result<HashMap<Digest, Date>> {
+    let mut expiries = HashMap::new();
+    for permit in inner.objects_for_predicate(known_values::HAS_RECIPIENT) {
+        let Some(expires) = permit
+            .extract_optional_object_for_predicate::<Date>(PERMIT_EXPIRES)
+            .context("permit expiry must be a date")?
+        else {
+            continue;
+        };
+        let sealed = permit
+            .extract_subject::<SealedMessage>()
+            .context("permit subject is not a sealed message")?;
+        expiries.insert(sealed_digest(&sealed), expires);
+    }
+    Ok(expiries)
+}
+
+/// Recipient key references recorded on the unannotated permits of an
+/// (unwrapped) edition envelope, keyed by `sealed_digest`.
+pub fn permit_recipient_keys(
+    inner: &Envelope,
+) -> Result<HashMap<Digest, Reference>> {
+    let mut references = HashMap::new();
+    for permit in inner.objects_for_predicate(known_values::HAS_RECIPIENT) {
+        let Some(object) = permit
+            .optional_object_for_predicate(PERMIT_RECIPIENT_KEY)
+            .context("permit has more than one recipient key")?
+        else {
+            continue;
+        };
+        let bytes = object
+            .subject()
+            .as_leaf()
+            .and_then(|cbor| cbor.try_into_byte_string().ok())
+            .context("permit recipient key is not a byte string")?;
+        let reference = Reference::from_data_ref(bytes)
+            .context("permit recipient key is not a key reference")?;
+        let sealed = permit
+            .extract_subject::<SealedMessage>()
+            .context("permit subject is not a sealed message")?;
+        references.insert(sealed_digest(&sealed), reference);
+    }
+    Ok(references)
+}
+
+/// A `'hasRecipient'` permit sealing `key` to `descriptor` under `aad`,
+/// with the descriptor's own expiry or else `default_expiry`, and with
+/// `annotate` the recipient key reference `edition permits
+/// --recipients-file` matches anonymous permits by. Shared by `compose`
+/// and `add-permit`, so a permit added later looks like a composed one.
+pub fn recipient_permit(
+    descriptor: &RecipientDescriptor,
+    key: &SymmetricKey,
+    aad: &[u8],
+    default_expiry: Option<&Date>,
+    annotate: bool,
+) -> Envelope {
+    let public_keys = descriptor.public_keys();
+    let sealed = SealedMessage::new_with_aad(
+        key.tagged_cbor().to_cbor_data(),
+        public_keys,
+        Some(aad),
+    );
+    permit_object(
+        sealed,
+        descriptor.member_xid(),
+        descriptor.expires().or(default_expiry),
+        annotate.then(|| public_keys.reference()),
+    )
+}
+
+/// The `'hasRecipient'` object for `sealed`: its holder, expiry, and
+/// recipient key reference, each when given. `Edition` reads it back as a
+/// sealed `PublicKeyPermit`.
+pub fn permit_object(
+    sealed: SealedMessage,
+    holder: Option<XID>,
+    expires: Option<&Date>,
+    reference: Option<Reference>,
+) -> Envelope {
+    let mut permit = Envelope::new(sealed);
+    if let Some(holder) = holder {
+        permit = permit.add_assertion(known_values::HOLDER, holder);
+    }
+    if let Some(expires) = expires {
+        permit = permit.add_assertion(PERMIT_EXPIRES, *expires);
+    }
+    if let Some(reference) = reference {
+        permit = permit.add_assertion(
+            PERMIT_RECIPIENT_KEY,
+            CBOR::to_byte_string(reference.data()),
+        );
+    }
+    permit
+}
+
+/// Whether `expires` lies in the past relative to `now`.
+pub fn is_expired(expires: &Date, now: &Date) -> bool {
+    expires.timestamp() <= now.timestamp()
+}
+
+/// How strongly one edition's mark is shown to come immediately before
+/// another's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Linkage {
+    /// The previous mark's key chain generates the next mark, as checked by
+    /// `chain::links`; their dates are compared separately.
+    Cryptographic,
+    /// Only the sequence numbers are adjacent; the marks may come from
+    /// different chains.
+    SequenceOnly,
+}
+
+impl Linkage {
+    /// The linkage from `previous` to `mark`, or `None` when `previous`
+    /// does not even have the preceding sequence number.
+    pub fn between(
+        previous: &ProvenanceMark,
+        mark: &ProvenanceMark,
+    ) -> Option<Self> {
+        if chain::links(previous, mark) {
+            Some(Self::Cryptographic)
+        } else if previous.seq().checked_add(1) == Some(mark.seq()) {
+            Some(Self::SequenceOnly)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Cryptographic => "cryptographic",
+            Self::SequenceOnly => "sequence-only",
+        }
+    }
+}
+
+/// Outcome of comparing a provenance mark's info digest with the content it
+/// is bound to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DigestBinding {
+    /// The mark's info field holds the content digest.
+    Match,
+    /// The mark's info field holds a different digest.
+    Mismatch { bound: Digest, content: Digest },
+    /// The mark carries no info field, or one that is not a digest.
+    Absent,
+}
+
+impl DigestBinding {
+    /// Compare `mark`'s info digest with the digest of `content`. Marks bind
+    /// the content as authored, while an edition seals it wrapped (and
+    /// encrypted), so sealed content matches when it wraps the bound digest.
+    pub fn check(mark: &ProvenanceMark, content: &Envelope) -> Self {
+        let Some(bound) =
+            mark.info().and_then(|info| Digest::try_from(info).ok())
+        else {
+            return Self::Absent;
+        };
+        let sealed = content.is_wrapped() || content.is_encrypted();
+        let content = content.digest();
+        if bound == content || (sealed && wrapped_digest(&bound) == content) {
+            Self::Match
+        } else {
+            Self::Mismatch { bound, content }
+        }
+    }
+
+    /// Short machine-readable name of the outcome.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Match => "match",
+            Self::Mismatch { .. } => "mismatch",
+            Self::Absent => "absent",
+        }
+    }
+
+    /// One-line description for summaries.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Match => "bound and matching".to_string(),
+            Self::Mismatch { bound, content } => format!(
+                "bound but MISMATCHED (mark binds {}, content is {})",
+                bound.hex(),
+                content.hex()
+            ),
+            Self::Absent => "no digest binding".to_string(),
+        }
+    }
+}
+
+/// The digest of a wrapped envelope whose content has `digest`, which is
+/// the digest sealed content declares, encrypted or not.
+fn wrapped_digest(digest: &Digest) -> Digest {
+    Digest::from_digests(std::slice::from_ref(digest))
+}
+
+/// Resolution of newly minted provenance marks, as named on the command
+/// line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum MarkResolution {
+    Low,
+    Medium,
+    #[default]
+    Quartile,
+    High,
+}
+
+impl From<MarkResolution> for ProvenanceMarkResolution {
+    fn from(resolution: MarkResolution) -> Self {
+        match resolution {
+            MarkResolution::Low => Self::Low,
+            MarkResolution::Medium => Self::Medium,
+            MarkResolution::Quartile => Self::Quartile,
+            MarkResolution::High => Self::High,
+        }
+    }
+}
+
+/// Command-line name of a mark resolution.
+pub fn resolution_label(resolution: ProvenanceMarkResolution) -> &'static str {
+    match resolution {
+        ProvenanceMarkResolution::Low => "low",
+        ProvenanceMarkResolution::Medium => "medium",
+        ProvenanceMarkResolution::Quartile => "quartile",
+        ProvenanceMarkResolution::High => "high",
+    }
+}
+
+/// Parse a `--date` value for minting: `now` or an RFC 3339 date.
+pub fn parse_mark_date(spec: Option<&str>) -> Result<Date> {
+    match spec.map(str::trim) {
+        None | Some("now") => Ok(Date::now()),
+        Some(spec) => io::parse_date(spec),
+    }
+}
+
+/// Mint the next mark from `generator`, committing it to `content` by
+/// placing the content digest in the mark's info field. Fails if the
+/// generator's resolution cannot encode `date` or carry the digest.
+pub fn mint_bound_mark(
+    generator: &mut ProvenanceMarkGenerator,
+    content: &Envelope,
+    date: Date,
+) -> Result<ProvenanceMark> {
+    let resolution = *generator.res();
+    if resolution.serialize_date(date).is_err() {
+        bail!(
+            "date {date} is outside the range of {} resolution provenance marks",
+            resolution_label(resolution)
+        );
+    }
+    let mark = generator.next(date, Some(content.digest()));
+    // The in-memory mark always holds the info it was given; what matters
+    // is whether the digest survives the mark's encoded form.
+    let encoded = ProvenanceMark::from_ur_string(mark.ur_string())
+        .context("minted provenance mark does not decode")?;
+    if DigestBinding::check(&encoded, content) != DigestBinding::Match {
+        bail!(
+            "{} resolution provenance marks cannot carry the content digest",
+            resolution_label(resolution)
+        );
+    }
+    Ok(mark)
+}
+
+#[cfg(test)]
+mod tests {
+    use provenance_mark::{ProvenanceMarkGenerator, ProvenanceMarkResolution};
+
+    use super::*;
+    use crate::test_support::register_tags;
+
+    fn mark(info: Option<Digest>) -> ProvenanceMark {
+        let mut generator = ProvenanceMarkGenerator::new_with_passphrase(
+            ProvenanceMarkResolution::Quartile,
+            "digest binding",
+        );
+        generator.next(Date::now(), info)
+    }
+
+    #[test]
+    fn reports_digest_binding_states() {
+        let content = Envelope::new("edition content");
+        let other = Envelope::new("something else");
+
+        let bound = mark(Some(content.digest()));
+        assert_eq!(
+            DigestBinding::check(&bound, &content),
+            DigestBinding::Match
+        );
+        assert!(matches!(
+            DigestBinding::check(&bound, &other),
+            DigestBinding::Mismatch { .. }
+        ));
+        assert_eq!(
+            DigestBinding::check(&mark(None), &content),
+            DigestBinding::Absent
+        );
+
+        // Sealed content declares the wrapped digest, encrypted or not.
+        let wrapped = content.wrap();
+        assert_eq!(wrapped.digest(), wrapped_digest(&content.digest()));
+        let encrypted = wrapped.encrypt_subject(&SymmetricKey::new()).unwrap();
+        for sealed in [&wrapped, &encrypted] {
+            assert_eq!(
+                DigestBinding::check(&bound, sealed),
+                DigestBinding::Match
+            );
+        }
+        let other = other.wrap().encrypt_subject(&SymmetricKey::new()).unwrap();
+        assert!(matches!(
+            DigestBinding::check(&bound, &other),
+            DigestBinding::Mismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn minted_marks_bind_content_and_chain_from_saved_state() {
+        register_tags();
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        let genesis_content = Envelope::new("genesis");
+        let genesis =
+            mint_bound_mark(&mut generator, &genesis_content, Date::now())
+                .unwrap();
+        assert!(genesis.is_genesis());
+        assert_eq!(
+            DigestBinding::check(&genesis, &genesis_content),
+            DigestBinding::Match
+        );
+
+        let state = crate::io::provenance_state_ur(&generator).unwrap();
+        let (mut restored, _) = crate::io::parse_provenance_state(
+            &state,
+            &crate::io::PassphraseArgs::default(),
+        )
+        .unwrap();
+        let next_content = Envelope::new("edition 2");
+        let next =
+            mint_bound_mark(&mut restored, &next_content, Date::now()).unwrap();
+        assert_eq!(next.seq(), 1);
+        assert!(genesis.precedes(&next));
+        assert_eq!(
+            DigestBinding::check(&next, &next_content),
+            DigestBinding::Match
+        );
+    }
+
+    #[test]
+    fn every_resolution_encodes_the_full_content_digest() {
+        register_tags();
+        let content = Envelope::new("edition content");
+        let encoded_len =
+            |mark: &ProvenanceMark| mark.ur().cbor().to_cbor_data().len();
+        for resolution in [
+            MarkResolution::Low,
+            MarkResolution::Medium,
+            MarkResolution::Quartile,
+            MarkResolution::High,
+        ] {
+            let resolution = ProvenanceMarkResolution::from(resolution);
+            let date = Date::now();
+            let generator = || {
+                ProvenanceMarkGenerator::new_with_passphrase(resolution, "cap")
+            };
+            let (mut bound_generator, mut bare_generator) =
+                (generator(), generator());
+            let bound =
+                mint_bound_mark(&mut bound_generator, &content, date)
+                    .unwrap();
+            let bare = bare_generator.next(date, None::<Digest>);
+
+            let label = resolution_label(resolution);
+            let decoded =
+                ProvenanceMark::from_ur_string(bound.ur_string()).unwrap();
+            assert_eq!(
+                DigestBinding::check(&decoded, &content),
+                DigestBinding::Match,
+                "{label}"
+            );
+            // Every byte of the digest is carried, whatever the link size.
+            assert!(
+                encoded_len(&bound) >= encoded_len(&bare) + Digest::DIGEST_SIZE,
+                "{label}: {} vs {}",
+                encoded_len(&bound),
+                encoded_len(&bare)
+            );
+        }
+    }
+}
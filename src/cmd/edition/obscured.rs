@@ -0,0 +1,113 @@
+use bc_envelope::prelude::*;
+use known_values::{HAS_RECIPIENT_RAW, PROVENANCE_RAW};
+use serde::Serialize;
+
+/// Obscured (elided, encrypted, or compressed) assertions found on an
+/// unwrapped edition envelope, as left by selective disclosure.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ObscuredNodes {
+    /// Assertions that are obscured as a whole or whose object is.
+    pub assertions: usize,
+    /// Of those, permits (`'hasRecipient'` assertions with obscured objects).
+    pub permits: usize,
+    /// The `"club"` assertion's object is obscured.
+    pub club: bool,
+    /// The `'provenance'` assertion's object is obscured.
+    pub provenance: bool,
+}
+
+impl ObscuredNodes {
+    /// Count the obscured assertions on `inner`.
+    pub fn survey(inner: &Envelope) -> Self {
+        let mut found = Self::default();
+        for assertion in inner.assertions() {
+            if !is_obscured_assertion(&assertion) {
+                continue;
+            }
+            found.assertions += 1;
+            match assertion.as_predicate() {
+                Some(predicate) if predicate == Envelope::new("club") => {
+                    found.club = true
+                }
+                Some(predicate) => {
+                    match predicate.as_known_value().map(|kv| kv.value()) {
+                        Some(HAS_RECIPIENT_RAW) => found.permits += 1,
+                        Some(PROVENANCE_RAW) => found.provenance = true,
+                        _ => {}
+                    }
+                }
+                None => {}
+            }
+        }
+        found
+    }
+
+    pub fn any(&self) -> bool { self.assertions > 0 }
+
+    /// Whether the club or provenance assertion is hidden, which leaves the
+    /// edition impossible to place in its chain.
+    pub fn blocks_chain_validation(&self) -> bool {
+        self.club || self.provenance
+    }
+
+    /// One-line description, e.g. `2 assertions (1 permit)`.
+    pub fn describe(&self) -> String {
+        if !self.any() {
+            return "none".to_string();
+        }
+        let mut parts = Vec::new();
+        if self.permits > 0 {
+            parts.push(plural(self.permits, "permit"));
+        }
+        if self.club {
+            parts.push("the club assertion".to_string());
+        }
+        if self.provenance {
+            parts.push("the provenance assertion".to_string());
+        }
+        if parts.is_empty() {
+            plural(self.assertions, "assertion")
+        } else {
+            format!(
+                "{} ({})",
+                plural(self.assertions, "assertion"),
+                parts.join(", ")
+            )
+        }
+    }
+}
+
+/// `inner` without its obscured assertions, so the remaining edition can be
+/// parsed. The digest changes, so only use the result for reading.
+pub fn strip_obscured(inner: &Envelope) -> Envelope {
+    inner
+        .assertions()
+        .into_iter()
+        .filter(is_obscured_assertion)
+        .fold(inner.clone(), |envelope, assertion| {
+            envelope.remove_assertion(assertion)
+        })
+}
+
+/// Whether `assertion`, its predicate, or its object (or the object's
+/// subject) is obscured.
+pub fn is_obscured_assertion(assertion: &Envelope) -> bool {
+    if assertion.is_obscured() {
+        return true;
+    }
+    let hidden = |part: Option<Envelope>| {
+        part.is_some_and(|part| {
+            part.is_obscured() || part.subject().is_obscured()
+        })
+    };
+    hidden(assertion.as_predicate()) || hidden(assertion.as_object())
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
@@ -0,0 +1,293 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use anyhow::{Context, Result};
+use bc_components::XID;
+use clap::Args;
+use clubs::edition::Edition;
+use serde::Serialize;
+
+use super::permits;
+use crate::{
+    directory::MemberDirectory,
+    io,
+    output::{self, OutputFormat},
+};
+
+/// Report permit counts and membership changes across an edition chain.
+#[derive(Debug, Args)]
+pub struct CommandArgs {
+    /// Directory of edition `.ur` files; they are ordered by provenance
+    /// seq.
+    #[arg(long, value_name = "DIR")]
+    pub edition_dir: PathBuf,
+    /// Publisher descriptor (XID document or public-keys UR); editions that
+    /// do not verify against it are reported and skipped.
+    #[arg(long, value_name = "UR")]
+    pub publisher: Option<String>,
+    /// Member directory (TOML mapping XIDs to names) used to name added and
+    /// removed holders.
+    #[arg(long, value_name = "PATH")]
+    pub directory: Option<PathBuf>,
+    /// Output format. `json` prints the time series as one object on
+    /// stdout.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+pub fn exec(args: CommandArgs) -> Result<()> {
+    let descriptor = args
+        .publisher
+        .as_ref()
+        .map(|spec| {
+            io::parse_recipient_descriptor(spec)
+                .context("failed to parse publisher input")
+        })
+        .transpose()?;
+    let directory = args
+        .directory
+        .as_deref()
+        .map(MemberDirectory::load)
+        .transpose()?;
+    let specs: Vec<String> = io::ur_files_in(&args.edition_dir)?
+        .into_iter()
+        .map(|path| format!("@{}", path.display()))
+        .collect();
+    let editions: Vec<Edition> =
+        permits::load_editions(&specs, descriptor.as_ref(), false)?
+            .into_iter()
+            .map(|(edition, _)| edition)
+            .collect();
+    let series = PermitSeries::build(&editions, directory.as_ref());
+
+    match args.format {
+        OutputFormat::Text => {
+            for line in series.lines() {
+                eprintln!("{line}");
+            }
+        }
+        OutputFormat::Json => output::print_json(&series)?,
+    }
+    Ok(())
+}
+
+/// Permit statistics for each edition of a chain, in provenance order.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PermitSeries {
+    pub editions: Vec<EditionPermitStats>,
+    /// Unannotated permits across all editions, which the deltas leave out.
+    pub anonymous: usize,
+}
+
+/// One edition's permit counts and its holder changes since the previous
+/// edition.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct EditionPermitStats {
+    pub seq: u32,
+    pub permits: usize,
+    /// Distinct annotated holders.
+    pub holders: usize,
+    pub anonymous: usize,
+    /// Holders without a permit in the previous edition; absent for the
+    /// first edition.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub added: Option<Vec<HolderEntry>>,
+    /// Holders of the previous edition without a permit in this one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed: Option<Vec<HolderEntry>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct HolderEntry {
+    pub xid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl HolderEntry {
+    fn new(xid: &XID, directory: Option<&MemberDirectory>) -> Self {
+        Self {
+            xid: xid.to_string(),
+            name: directory
+                .and_then(|directory| directory.name(xid))
+                .map(str::to_owned),
+        }
+    }
+
+    fn label(&self) -> String {
+        match self.name.as_deref() {
+            Some(name) => format!("{name} {}", self.xid),
+            None => self.xid.clone(),
+        }
+    }
+}
+
+impl PermitSeries {
+    /// Statistics for `editions`, which must be in provenance order.
+    pub fn build(
+        editions: &[Edition],
+        directory: Option<&MemberDirectory>,
+    ) -> Self {
+        let mut stats = Vec::with_capacity(editions.len());
+        let mut previous: Option<HashSet<XID>> = None;
+        let mut anonymous = 0;
+        for edition in editions {
+            let permits = permits::sealed_permits(edition);
+            let holders: HashSet<XID> =
+                permits.iter().filter_map(|(_, holder)| *holder).collect();
+            let unannotated = permits
+                .iter()
+                .filter(|(_, holder)| holder.is_none())
+                .count();
+            anonymous += unannotated;
+            let delta = |from: &HashSet<XID>, to: &HashSet<XID>| {
+                let mut entries: Vec<HolderEntry> = to
+                    .difference(from)
+                    .map(|xid| HolderEntry::new(xid, directory))
+                    .collect();
+                entries.sort_by(|a, b| a.xid.cmp(&b.xid));
+                entries
+            };
+            stats.push(EditionPermitStats {
+                seq: edition.provenance.seq(),
+                permits: permits.len(),
+                holders: holders.len(),
+                anonymous: unannotated,
+                added: previous.as_ref().map(|prior| delta(prior, &holders)),
+                removed: previous.as_ref().map(|prior| delta(&holders, prior)),
+            });
+            previous = Some(holders);
+        }
+        Self { editions: stats, anonymous }
+    }
+
+    /// A table of counts, each edition's changes beneath its row, and a
+    /// note when unannotated permits were left out of the deltas.
+    pub fn lines(&self) -> Vec<String> {
+        if self.editions.is_empty() {
+            return vec!["Editions: none".to_string()];
+        }
+        let mut lines = vec![format!(
+            "{:>6}  {:>7}  {:>7}  {:>9}  {:>5}  {:>7}",
+            "seq", "permits", "holders", "anonymous", "added", "removed"
+        )];
+        let count = |entries: &Option<Vec<HolderEntry>>| {
+            entries
+                .as_ref()
+                .map_or("-".to_string(), |entries| entries.len().to_string())
+        };
+        for edition in &self.editions {
+            lines.push(format!(
+                "{:>6}  {:>7}  {:>7}  {:>9}  {:>5}  {:>7}",
+                edition.seq,
+                edition.permits,
+                edition.holders,
+                edition.anonymous,
+                count(&edition.added),
+                count(&edition.removed)
+            ));
+            for entry in edition.added.iter().flatten() {
+                lines.push(format!("        + {}", entry.label()));
+            }
+            for entry in edition.removed.iter().flatten() {
+                lines.push(format!("        - {}", entry.label()));
+            }
+        }
+        if self.anonymous > 0 {
+            lines.push(format!(
+                "Note: {} unannotated permit(s) are counted but left out of the added/removed deltas",
+                self.anonymous
+            ));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use bc_components::{PrivateKeyBase, PublicKeysProvider, XIDProvider};
+    use bc_envelope::prelude::*;
+
+    use super::*;
+    use crate::test_support::{compose_for, marks, xid_document};
+
+    #[test]
+    fn tracks_holders_added_and_removed_along_a_chain() {
+        let publisher = xid_document();
+        let (alice, bob, carol) =
+            (xid_document(), xid_document(), xid_document());
+        let stranger = PrivateKeyBase::new().public_keys().ur_string();
+        let marks = marks(3);
+        let editions = [
+            compose_for(&publisher, &marks[0], &[&alice, &bob], &[]),
+            compose_for(&publisher, &marks[1], &[&alice, &bob, &carol], &[]),
+            compose_for(
+                &publisher,
+                &marks[2],
+                &[&alice, &carol],
+                &["--permit", &stranger],
+            ),
+        ];
+
+        // File names run against provenance order.
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-permit-stats-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (index, edition) in editions.iter().enumerate() {
+            fs::write(dir.join(format!("{}.ur", 9 - index)), edition).unwrap();
+        }
+        let names = dir.join("names.toml");
+        fs::write(
+            &names,
+            format!(
+                "[members]\n\"{}\" = \"Carol\"\n",
+                hex::encode(carol.xid().data())
+            ),
+        )
+        .unwrap();
+
+        let specs: Vec<String> = io::ur_files_in(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|path| format!("@{}", path.display()))
+            .collect();
+        let loaded: Vec<Edition> = permits::load_editions(&specs, None, false)
+            .unwrap()
+            .into_iter()
+            .map(|(edition, _)| edition)
+            .collect();
+        let directory = MemberDirectory::load(&names).unwrap();
+        let series = PermitSeries::build(&loaded, Some(&directory));
+
+        let counts: Vec<(u32, usize, usize, usize)> = series
+            .editions
+            .iter()
+            .map(|stats| {
+                (stats.seq, stats.permits, stats.holders, stats.anonymous)
+            })
+            .collect();
+        assert_eq!(counts, [(0, 2, 2, 0), (1, 3, 3, 0), (2, 3, 2, 1)]);
+        assert!(series.editions[0].added.is_none());
+        let added = series.editions[1].added.as_ref().unwrap();
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].name.as_deref(), Some("Carol"));
+        assert!(series.editions[1].removed.as_ref().unwrap().is_empty());
+        assert!(series.editions[2].added.as_ref().unwrap().is_empty());
+        let removed = series.editions[2].removed.as_ref().unwrap();
+        assert_eq!(removed[0].xid, bob.xid().to_string());
+        assert_eq!(series.anonymous, 1);
+
+        let lines = series.lines();
+        assert!(lines.iter().any(|line| line.contains("+ Carol")));
+        assert!(lines.last().unwrap().starts_with("Note: 1 unannotated"));
+        let json = serde_json::to_value(&series).unwrap();
+        assert!(json["editions"][0].get("added").is_none());
+        assert_eq!(json["editions"][2]["removed"][0]["xid"], removed[0].xid);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
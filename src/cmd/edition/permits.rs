@@ -1,46 +1,1526 @@
-use anyhow::{Context, Result};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use bc_components::{
+    Digest, PrivateKeys, Reference, ReferenceProvider, SealedMessage, XID,
+};
+use bc_envelope::prelude::*;
 use bc_ur::UREncodable;
-use clap::Args;
+use clap::{Args, Subcommand};
 use clubs::{edition::Edition, public_key_permit::PublicKeyPermit};
+use serde::Serialize;
+
+use super::{add_permit, permit_stats, redact};
+use crate::{
+    cmd::{ExpectationFailed, VerificationFailed, content::decrypt},
+    directory::MemberDirectory,
+    io::{self, RecipientDescriptor},
+    output::{self, OutputFormat},
+    workspace::edition_stem,
+};
+
+/// Index written next to extracted permit files, one `<file> <holder>` line
+/// per permit.
+pub const PERMIT_INDEX_FILE: &str = "index.txt";
 
-use crate::io;
+/// JSON index written next to extracted permit files, with the edition's
+/// provenance seq.
+pub const PERMIT_INDEX_JSON_FILE: &str = "index.json";
+
+/// Hex digits of the holder XID or sealed digest that name a permit file,
+/// unless another holder shares them.
+const PERMIT_FILE_PREFIX_LEN: usize = 8;
 
 /// Arguments for extracting sealed permits from an edition.
 #[derive(Debug, Args)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct CommandArgs {
-    /// Edition UR to inspect for permits.
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+    /// Edition UR to inspect for permits. Repeat, or add `--edition-dir`,
+    /// to extract from several editions at once; each permit is then
+    /// tagged with its edition's provenance seq.
+    #[arg(
+        long = "edition",
+        value_name = "UR",
+        required_unless_present = "edition_dir"
+    )]
+    pub editions: Vec<String>,
+    /// Extract permits from every `.ur` file in this directory.
+    #[arg(long, value_name = "DIR")]
+    pub edition_dir: Option<PathBuf>,
+    /// With several editions, stop at the first one that cannot be read or
+    /// verified instead of reporting and skipping it.
+    #[arg(long)]
+    pub fail_fast: bool,
+    /// Keep only the permits for each holder XID from the most recent
+    /// edition (highest seq) that has one. Unannotated permits are all
+    /// kept.
+    #[arg(long)]
+    pub dedupe_holders: bool,
+    /// Publisher descriptor (XID document or public-keys UR); when given,
+    /// the edition must verify against it before any permit is emitted.
     #[arg(long, value_name = "UR")]
-    pub edition: String,
-    /// Emit a human-readable summary to stderr.
+    pub publisher: Option<String>,
+    /// Extract permits without `--publisher` and without warning that the
+    /// edition is unverified.
+    #[arg(long, conflicts_with = "publisher")]
+    pub allow_unverified: bool,
+    /// Write each sealed permit to its own file in this directory, named
+    /// by holder XID or sealed digest, instead of printing them.
+    #[arg(long, value_name = "DIR", visible_alias = "out-dir")]
+    pub permits_dir: Option<PathBuf>,
+    /// Overwrite permit files and indexes already in `--permits-dir`.
+    #[arg(long, requires = "permits_dir")]
+    pub force: bool,
+    /// Emit a human-readable summary to stderr, counting annotated and
+    /// anonymous permits and listing each permit's identifiers.
     #[arg(long)]
     pub summary: bool,
+    /// Known recipients (one descriptor UR per line, as for `edition
+    /// compose --carry-permits`) to match anonymous permits against by the
+    /// recipient key reference recorded by `edition compose
+    /// --annotate-recipient-refs`.
+    #[arg(long, value_name = "PATH")]
+    pub recipients_file: Option<PathBuf>,
+    /// Member directory (TOML mapping XIDs to names), as for `edition
+    /// inspect`, used to name the known recipients permits match.
+    #[arg(long, value_name = "PATH", requires = "recipients_file")]
+    pub directory: Option<PathBuf>,
+    /// Output format. `json` prints an array of permits, each with its
+    /// identifiers and UR, instead of bare URs.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// Prefix each printed UR with its holder XID (or `anonymous`) and a
+    /// tab.
+    #[arg(long, conflicts_with_all = ["format", "permits_dir"])]
+    pub with_holders: bool,
+    /// Emit only the permits annotated with this member XID. Repeatable;
+    /// fails (exit status 3) when no permit matches.
+    #[arg(long = "member", value_name = "XID")]
+    pub members: Vec<String>,
+    /// Emit only the permits whose sealed digest starts with this hex
+    /// prefix, as shown by `edition inspect` and the compose permit
+    /// manifest. Repeatable; fails (exit status 3) when a prefix matches
+    /// no permit.
+    #[arg(long = "permit-id", value_name = "PREFIX")]
+    pub permit_ids: Vec<String>,
+    /// Instead of emitting permits, report which `--identity` opens each
+    /// one, and which permits none of them opens. Recovered content keys
+    /// are discarded.
+    #[arg(
+        long = "match",
+        requires = "identities",
+        conflicts_with_all = ["permits_dir", "with_holders"]
+    )]
+    pub match_identities: bool,
+    /// With `--match`, private-key material to probe the permits with (XID
+    /// document or private-keys UR). Repeatable; identities are numbered
+    /// in the order given.
+    #[arg(long = "identity", value_name = "UR", requires = "match_identities")]
+    pub identities: Vec<String>,
+    #[command(flatten)]
+    pub passphrase: io::PassphraseArgs,
 }
 
-pub fn exec(args: CommandArgs) -> Result<()> {
-    let edition_env =
-        io::parse_envelope(&args.edition).context("failed to parse edition")?;
-    let inner_envelope = edition_env
-        .clone()
-        .try_unwrap()
-        .context("edition envelope is not directly accessible")?;
-    let edition = Edition::try_from(inner_envelope)
-        .context("edition payload is not a valid club edition")?;
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    /// Elide permits from a signed edition, keeping its signature valid,
+    /// for selective disclosure.
+    Redact(redact::CommandArgs),
+    /// Seal the content key to more recipients and re-sign the edition.
+    Add(add_permit::CommandArgs),
+    /// Report permit counts and membership changes across an edition
+    /// chain.
+    Stats(permit_stats::CommandArgs),
+}
 
-    let mut extracted = 0usize;
-    for permit in &edition.permits {
-        if let PublicKeyPermit::Decode { sealed, .. } = permit {
-            println!("{}", sealed.ur_string());
-            extracted += 1;
-        }
+pub fn exec(args: CommandArgs) -> Result<()> {
+    match args.command {
+        Some(Commands::Redact(args)) => return redact::exec(args),
+        Some(Commands::Add(args)) => return add_permit::exec(args),
+        Some(Commands::Stats(args)) => return permit_stats::exec(args),
+        None => {}
+    }
+    let members: Vec<XID> = args
+        .members
+        .iter()
+        .map(|spec| {
+            io::parse_xid_value(spec)
+                .with_context(|| format!("failed to parse --member '{spec}'"))
+        })
+        .collect::<Result<_>>()?;
+    let mut specs = args.editions.clone();
+    if let Some(dir) = args.edition_dir.as_ref() {
+        specs.extend(
+            io::ur_files_in(dir)?
+                .into_iter()
+                .map(|path| format!("@{}", path.display())),
+        );
+    }
+    let batch = specs.len() > 1 || args.edition_dir.is_some();
+    let descriptor = publisher_descriptor(&args)?;
+    if descriptor.is_none() && !args.allow_unverified {
+        eprintln!(
+            "warning: permits were extracted from {}; pass --publisher to verify {}",
+            if batch {
+                "unverified editions"
+            } else {
+                "an unverified edition"
+            },
+            if batch { "them" } else { "it" }
+        );
     }
+    let known = args
+        .recipients_file
+        .as_deref()
+        .map(io::parse_recipients_file)
+        .transpose()
+        .context("failed to load --recipients-file")?;
+    let directory = args
+        .directory
+        .as_deref()
+        .map(MemberDirectory::load)
+        .transpose()?;
+    let loaded =
+        load_editions(&specs, descriptor.as_ref(), !batch || args.fail_fast)?;
+    let recipients = RecipientKeys {
+        recorded: loaded
+            .iter()
+            .flat_map(|(_, recorded)| recorded.clone())
+            .collect(),
+        known,
+        directory,
+    };
+    let editions: Vec<Edition> =
+        loaded.into_iter().map(|(edition, _)| edition).collect();
+    let mut permits: Vec<SourcedPermit> =
+        editions.iter().flat_map(sourced_permits).collect();
+    if args.dedupe_holders {
+        permits = dedupe_holders(permits);
+    }
+    let selection = Selection::new(permits, &members);
+    if !members.is_empty() {
+        selection.check(&members)?;
+    }
+    let permits = &select_permit_ids(selection.permits, &args.permit_ids)?;
+    // A lone edition's seq goes in the index; a batch tags each permit.
+    let seq = match editions.as_slice() {
+        [edition] if !batch => Some(edition.provenance.seq()),
+        _ => None,
+    };
+    let extracted = match (args.permits_dir.as_ref(), args.format) {
+        _ if args.match_identities => {
+            let identities = decrypt::parse_private_keys(
+                &args.identities,
+                &args.passphrase,
+            )?;
+            let matches = permit_matches(permits, &identities, seq.is_none());
+            match args.format {
+                OutputFormat::Json => output::print_json(&matches)?,
+                OutputFormat::Text => {
+                    for entry in &matches {
+                        println!("{}", entry.describe());
+                    }
+                }
+            }
+            permits.len()
+        }
+        (Some(dir), _) => write_permit_files(dir, permits, seq, args.force)?,
+        (None, OutputFormat::Json) => {
+            let listing = permit_listing(permits, seq.is_none(), &recipients);
+            output::print_json(&listing)?;
+            listing.len()
+        }
+        (None, OutputFormat::Text) => {
+            for line in permit_lines(permits, args.with_holders) {
+                println!("{line}");
+            }
+            permits.len()
+        }
+    };
 
     if args.summary {
         if extracted == 0 {
             eprintln!("Permits: none");
         } else {
-            eprintln!("Permits extracted: {extracted}");
+            let anonymous = permits
+                .iter()
+                .filter(|permit| permit.holder.is_none())
+                .count();
+            eprintln!(
+                "Permits extracted: {extracted} ({} annotated, {anonymous} anonymous)",
+                permits.len() - anonymous
+            );
+        }
+        for permit in permits {
+            let mut id =
+                PermitId::new(permit.position, permit.sealed, permit.holder)
+                    .describe();
+            if let Some(note) = recipients.describe(permit) {
+                id = format!("{id}, {note}");
+            }
+            match seq {
+                Some(_) => eprintln!("  {id}"),
+                None => eprintln!("  seq {} {id}", permit.seq),
+            }
+        }
+        if let Some(dir) = args.permits_dir.as_ref() {
+            eprintln!("Output: {}", dir.display());
         }
     }
 
     Ok(())
 }
+
+/// One sealed permit of an edition, with where it came from.
+#[derive(Debug, Clone, Copy)]
+pub struct SourcedPermit<'a> {
+    pub sealed: &'a SealedMessage,
+    pub holder: Option<XID>,
+    /// 1-based position among the edition's sealed permits.
+    pub position: usize,
+    /// Provenance seq of the edition.
+    pub seq: u32,
+}
+
+/// The sealed permits of `edition`, in order, with their positions.
+pub fn sourced_permits(edition: &Edition) -> Vec<SourcedPermit<'_>> {
+    let seq = edition.provenance.seq();
+    sealed_permits(edition)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (sealed, holder))| SourcedPermit {
+            sealed,
+            holder,
+            position: index + 1,
+            seq,
+        })
+        .collect()
+}
+
+/// Drop each holder's permits from all but the most recent edition that
+/// has one for them.
+fn dedupe_holders(permits: Vec<SourcedPermit<'_>>) -> Vec<SourcedPermit<'_>> {
+    let mut latest: HashMap<XID, u32> = HashMap::new();
+    for permit in &permits {
+        if let Some(holder) = permit.holder {
+            let seq = latest.entry(holder).or_insert(permit.seq);
+            *seq = (*seq).max(permit.seq);
+        }
+    }
+    permits
+        .into_iter()
+        .filter(|permit| {
+            permit
+                .holder
+                .is_none_or(|holder| latest[&holder] == permit.seq)
+        })
+        .collect()
+}
+
+/// Recipient key references recorded on permits, and the known recipients
+/// from `--recipients-file` to match them against.
+#[derive(Default)]
+struct RecipientKeys {
+    /// Recorded references, keyed by sealed digest.
+    recorded: HashMap<Digest, Reference>,
+    known: Option<Vec<RecipientDescriptor>>,
+    directory: Option<MemberDirectory>,
+}
+
+/// A `--recipients-file` entry whose keys match a permit's recorded
+/// reference.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct KnownRecipient {
+    /// 1-based position in `--recipients-file`.
+    line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    xid: Option<String>,
+    /// Name from `--directory`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+impl KnownRecipient {
+    /// `#<line>`, followed by the name or XID when known.
+    fn label(&self) -> String {
+        match (self.name.as_deref(), self.xid.as_deref()) {
+            (Some(name), _) => format!("#{} ({name})", self.line),
+            (None, Some(xid)) => format!("#{} ({xid})", self.line),
+            (None, None) => format!("#{}", self.line),
+        }
+    }
+}
+
+impl RecipientKeys {
+    fn recorded(&self, permit: &SourcedPermit) -> Option<&Reference> {
+        self.recorded.get(&super::sealed_digest(permit.sealed))
+    }
+
+    /// The known recipients whose keys have `reference`.
+    fn known_matches(&self, reference: &Reference) -> Vec<KnownRecipient> {
+        self.known
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter(|(_, descriptor)| {
+                descriptor.public_keys().reference() == *reference
+            })
+            .map(|(index, descriptor)| {
+                let xid = descriptor.member_xid();
+                KnownRecipient {
+                    line: index + 1,
+                    xid: xid.map(|xid| xid.to_string()),
+                    name: xid.and_then(|xid| {
+                        self.directory.as_ref()?.name(&xid).map(str::to_owned)
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// How an anonymous permit relates to the known recipients; `None` for
+    /// annotated permits.
+    fn describe(&self, permit: &SourcedPermit) -> Option<String> {
+        if permit.holder.is_some() {
+            return None;
+        }
+        let Some(reference) = self.recorded(permit) else {
+            return Some("recipient reference not recorded".to_string());
+        };
+        let key = format!("recipient reference {}", reference.ref_hex_short());
+        if self.known.is_none() {
+            return Some(key);
+        }
+        let matches = self.known_matches(reference);
+        if matches.is_empty() {
+            return Some(format!("{key}, not a known recipient"));
+        }
+        let matches: Vec<String> =
+            matches.iter().map(KnownRecipient::label).collect();
+        Some(format!(
+            "{key}, matches known recipient {}",
+            matches.join(", ")
+        ))
+    }
+}
+
+/// The permits `--member` selects.
+#[derive(Debug)]
+struct Selection<'a> {
+    permits: Vec<SourcedPermit<'a>>,
+    /// Requested members no permit is annotated with.
+    unmatched: Vec<XID>,
+    /// Unannotated permits, which no member can be matched against.
+    anonymous: usize,
+}
+
+impl<'a> Selection<'a> {
+    /// Every permit when `members` is empty, otherwise those annotated
+    /// with one of them.
+    fn new(permits: Vec<SourcedPermit<'a>>, members: &[XID]) -> Self {
+        let anonymous = permits
+            .iter()
+            .filter(|permit| permit.holder.is_none())
+            .count();
+        let unmatched = members
+            .iter()
+            .filter(|member| {
+                !permits.iter().any(|permit| permit.holder == Some(**member))
+            })
+            .copied()
+            .collect();
+        let permits = permits
+            .into_iter()
+            .filter(|permit| {
+                members.is_empty()
+                    || permit.holder.is_some_and(|xid| members.contains(&xid))
+            })
+            .collect();
+        Self { permits, unmatched, anonymous }
+    }
+
+    /// Warn about members without a permit and about unannotated permits
+    /// the filter could not be applied to; fail when nothing matched.
+    fn check(&self, members: &[XID]) -> Result<()> {
+        match self.anonymous {
+            0 => {}
+            1 => eprintln!(
+                "warning: 1 unannotated permit could not be checked against --member"
+            ),
+            count => eprintln!(
+                "warning: {count} unannotated permits could not be checked against --member"
+            ),
+        }
+        let unmatched: Vec<String> =
+            self.unmatched.iter().map(XID::to_string).collect();
+        if self.permits.is_empty() {
+            return Err(ExpectationFailed(format!(
+                "no permit is annotated with {}",
+                unmatched.join(", ")
+            ))
+            .into());
+        }
+        if !unmatched.is_empty() && unmatched.len() < members.len() {
+            eprintln!(
+                "warning: no permit is annotated with {}",
+                unmatched.join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The permits whose sealed digest starts with one of `prefixes`, or all of
+/// them when there are none.
+fn select_permit_ids<'a>(
+    permits: Vec<SourcedPermit<'a>>,
+    prefixes: &[String],
+) -> Result<Vec<SourcedPermit<'a>>> {
+    if prefixes.is_empty() {
+        return Ok(permits);
+    }
+    let prefixes: Vec<String> = prefixes
+        .iter()
+        .map(|prefix| {
+            let prefix = prefix.trim().to_ascii_lowercase();
+            if prefix.is_empty()
+                || !prefix.chars().all(|c| c.is_ascii_hexdigit())
+            {
+                bail!("--permit-id '{prefix}' is not a hex digest prefix");
+            }
+            Ok(prefix)
+        })
+        .collect::<Result<_>>()?;
+    let digests: Vec<String> = permits
+        .iter()
+        .map(|permit| super::sealed_digest(permit.sealed).hex())
+        .collect();
+    let unmatched: Vec<&str> = prefixes
+        .iter()
+        .filter(|prefix| !digests.iter().any(|hex| hex.starts_with(*prefix)))
+        .map(String::as_str)
+        .collect();
+    if !unmatched.is_empty() {
+        return Err(ExpectationFailed(format!(
+            "no permit has a sealed digest starting with {}",
+            unmatched.join(", ")
+        ))
+        .into());
+    }
+    Ok(permits
+        .into_iter()
+        .zip(digests)
+        .filter(|(_, hex)| {
+            prefixes.iter().any(|prefix| hex.starts_with(prefix))
+        })
+        .map(|(permit, _)| permit)
+        .collect())
+}
+
+fn publisher_descriptor(
+    args: &CommandArgs,
+) -> Result<Option<RecipientDescriptor>> {
+    args.publisher
+        .as_ref()
+        .map(|spec| {
+            io::parse_recipient_descriptor(spec)
+                .context("failed to parse publisher input")
+        })
+        .transpose()
+}
+
+/// Load the editions in `specs`, in provenance order, each with the
+/// recipient keys recorded on its permits. An edition that cannot be read
+/// or verified fails the whole run when `fail_fast`, and is otherwise
+/// reported and skipped.
+pub fn load_editions(
+    specs: &[String],
+    descriptor: Option<&RecipientDescriptor>,
+    fail_fast: bool,
+) -> Result<Vec<(Edition, HashMap<Digest, Reference>)>> {
+    let mut editions = Vec::with_capacity(specs.len());
+    for (index, spec) in specs.iter().enumerate() {
+        let source = match spec.strip_prefix('@') {
+            Some(path) => path.trim().to_string(),
+            None => format!("--edition #{}", index + 1),
+        };
+        match load_edition(spec, descriptor) {
+            Ok(edition) => editions.push(edition),
+            Err(err) if fail_fast && specs.len() == 1 => return Err(err),
+            Err(err) if fail_fast => {
+                return Err(err.context(format!("failed to read {source}")));
+            }
+            Err(err) => eprintln!("warning: skipping {source}: {err:#}"),
+        }
+    }
+    if editions.is_empty() && !specs.is_empty() {
+        bail!("none of the {} editions could be read", specs.len());
+    }
+    editions.sort_by_key(|(edition, _)| edition.provenance.seq());
+    Ok(editions)
+}
+
+/// The edition to extract permits from, verified against `descriptor` when
+/// given and otherwise only unwrapped, with the recipient keys recorded on
+/// its permits.
+fn load_edition(
+    spec: &str,
+    descriptor: Option<&RecipientDescriptor>,
+) -> Result<(Edition, HashMap<Digest, Reference>)> {
+    let edition_env =
+        io::parse_envelope(spec).context("failed to parse edition")?;
+    let inner_envelope = match descriptor {
+        Some(descriptor) => edition_env
+            .verify(descriptor.public_keys())
+            .map_err(|err| {
+                VerificationFailed(format!(
+                    "failed to verify edition signature; no permits extracted: {err}"
+                ))
+            })?,
+        None => edition_env
+            .try_unwrap()
+            .context("edition envelope is not directly accessible")?,
+    };
+    let recorded = super::permit_recipient_keys(&inner_envelope)?;
+    let edition = super::decode_edition(&inner_envelope)
+        .context("edition payload is not a valid club edition")?;
+    if let Some(descriptor) = descriptor
+        && let Some(expected_xid) = descriptor.member_xid()
+        && edition.club_xid != expected_xid
+    {
+        return Err(VerificationFailed(format!(
+            "edition references club XID {} but publisher is {expected_xid}; no permits extracted",
+            edition.club_xid
+        ))
+        .into());
+    }
+    Ok((edition, recorded))
+}
+
+/// Stable identifiers of one sealed permit, shared by `edition permits` and
+/// `edition inspect` so their output can be cross-referenced.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PermitId {
+    /// 1-based position among the edition's sealed permits.
+    pub index: usize,
+    pub sealed_digest: String,
+    pub sealed_digest_short: String,
+    /// Holder XID, or `null` for an unannotated permit.
+    pub holder_xid: Option<String>,
+}
+
+impl PermitId {
+    pub fn new(
+        index: usize,
+        sealed: &SealedMessage,
+        holder: Option<XID>,
+    ) -> Self {
+        let digest = super::sealed_digest(sealed);
+        Self {
+            index,
+            sealed_digest: digest.hex(),
+            sealed_digest_short: hex::encode(&digest.data()[..4]),
+            holder_xid: holder.map(|xid| xid.to_string()),
+        }
+    }
+
+    /// `#<index> sealed <digest> holder <XID or anonymous>`.
+    pub fn describe(&self) -> String {
+        format!(
+            "#{} sealed {} holder {}",
+            self.index,
+            self.sealed_digest,
+            self.holder_xid.as_deref().unwrap_or("anonymous")
+        )
+    }
+}
+
+/// One entry of `edition permits --format json`.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+struct PermitListing {
+    #[serde(flatten)]
+    id: PermitId,
+    /// The edition's provenance seq, when extracting from several.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<u32>,
+    /// Recipient key reference recorded on the permit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recipient_key: Option<String>,
+    /// The `--recipients-file` entries that hold the recorded key.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    known_recipients: Vec<KnownRecipient>,
+    /// The sealed permit's UR.
+    sealed: String,
+}
+
+fn permit_listing(
+    permits: &[SourcedPermit],
+    with_seq: bool,
+    recipients: &RecipientKeys,
+) -> Vec<PermitListing> {
+    permits
+        .iter()
+        .map(|permit| {
+            let reference = recipients.recorded(permit);
+            PermitListing {
+                id: PermitId::new(
+                    permit.position,
+                    permit.sealed,
+                    permit.holder,
+                ),
+                seq: with_seq.then_some(permit.seq),
+                recipient_key: reference.map(Reference::ref_hex),
+                known_recipients: reference
+                    .map(|reference| recipients.known_matches(reference))
+                    .unwrap_or_default(),
+                sealed: permit.sealed.ur_string(),
+            }
+        })
+        .collect()
+}
+
+/// Which identities open one permit, for `edition permits --match`.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+struct PermitMatch {
+    #[serde(flatten)]
+    id: PermitId,
+    /// The edition's provenance seq, when extracting from several.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<u32>,
+    /// 1-based `--identity` positions that open the permit; empty when
+    /// none does.
+    opened_by: Vec<usize>,
+}
+
+impl PermitMatch {
+    /// The permit's identifiers, a tab, and the identities that open it.
+    fn describe(&self) -> String {
+        let id = match self.seq {
+            Some(seq) => format!("seq {seq} {}", self.id.describe()),
+            None => self.id.describe(),
+        };
+        if self.opened_by.is_empty() {
+            return format!("{id}\topened by nobody");
+        }
+        let identities: Vec<String> = self
+            .opened_by
+            .iter()
+            .map(|identity| format!("#{identity}"))
+            .collect();
+        format!("{id}\topened by identity {}", identities.join(", "))
+    }
+}
+
+/// Probe every permit with every identity.
+fn permit_matches(
+    permits: &[SourcedPermit],
+    identities: &[PrivateKeys],
+    with_seq: bool,
+) -> Vec<PermitMatch> {
+    permits
+        .iter()
+        .map(|permit| PermitMatch {
+            id: PermitId::new(permit.position, permit.sealed, permit.holder),
+            seq: with_seq.then_some(permit.seq),
+            opened_by: identities
+                .iter()
+                .enumerate()
+                .filter(|(_, keys)| decrypt::opens_permit(permit.sealed, keys))
+                .map(|(index, _)| index + 1)
+                .collect(),
+        })
+        .collect()
+}
+
+/// One UR per permit, prefixed with `<holder>\t` when `with_holders`.
+fn permit_lines(permits: &[SourcedPermit], with_holders: bool) -> Vec<String> {
+    permits
+        .iter()
+        .map(|permit| {
+            let ur = permit.sealed.ur_string();
+            if !with_holders {
+                return ur;
+            }
+            match permit.holder {
+                Some(xid) => format!("{xid}\t{ur}"),
+                None => format!("anonymous\t{ur}"),
+            }
+        })
+        .collect()
+}
+
+/// The sealed permits of `edition` with their annotated holders.
+pub fn sealed_permits(edition: &Edition) -> Vec<(&SealedMessage, Option<XID>)> {
+    edition
+        .permits
+        .iter()
+        .filter_map(|permit| match permit {
+            PublicKeyPermit::Decode { sealed, member_xid } => {
+                Some((sealed, *member_xid))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Problems with the permits of an unwrapped edition envelope, each naming
+/// the permit's 1-based position: an object that is not a sealed message,
+/// a holder annotation that is not an XID, or a sealed message already
+/// carried by an earlier permit. Obscured permits are skipped.
+pub fn permit_problems(inner: &Envelope) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut seen: HashMap<Digest, usize> = HashMap::new();
+    let permits = inner.objects_for_predicate(known_values::HAS_RECIPIENT);
+    for (index, permit) in permits.iter().enumerate() {
+        let position = index + 1;
+        if permit.is_obscured() || permit.subject().is_obscured() {
+            continue;
+        }
+        match permit.extract_subject::<SealedMessage>() {
+            Ok(sealed) => {
+                let digest = super::sealed_digest(&sealed);
+                if let Some(first) = seen.get(&digest) {
+                    problems.push(format!(
+                        "permit #{position} repeats the sealed message of permit #{first}"
+                    ));
+                } else {
+                    seen.insert(digest, position);
+                }
+            }
+            Err(_) => problems
+                .push(format!("permit #{position} is not a sealed message")),
+        }
+        match permit.optional_object_for_predicate(known_values::HOLDER) {
+            Ok(Some(holder)) if holder.extract_subject::<XID>().is_err() => {
+                problems.push(format!(
+                    "permit #{position} has a holder annotation that is not an XID"
+                ));
+            }
+            Ok(_) => {}
+            Err(_) => problems.push(format!(
+                "permit #{position} has more than one holder annotation"
+            )),
+        }
+    }
+    problems
+}
+
+/// Write each permit to `dir` as `permit-<prefix>.ur`, where the prefix
+/// comes from the holder XID or, for anonymous permits, the sealed digest,
+/// plus `index.txt` and `index.json` mapping file names to holders. `seq`
+/// is that of the edition all permits come from; without it, permits from
+/// several editions are told apart by a `<seq>-` tag in their file names
+/// and index entries. Files already there are only overwritten with
+/// `force`. Returns the number of permits written.
+pub fn write_permit_files(
+    dir: &Path,
+    permits: &[SourcedPermit],
+    seq: Option<u32>,
+    force: bool,
+) -> Result<usize> {
+    let names = permit_file_names(permits, seq.is_none());
+    if !force
+        && let Some(existing) = names
+            .iter()
+            .map(String::as_str)
+            .chain([PERMIT_INDEX_FILE, PERMIT_INDEX_JSON_FILE])
+            .map(|name| dir.join(name))
+            .find(|path| path.exists())
+    {
+        bail!(
+            "'{}' already exists; pass --force to overwrite the permit files in '{}'",
+            existing.display(),
+            dir.display()
+        );
+    }
+    output::ensure_dir(dir)?;
+    let mut index = Vec::with_capacity(permits.len());
+    let mut entries = Vec::with_capacity(permits.len());
+    for (permit, name) in permits.iter().zip(&names) {
+        output::write_lines(&dir.join(name), [permit.sealed.ur_string()])?;
+        let label = match permit.holder {
+            Some(xid) => xid.to_string(),
+            None => "anonymous".to_string(),
+        };
+        index.push(format!("{name} {label}"));
+        entries.push(PermitIndexEntry {
+            file: name.clone(),
+            seq: seq.is_none().then_some(permit.seq),
+            holder_xid: permit.holder.map(|xid| xid.to_string()),
+            sealed_digest: super::sealed_digest(permit.sealed).hex(),
+        });
+    }
+    output::write_lines(&dir.join(PERMIT_INDEX_FILE), &index)?;
+    let json =
+        serde_json::to_string_pretty(&PermitIndex { seq, permits: entries })
+            .context("failed to serialize permit index")?;
+    output::write_atomic(
+        &dir.join(PERMIT_INDEX_JSON_FILE),
+        format!("{json}\n").as_bytes(),
+    )?;
+    Ok(permits.len())
+}
+
+/// The `index.json` written with permit files.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct PermitIndex {
+    /// Provenance seq of the edition the permits came from, when they all
+    /// came from one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<u32>,
+    permits: Vec<PermitIndexEntry>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct PermitIndexEntry {
+    file: String,
+    /// Provenance seq of the permit's edition, when extracting from
+    /// several.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<u32>,
+    /// Holder XID, or `null` for an unannotated permit.
+    holder_xid: Option<String>,
+    sealed_digest: String,
+}
+
+/// File names for `permits`, in order. The prefix is lengthened a byte at a
+/// time past [`PERMIT_FILE_PREFIX_LEN`] digits until it tells apart holders
+/// (or sealed digests) that share it; further permits for one holder get `-2`,
+/// `-3`, … suffixes. With `tag_seq`, each name starts with the permit's
+/// edition stem, e.g. `permit-0003-<prefix>.ur`.
+fn permit_file_names(permits: &[SourcedPermit], tag_seq: bool) -> Vec<String> {
+    let keys: Vec<String> = permits
+        .iter()
+        .map(|permit| match permit.holder {
+            Some(xid) => hex::encode(xid.data()),
+            None => super::sealed_digest(permit.sealed).hex(),
+        })
+        .collect();
+    let distinct: BTreeSet<&str> = keys.iter().map(String::as_str).collect();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    keys.iter()
+        .zip(permits)
+        .map(|(key, permit)| {
+            let len = (PERMIT_FILE_PREFIX_LEN..key.len())
+                .step_by(2)
+                .find(|&len| {
+                    distinct.iter().all(|other| {
+                        *other == key || !other.starts_with(&key[..len])
+                    })
+                })
+                .unwrap_or(key.len());
+            let prefix = if tag_seq {
+                format!("{}-{}", edition_stem(permit.seq), &key[..len])
+            } else {
+                key[..len].to_string()
+            };
+            let count = seen.entry(prefix.clone()).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                format!("permit-{prefix}.ur")
+            } else {
+                format!("permit-{prefix}-{count}.ur")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use bc_components::{
+        PrivateKeyBase, PrivateKeysProvider, PublicKeysProvider, SymmetricKey,
+        XIDProvider,
+    };
+    use bc_xid::XIDDocument;
+
+    use super::*;
+    use crate::test_support::{
+        compose_edition, compose_for, genesis_mark, marks, parse, private_ur,
+        public_ur, register_tags, tamper_with, try_parse, xid_document,
+    };
+
+    #[test]
+    fn expired_permits_extracted_from_an_edition_are_refused_by_decrypt() {
+        let publisher = xid_document();
+        let (contractor, member) = (xid_document(), xid_document());
+        let still_valid = io::permit_descriptor_envelope(
+            member.inception_key().unwrap().public_keys(),
+            Some(member.xid()),
+            Some(&io::parse_date("2999-01-01").unwrap()),
+        )
+        .ur_string();
+        let edition = compose_for(
+            &publisher,
+            &genesis_mark(),
+            &[&contractor],
+            &["--permit", &still_valid, "--permit-expiry", "2020-01-01"],
+        );
+
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-permits-expiry-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let dir_arg = dir.display().to_string();
+        let verifier = public_ur(&publisher);
+        exec(parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &edition,
+            "--publisher",
+            &verifier,
+            "--permits-dir",
+            &dir_arg,
+        ]))
+        .unwrap();
+        let index = fs::read_to_string(dir.join(PERMIT_INDEX_FILE)).unwrap();
+        let permit_file = |holder: &XIDDocument| {
+            let holder = holder.xid().to_string();
+            let line =
+                index.lines().find(|line| line.ends_with(&holder)).unwrap();
+            let name = line.split(' ').next().unwrap();
+            format!("@{}", dir.join(name).display())
+        };
+
+        let decrypt = |holder: &XIDDocument, extra: &[&str]| {
+            let permit = permit_file(holder);
+            let identity = private_ur(holder);
+            let mut argv = vec![
+                "clubs",
+                "--edition",
+                edition.as_str(),
+                "--permit",
+                &permit,
+                "--identity",
+                &identity,
+            ];
+            argv.extend(extra);
+            decrypt::run(&parse::<decrypt::CommandArgs>(&argv))
+                .map(|content| content.extract_subject::<String>().unwrap())
+        };
+        assert_eq!(decrypt(&member, &[]).unwrap(), "issue 0");
+        let err = decrypt(&contractor, &[]).err().unwrap();
+        assert!(format!("{err:#}").contains("expired"), "{err:#}");
+        assert_eq!(
+            decrypt(&contractor, &["--ignore-expiry"]).unwrap(),
+            "issue 0"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn sealed() -> SealedMessage {
+        SealedMessage::new(b"content key", &PrivateKeyBase::new().public_keys())
+    }
+
+    /// `permits` as the permits of one edition at `seq`.
+    fn sourced<'a>(
+        permits: &[(&'a SealedMessage, Option<XID>)],
+        seq: u32,
+    ) -> Vec<SourcedPermit<'a>> {
+        permits
+            .iter()
+            .enumerate()
+            .map(|(index, (sealed, holder))| SourcedPermit {
+                sealed,
+                holder: *holder,
+                position: index + 1,
+                seq,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn names_permits_by_holder_and_disambiguates_collisions() {
+        register_tags();
+        let holder = XID::from_data([0xab; 32]);
+        let (first, second, anonymous) = (sealed(), sealed(), sealed());
+        let names = permit_file_names(
+            &sourced(
+                &[
+                    (&first, Some(holder)),
+                    (&second, Some(holder)),
+                    (&anonymous, None),
+                ],
+                7,
+            ),
+            false,
+        );
+        assert_eq!(names[0], "permit-abababab.ur");
+        assert_eq!(names[1], "permit-abababab-2.ur");
+        let digest = super::super::sealed_digest(&anonymous);
+        assert_eq!(
+            names[2],
+            format!("permit-{}.ur", hex::encode(&digest.data()[..4]))
+        );
+
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-permit-files-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let written = write_permit_files(
+            &dir,
+            &sourced(&[(&first, Some(holder)), (&second, Some(holder))], 7),
+            Some(7),
+            false,
+        )
+        .unwrap();
+        assert_eq!(written, 2);
+        assert_eq!(
+            fs::read_to_string(dir.join(&names[1])).unwrap().trim(),
+            second.ur_string()
+        );
+        let index = fs::read_to_string(dir.join(PERMIT_INDEX_FILE)).unwrap();
+        assert_eq!(
+            index.lines().collect::<Vec<_>>(),
+            [
+                format!("permit-abababab.ur {holder}"),
+                format!("permit-abababab-2.ur {holder}"),
+            ]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn lengthens_shared_prefixes_and_overwrites_only_with_force() {
+        register_tags();
+        let alice = XID::from_data([0xab; 32]);
+        let mut bytes = [0xab; 32];
+        bytes[5] = 0xcd;
+        let bob = XID::from_data(bytes);
+        let (first, second, anonymous) = (sealed(), sealed(), sealed());
+        let permits = sourced(
+            &[
+                (&first, Some(alice)),
+                (&second, Some(bob)),
+                (&anonymous, None),
+            ],
+            3,
+        );
+        let names = permit_file_names(&permits, false);
+        assert_eq!(names[0], "permit-abababababab.ur");
+        assert_eq!(names[1], "permit-abababababcd.ur");
+        let digest = super::super::sealed_digest(&anonymous);
+        assert_eq!(
+            names[2],
+            format!("permit-{}.ur", hex::encode(&digest.data()[..4]))
+        );
+
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-permit-index-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(
+            write_permit_files(&dir, &permits, Some(3), false).unwrap(),
+            3
+        );
+        let read_index = || {
+            serde_json::from_str::<serde_json::Value>(
+                &fs::read_to_string(dir.join(PERMIT_INDEX_JSON_FILE)).unwrap(),
+            )
+            .unwrap()
+        };
+        let index = read_index();
+        assert_eq!(index["seq"], 3);
+        assert_eq!(index["permits"][0]["file"], names[0]);
+        assert_eq!(index["permits"][1]["holder_xid"], bob.to_string());
+        assert_eq!(index["permits"][2]["holder_xid"], serde_json::Value::Null);
+        assert_eq!(index["permits"][2]["sealed_digest"], digest.hex());
+
+        let err =
+            write_permit_files(&dir, &permits, Some(3), false).unwrap_err();
+        assert!(err.to_string().contains("pass --force"));
+        assert_eq!(
+            write_permit_files(&dir, &permits, Some(3), true).unwrap(),
+            3
+        );
+        assert_eq!(read_index(), index);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refuses_permits_from_a_tampered_edition() {
+        let publisher = xid_document();
+        let edition =
+            compose_for(&publisher, &genesis_mark(), &[&xid_document()], &[]);
+
+        // The payload gains a permit but keeps the original signatures.
+        let forged_permit = SealedMessage::new(
+            b"forged key",
+            &PrivateKeyBase::new().public_keys(),
+        );
+        let tampered = tamper_with(&edition, |inner| {
+            inner.add_assertion(known_values::HAS_RECIPIENT, forged_permit)
+        });
+
+        let verifier = io::xid_document_ur(&publisher, false).unwrap();
+        let load = |edition: &str, extra: &[&str]| {
+            let mut argv = vec!["clubs", "--edition", edition];
+            argv.extend(extra);
+            let args = parse::<CommandArgs>(&argv);
+            load_edition(edition, publisher_descriptor(&args)?.as_ref())
+                .map(|(edition, _)| edition)
+        };
+        let verified = load(&edition, &["--publisher", &verifier]).unwrap();
+        assert_eq!(sealed_permits(&verified).len(), 1);
+        let err = load(&tampered, &["--publisher", &verifier]).unwrap_err();
+        assert!(err.is::<VerificationFailed>());
+        assert!(err.to_string().contains("no permits extracted"));
+
+        let unverified = load(&tampered, &["--allow-unverified"]).unwrap();
+        assert_eq!(sealed_permits(&unverified).len(), 2);
+        assert!(
+            try_parse::<CommandArgs>(&[
+                "clubs",
+                "--edition",
+                &edition,
+                "--publisher",
+                &verifier,
+                "--allow-unverified",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn lists_permits_with_their_holders() {
+        register_tags();
+        let holder = XID::from_data([0xab; 32]);
+        let (annotated, anonymous) = (sealed(), sealed());
+        let permits =
+            sourced(&[(&annotated, Some(holder)), (&anonymous, None)], 0);
+
+        assert_eq!(
+            permit_lines(&permits, false),
+            [annotated.ur_string(), anonymous.ur_string()]
+        );
+        assert_eq!(
+            permit_lines(&permits, true),
+            [
+                format!("{holder}\t{}", annotated.ur_string()),
+                format!("anonymous\t{}", anonymous.ur_string()),
+            ]
+        );
+
+        let json = serde_json::to_value(permit_listing(
+            &permits,
+            false,
+            &RecipientKeys::default(),
+        ))
+        .unwrap();
+        let (first, second) = (
+            super::super::sealed_digest(&annotated),
+            super::super::sealed_digest(&anonymous),
+        );
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "index": 1,
+                    "sealed_digest": first.hex(),
+                    "sealed_digest_short": hex::encode(&first.data()[..4]),
+                    "holder_xid": holder.to_string(),
+                    "sealed": annotated.ur_string(),
+                },
+                {
+                    "index": 2,
+                    "sealed_digest": second.hex(),
+                    "sealed_digest_short": hex::encode(&second.data()[..4]),
+                    "holder_xid": null,
+                    "sealed": anonymous.ur_string(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn filters_permits_by_member() {
+        register_tags();
+        let (alice, bob, carol) = (
+            XID::from_data([0xaa; 32]),
+            XID::from_data([0xbb; 32]),
+            XID::from_data([0xcc; 32]),
+        );
+        let (first, second, third) = (sealed(), sealed(), sealed());
+        let permits = || {
+            sourced(
+                &[(&first, Some(alice)), (&second, None), (&third, Some(bob))],
+                0,
+            )
+        };
+        let positions = |selection: &Selection| -> Vec<usize> {
+            selection
+                .permits
+                .iter()
+                .map(|permit| permit.position)
+                .collect()
+        };
+
+        let all = Selection::new(permits(), &[]);
+        assert_eq!(positions(&all), [1, 2, 3]);
+
+        let selected = Selection::new(permits(), &[bob]);
+        assert_eq!(positions(&selected), [3]);
+        assert_eq!(selected.anonymous, 1);
+        selected.check(&[bob]).unwrap();
+        let listing =
+            permit_listing(&selected.permits, false, &RecipientKeys::default());
+        assert_eq!(listing[0].id.index, 3);
+        assert_eq!(listing[0].sealed, third.ur_string());
+
+        let partly = Selection::new(permits(), &[alice, carol]);
+        assert_eq!(positions(&partly), [1]);
+        assert_eq!(partly.unmatched, [carol]);
+        partly.check(&[alice, carol]).unwrap();
+
+        let missing = Selection::new(permits(), &[carol]);
+        let err = missing.check(&[carol]).unwrap_err();
+        assert!(err.is::<ExpectationFailed>());
+        assert_eq!(
+            err.to_string(),
+            format!("no permit is annotated with {carol}")
+        );
+    }
+
+    #[test]
+    fn extracts_and_dedupes_permits_across_editions() {
+        let publisher = xid_document();
+        let (alice, bob, carol) =
+            (xid_document(), xid_document(), xid_document());
+        let marks = marks(3);
+        // Given out of order; Bob drops out after seq 1 and Carol joins.
+        let specs = [
+            compose_for(&publisher, &marks[2], &[&alice, &carol], &[]),
+            compose_for(&publisher, &marks[0], &[&alice, &bob], &[]),
+            compose_for(&publisher, &marks[1], &[&alice, &bob], &[]),
+            "ur:envelope/not-an-edition".to_string(),
+        ];
+        let holders = |permits: &[SourcedPermit]| -> Vec<(u32, XID)> {
+            permits
+                .iter()
+                .map(|permit| (permit.seq, permit.holder.unwrap()))
+                .collect()
+        };
+
+        let editions: Vec<Edition> = load_editions(&specs, None, false)
+            .unwrap()
+            .into_iter()
+            .map(|(edition, _)| edition)
+            .collect();
+        let seqs: Vec<u32> = editions
+            .iter()
+            .map(|edition| edition.provenance.seq())
+            .collect();
+        assert_eq!(seqs, [0, 1, 2]);
+        let permits: Vec<SourcedPermit> =
+            editions.iter().flat_map(sourced_permits).collect();
+        assert_eq!(permits.len(), 6);
+
+        let latest = holders(&dedupe_holders(permits.clone()));
+        assert_eq!(latest.len(), 3);
+        for entry in [(1, bob.xid()), (2, alice.xid()), (2, carol.xid())] {
+            assert!(latest.contains(&entry), "{entry:?} missing");
+        }
+
+        let listing = serde_json::to_value(permit_listing(
+            &permits,
+            true,
+            &RecipientKeys::default(),
+        ))
+        .unwrap();
+        assert_eq!(listing[0]["seq"], 0);
+        assert_eq!(listing[5]["seq"], 2);
+        let names = permit_file_names(&permits, true);
+        assert!(names[0].starts_with("permit-0001-genesis-"));
+        assert!(names[5].starts_with("permit-0003-"));
+
+        let err = load_editions(&specs, None, true).unwrap_err();
+        assert!(format!("{err:#}").starts_with("failed to read --edition #4"));
+    }
+
+    #[test]
+    fn matches_recorded_recipient_refs_against_known_recipients() {
+        let publisher = xid_document();
+        let marks = marks(2);
+        let member = xid_document();
+        let (known, unknown) = (
+            PrivateKeyBase::new().public_keys(),
+            PrivateKeyBase::new().public_keys(),
+        );
+        let recipient_urs = [
+            io::xid_document_ur(&member, false).unwrap(),
+            known.ur_string(),
+            unknown.ur_string(),
+        ];
+        let permit_flags: Vec<&str> = recipient_urs
+            .iter()
+            .flat_map(|ur| ["--permit", ur.as_str()])
+            .collect();
+        let mut annotated_flags = permit_flags.clone();
+        annotated_flags.push("--annotate-recipient-refs");
+        let annotated =
+            compose_edition(&publisher, &marks[0], &annotated_flags);
+        let legacy = compose_edition(&publisher, &marks[1], &permit_flags);
+
+        let bob = XID::from_data([0xb0; 32]);
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-recipient-refs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let directory_path = dir.join("members.toml");
+        fs::write(
+            &directory_path,
+            format!("[members]\n\"{}\" = \"Bob\"\n", hex::encode(bob.data())),
+        )
+        .unwrap();
+        let known_recipients = || {
+            Some(vec![
+                RecipientDescriptor::from_public_keys(
+                    PrivateKeyBase::new().public_keys(),
+                    None,
+                ),
+                RecipientDescriptor::from_public_keys(known.clone(), Some(bob)),
+            ])
+        };
+
+        let (edition, recorded) = load_edition(&annotated, None).unwrap();
+        assert_eq!(recorded.len(), 3);
+        let recipients = RecipientKeys {
+            recorded,
+            known: known_recipients(),
+            directory: Some(MemberDirectory::load(&directory_path).unwrap()),
+        };
+        let permits = sourced_permits(&edition);
+        let holder = permits
+            .iter()
+            .find(|permit| permit.holder == Some(member.xid()))
+            .unwrap();
+        assert_eq!(recipients.describe(holder), None);
+        let mut notes: Vec<String> = permits
+            .iter()
+            .filter_map(|permit| recipients.describe(permit))
+            .collect();
+        notes.sort();
+        let mut expected = vec![
+            format!(
+                "recipient reference {}, matches known recipient #2 (Bob)",
+                known.reference().ref_hex_short()
+            ),
+            format!(
+                "recipient reference {}, not a known recipient",
+                unknown.reference().ref_hex_short()
+            ),
+        ];
+        expected.sort();
+        assert_eq!(notes, expected);
+
+        let listing =
+            serde_json::to_value(permit_listing(&permits, false, &recipients))
+                .unwrap();
+        let matched: Vec<&serde_json::Value> = listing
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|entry| entry.get("known_recipients").is_some())
+            .collect();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(
+            matched[0]["known_recipients"],
+            serde_json::json!([
+                { "line": 2, "xid": bob.to_string(), "name": "Bob" }
+            ])
+        );
+        assert_eq!(matched[0]["recipient_key"], known.reference().ref_hex());
+
+        let (edition, recorded) = load_edition(&legacy, None).unwrap();
+        assert!(recorded.is_empty());
+        let recipients = RecipientKeys {
+            recorded,
+            known: known_recipients(),
+            directory: None,
+        };
+        let permits = sourced_permits(&edition);
+        let notes: Vec<String> = permits
+            .iter()
+            .filter_map(|permit| recipients.describe(permit))
+            .collect();
+        assert_eq!(notes, ["recipient reference not recorded"; 2]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn matches_identities_to_the_permits_they_open() {
+        let (opener, bystander) =
+            (PrivateKeyBase::new(), PrivateKeyBase::new());
+        let seal = |keys: &PrivateKeyBase| {
+            SealedMessage::new(
+                SymmetricKey::new().tagged_cbor().to_cbor_data(),
+                &keys.public_keys(),
+            )
+        };
+        let (first, second, stranger) =
+            (seal(&opener), seal(&opener), seal(&PrivateKeyBase::new()));
+        let holder = XID::from_data([0xab; 32]);
+        let permits = sourced(
+            &[(&first, Some(holder)), (&stranger, None), (&second, None)],
+            4,
+        );
+        let identities = [opener.private_keys(), bystander.private_keys()];
+
+        let matches = permit_matches(&permits, &identities, false);
+        let opened: Vec<&[usize]> = matches
+            .iter()
+            .map(|entry| entry.opened_by.as_slice())
+            .collect();
+        assert_eq!(opened, [&[1][..], &[], &[1]]);
+        assert!(matches[1].describe().ends_with("\topened by nobody"));
+        assert!(matches[2].describe().ends_with("\topened by identity #1"));
+
+        let json = serde_json::to_value(&matches).unwrap();
+        assert_eq!(json[0]["opened_by"], serde_json::json!([1]));
+        assert_eq!(json[1]["opened_by"], serde_json::json!([]));
+        assert!(!json.to_string().contains("crypto-key"));
+
+        assert!(
+            try_parse::<CommandArgs>(&[
+                "clubs",
+                "--edition",
+                "ur:envelope/x",
+                "--identity",
+                "ur:crypto-prvkeys/x",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn selects_permits_by_sealed_digest_prefix() {
+        let (first, second) = (sealed(), sealed());
+        let permits = sourced(&[(&first, None), (&second, None)], 3);
+        let digest = super::super::sealed_digest(&second).hex();
+
+        let selected =
+            select_permit_ids(permits.clone(), &[digest[..8].to_uppercase()])
+                .unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].position, 2);
+        assert_eq!(select_permit_ids(permits.clone(), &[]).unwrap().len(), 2);
+
+        let err = select_permit_ids(permits.clone(), &["zz".to_string()])
+            .unwrap_err();
+        assert!(err.to_string().contains("not a hex digest prefix"));
+        let elsewhere = super::super::sealed_digest(&sealed()).hex();
+        let err = select_permit_ids(permits, &[elsewhere]).unwrap_err();
+        assert!(err.is::<ExpectationFailed>());
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::{Context, Result, anyhow, bail};
+use bc_components::{Digest, PrivateKeys, PublicKeys, XID};
+use bc_envelope::prelude::*;
+use clubs::{edition::Edition, public_key_permit::PublicKeyPermit};
+
+use super::sealed_digest;
+
+const RECEIPT_TYPE: &str = "EditionReceipt";
+
+/// A recipient listed on a receipt: the holder XID when known, otherwise the
+/// recipient's public-keys reference.
+pub enum ReceiptRecipient {
+    Member(XID),
+    Keys(String),
+}
+
+/// Outcome of checking a receipt against an edition.
+pub struct ReceiptCheck {
+    pub signature_verified: bool,
+    pub recipients: usize,
+}
+
+/// Build a publisher-signed receipt recording the edition's provenance seq,
+/// content digest, recipients, and sealed-permit digests. `decoys` counts
+/// padding permits that belong to no recipient.
+pub fn build_receipt(
+    edition: &Edition,
+    recipients: &[ReceiptRecipient],
+    decoys: usize,
+    signing_keys: &PrivateKeys,
+) -> Envelope {
+    let mut receipt = Envelope::new(edition.content.digest())
+        .add_type(RECEIPT_TYPE)
+        .add_assertion("club", edition.club_xid)
+        .add_assertion("seq", edition.provenance.seq());
+    for recipient in recipients {
+        receipt = match recipient {
+            ReceiptRecipient::Member(xid) => {
+                receipt.add_assertion("recipient", *xid)
+            }
+            ReceiptRecipient::Keys(reference) => {
+                receipt.add_assertion("recipient", reference.as_str())
+            }
+        };
+    }
+    for digest in permit_digests(edition) {
+        receipt = receipt.add_assertion("permit", digest);
+    }
+    if decoys > 0 {
+        receipt = receipt.add_assertion("decoys", decoys as u64);
+    }
+    receipt.sign(signing_keys)
+}
+
+/// Check that `receipt` describes `edition`: same club, seq, content digest,
+/// and exactly the edition's sealed permits. The receipt signature is
+/// verified when `verifier` is supplied.
+pub fn check_receipt(
+    receipt: &Envelope,
+    edition: &Edition,
+    verifier: Option<&PublicKeys>,
+) -> Result<ReceiptCheck> {
+    let body = match verifier {
+        Some(keys) => receipt
+            .verify(keys)
+            .context("receipt signature does not verify")?,
+        None => receipt
+            .try_unwrap()
+            .context("receipt is not a signed envelope")?,
+    };
+    body.check_type(RECEIPT_TYPE)
+        .map_err(|_| anyhow!("envelope is not an edition receipt"))?;
+
+    let club: XID = body.extract_object_for_predicate("club")?;
+    if club != edition.club_xid {
+        bail!(
+            "receipt is for club {club} but the edition belongs to {}",
+            edition.club_xid
+        );
+    }
+    let seq: u32 = body.extract_object_for_predicate("seq")?;
+    if seq != edition.provenance.seq() {
+        bail!(
+            "receipt is for seq {seq} but the edition is seq {}",
+            edition.provenance.seq()
+        );
+    }
+    let content_digest: Digest = body.extract_subject()?;
+    if content_digest != edition.content.digest() {
+        bail!("receipt content digest does not match the edition content");
+    }
+
+    let recipients = body.objects_for_predicate("recipient").len();
+    let decoys = body
+        .extract_optional_object_for_predicate::<u64>("decoys")?
+        .unwrap_or(0) as usize;
+    let mut listed: Vec<String> = body
+        .extract_objects_for_predicate::<Digest>("permit")?
+        .iter()
+        .map(Digest::hex)
+        .collect();
+    let mut actual: Vec<String> =
+        permit_digests(edition).iter().map(Digest::hex).collect();
+    if recipients + decoys != actual.len() {
+        bail!(
+            "receipt lists {recipients} recipients and {decoys} decoys but the edition carries {} permits",
+            actual.len()
+        );
+    }
+    listed.sort();
+    actual.sort();
+    if listed != actual {
+        bail!("receipt permit digests do not match the edition's permits");
+    }
+
+    Ok(ReceiptCheck { signature_verified: verifier.is_some(), recipients })
+}
+
+fn permit_digests(edition: &Edition) -> Vec<Digest> {
+    edition
+        .permits
+        .iter()
+        .filter_map(|permit| match permit {
+            PublicKeyPermit::Decode { sealed, .. } => {
+                Some(sealed_digest(sealed))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::{
+        PrivateKeyBase, PrivateKeysProvider, PublicKeysProvider, XIDProvider,
+    };
+
+    use super::*;
+    use crate::{
+        cmd::edition::compose,
+        io,
+        test_support::{
+            compose_args, genesis_mark, public_ur, tamper_with, xid_document,
+            xid_document_with,
+        },
+    };
+
+    #[test]
+    fn receipt_matches_its_edition_and_rejects_a_phantom_recipient() {
+        let keys = PrivateKeyBase::new();
+        let publisher = xid_document_with(&keys);
+        let member = public_ur(&xid_document());
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-receipt-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("receipt.ur").display().to_string();
+        let composed = compose::run(compose_args(
+            &publisher,
+            &genesis_mark(),
+            &["--permit", &member, "--receipt", &path],
+        ))
+        .unwrap();
+        let edition = super::super::decode_edition(
+            &io::parse_envelope(&composed.edition_ur)
+                .unwrap()
+                .try_unwrap()
+                .unwrap(),
+        )
+        .unwrap();
+        let receipt_ur = std::fs::read_to_string(&path).unwrap();
+        let receipt_ur = receipt_ur.trim();
+        let receipt = io::parse_envelope(receipt_ur).unwrap();
+        let verifier = keys.public_keys();
+
+        let check = check_receipt(&receipt, &edition, Some(&verifier)).unwrap();
+        assert!(check.signature_verified);
+        assert_eq!(check.recipients, 1);
+        let other = PrivateKeyBase::new().public_keys();
+        assert!(check_receipt(&receipt, &edition, Some(&other)).is_err());
+
+        let phantom = xid_document().xid();
+        let tampered = io::parse_envelope(&tamper_with(receipt_ur, |body| {
+            body.add_assertion("recipient", phantom)
+        }))
+        .unwrap();
+        let err = check_receipt(&tampered, &edition, Some(&verifier))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("does not verify"), "{err:#}");
+        let err = check_receipt(&tampered, &edition, None).err().unwrap();
+        assert!(err.to_string().contains("lists 2 recipients"), "{err:#}");
+
+        // Even a receipt the publisher re-signs cannot list more recipients
+        // than the edition has permits.
+        let resigned =
+            tampered.try_unwrap().unwrap().sign(&keys.private_keys());
+        let err = check_receipt(&resigned, &edition, Some(&verifier))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("lists 2 recipients"), "{err:#}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result, bail};
+use bc_components::{Digest, SealedMessage, XID};
+use bc_envelope::prelude::*;
+use clap::Args;
+
+use crate::{
+    cmd::{ExpectationFailed, VerificationFailed},
+    io,
+};
+
+/// Elide permits from a signed edition for selective disclosure.
+#[derive(Debug, Args)]
+pub struct CommandArgs {
+    /// Signed edition UR to redact.
+    #[arg(long, value_name = "UR")]
+    pub edition: String,
+    /// Publisher descriptor (XID document or public-keys UR); the edition
+    /// must verify against it before and after redaction.
+    #[arg(long, value_name = "UR")]
+    pub publisher: String,
+    /// Permit to keep, by holder XID or sealed-digest prefix (permit id).
+    /// Repeatable; every other permit is elided.
+    #[arg(
+        long = "keep",
+        value_name = "XID|PERMIT-ID",
+        required_unless_present = "removes",
+        conflicts_with = "removes"
+    )]
+    pub keeps: Vec<String>,
+    /// Permit to elide, by holder XID or sealed-digest prefix (permit id).
+    /// Repeatable; every other permit is kept.
+    #[arg(long = "remove", value_name = "XID|PERMIT-ID")]
+    pub removes: Vec<String>,
+    /// Emit a human-readable summary to stderr.
+    #[arg(long)]
+    pub summary: bool,
+}
+
+pub fn exec(args: CommandArgs) -> Result<()> {
+    let descriptor = io::parse_recipient_descriptor(&args.publisher)
+        .context("failed to parse publisher input")?;
+    let edition_env =
+        io::parse_envelope(&args.edition).context("failed to parse edition")?;
+    let inner = edition_env.verify(descriptor.public_keys()).map_err(
+        |err| {
+            VerificationFailed(format!(
+                "failed to verify edition signature; nothing redacted: {err}"
+            ))
+        },
+    )?;
+
+    let (selectors, keep) = if args.keeps.is_empty() {
+        (&args.removes, false)
+    } else {
+        (&args.keeps, true)
+    };
+    let selectors: Vec<PermitSelector> = selectors
+        .iter()
+        .map(|spec| PermitSelector::parse(spec))
+        .collect::<Result<_>>()?;
+    let redaction = Redaction::plan(&inner, &selectors, keep)?;
+    let redacted = redaction.apply(&edition_env);
+
+    // Elision must leave the signed digest, and so the signature, intact.
+    if redacted.digest() != edition_env.digest()
+        || redacted.verify(descriptor.public_keys()).is_err()
+    {
+        return Err(VerificationFailed(
+            "redacted edition no longer verifies; nothing emitted".to_string(),
+        )
+        .into());
+    }
+
+    println!("{}", redacted.ur_string());
+    if args.summary {
+        eprintln!(
+            "Permits kept: {}, elided: {}",
+            redaction.kept,
+            redaction.elided.len()
+        );
+        eprintln!("Signature: verified after redaction");
+    }
+    Ok(())
+}
+
+/// A `--keep` or `--remove` value: a holder XID or a sealed-digest prefix.
+/// A value that reads as both matches either way.
+#[derive(Debug)]
+struct PermitSelector {
+    spec: String,
+    holder: Option<XID>,
+    digest_prefix: Option<String>,
+}
+
+impl PermitSelector {
+    fn parse(spec: &str) -> Result<Self> {
+        let holder = io::parse_xid_value(spec).ok();
+        let prefix = spec.trim().to_ascii_lowercase();
+        let digest_prefix = (!prefix.is_empty()
+            && prefix.chars().all(|c| c.is_ascii_hexdigit()))
+        .then_some(prefix);
+        if holder.is_none() && digest_prefix.is_none() {
+            bail!("'{spec}' is neither an XID nor a hex permit id");
+        }
+        Ok(Self { spec: spec.to_string(), holder, digest_prefix })
+    }
+
+    fn matches(&self, sealed_digest: &Digest, holder: Option<XID>) -> bool {
+        self.holder.is_some_and(|xid| holder == Some(xid))
+            || self
+                .digest_prefix
+                .as_ref()
+                .is_some_and(|prefix| sealed_digest.hex().starts_with(prefix))
+    }
+}
+
+/// The permit objects to elide from an edition.
+struct Redaction {
+    elided: HashSet<Digest>,
+    kept: usize,
+}
+
+impl Redaction {
+    /// Elide the permits of the unwrapped `inner` edition that the
+    /// selectors match (`keep == false`) or do not match (`keep == true`).
+    /// Every selector must match a permit; already obscured permits are
+    /// left alone.
+    fn plan(
+        inner: &Envelope,
+        selectors: &[PermitSelector],
+        keep: bool,
+    ) -> Result<Self> {
+        let mut matched = vec![false; selectors.len()];
+        let mut elided = HashSet::new();
+        let mut kept = 0;
+        for permit in inner.objects_for_predicate(known_values::HAS_RECIPIENT) {
+            if permit.is_obscured() || permit.subject().is_obscured() {
+                continue;
+            }
+            let sealed = permit
+                .extract_subject::<SealedMessage>()
+                .context("permit subject is not a sealed message")?;
+            let holder = permit
+                .extract_optional_object_for_predicate::<XID>(
+                    known_values::HOLDER,
+                )
+                .context("permit holder is not an XID")?;
+            let digest = super::sealed_digest(&sealed);
+            let mut selected = false;
+            for (index, selector) in selectors.iter().enumerate() {
+                if selector.matches(&digest, holder) {
+                    matched[index] = true;
+                    selected = true;
+                }
+            }
+            if selected == keep {
+                kept += 1;
+            } else {
+                elided.insert(permit.digest());
+            }
+        }
+        let unmatched: Vec<&str> = selectors
+            .iter()
+            .zip(&matched)
+            .filter(|(_, matched)| !**matched)
+            .map(|(selector, _)| selector.spec.as_str())
+            .collect();
+        if !unmatched.is_empty() {
+            return Err(ExpectationFailed(format!(
+                "no permit matches {}",
+                unmatched.join(", ")
+            ))
+            .into());
+        }
+        Ok(Self { elided, kept })
+    }
+
+    /// `edition_env` with the planned permit objects elided in place, so
+    /// its digest is unchanged.
+    fn apply(&self, edition_env: &Envelope) -> Envelope {
+        edition_env.elide_removing_set(&self.elided)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::{
+        PrivateKeyBase, PrivateKeysProvider, PublicKeysProvider, XIDProvider,
+    };
+    use bc_xid::XIDDocument;
+
+    use super::*;
+    use crate::{
+        cmd::{
+            content::decrypt,
+            edition::{
+                decode_edition, inspect,
+                obscured::{ObscuredNodes, strip_obscured},
+                permits,
+            },
+        },
+        test_support::{Club, compose_for, genesis_mark, xid_document_with},
+    };
+
+    #[test]
+    fn elides_all_but_the_kept_permit_and_still_verifies() {
+        let publisher = Club::new();
+        let keys: Vec<PrivateKeyBase> =
+            (0..3).map(|_| PrivateKeyBase::new()).collect();
+        let members: Vec<XIDDocument> =
+            keys.iter().map(xid_document_with).collect();
+        let content = Envelope::new("issue one").ur_string();
+        let recipients: Vec<&XIDDocument> = members.iter().collect();
+        let edition_env = io::parse_envelope(&compose_for(
+            &publisher.doc,
+            &genesis_mark(),
+            &recipients,
+            &["--content", &content],
+        ))
+        .unwrap();
+
+        let verifier = publisher.keys.public_keys();
+        let inner = edition_env.verify(&verifier).unwrap();
+        let keep = PermitSelector::parse(&hex::encode(members[1].xid().data()))
+            .unwrap();
+        let redaction = Redaction::plan(&inner, &[keep], true).unwrap();
+        assert_eq!((redaction.kept, redaction.elided.len()), (1, 2));
+
+        let redacted = redaction.apply(&edition_env);
+        assert_eq!(redacted.digest(), edition_env.digest());
+        let redacted_inner = redacted.verify(&verifier).unwrap();
+        assert_eq!(
+            ObscuredNodes::survey(&redacted_inner),
+            ObscuredNodes {
+                assertions: 2,
+                permits: 2,
+                club: false,
+                provenance: false,
+            }
+        );
+        let (payload, _) = inspect::edition_payload(&redacted).unwrap();
+        assert_eq!(ObscuredNodes::survey(&payload).permits, 2);
+
+        let edition =
+            decode_edition(&strip_obscured(&redacted_inner)).unwrap();
+        let remaining = permits::sealed_permits(&edition);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].1, Some(members[1].xid()));
+        let key = decrypt::recover_key_from_permits(
+            &[remaining[0].0.clone()],
+            &[keys[1].private_keys()],
+        )
+        .unwrap();
+        let content = edition.content.decrypt(&key).unwrap();
+        assert_eq!(content.extract_subject::<String>().unwrap(), "issue one");
+
+        let elsewhere = SealedMessage::new(
+            b"key",
+            &PrivateKeyBase::new().public_keys(),
+        );
+        let unknown = PermitSelector::parse(
+            &super::super::sealed_digest(&elsewhere).hex(),
+        )
+        .unwrap();
+        let err = Redaction::plan(&inner, &[unknown], false).err().unwrap();
+        assert!(err.is::<ExpectationFailed>());
+        assert!(PermitSelector::parse("not a permit").is_err());
+    }
+}
@@ -1,17 +1,93 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread,
+};
+
 use anyhow::{Context, Result, anyhow, bail};
-use bc_components::XID;
+use bc_components::{Digest, PublicKeys, XID};
 use bc_envelope::prelude::*;
 use clap::Args;
 use clubs::provenance_mark_provider::ProvenanceMarkProvider;
 use known_values::{HAS_RECIPIENT_RAW, PROVENANCE_RAW, SIGNED_RAW};
 use provenance_mark::ProvenanceMark;
+use serde::Serialize;
 
-use crate::io;
+use super::Linkage;
+use crate::{
+    chain::{ChainFile, ChainLock},
+    cmd::VerificationFailed,
+    io::{self, EditionOrMark},
+    output::{self, OutputFormat},
+};
 
 #[derive(Clone)]
 struct EditionSummary {
-    club_xid: XID,
+    /// `--edition #N`, or the file the edition was read from.
+    source: String,
+    /// Digest of the edition envelope as given.
+    digest: Digest,
+    /// Where the input came from, to emit it again; the envelope itself is
+    /// not kept.
+    origin: InputOrigin,
+    kind: EntryKind,
     provenance: ProvenanceMark,
+    /// Whether the signature verified against `--publisher`; `None` when no
+    /// publisher was given.
+    verified: Option<bool>,
+}
+
+/// Where an input was read from.
+#[derive(Clone)]
+enum InputOrigin {
+    /// A file, read again when the input is emitted.
+    File(PathBuf),
+    /// A UR given inline or on standard input.
+    Inline(String),
+}
+
+impl InputOrigin {
+    /// The origin of `spec` and its UR text.
+    fn read(spec: &str) -> Result<(Self, String)> {
+        let ur = io::load_from_spec(spec)?;
+        let origin = match spec.strip_prefix('@').map(str::trim) {
+            Some(path) if !path.is_empty() && path != "-" => {
+                Self::File(PathBuf::from(path))
+            }
+            _ => Self::Inline(ur.clone()),
+        };
+        Ok((origin, ur))
+    }
+
+    /// The input exactly as read: a file's bytes as they are on disk, or
+    /// the inline UR followed by a newline.
+    fn bytes(&self) -> Result<Vec<u8>> {
+        match self {
+            Self::File(path) => fs::read(path).with_context(|| {
+                format!("failed to read input file '{}'", path.display())
+            }),
+            Self::Inline(ur) => Ok(format!("{ur}\n").into_bytes()),
+        }
+    }
+
+    fn ur(&self) -> Result<String> {
+        Ok(String::from_utf8_lossy(&self.bytes()?).trim().to_string())
+    }
+}
+
+/// What an input contributes to the chain.
+#[derive(Clone)]
+enum EntryKind {
+    /// A club edition, naming its club.
+    Edition { club_xid: XID },
+    /// A bare provenance mark, minted for an edition held elsewhere or
+    /// never published. It takes part in ordering and linkage only.
+    Mark,
 }
 
 impl clubs::provenance_mark_provider::ProvenanceMarkProvider
@@ -24,88 +100,1231 @@ impl clubs::provenance_mark_provider::ProvenanceMarkProvider
 /// provenance chain.
 #[derive(Debug, Args)]
 pub struct CommandArgs {
-    /// Edition URs to inspect.
-    #[arg(long = "edition", value_name = "UR", required = true)]
+    /// Edition URs to inspect, or bare provenance marks (`ur:provenance`)
+    /// standing in for editions held elsewhere.
+    #[arg(
+        long = "edition",
+        value_name = "UR",
+        required_unless_present = "edition_dir"
+    )]
     pub editions: Vec<String>,
+    /// Also read every `.ur` file in this directory as an edition. Editions
+    /// given more than once are checked once.
+    #[arg(long, value_name = "DIR")]
+    pub edition_dir: Option<PathBuf>,
+    /// With `--edition-dir`, read the files whose names match this pattern
+    /// (`*` and `?` wildcards) instead of the `.ur` files.
+    #[arg(long, value_name = "PATTERN", requires = "edition_dir")]
+    pub glob: Option<String>,
+    /// Stop at the first edition that cannot be read instead of listing it
+    /// and checking the rest.
+    #[arg(long)]
+    pub fail_fast: bool,
+    /// Output format. `json` prints the chain report as one object on
+    /// stdout.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// Publisher descriptor (XID document or public-keys UR). Each edition's
+    /// signature must verify against it; editions that do not are reported
+    /// and left out of the chain.
+    #[arg(long, value_name = "UR")]
+    pub publisher: Option<String>,
+    /// Exit with status 4 unless the chain is intact: no breaks or forks,
+    /// and the earliest edition is the genesis edition. Every input must
+    /// also be readable and, with `--publisher`, verify.
+    #[arg(long)]
+    pub strict: bool,
+    /// Exit with status 4 when the chain has more than N breaks. With
+    /// `--strict`, tolerate up to N breaks instead of none.
+    #[arg(long, value_name = "N")]
+    pub max_gaps: Option<usize>,
+    /// Treat gaps (missing seqs) as expected, as for editions deliberately
+    /// withheld: they are still listed, but neither `--strict` nor
+    /// `--max-gaps` counts them.
+    #[arg(long)]
+    pub allow_gaps: bool,
+    /// Exit with status 4 when a single gap is missing more than N seqs,
+    /// even with `--allow-gaps`.
+    #[arg(long, value_name = "N")]
+    pub max_gap_size: Option<usize>,
+    /// Once checked, print the chain's edition URs in seq order, one per
+    /// line, or write them into DIR as `0000-genesis.ur`, `0001.ur`, ...
+    /// exactly as they were read. Editions that failed verification are
+    /// left out.
+    #[arg(long, value_name = "DIR", num_args = 0..=1)]
+    pub emit_chain: Option<Option<PathBuf>>,
+    /// Once checked, print the UR of the latest edition reachable from the
+    /// genesis edition without a break on stdout, exactly as it was read.
+    /// When the highest seq lies beyond a break, the latest edition before
+    /// the break is emitted, with a warning.
+    #[arg(long, conflicts_with = "emit_chain")]
+    pub emit_latest: bool,
+    /// Like `--emit-latest`, but write the edition to PATH.
+    #[arg(long, value_name = "PATH")]
+    pub latest_to: Option<PathBuf>,
+    /// With `--emit-latest` or `--latest-to`, exit with status 4 instead of
+    /// emitting an edition short of the highest seq.
+    #[arg(long)]
+    pub require_intact: bool,
+    /// Once checked, record the chain in a chain file at PATH (club, chain
+    /// id, and each edition's seq, mark, and digest) for `edition compose
+    /// --chain` to extend.
+    #[arg(long, value_name = "PATH")]
+    pub write_chain: Option<PathBuf>,
+    /// Check the editions of each club (and provenance chain) as a chain of
+    /// their own instead of refusing inputs from several clubs. `json`
+    /// output becomes an array of per-club reports.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "emit_chain",
+            "emit_latest",
+            "latest_to",
+            "write_chain"
+        ]
+    )]
+    pub group_by_club: bool,
+    /// Read and check this many inputs at a time. Results do not depend on
+    /// it.
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    pub jobs: usize,
+    /// With `--emit-chain`, emit every branch of a fork instead of
+    /// refusing; forked editions are named `0003-<digest>.ur`.
+    #[arg(long, requires = "emit_chain")]
+    pub force: bool,
 }
 
 pub fn exec(args: CommandArgs) -> Result<()> {
-    if args.editions.len() < 2 {
+    let mut inputs: Vec<(String, String)> = args
+        .editions
+        .iter()
+        .enumerate()
+        .map(|(index, spec)| {
+            (format!("--edition #{}", index + 1), spec.clone())
+        })
+        .collect();
+    if let Some(dir) = args.edition_dir.as_ref() {
+        inputs.extend(
+            io::files_matching(dir, args.glob.as_deref())?
+                .into_iter()
+                .map(|path| {
+                    (path.display().to_string(), format!("@{}", path.display()))
+                }),
+        );
+    }
+    if inputs.len() < 2 {
         bail!("at least two editions are required");
     }
+    if matches!(args.emit_chain, Some(None))
+        && args.format == OutputFormat::Json
+    {
+        bail!(
+            "--emit-chain without a directory prints to stdout, which --format json already uses"
+        );
+    }
+    if args.emit_latest && args.format == OutputFormat::Json {
+        bail!(
+            "--emit-latest prints to stdout, which --format json already uses"
+        );
+    }
+    let verifier = args
+        .publisher
+        .as_ref()
+        .map(|spec| {
+            io::parse_recipient_descriptor(spec)
+                .context("failed to parse publisher input")
+                .map(|descriptor| descriptor.public_keys().clone())
+        })
+        .transpose()?;
 
-    let mut summaries: Vec<EditionSummary> =
-        Vec::with_capacity(args.editions.len());
-    for (index, spec) in args.editions.iter().enumerate() {
-        let envelope = io::parse_envelope(spec).with_context(|| {
-            format!("failed to parse edition at position {}", index + 1)
-        })?;
+    let (summaries, failed) =
+        summarize(&inputs, verifier.as_ref(), args.fail_fast, args.jobs)?;
+    if args.group_by_club {
+        return exec_grouped(&args, summaries, failed);
+    }
 
-        let summary = extract_summary(envelope).with_context(|| {
-            format!(
-                "input edition at position {} is not a valid club edition",
-                index + 1
-            )
-        })?;
+    // Editions that fail verification cannot speak for the club or chain,
+    // and bare marks name no club.
+    let included: Vec<&EditionSummary> = summaries
+        .iter()
+        .filter(|edition| edition.included())
+        .collect();
+    let clubs: HashSet<XID> = included
+        .iter()
+        .filter_map(|edition| edition.club_xid())
+        .collect();
+    if clubs.len() > 1 {
+        bail!("editions reference multiple clubs");
+    }
+    if let Some(first) = included.first() {
+        let first_chain = first.provenance.chain_id().to_vec();
+        if included.iter().any(|edition| {
+            edition.provenance.chain_id() != first_chain.as_slice()
+        }) {
+            bail!("editions originate from different provenance chains");
+        }
+    }
 
-        summaries.push(summary);
+    let mut report = SequenceReport::check(&summaries);
+    report.failed = failed;
+    if args.allow_gaps {
+        report.expect_gaps();
+    }
+    match args.format {
+        OutputFormat::Text => report.print_text(),
+        OutputFormat::Json => output::print_json(&report)?,
     }
 
-    let first = &summaries[0];
-    let first_club = first.club_xid;
-    if summaries
+    report.gate(args.strict, args.max_gaps, args.max_gap_size)?;
+    if let Some(target) = &args.emit_chain {
+        let emitted =
+            emit_chain(&summaries, &report, target.as_deref(), args.force)?;
+        if target.is_none() {
+            for ur in emitted {
+                println!("{ur}");
+            }
+        } else if args.format == OutputFormat::Text {
+            eprintln!("Emitted {} edition(s)", emitted.len());
+        }
+    }
+    if let Some(path) = args.write_chain.as_ref() {
+        let _lock = ChainLock::acquire(path)?;
+        let chain = chain_file(&summaries, &report)?;
+        chain.save(path)?;
+        if args.format == OutputFormat::Text {
+            eprintln!(
+                "Wrote {} edition(s) to chain file {}",
+                chain.editions.len(),
+                path.display()
+            );
+        }
+    }
+    if args.emit_latest || args.latest_to.is_some() {
+        let (latest, warning) =
+            reachable_edition(&summaries, &report, args.require_intact)?;
+        if let Some(warning) = warning {
+            eprintln!("warning: {warning}");
+        }
+        if let Some(path) = args.latest_to.as_ref() {
+            output::write_atomic(path, &latest.origin.bytes()?)?;
+        }
+        if args.emit_latest {
+            println!("{}", latest.origin.ur()?);
+        }
+    }
+    Ok(())
+}
+
+/// `--group-by-club`: check and report each club's chain on its own, then
+/// gate each in turn.
+fn exec_grouped(
+    args: &CommandArgs,
+    summaries: Vec<EditionSummary>,
+    failed: Vec<FailedInput>,
+) -> Result<()> {
+    let mut reports: Vec<SequenceReport> = group_by_club(summaries)
         .iter()
-        .any(|edition| edition.club_xid != first_club)
-    {
-        bail!("editions reference multiple clubs");
+        .map(|group| SequenceReport::check(group))
+        .collect();
+    // Unreadable inputs name no club; they are listed with the first group.
+    if let Some(first) = reports.first_mut() {
+        first.failed = failed;
+    }
+    if args.allow_gaps {
+        reports.iter_mut().for_each(SequenceReport::expect_gaps);
+    }
+    match args.format {
+        OutputFormat::Text => {
+            for (index, report) in reports.iter().enumerate() {
+                if index > 0 {
+                    eprintln!();
+                }
+                eprintln!("{}", report.group_label());
+                report.print_text();
+            }
+        }
+        OutputFormat::Json => output::print_json(&reports)?,
     }
+    for report in &reports {
+        report
+            .gate(args.strict, args.max_gaps, args.max_gap_size)
+            .with_context(|| report.group_label())?;
+    }
+    Ok(())
+}
 
-    let first_chain = first.provenance.chain_id().to_vec();
-    if summaries
+/// Partition `summaries` by club and provenance chain, keeping input order
+/// within each group. A bare mark joins the club whose chain it belongs
+/// to, or a group of its own when no edition shares its chain. Groups are
+/// ordered by club XID, then chain id.
+fn group_by_club(summaries: Vec<EditionSummary>) -> Vec<Vec<EditionSummary>> {
+    let clubs_by_chain: HashMap<Vec<u8>, XID> = summaries
         .iter()
-        .any(|edition| edition.provenance.chain_id() != first_chain.as_slice())
-    {
-        bail!("editions originate from different provenance chains");
+        .filter_map(|summary| {
+            summary
+                .club_xid()
+                .map(|club| (summary.provenance.chain_id().to_vec(), club))
+        })
+        .collect();
+    let mut groups: BTreeMap<(Option<String>, String), Vec<EditionSummary>> =
+        BTreeMap::new();
+    for summary in summaries {
+        let chain_id = summary.provenance.chain_id().to_vec();
+        let club = summary
+            .club_xid()
+            .or_else(|| clubs_by_chain.get(&chain_id).copied())
+            .map(|club| club.to_string());
+        groups
+            .entry((club, hex::encode(&chain_id)))
+            .or_default()
+            .push(summary);
     }
+    groups.into_values().collect()
+}
 
-    let mut sorted: Vec<&EditionSummary> = summaries.iter().collect();
-    sorted.sort_by_key(|edition| edition.provenance.seq());
+impl EditionSummary {
+    /// Whether the edition takes part in the chain: it verified, or no
+    /// publisher was given.
+    fn included(&self) -> bool { self.verified != Some(false) }
 
-    let mut breaks = Vec::new();
-    for pair in sorted.windows(2) {
-        if !pair[0].precedes(pair[1]) {
-            breaks.push((pair[0].provenance.seq(), pair[1].provenance.seq()));
+    /// The club an edition names; `None` for a bare mark.
+    fn club_xid(&self) -> Option<XID> {
+        match self.kind {
+            EntryKind::Edition { club_xid } => Some(club_xid),
+            EntryKind::Mark => None,
         }
     }
 
-    for (prev, next) in &breaks {
-        eprintln!(
-            "warning: provenance break between seq {} and {}",
-            prev, next
+    fn is_mark(&self) -> bool { matches!(self.kind, EntryKind::Mark) }
+}
+
+/// The `--format json` report. Field names are part of the CLI's interface.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct SequenceReport {
+    /// The editions in input order.
+    editions: Vec<SequenceEntry>,
+    /// Input positions of the editions, ordered by provenance seq. Editions
+    /// that failed verification are left out.
+    order: Vec<usize>,
+    /// Each pair of consecutive editions along the chain.
+    links: Vec<SequenceLink>,
+    /// Whether the marks, in chain order, pass the provenance-mark crate's
+    /// own sequence validation.
+    marks_valid: bool,
+    breaks: Vec<SequenceBreak>,
+    /// Seqs held by more than one edition.
+    forks: Vec<SequenceFork>,
+    starts_at_genesis: bool,
+    /// No breaks or forks, valid marks, and the earliest edition is the
+    /// genesis edition.
+    intact: bool,
+    /// Editions whose signature did not verify against `--publisher`.
+    unverified: usize,
+    /// Inputs that could not be read as editions, in input order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failed: Vec<FailedInput>,
+    /// The headline facts; absent when no edition takes part in the chain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<ChainSummary>,
+    /// The `index` of each edition along the chain, in seq order, through
+    /// the continuing branch of any fork.
+    #[serde(skip)]
+    path: Vec<usize>,
+}
+
+/// The chain at a glance, printed as the text epilogue.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct ChainSummary {
+    /// Absent when the chain holds only bare marks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    club_xid: Option<String>,
+    chain_id: String,
+    lowest_seq: u32,
+    highest_seq: u32,
+    /// Editions taking part in the chain, fork branches included; bare
+    /// marks are not counted.
+    editions: usize,
+    /// Seqs along the chain held by a bare mark, with no edition.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    mark_only: Vec<u32>,
+    genesis_present: bool,
+    gaps: usize,
+    /// The `index` of the edition with the highest seq, on the branch
+    /// that continues the chain when it forks. Bare marks are passed over.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_index: Option<usize>,
+    /// The first four bytes of the latest edition's digest, in hex.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_digest: Option<String>,
+    /// The `index` and seq of the latest edition reachable from the genesis
+    /// edition without a break; absent when the chain does not start at
+    /// genesis. Gaps accepted with `--allow-gaps` do not count as breaks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reachable_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reachable_seq: Option<u32>,
+}
+
+impl ChainSummary {
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "Club: {}",
+                self.club_xid.as_deref().unwrap_or("unknown (marks only)")
+            ),
+            format!("Chain: {}", self.chain_id),
+            format!(
+                "Seqs: {} through {} ({} editions)",
+                self.lowest_seq, self.highest_seq, self.editions
+            ),
+            format!(
+                "Genesis: {}",
+                if self.genesis_present {
+                    "present"
+                } else {
+                    "missing"
+                }
+            ),
+            format!("Gaps: {}", self.gaps),
+        ];
+        if !self.mark_only.is_empty() {
+            lines.push(format!(
+                "Mark only (no edition): seq {}",
+                self.mark_only
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        if let Some(digest) = &self.latest_digest {
+            lines.push(format!("Latest edition: digest {digest}"));
+        }
+        if self.reachable_index != self.latest_index {
+            lines.push(match self.reachable_seq {
+                Some(seq) => {
+                    format!("Latest reachable from genesis: seq {seq}")
+                }
+                None => "Latest reachable from genesis: none".to_string(),
+            });
+        }
+        lines
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct FailedInput {
+    source: String,
+    error: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct SequenceEntry {
+    /// 1-based position among the editions read, `--edition` inputs
+    /// first, once duplicates are dropped.
+    index: usize,
+    source: String,
+    seq: u32,
+    /// The first four bytes of the provenance chain id, in hex.
+    chain_id: String,
+    date: String,
+    /// `verified` or `failed`; absent without `--publisher` and for bare
+    /// marks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<&'static str>,
+    /// A bare provenance mark: mark only, no edition.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    mark_only: bool,
+}
+
+/// Several editions with the same seq: a fork, or a re-publication.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct SequenceFork {
+    seq: u32,
+    branches: Vec<ForkBranch>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct ForkBranch {
+    /// The edition's `index` in the report.
+    index: usize,
+    /// The first four bytes of the edition's digest, in hex.
+    digest: String,
+    /// Whether an edition at the previous seq precedes this one; absent
+    /// when there is none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    follows_previous: Option<bool>,
+    /// Whether this edition precedes one at the next seq; absent when
+    /// there is none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    continues: Option<bool>,
+}
+
+impl ForkBranch {
+    fn describe(&self) -> String {
+        let continues = match self.continues {
+            Some(true) => "continues the chain",
+            Some(false) => "does not continue the chain",
+            None => "no later edition",
+        };
+        format!("{} (edition {}, {continues})", self.digest, self.index)
+    }
+}
+
+/// The assurance that one edition's mark comes immediately before the
+/// next's.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct SequenceLink {
+    previous_seq: u32,
+    next_seq: u32,
+    /// `cryptographic`, `sequence-only`, or `broken`.
+    linkage: &'static str,
+}
+
+/// Two consecutive editions, by seq, that do not link up.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct SequenceBreak {
+    previous_seq: u32,
+    next_seq: u32,
+    previous_date: String,
+    next_date: String,
+    reason: BreakReason,
+    /// For a gap, the seqs between the two.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    missing: Option<MissingSeqs>,
+    /// A gap accepted with `--allow-gaps`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    expected: bool,
+}
+
+/// Gaps missing at most this many seqs list each of them.
+const MISSING_SEQS_LISTED: u32 = 64;
+
+/// The seqs a gap is missing: the range, and each seq when there are no
+/// more than [`MISSING_SEQS_LISTED`], so a gap across a corrupt seq costs
+/// no more than a small one.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct MissingSeqs {
+    missing_first: u32,
+    missing_last: u32,
+    missing_count: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    missing_seqs: Vec<u32>,
+}
+
+impl MissingSeqs {
+    /// The seqs strictly between `previous` and `next`, or `None` when
+    /// they are adjacent.
+    fn between(previous: u32, next: u32) -> Option<Self> {
+        let first = previous.checked_add(1)?;
+        let last = next.checked_sub(1)?;
+        if first > last {
+            return None;
+        }
+        let count = last - first + 1;
+        Some(Self {
+            missing_first: first,
+            missing_last: last,
+            missing_count: count,
+            missing_seqs: if count <= MISSING_SEQS_LISTED {
+                (first..=last).collect()
+            } else {
+                Vec::new()
+            },
+        })
+    }
+}
+
+impl SequenceBreak {
+    fn is_date_regression(&self) -> bool {
+        self.reason == BreakReason::DateRegression
+    }
+
+    /// How many seqs the gap is missing; 0 for other breaks.
+    fn missing_count(&self) -> u32 {
+        self.missing
+            .as_ref()
+            .map_or(0, |missing| missing.missing_count)
+    }
+
+    /// The missing seqs, listed when there are a few and as a range
+    /// otherwise.
+    fn describe_missing(&self) -> String {
+        let Some(missing) = self.missing.as_ref() else {
+            return String::new();
+        };
+        if missing.missing_count <= 5 {
+            return format!(
+                "missing seq {}",
+                missing
+                    .missing_seqs
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        format!(
+            "missing seq {} through {} ({} seqs)",
+            missing.missing_first, missing.missing_last, missing.missing_count
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum BreakReason {
+    /// Editions between the two are missing.
+    Gap,
+    /// The seqs are adjacent, but the earlier mark's key chain does not
+    /// generate the later one.
+    Unlinked,
+    /// The later edition's mark is dated before the earlier one's. Equal
+    /// dates, as for editions published the same day, are not a
+    /// regression.
+    DateRegression,
+}
+
+impl SequenceReport {
+    fn check(summaries: &[EditionSummary]) -> Self {
+        let editions: Vec<SequenceEntry> = summaries
+            .iter()
+            .enumerate()
+            .map(|(index, summary)| SequenceEntry {
+                index: index + 1,
+                source: summary.source.clone(),
+                seq: summary.provenance.seq(),
+                chain_id: hex::encode(&summary.provenance.chain_id()[..4]),
+                date: summary.provenance.date().to_string(),
+                signature: summary.verified.map(|verified| {
+                    if verified { "verified" } else { "failed" }
+                }),
+                mark_only: summary.is_mark(),
+            })
+            .collect();
+
+        let mut sorted: Vec<(usize, &EditionSummary)> = summaries
+            .iter()
+            .enumerate()
+            .filter(|(_, edition)| edition.included())
+            .collect();
+        sorted.sort_by_key(|(_, edition)| edition.provenance.seq());
+
+        // Editions sharing a seq are forks. The chain is followed through
+        // the branch that continues it, so a fork is not also a break.
+        let groups: Vec<&[(usize, &EditionSummary)]> = sorted
+            .chunk_by(|a, b| a.1.provenance.seq() == b.1.provenance.seq())
+            .collect();
+        let mut forks = Vec::new();
+        let mut path: Vec<&EditionSummary> = Vec::with_capacity(groups.len());
+        let mut path_indices = Vec::with_capacity(groups.len());
+        let mut latest: Option<(usize, &EditionSummary)> = None;
+        let mut mark_only = Vec::new();
+        for (position, group) in groups.iter().enumerate() {
+            let seq = group[0].1.provenance.seq();
+            let adjacent = |offset: isize, want: u32| {
+                position
+                    .checked_add_signed(offset)
+                    .and_then(|other| groups.get(other))
+                    .filter(|other| other[0].1.provenance.seq() == want)
+            };
+            let previous =
+                seq.checked_sub(1).and_then(|want| adjacent(-1, want));
+            let next = seq.checked_add(1).and_then(|want| adjacent(1, want));
+            let branches: Vec<ForkBranch> = group
+                .iter()
+                .map(|(index, edition)| ForkBranch {
+                    index: index + 1,
+                    digest: hex::encode(&edition.digest.data()[..4]),
+                    follows_previous: previous.map(|previous| {
+                        previous.iter().any(|(_, prev)| prev.precedes(*edition))
+                    }),
+                    continues: next.map(|next| {
+                        next.iter().any(|(_, after)| edition.precedes(*after))
+                    }),
+                })
+                .collect();
+            let chosen = branches
+                .iter()
+                .position(|branch| branch.continues == Some(true))
+                .or_else(|| {
+                    branches.iter().position(|branch| {
+                        branch.follows_previous == Some(true)
+                    })
+                })
+                .unwrap_or(0);
+            path.push(group[chosen].1);
+            path_indices.push(group[chosen].0 + 1);
+            if group[chosen].1.is_mark() {
+                mark_only.push(seq);
+            } else {
+                latest = Some(group[chosen]);
+            }
+            if branches.len() > 1 {
+                forks.push(SequenceFork { seq, branches });
+            }
+        }
+
+        // The crate's own chain validation, over the marks in chain order,
+        // alongside the per-link assurance below.
+        let marks: Vec<ProvenanceMark> = path
+            .iter()
+            .map(|edition| edition.provenance.clone())
+            .collect();
+        let marks_valid =
+            marks.len() < 2 || ProvenanceMark::is_sequence_valid(&marks);
+
+        let mut links = Vec::new();
+        let mut breaks = Vec::new();
+        for pair in path.windows(2) {
+            let (prev, next) = (&pair[0].provenance, &pair[1].provenance);
+            let link = |reason| SequenceBreak {
+                previous_seq: prev.seq(),
+                next_seq: next.seq(),
+                previous_date: prev.date().to_string(),
+                next_date: next.date().to_string(),
+                reason,
+                missing: if reason == BreakReason::Gap {
+                    MissingSeqs::between(prev.seq(), next.seq())
+                } else {
+                    None
+                },
+                expected: false,
+            };
+            let linkage = Linkage::between(prev, next);
+            links.push(SequenceLink {
+                previous_seq: prev.seq(),
+                next_seq: next.seq(),
+                linkage: linkage.map_or("broken", Linkage::label),
+            });
+            match linkage {
+                Some(Linkage::Cryptographic) => {}
+                Some(Linkage::SequenceOnly) => {
+                    breaks.push(link(BreakReason::Unlinked))
+                }
+                None => breaks.push(link(BreakReason::Gap)),
+            }
+            if next.date() < prev.date() {
+                breaks.push(link(BreakReason::DateRegression));
+            }
+        }
+
+        let starts_at_genesis = sorted
+            .first()
+            .is_some_and(|(_, edition)| edition.provenance.is_genesis());
+        let summary = path.last().map(|last| ChainSummary {
+            club_xid: path
+                .iter()
+                .find_map(|edition| edition.club_xid())
+                .map(|xid| xid.to_string()),
+            chain_id: hex::encode(last.provenance.chain_id()),
+            lowest_seq: path[0].provenance.seq(),
+            highest_seq: last.provenance.seq(),
+            editions: sorted
+                .iter()
+                .filter(|(_, entry)| !entry.is_mark())
+                .count(),
+            mark_only,
+            genesis_present: path
+                .iter()
+                .any(|edition| edition.provenance.is_genesis()),
+            gaps: breaks
+                .iter()
+                .filter(|b| b.reason == BreakReason::Gap)
+                .count(),
+            latest_index: latest.map(|(index, _)| index + 1),
+            latest_digest: latest
+                .map(|(_, edition)| hex::encode(&edition.digest.data()[..4])),
+            reachable_index: None,
+            reachable_seq: None,
+        });
+        let mut report = Self {
+            intact: breaks.is_empty()
+                && forks.is_empty()
+                && starts_at_genesis
+                && marks_valid,
+            order: sorted.iter().map(|(index, _)| index + 1).collect(),
+            editions,
+            links,
+            marks_valid,
+            breaks,
+            forks,
+            starts_at_genesis,
+            unverified: summaries
+                .iter()
+                .filter(|edition| !edition.included())
+                .count(),
+            failed: Vec::new(),
+            summary,
+            path: path_indices,
+        };
+        report.find_reachable();
+        report
+    }
+
+    /// Record in the summary the latest edition reachable from the genesis
+    /// edition along the chain before its first break, expected gaps aside.
+    fn find_reachable(&mut self) {
+        let broken: HashSet<u32> = self
+            .breaks
+            .iter()
+            .filter(|b| !b.expected)
+            .map(|b| b.previous_seq)
+            .collect();
+        let mut reachable = None;
+        if self.starts_at_genesis {
+            for &index in &self.path {
+                let entry = &self.editions[index - 1];
+                if !entry.mark_only {
+                    reachable = Some((index, entry.seq));
+                }
+                if broken.contains(&entry.seq) {
+                    break;
+                }
+            }
+        }
+        if let Some(summary) = self.summary.as_mut() {
+            summary.reachable_index = reachable.map(|(index, _)| index);
+            summary.reachable_seq = reachable.map(|(_, seq)| seq);
+        }
+    }
+
+    /// Warnings, the chain summary, and the status line, on stderr.
+    fn print_text(&self) {
+        for warning in self.warnings() {
+            eprintln!("warning: {warning}");
+        }
+        for line in self.summary.iter().flat_map(ChainSummary::lines) {
+            eprintln!("{line}");
+        }
+        eprintln!("{}", self.status_line());
+    }
+
+    /// The club and chain a `--group-by-club` report covers.
+    fn group_label(&self) -> String {
+        match self.summary.as_ref() {
+            Some(summary) => format!(
+                "club {} (chain {})",
+                summary.club_xid.as_deref().unwrap_or("unknown"),
+                &summary.chain_id[..8]
+            ),
+            None => "club unknown".to_string(),
+        }
+    }
+
+    /// Mark every gap as expected (`--allow-gaps`). Unlinked adjacent
+    /// marks and date regressions are still breaks.
+    fn expect_gaps(&mut self) {
+        for gap in self.breaks.iter_mut() {
+            gap.expected = gap.reason == BreakReason::Gap;
+        }
+        self.find_reachable();
+    }
+
+    /// Fail with [`VerificationFailed`] when `strict` and the chain is not
+    /// intact, or when it has more than `max_gaps` breaks. Under `strict`,
+    /// `max_gaps` tolerates that many breaks but nothing else. Expected
+    /// gaps are not counted, but any gap missing more than `max_gap_size`
+    /// seqs fails.
+    fn gate(
+        &self,
+        strict: bool,
+        max_gaps: Option<usize>,
+        max_gap_size: Option<usize>,
+    ) -> Result<()> {
+        let fail = |message: String| Err(VerificationFailed(message).into());
+        if strict && !self.failed.is_empty() {
+            return fail(format!(
+                "{} edition(s) could not be read",
+                self.failed.len()
+            ));
+        }
+        if strict && self.unverified > 0 {
+            return fail(format!(
+                "{} edition(s) do not verify against the publisher",
+                self.unverified
+            ));
+        }
+        if let Some(bound) = max_gap_size
+            && let Some(gap) = self
+                .breaks
+                .iter()
+                .find(|b| b.missing_count() as usize > bound)
+        {
+            return fail(format!(
+                "gap between seq {} and {} is missing {} seq(s), more than the {bound} tolerated",
+                gap.previous_seq,
+                gap.next_seq,
+                gap.missing_count()
+            ));
+        }
+        let (regressions, gaps): (Vec<&SequenceBreak>, Vec<&SequenceBreak>) =
+            self.breaks
+                .iter()
+                .filter(|b| !b.expected)
+                .partition(|b| b.is_date_regression());
+        let allowed = max_gaps.or(strict.then_some(0));
+        if let Some(allowed) = allowed
+            && gaps.len() > allowed
+        {
+            return fail(format!(
+                "edition sequence has {} break(s), more than the {allowed} tolerated",
+                gaps.len()
+            ));
+        }
+        if strict && let Some(regression) = regressions.first() {
+            return fail(format!(
+                "provenance date regresses from {} (seq {}) to {} (seq {})",
+                regression.previous_date,
+                regression.previous_seq,
+                regression.next_date,
+                regression.next_seq
+            ));
+        }
+        if strict && !self.forks.is_empty() {
+            return fail(format!(
+                "edition sequence forks at {} seq(s)",
+                self.forks.len()
+            ));
+        }
+        if strict && !self.starts_at_genesis {
+            return fail(
+                "edition sequence does not start at genesis".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// The closing line of the human-readable output.
+    fn status_line(&self) -> String {
+        if self.intact {
+            return "chain INTACT".to_string();
+        }
+        let plural = |count: usize, noun: &str| {
+            format!("{count} {noun}{}", if count == 1 { "" } else { "s" })
+        };
+        let count = |f: fn(&SequenceBreak) -> bool| {
+            self.breaks.iter().filter(|b| f(b)).count()
+        };
+        let regressions = count(SequenceBreak::is_date_regression);
+        let expected = count(|b| b.expected);
+        let unexpected = self.breaks.len() - regressions - expected;
+        let mut problems = Vec::new();
+        if unexpected > 0 {
+            problems.push(plural(unexpected, "break"));
+        }
+        if regressions > 0 {
+            problems.push(plural(regressions, "date regression"));
+        }
+        if !self.forks.is_empty() {
+            problems.push(plural(self.forks.len(), "fork"));
+        }
+        if !self.starts_at_genesis {
+            problems.push("no genesis".to_string());
+        }
+        if problems.is_empty() {
+            return format!(
+                "chain INTACT apart from {}",
+                plural(expected, "expected gap")
+            );
+        }
+        if expected > 0 {
+            problems.push(plural(expected, "expected gap"));
+        }
+        format!("chain BROKEN: {}", problems.join(", "))
+    }
+
+    /// The free-text warnings printed for humans.
+    fn warnings(&self) -> Vec<String> {
+        let forks = self.forks.iter().map(|fork| {
+            format!(
+                "fork at seq {}: {}",
+                fork.seq,
+                fork.branches
+                    .iter()
+                    .map(ForkBranch::describe)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        });
+        let mut warnings: Vec<String> = self
+            .editions
+            .iter()
+            .filter(|entry| entry.signature == Some("failed"))
+            .map(|entry| {
+                format!(
+                    "{} (seq {}) does not verify against the publisher; left out of the chain",
+                    entry.source, entry.seq
+                )
+            })
+            .collect();
+        warnings.extend(forks);
+        warnings.extend(
+            self.breaks
+                .iter()
+                .filter(|b| !b.is_date_regression())
+                .map(|b| match b.reason {
+                    BreakReason::Unlinked => format!(
+                        "provenance marks at seq {} and {} are adjacent but do not cryptographically link",
+                        b.previous_seq, b.next_seq
+                    ),
+                    _ if b.expected => format!(
+                        "expected gap between seq {} and {}: {}",
+                        b.previous_seq,
+                        b.next_seq,
+                        b.describe_missing()
+                    ),
+                    _ => format!(
+                        "provenance break between seq {} and {}: {}",
+                        b.previous_seq,
+                        b.next_seq,
+                        b.describe_missing()
+                    ),
+                }),
         );
+        warnings
+            .extend(self.breaks.iter().filter(|b| b.is_date_regression()).map(
+            |b| {
+                format!(
+                    "provenance date regresses from {} (seq {}) to {} (seq {})",
+                    b.previous_date, b.previous_seq, b.next_date, b.next_seq
+                )
+            },
+        ));
+        if !self.starts_at_genesis
+            && let Some(&index) = self.order.first()
+        {
+            warnings.push(format!(
+                "sequence starts at seq {}",
+                self.editions[index - 1].seq
+            ));
+        }
+        warnings.extend(self.failed.iter().map(|failed| {
+            format!("skipped {}: {}", failed.source, failed.error)
+        }));
+        warnings
     }
+}
 
-    if let Some(first_sorted) = sorted.first()
-        && !first_sorted.provenance.is_genesis()
-    {
-        eprintln!(
-            "warning: sequence starts at seq {}",
-            first_sorted.provenance.seq()
+/// Emit the chain's editions in `report.order`: into `dir`, returning the
+/// file names written, or, without a directory, returning their URs.
+/// Refuses when the chain forks, unless `force`.
+fn emit_chain(
+    summaries: &[EditionSummary],
+    report: &SequenceReport,
+    dir: Option<&Path>,
+    force: bool,
+) -> Result<Vec<String>> {
+    if !report.forks.is_empty() && !force {
+        bail!(
+            "not emitting the chain: it forks at seq {}; pass --force to emit every branch",
+            report
+                .forks
+                .iter()
+                .map(|fork| fork.seq.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
         );
     }
+    let chain = report.order.iter().map(|index| &summaries[index - 1]);
+    let Some(dir) = dir else {
+        return chain.map(|edition| edition.origin.ur()).collect();
+    };
+    output::ensure_dir(dir)?;
+    let forked: HashSet<u32> =
+        report.forks.iter().map(|fork| fork.seq).collect();
+    let mut names = Vec::with_capacity(report.order.len());
+    for edition in chain {
+        let seq = edition.provenance.seq();
+        let name = if forked.contains(&seq) {
+            format!("{seq:04}-{}.ur", hex::encode(&edition.digest.data()[..4]))
+        } else if edition.provenance.is_genesis() {
+            format!("{seq:04}-genesis.ur")
+        } else {
+            format!("{seq:04}.ur")
+        };
+        output::write_atomic(&dir.join(&name), &edition.origin.bytes()?)?;
+        names.push(name);
+    }
+    Ok(names)
+}
 
-    Ok(())
+/// The chain file recording the chain's editions in seq order. A fork
+/// leaves the tip ambiguous, so a forked chain is refused.
+fn chain_file(
+    summaries: &[EditionSummary],
+    report: &SequenceReport,
+) -> Result<ChainFile> {
+    if !report.forks.is_empty() {
+        bail!(
+            "not writing the chain file: the chain forks at seq {}",
+            report
+                .forks
+                .iter()
+                .map(|fork| fork.seq.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    let chain: Vec<&EditionSummary> = report
+        .order
+        .iter()
+        .map(|index| &summaries[index - 1])
+        .collect();
+    let club = chain
+        .iter()
+        .find_map(|edition| edition.club_xid())
+        .context("the chain holds no edition naming a club, only bare marks")?;
+    let mut file = ChainFile::new(club, chain[0].provenance.chain_id());
+    for edition in chain {
+        let digest = (!edition.is_mark()).then_some(&edition.digest);
+        file.push(&edition.provenance, digest);
+    }
+    Ok(file)
 }
 
-fn extract_summary(mut envelope: Envelope) -> Result<EditionSummary> {
+/// The latest edition reachable from the genesis edition, as
+/// `--emit-latest` emits it, with a warning when it falls short of the
+/// highest seq. Falling short fails instead when `require_intact`.
+fn reachable_edition<'a>(
+    summaries: &'a [EditionSummary],
+    report: &SequenceReport,
+    require_intact: bool,
+) -> Result<(&'a EditionSummary, Option<String>)> {
+    let summary = report.summary.as_ref();
+    let latest = summary
+        .and_then(|summary| summary.reachable_index)
+        .map(|index| &summaries[index - 1])
+        .context(
+            "no edition (other than bare marks) is reachable from the genesis edition without a break",
+        )?;
+    let Some(summary) = summary
+        .filter(|summary| summary.reachable_index != summary.latest_index)
+    else {
+        return Ok((latest, None));
+    };
+    let message = format!(
+        "the highest seq {} lies beyond a break; the latest edition reachable from genesis is seq {}",
+        summary.highest_seq,
+        latest.provenance.seq()
+    );
+    if require_intact {
+        return Err(VerificationFailed(message).into());
+    }
+    Ok((latest, Some(message)))
+}
+
+/// Parse each `(source, spec)` input, `jobs` at a time, verifying its
+/// signature against `verifier` when given. Only the club and provenance
+/// mark are kept, not the envelope. Repeated editions are kept once.
+/// Inputs that cannot be read are returned with their errors, in input
+/// order, or the first of them fails the whole run when `fail_fast`.
+fn summarize(
+    inputs: &[(String, String)],
+    verifier: Option<&PublicKeys>,
+    fail_fast: bool,
+    jobs: usize,
+) -> Result<(Vec<EditionSummary>, Vec<FailedInput>)> {
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let results: Mutex<Vec<Option<Result<EditionSummary>>>> =
+        Mutex::new(inputs.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, inputs.len().max(1)) {
+            scope.spawn(|| {
+                while !stop.load(Ordering::Relaxed) {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some((source, spec)) = inputs.get(index) else {
+                        break;
+                    };
+                    let result = summarize_one(source, spec, verifier);
+                    if fail_fast && result.is_err() {
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                    results.lock().expect("no reader panicked")[index] =
+                        Some(result);
+                }
+            });
+        }
+    });
+
+    let mut summaries = Vec::with_capacity(inputs.len());
+    let mut failed = Vec::new();
+    let mut seen: HashSet<Digest> = HashSet::new();
+    let results = results.into_inner().expect("no reader panicked");
+    for ((source, _), result) in inputs.iter().zip(results) {
+        // With `fail_fast`, inputs after a failure may not have been read.
+        let Some(result) = result else {
+            continue;
+        };
+        match result {
+            Ok(summary) => {
+                if seen.insert(summary.digest) {
+                    summaries.push(summary);
+                }
+            }
+            Err(err) if fail_fast => return Err(err),
+            Err(err) => failed.push(FailedInput {
+                source: source.clone(),
+                error: format!("{err:#}"),
+            }),
+        }
+    }
+    Ok((summaries, failed))
+}
+
+fn summarize_one(
+    source: &str,
+    spec: &str,
+    verifier: Option<&PublicKeys>,
+) -> Result<EditionSummary> {
+    let (origin, ur) = InputOrigin::read(spec)
+        .with_context(|| format!("failed to parse edition {source}"))?;
+    let parsed = io::parse_edition_or_mark(&ur)
+        .with_context(|| format!("failed to parse edition {source}"))?;
+    match parsed {
+        EditionOrMark::Edition(envelope) => {
+            let verified = verifier.map(|keys| envelope.verify(keys).is_ok());
+            let summary = extract_summary(source, origin, envelope)
+                .with_context(|| {
+                    format!("{source} is not a valid club edition")
+                })?;
+            Ok(EditionSummary { verified, ..summary })
+        }
+        EditionOrMark::Mark(mark) => Ok(EditionSummary {
+            source: source.to_string(),
+            digest: Digest::from_image(mark.tagged_cbor().to_cbor_data()),
+            origin,
+            kind: EntryKind::Mark,
+            provenance: mark,
+            verified: None,
+        }),
+    }
+}
+
+fn extract_summary(
+    source: &str,
+    origin: InputOrigin,
+    mut envelope: Envelope,
+) -> Result<EditionSummary> {
+    let digest = envelope.digest();
     loop {
         if envelope.check_type("Edition").is_ok() {
             break;
         }
 
-        if envelope
-            .optional_assertion_with_predicate(known_values::SIGNED)?
-            .is_some()
+        // Co-signed editions carry several signatures.
+        if !envelope
+            .assertions_with_predicate(known_values::SIGNED)
+            .is_empty()
         {
             envelope = envelope.subject();
             continue;
@@ -153,5 +1372,831 @@ fn extract_summary(mut envelope: Envelope) -> Result<EditionSummary> {
         provenance.ok_or_else(|| anyhow!("missing provenance mark"))?;
     let club = club.ok_or_else(|| anyhow!("missing club assertion"))?;
 
-    Ok(EditionSummary { club_xid: club, provenance })
+    Ok(EditionSummary {
+        source: source.to_string(),
+        digest,
+        origin,
+        kind: EntryKind::Edition { club_xid: club },
+        provenance,
+        verified: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::{
+        PrivateKeyBase, PrivateKeysProvider, PublicKeysProvider, XIDProvider,
+    };
+    use clubs::edition::Edition;
+    use dcbor::Date;
+    use provenance_mark::{ProvenanceMarkGenerator, ProvenanceMarkResolution};
+
+    use super::*;
+    use crate::{
+        cmd::edition::compose,
+        test_support::{compose_args, marks, register_tags, xid_document},
+    };
+
+    fn chain(len: usize) -> Vec<EditionSummary> {
+        let club_xid = XID::from_data([7; 32]);
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        (0..len)
+            .map(|index| EditionSummary {
+                source: format!("--edition #{}", index + 1),
+                digest: Digest::from_image(format!("edition {index}")),
+                origin: InputOrigin::Inline(format!("edition {index}")),
+                kind: EntryKind::Edition { club_xid },
+                provenance: generator.next(Date::now(), None::<String>),
+                verified: None,
+            })
+            .collect()
+    }
+
+    /// Unsigned editions of one club, one per mark of a fresh chain, all
+    /// carrying `content`, written to `dir` as `<seq>.ur`. Returns the
+    /// inputs for `summarize` in file order.
+    fn write_editions(
+        dir: &Path,
+        len: usize,
+        content: &Envelope,
+    ) -> Vec<(String, String)> {
+        let club_xid = XID::from_data([7; 32]);
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        let _ = fs::remove_dir_all(dir);
+        fs::create_dir_all(dir).unwrap();
+        for seq in 0..len {
+            let mark = generator.next(Date::now(), None::<String>);
+            let edition =
+                Edition::new(club_xid, mark, content.clone()).unwrap();
+            fs::write(
+                dir.join(format!("{seq:05}.ur")),
+                Envelope::from(edition).ur_string(),
+            )
+            .unwrap();
+        }
+        io::files_matching(dir, None)
+            .unwrap()
+            .into_iter()
+            .map(|path| {
+                (path.display().to_string(), format!("@{}", path.display()))
+            })
+            .collect()
+    }
+
+    #[test]
+    #[ignore = "writes 10,000 edition files; run with --ignored"]
+    fn checks_a_chain_of_ten_thousand_edition_files() {
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-sequence-10k-{}", std::process::id()));
+        // Content many times the size of a mark; summaries keep none of it.
+        let content = Envelope::new("x".repeat(4096));
+        let mut inputs = write_editions(&dir, 10_000, &content);
+        inputs.reverse();
+
+        let (summaries, failed) = summarize(&inputs, None, false, 8).unwrap();
+        assert!(failed.is_empty());
+        assert_eq!(summaries.len(), 10_000);
+        assert!(
+            summaries
+                .iter()
+                .all(|summary| matches!(summary.origin, InputOrigin::File(_)))
+        );
+        let report = SequenceReport::check(&summaries);
+        assert!(report.intact);
+        assert_eq!(report.links.len(), 9_999);
+        assert_eq!(report.order[0], 10_000);
+        let summary = report.summary.unwrap();
+        assert_eq!((summary.lowest_seq, summary.highest_seq), (0, 9_999));
+        assert_eq!(summary.latest_index, Some(1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_an_intact_chain_in_seq_order() {
+        let mut editions = chain(3);
+        editions.swap(0, 2);
+        let report = SequenceReport::check(&editions);
+        assert!(report.intact && report.starts_at_genesis);
+        assert!(report.breaks.is_empty());
+        assert_eq!(report.order, [3, 2, 1]);
+        assert_eq!(report.editions[0].seq, 2);
+        assert_eq!(report.editions[0].chain_id.len(), 8);
+        assert!(report.warnings().is_empty());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["intact"], true);
+        assert_eq!(json["starts_at_genesis"], true);
+        assert_eq!(json["order"], serde_json::json!([3, 2, 1]));
+    }
+
+    #[test]
+    fn reports_a_gap_as_a_break() {
+        let mut editions = chain(4);
+        editions.remove(2);
+        let report = SequenceReport::check(&editions);
+        assert!(!report.intact);
+        assert!(report.starts_at_genesis);
+        assert_eq!(report.breaks.len(), 1);
+        let gap = &report.breaks[0];
+        assert_eq!((gap.previous_seq, gap.next_seq), (1, 3));
+        assert_eq!(gap.reason, BreakReason::Gap);
+        assert_eq!(
+            report.warnings(),
+            ["provenance break between seq 1 and 3: missing seq 2"]
+        );
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["breaks"][0]["reason"], "gap");
+    }
+
+    #[test]
+    fn a_chain_without_its_genesis_edition_is_not_intact() {
+        let editions = chain(3).split_off(1);
+        let report = SequenceReport::check(&editions);
+        assert!(report.breaks.is_empty());
+        assert!(!report.starts_at_genesis);
+        assert!(!report.intact);
+        assert_eq!(report.warnings(), ["sequence starts at seq 1"]);
+    }
+
+    /// A signed edition UR for `summary`'s club and mark.
+    fn sign(summary: &EditionSummary, signer: &PrivateKeyBase) -> String {
+        let inner =
+            Envelope::new(format!("issue {}", summary.provenance.seq()))
+                .add_type("Edition")
+                .add_assertion("club", summary.club_xid().unwrap())
+                .add_assertion(
+                    known_values::PROVENANCE,
+                    summary.provenance.clone(),
+                );
+        compose::sign_edition(
+            &inner,
+            &signer.private_keys(),
+            SignatureMetadata::new(),
+        )
+        .ur_string()
+    }
+
+    /// Signed edition URs for a fresh chain, signed by `signers[i]`.
+    fn signed_chain(signers: &[&PrivateKeyBase]) -> Vec<String> {
+        chain(signers.len())
+            .iter()
+            .zip(signers)
+            .map(|(summary, signer)| sign(summary, signer))
+            .collect()
+    }
+
+    fn positional(specs: &[String]) -> Vec<(String, String)> {
+        specs
+            .iter()
+            .enumerate()
+            .map(|(index, spec)| {
+                (format!("--edition #{}", index + 1), spec.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn leaves_out_an_edition_signed_by_another_key() {
+        register_tags();
+        let publisher = PrivateKeyBase::new();
+        let forger = PrivateKeyBase::new();
+        let specs = positional(&signed_chain(&[
+            &publisher, &publisher, &forger, &publisher, &publisher,
+        ]));
+
+        let (summaries, _) = summarize(&specs, None, true, 1).unwrap();
+        let lenient = SequenceReport::check(&summaries);
+        assert!(lenient.intact);
+        assert!(
+            lenient
+                .editions
+                .iter()
+                .all(|entry| entry.signature.is_none())
+        );
+
+        let descriptor = io::parse_recipient_descriptor(
+            &publisher.public_keys().ur_string(),
+        )
+        .unwrap();
+        let (summaries, _) =
+            summarize(&specs, Some(descriptor.public_keys()), true, 1).unwrap();
+        let report = SequenceReport::check(&summaries);
+        assert_eq!(report.unverified, 1);
+        assert_eq!(report.editions[2].signature, Some("failed"));
+        assert_eq!(report.editions[3].signature, Some("verified"));
+        assert_eq!(report.order, [1, 2, 4, 5]);
+        assert!(!report.intact);
+        assert_eq!(
+            (report.breaks[0].previous_seq, report.breaks[0].next_seq),
+            (1, 3)
+        );
+        assert!(
+            report.warnings()[0]
+                .starts_with("--edition #3 (seq 2) does not verify")
+        );
+    }
+
+    #[test]
+    fn reads_a_co_signed_edition() {
+        register_tags();
+        let publisher = PrivateKeyBase::new();
+        let specs: Vec<String> = signed_chain(&[&publisher, &publisher])
+            .iter()
+            .map(|edition| {
+                Envelope::from_ur_string(edition)
+                    .unwrap()
+                    .add_signature(&PrivateKeyBase::new())
+                    .ur_string()
+            })
+            .collect();
+
+        let descriptor = io::parse_recipient_descriptor(
+            &publisher.public_keys().ur_string(),
+        )
+        .unwrap();
+        let (summaries, failed) = summarize(
+            &positional(&specs),
+            Some(descriptor.public_keys()),
+            true,
+            1,
+        )
+        .unwrap();
+        assert!(failed.is_empty());
+        let report = SequenceReport::check(&summaries);
+        assert!(report.intact);
+        assert_eq!(report.editions[1].signature, Some("verified"));
+    }
+
+    #[test]
+    fn reads_a_directory_and_lists_files_that_are_not_editions() {
+        register_tags();
+        let publisher = PrivateKeyBase::new();
+        let urs = signed_chain(&[&publisher; 3]);
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-sequence-dir-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (index, ur) in urs.iter().enumerate() {
+            std::fs::write(dir.join(format!("issue-{index}.ur")), ur).unwrap();
+        }
+        let stray = dir.join("notes.ur");
+        std::fs::write(&stray, "not an edition").unwrap();
+
+        // The explicit edition is also in the directory.
+        let mut inputs = positional(&urs[1..2]);
+        inputs.extend(
+            io::files_matching(&dir, Some("*.ur"))
+                .unwrap()
+                .into_iter()
+                .map(|path| {
+                    (path.display().to_string(), format!("@{}", path.display()))
+                }),
+        );
+        let (summaries, failed) = summarize(&inputs, None, false, 1).unwrap();
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(summaries[0].source, "--edition #1");
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].source, stray.display().to_string());
+
+        let mut report = SequenceReport::check(&summaries);
+        report.failed = failed;
+        assert!(report.intact);
+        let warnings = report.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].starts_with(&format!("skipped {}", stray.display()))
+        );
+
+        let err = summarize(&inputs, None, true, 1).err().unwrap();
+        assert!(
+            format!("{err:#}").contains(&stray.display().to_string()),
+            "{err:#}"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_a_republished_seq_as_a_fork() {
+        let mut editions = chain(5);
+        let mut republished = editions[3].clone();
+        republished.source = "--edition #6".to_string();
+        republished.digest = Digest::from_image("issue 3, corrected");
+        editions.push(republished);
+
+        let report = SequenceReport::check(&editions);
+        assert!(report.breaks.is_empty());
+        assert!(!report.intact);
+        assert_eq!(report.forks.len(), 1);
+        let fork = &report.forks[0];
+        assert_eq!(fork.seq, 3);
+        let indexes: Vec<usize> =
+            fork.branches.iter().map(|branch| branch.index).collect();
+        assert_eq!(indexes, [4, 6]);
+        assert_ne!(fork.branches[0].digest, fork.branches[1].digest);
+        assert!(fork.branches.iter().all(|branch| {
+            branch.follows_previous == Some(true)
+                && branch.continues == Some(true)
+        }));
+        assert!(report.warnings()[0].starts_with("fork at seq 3: "));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["forks"][0]["seq"], 3);
+        assert_eq!(json["forks"][0]["branches"][1]["continues"], true);
+    }
+
+    #[test]
+    fn emits_the_chain_in_seq_order_and_refuses_forks() {
+        register_tags();
+        let publisher = PrivateKeyBase::new();
+        let urs = signed_chain(&[&publisher; 3]);
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-sequence-emit-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        // Out of order, and without a trailing newline.
+        let inputs: Vec<(String, String)> = [2, 0, 1]
+            .iter()
+            .map(|&index| {
+                let path = dir.join(format!("in-{index}.ur"));
+                fs::write(&path, &urs[index]).unwrap();
+                (path.display().to_string(), format!("@{}", path.display()))
+            })
+            .collect();
+        let (summaries, _) = summarize(&inputs, None, true, 1).unwrap();
+        let report = SequenceReport::check(&summaries);
+
+        let printed = emit_chain(&summaries, &report, None, false).unwrap();
+        assert_eq!(printed, urs);
+
+        let out = dir.join("chain");
+        let names = emit_chain(&summaries, &report, Some(&out), false).unwrap();
+        assert_eq!(names, ["0000-genesis.ur", "0001.ur", "0002.ur"]);
+        for (name, ur) in names.iter().zip(&urs) {
+            assert_eq!(fs::read(out.join(name)).unwrap(), ur.as_bytes());
+        }
+
+        let mut forked = summaries.clone();
+        let mut republished = forked[2].clone();
+        republished.digest = Digest::from_image("issue 1, corrected");
+        forked.push(republished);
+        let report = SequenceReport::check(&forked);
+        let err = emit_chain(&forked, &report, Some(&out), false)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("forks at seq 1"), "{err}");
+        let names = emit_chain(&forked, &report, Some(&out), true).unwrap();
+        assert_eq!(names.len(), 4);
+        assert!(names[1].starts_with("0001-") && names[2].starts_with("0001-"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reads_inputs_in_parallel_with_the_same_result() {
+        register_tags();
+        let publisher = PrivateKeyBase::new();
+        let urs = signed_chain(&[&publisher; 6]);
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-sequence-jobs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let mut inputs: Vec<(String, String)> = urs
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, ur)| {
+                let path = dir.join(format!("{index}.ur"));
+                fs::write(&path, ur).unwrap();
+                (path.display().to_string(), format!("@{}", path.display()))
+            })
+            .collect();
+        inputs.push(("--edition #1".to_string(), urs[3].clone()));
+        inputs.push(("--edition #2".to_string(), "ur:bogus".to_string()));
+
+        let read = |jobs| {
+            let (summaries, failed) =
+                summarize(&inputs, Some(&publisher.public_keys()), false, jobs)
+                    .unwrap();
+            let report =
+                SequenceReport { failed, ..SequenceReport::check(&summaries) };
+            serde_json::to_value(&report).unwrap()
+        };
+        let serial = read(1);
+        assert_eq!(serial["editions"].as_array().unwrap().len(), 6);
+        assert_eq!(serial["failed"][0]["source"], "--edition #2");
+        assert_eq!(read(4), serial);
+        assert_eq!(read(0), serial);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn writes_a_chain_file_that_compose_extends() {
+        let publisher = xid_document();
+        let marks = marks(5);
+        let compose_edition = |mark: &ProvenanceMark, extra: &[&str]| {
+            compose::run(compose_args(&publisher, mark, extra))
+                .map(|composed| composed.edition_ur)
+        };
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-sequence-chain-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("chain.json");
+        let chain_arg = path.display().to_string();
+
+        let mut urs: Vec<String> = marks[..3]
+            .iter()
+            .map(|mark| compose_edition(mark, &[]).unwrap())
+            .collect();
+        let (summaries, _) =
+            summarize(&positional(&urs), None, true, 1).unwrap();
+        let report = SequenceReport::check(&summaries);
+        chain_file(&summaries, &report)
+            .unwrap()
+            .save(&path)
+            .unwrap();
+
+        // Seq 4 skips seq 3, so it does not extend the tip.
+        let err = compose_edition(&marks[4], &["--chain", &chain_arg])
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .contains("does not extend the chain file's tip at seq 2"),
+            "{err}"
+        );
+        assert_eq!(ChainFile::load(&path).unwrap().editions.len(), 3);
+
+        urs.push(compose_edition(&marks[3], &["--chain", &chain_arg]).unwrap());
+        assert!(!dir.join("chain.json.lock").exists());
+        let recorded = ChainFile::load(&path).unwrap();
+        assert_eq!(recorded.club, publisher.xid().ur_string());
+        assert_eq!(recorded.editions.last().unwrap().seq, 3);
+
+        let (summaries, _) =
+            summarize(&positional(&urs), None, true, 1).unwrap();
+        let report = SequenceReport::check(&summaries);
+        assert!(report.intact);
+        let rewritten = chain_file(&summaries, &report).unwrap();
+        assert_eq!(
+            serde_json::to_value(&rewritten).unwrap(),
+            serde_json::to_value(&recorded).unwrap()
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn groups_interleaved_clubs_into_chains_of_their_own() {
+        register_tags();
+        let (first, second) = (PrivateKeyBase::new(), PrivateKeyBase::new());
+        let mut other = chain(2);
+        for summary in &mut other {
+            summary.kind =
+                EntryKind::Edition { club_xid: XID::from_data([9; 32]) };
+        }
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-sequence-clubs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        // File names interleave the two clubs' editions.
+        for (index, summary) in chain(3).iter().enumerate() {
+            fs::write(dir.join(format!("{index}-a.ur")), sign(summary, &first))
+                .unwrap();
+        }
+        for (index, summary) in other.iter().enumerate() {
+            fs::write(
+                dir.join(format!("{index}-b.ur")),
+                sign(summary, &second),
+            )
+            .unwrap();
+        }
+        let inputs: Vec<(String, String)> = io::ur_files_in(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|path| {
+                (path.display().to_string(), format!("@{}", path.display()))
+            })
+            .collect();
+        let (summaries, _) = summarize(&inputs, None, true, 1).unwrap();
+
+        let groups = group_by_club(summaries);
+        assert_eq!(groups.iter().map(Vec::len).collect::<Vec<_>>(), [3, 2]);
+        let reports: Vec<SequenceReport> = groups
+            .iter()
+            .map(|group| SequenceReport::check(group))
+            .collect();
+        assert!(reports.iter().all(|report| report.intact));
+        assert!(
+            groups[1]
+                .iter()
+                .all(|summary| summary.source.ends_with("-b.ur"))
+        );
+        assert!(
+            reports[1]
+                .group_label()
+                .starts_with(&format!("club {}", XID::from_data([9; 32])))
+        );
+        let json = serde_json::to_value(&reports).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+        assert_eq!(json[0]["summary"]["editions"], 3);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn gates_on_breaks_within_and_beyond_the_tolerance() {
+        let intact = SequenceReport::check(&chain(4));
+        assert!(intact.gate(true, None, None).is_ok());
+        assert_eq!(intact.status_line(), "chain INTACT");
+
+        let mut gapped = chain(6);
+        gapped.remove(4);
+        gapped.remove(2);
+        let report = SequenceReport::check(&gapped);
+        assert_eq!(report.breaks.len(), 2);
+        assert_eq!(report.status_line(), "chain BROKEN: 2 breaks");
+        assert!(report.gate(false, None, None).is_ok());
+
+        let strict = report.gate(true, None, None).err().unwrap();
+        assert!(strict.is::<VerificationFailed>());
+        assert!(report.gate(false, Some(2), None).is_ok());
+        assert!(report.gate(true, Some(2), None).is_ok());
+        let exceeded = report.gate(false, Some(1), None).err().unwrap();
+        assert!(exceeded.is::<VerificationFailed>());
+        assert!(exceeded.to_string().contains("more than the 1 tolerated"));
+
+        let mut unrooted = chain(4).split_off(1);
+        unrooted.push(unrooted[1].clone());
+        unrooted[3].digest = Digest::from_image("issue 2, again");
+        let report = SequenceReport::check(&unrooted);
+        assert_eq!(report.status_line(), "chain BROKEN: 1 fork, no genesis");
+        assert!(report.gate(false, Some(0), None).is_ok());
+        assert!(report.gate(true, Some(5), None).is_err());
+    }
+
+    #[test]
+    fn lists_missing_seqs_and_allows_gaps_up_to_a_size() {
+        let mut editions = chain(210);
+        editions.drain(8..208);
+        editions.drain(3..5);
+        let mut report = SequenceReport::check(&editions);
+        let missing: Vec<&MissingSeqs> = report
+            .breaks
+            .iter()
+            .map(|gap| gap.missing.as_ref().unwrap())
+            .collect();
+        assert_eq!(missing[0].missing_seqs, [3, 4]);
+        // Too many to list; only the range and count are kept.
+        assert!(missing[1].missing_seqs.is_empty());
+        assert_eq!(
+            (
+                missing[1].missing_first,
+                missing[1].missing_last,
+                missing[1].missing_count
+            ),
+            (8, 207, 200)
+        );
+        assert_eq!(
+            report.warnings(),
+            [
+                "provenance break between seq 2 and 5: missing seq 3, 4",
+                "provenance break between seq 7 and 208: missing seq 8 through 207 (200 seqs)",
+            ]
+        );
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json["breaks"][0]["missing_seqs"],
+            serde_json::json!([3, 4])
+        );
+        assert!(json["breaks"][1].get("missing_seqs").is_none());
+        assert_eq!(json["breaks"][1]["missing_count"], 200);
+        assert!(report.gate(true, None, None).is_err());
+
+        report.expect_gaps();
+        assert_eq!(
+            report.status_line(),
+            "chain INTACT apart from 2 expected gaps"
+        );
+        assert!(report.warnings()[0].starts_with("expected gap between seq 2"));
+        assert!(report.gate(true, None, None).is_ok());
+        assert!(report.gate(false, Some(0), None).is_ok());
+        assert!(report.gate(true, None, Some(200)).is_ok());
+        let err = report.gate(true, None, Some(199)).err().unwrap();
+        assert!(err.is::<VerificationFailed>());
+        assert!(
+            err.to_string()
+                .contains("missing 200 seq(s), more than the 199"),
+            "{err}"
+        );
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["breaks"][1]["expected"], true);
+    }
+
+    #[test]
+    fn reports_a_date_regression_between_adjacent_seqs() {
+        let club_xid = XID::from_data([7; 32]);
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        let editions: Vec<EditionSummary> =
+            ["2025-01-01", "2025-01-03", "2025-01-03", "2025-01-02"]
+                .iter()
+                .enumerate()
+                .map(|(index, date)| EditionSummary {
+                    source: format!("--edition #{}", index + 1),
+                    digest: Digest::from_image(format!("edition {index}")),
+                    origin: InputOrigin::Inline(String::new()),
+                    kind: EntryKind::Edition { club_xid },
+                    provenance: generator.next(
+                        Date::from_string(*date).unwrap(),
+                        None::<String>,
+                    ),
+                    verified: None,
+                })
+                .collect();
+
+        let report = SequenceReport::check(&editions);
+        assert_eq!(report.breaks.len(), 1);
+        let regression = &report.breaks[0];
+        assert!(regression.is_date_regression());
+        assert_eq!((regression.previous_seq, regression.next_seq), (2, 3));
+        assert!(regression.previous_date.starts_with("2025-01-03"));
+        assert!(regression.next_date.starts_with("2025-01-02"));
+        assert!(!report.intact);
+        assert_eq!(report.status_line(), "chain BROKEN: 1 date regression");
+        assert!(
+            report.warnings()[0].starts_with("provenance date regresses from")
+        );
+
+        // A regression is not a gap, but --strict refuses it.
+        assert!(report.gate(false, Some(0), None).is_ok());
+        let err = report.gate(true, Some(3), None).err().unwrap();
+        assert!(err.is::<VerificationFailed>());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["breaks"][0]["reason"], "date-regression");
+        assert!(
+            json["editions"][3]["date"]
+                .as_str()
+                .unwrap()
+                .starts_with("2025-01-02")
+        );
+    }
+
+    #[test]
+    fn flags_adjacent_marks_from_another_chain() {
+        let mut editions = chain(4);
+        // Seq 3 of another chain, for the same club.
+        editions[3] = chain(4).remove(3);
+        let report = SequenceReport::check(&editions);
+        let linkages: Vec<&str> =
+            report.links.iter().map(|link| link.linkage).collect();
+        assert_eq!(
+            linkages,
+            ["cryptographic", "cryptographic", "sequence-only"]
+        );
+        assert!(!report.marks_valid);
+        assert!(!report.intact);
+        assert_eq!(report.breaks.len(), 1);
+        assert_eq!(report.breaks[0].reason, BreakReason::Unlinked);
+        assert!(report.warnings()[0].contains("do not cryptographically link"));
+
+        let mut gapped = chain(4);
+        gapped.remove(1);
+        let report = SequenceReport::check(&gapped);
+        assert_eq!(report.links[0].linkage, "broken");
+        assert!(!report.marks_valid);
+        assert!(SequenceReport::check(&chain(3)).marks_valid);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["links"][1]["linkage"], "cryptographic");
+        assert_eq!(json["marks_valid"], false);
+    }
+
+    #[test]
+    fn summarizes_the_chain_and_finds_the_latest_of_shuffled_inputs() {
+        let mut editions = chain(5);
+        editions.swap(0, 3);
+        editions.swap(1, 4);
+        let report = SequenceReport::check(&editions);
+        let summary = report.summary.as_ref().unwrap();
+        assert_eq!((summary.lowest_seq, summary.highest_seq), (0, 4));
+        assert_eq!(summary.editions, 5);
+        assert!(summary.genesis_present);
+        assert_eq!(summary.gaps, 0);
+        assert_eq!(summary.latest_index, Some(2));
+        let (latest, warning) =
+            reachable_edition(&editions, &report, true).unwrap();
+        assert!(warning.is_none());
+        assert_eq!(latest.provenance.seq(), 4);
+        assert_eq!(
+            summary.latest_digest,
+            Some(hex::encode(&latest.digest.data()[..4]))
+        );
+        assert_eq!(
+            summary.club_xid,
+            editions[0].club_xid().map(|xid| xid.to_string())
+        );
+        assert!(
+            summary
+                .lines()
+                .contains(&"Seqs: 0 through 4 (5 editions)".to_string())
+        );
+
+        let mut gapped = chain(6);
+        gapped.remove(2);
+        gapped.reverse();
+        let report = SequenceReport::check(&gapped);
+        let summary = report.summary.as_ref().unwrap();
+        assert_eq!(summary.gaps, 1);
+        assert_eq!(summary.latest_index, Some(1));
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["summary"]["highest_seq"], 5);
+        assert_eq!(json["summary"]["genesis_present"], true);
+    }
+
+    #[test]
+    fn emits_the_latest_edition_before_the_first_break() {
+        let mut broken = chain(6);
+        broken.remove(3);
+        let mut report = SequenceReport::check(&broken);
+        let summary = report.summary.as_ref().unwrap();
+        assert_eq!(summary.latest_index, Some(5));
+        assert_eq!(
+            (summary.reachable_index, summary.reachable_seq),
+            (Some(3), Some(2))
+        );
+        assert!(
+            summary
+                .lines()
+                .contains(&"Latest reachable from genesis: seq 2".to_string())
+        );
+        let (latest, warning) =
+            reachable_edition(&broken, &report, false).unwrap();
+        assert_eq!(latest.provenance.seq(), 2);
+        assert_eq!(
+            warning.as_deref(),
+            Some(
+                "the highest seq 5 lies beyond a break; the latest edition reachable from genesis is seq 2"
+            )
+        );
+        let err = reachable_edition(&broken, &report, true).err().unwrap();
+        assert!(err.is::<VerificationFailed>());
+
+        // An expected gap does not stop the chain short of its tip.
+        report.expect_gaps();
+        let (latest, warning) =
+            reachable_edition(&broken, &report, true).unwrap();
+        assert_eq!(latest.provenance.seq(), 5);
+        assert!(warning.is_none());
+
+        let unrooted = chain(4).split_off(1);
+        let report = SequenceReport::check(&unrooted);
+        assert!(reachable_edition(&unrooted, &report, false).is_err());
+    }
+
+    #[test]
+    fn a_bare_mark_links_two_editions() {
+        register_tags();
+        let publisher = PrivateKeyBase::new();
+        let marks = chain(3);
+        let inputs = positional(&[
+            sign(&marks[2], &publisher),
+            marks[1].provenance.ur_string(),
+            sign(&marks[0], &publisher),
+        ]);
+        let descriptor = io::parse_recipient_descriptor(
+            &publisher.public_keys().ur_string(),
+        )
+        .unwrap();
+        let (summaries, failed) =
+            summarize(&inputs, Some(descriptor.public_keys()), true, 1)
+                .unwrap();
+        assert!(failed.is_empty());
+        assert!(summaries[1].is_mark());
+        assert_eq!(summaries[1].club_xid(), None);
+
+        let report = SequenceReport::check(&summaries);
+        assert!(report.intact, "{:?}", report.warnings());
+        assert_eq!(report.unverified, 0);
+        assert!(
+            report
+                .links
+                .iter()
+                .all(|link| link.linkage == "cryptographic")
+        );
+        assert!(report.editions[1].mark_only);
+        assert_eq!(report.editions[1].signature, None);
+        let summary = report.summary.as_ref().unwrap();
+        assert_eq!(summary.editions, 2);
+        assert_eq!(summary.mark_only, [1]);
+        assert_eq!(summary.latest_index, Some(1));
+        assert!(
+            summary
+                .lines()
+                .contains(&"Mark only (no edition): seq 1".to_string())
+        );
+    }
 }
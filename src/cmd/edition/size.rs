@@ -0,0 +1,248 @@
+use bc_envelope::prelude::*;
+use known_values::HAS_RECIPIENT_RAW;
+use serde::Serialize;
+
+use crate::io;
+
+/// Byte-level breakdown of a serialized edition.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct EditionSize {
+    pub cbor_bytes: usize,
+    pub ur_chars: usize,
+    pub content_bytes: usize,
+    pub permit_count: usize,
+    pub permit_bytes: usize,
+    /// Assertions at each nesting level: level 0 is the signed wrapper,
+    /// level 1 the edition itself, level 2 the permits' own assertions, and
+    /// so on.
+    pub assertions_by_level: Vec<usize>,
+}
+
+impl EditionSize {
+    /// Measure a signed (or bare) edition envelope by walking its parts.
+    pub fn measure(edition_env: &Envelope) -> Self {
+        let inner = if edition_env.subject().is_wrapped() {
+            edition_env
+                .try_unwrap()
+                .unwrap_or_else(|_| edition_env.clone())
+        } else {
+            edition_env.clone()
+        };
+
+        let permits: Vec<Envelope> = inner
+            .assertions()
+            .into_iter()
+            .filter(is_permit_assertion)
+            .collect();
+
+        Self {
+            cbor_bytes: edition_env.to_cbor_data().len(),
+            ur_chars: io::envelope_ur_len(edition_env),
+            content_bytes: inner.subject().to_cbor_data().len(),
+            permit_count: permits.len(),
+            permit_bytes: permits
+                .iter()
+                .map(|permit| permit.to_cbor_data().len())
+                .sum(),
+            assertions_by_level: assertions_by_level(edition_env),
+        }
+    }
+
+    /// Bytes not attributable to content or permits: signatures, the
+    /// wrapper, and the club and provenance assertions.
+    pub fn overhead_bytes(&self) -> usize {
+        self.cbor_bytes
+            .saturating_sub(self.content_bytes)
+            .saturating_sub(self.permit_bytes)
+    }
+
+    pub fn average_permit_bytes(&self) -> usize {
+        self.permit_bytes
+            .checked_div(self.permit_count)
+            .unwrap_or(0)
+    }
+
+    /// Print the size report to stderr.
+    pub fn print(&self) {
+        for line in self.lines() {
+            eprintln!("{line}");
+        }
+    }
+
+    /// The size report, one entry per line.
+    pub fn lines(&self) -> Vec<String> {
+        let levels: Vec<String> = self
+            .assertions_by_level
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        vec![
+            format!(
+                "Size: {} bytes CBOR, {} UR characters",
+                self.cbor_bytes, self.ur_chars
+            ),
+            format!("  content: {} bytes", self.content_bytes),
+            format!(
+                "  permits: {} x ~{} bytes = {} bytes",
+                self.permit_count,
+                self.average_permit_bytes(),
+                self.permit_bytes
+            ),
+            format!("  signature and wrapper: {} bytes", self.overhead_bytes()),
+            format!("  assertions by level: {}", levels.join(", ")),
+        ]
+    }
+}
+
+/// Count assertions at each nesting level, descending into wrapped
+/// subjects and assertion objects.
+fn assertions_by_level(envelope: &Envelope) -> Vec<usize> {
+    fn walk(envelope: &Envelope, level: usize, counts: &mut Vec<usize>) {
+        match envelope.case() {
+            EnvelopeCase::Node { subject, assertions, .. } => {
+                if counts.len() <= level {
+                    counts.resize(level + 1, 0);
+                }
+                counts[level] += assertions.len();
+                walk(subject, level, counts);
+                for assertion in assertions {
+                    if let Some(object) = assertion.as_object() {
+                        walk(&object, level + 1, counts);
+                    }
+                }
+            }
+            EnvelopeCase::Wrapped { envelope, .. } => {
+                walk(envelope, level + 1, counts)
+            }
+            _ => {}
+        }
+    }
+    let mut counts = Vec::new();
+    walk(envelope, 0, &mut counts);
+    counts
+}
+
+fn is_permit_assertion(assertion: &Envelope) -> bool {
+    assertion
+        .as_predicate()
+        .and_then(|predicate| predicate.as_known_value().cloned())
+        .is_some_and(|kv| kv.value() == HAS_RECIPIENT_RAW)
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::{
+        PrivateKeyBase, PrivateKeysProvider, PublicKeysProvider,
+    };
+
+    use super::*;
+    use crate::{
+        cmd::edition::compose,
+        io,
+        test_support::{
+            compose_args, genesis_mark, register_tags, xid_document,
+        },
+    };
+
+    fn permit_flags(count: usize) -> Vec<String> {
+        (0..count)
+            .flat_map(|_| {
+                [
+                    "--permit".to_string(),
+                    PrivateKeyBase::new().public_keys().ur_string(),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn breakdown_sums_to_the_total() {
+        register_tags();
+        let flags = permit_flags(3);
+        let flags: Vec<&str> = flags.iter().map(String::as_str).collect();
+        let composed = compose::run(compose_args(
+            &xid_document(),
+            &genesis_mark(),
+            &flags,
+        ))
+        .unwrap();
+        let size = EditionSize::measure(
+            &io::parse_envelope(&composed.edition_ur).unwrap(),
+        );
+
+        assert_eq!(size.permit_count, 3);
+        assert!(size.content_bytes > 0);
+        assert!(size.content_bytes + size.permit_bytes < size.cbor_bytes);
+        assert_eq!(
+            size.content_bytes + size.permit_bytes + size.overhead_bytes(),
+            size.cbor_bytes
+        );
+        // One signature, the wrapper, and the club and provenance
+        // assertions: small next to three sealed permits.
+        assert!(size.overhead_bytes() < size.permit_bytes, "{size:?}");
+        assert!(size.average_permit_bytes() * 3 <= size.permit_bytes);
+        assert!(size.ur_chars >= 2 * size.cbor_bytes);
+        assert_eq!(size.assertions_by_level[0], 1);
+        assert!(size.assertions_by_level[1] >= 5);
+    }
+
+    #[test]
+    fn max_size_fails_above_the_limit_only() {
+        let (publisher, mark) = (xid_document(), genesis_mark());
+        let flags = permit_flags(2);
+        let compose = |limit: &str| {
+            let mut argv: Vec<&str> =
+                flags.iter().map(String::as_str).collect();
+            argv.extend(["--max-size", limit]);
+            compose::run(compose_args(&publisher, &mark, &argv))
+        };
+        let err = compose("100").err().unwrap();
+        assert!(
+            err.to_string().contains("exceeding --max-size 100"),
+            "{err:#}"
+        );
+        let composed = compose("1000000").unwrap();
+        let size = EditionSize::measure(
+            &io::parse_envelope(&composed.edition_ur).unwrap(),
+        );
+        let exact = size.cbor_bytes.to_string();
+        let below = (size.cbor_bytes - 1).to_string();
+        // Permits are sealed afresh each run, but to the same size.
+        assert!(compose(&exact).is_ok());
+        assert!(compose(&below).is_err());
+    }
+
+    #[test]
+    fn json_form_uses_kebab_case_fields() {
+        register_tags();
+        let edition = compose::sign_edition(
+            &Envelope::new("content").add_assertion("club", "example"),
+            &PrivateKeyBase::new().private_keys(),
+            SignatureMetadata::new(),
+        );
+        let size = EditionSize::measure(&edition);
+        let json = serde_json::to_value(&size).unwrap();
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "assertions_by_level",
+                "cbor_bytes",
+                "content_bytes",
+                "permit_bytes",
+                "permit_count",
+                "ur_chars",
+            ]
+        );
+        assert_eq!(json["cbor_bytes"], size.cbor_bytes);
+        assert_eq!(json["permit_count"], 0);
+        assert_eq!(json["assertions_by_level"], serde_json::json!([1, 1]));
+    }
+}
@@ -1,61 +1,2979 @@
-use anyhow::{Context, Result, bail};
-use clap::Args;
-use clubs::{
-    edition::Edition, provenance_mark_provider::ProvenanceMarkProvider,
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
 };
 
-use crate::io;
+use anyhow::{Context, Result, anyhow};
+use bc_components::{
+    PrivateKeys, PublicKeys, ReferenceProvider, SealedMessage, Signature,
+    SymmetricKey, Verifier, XID,
+};
+use bc_envelope::{EnvelopeCase, prelude::*};
+use bc_xid::{HasPermissions, Key, Privilege};
+use clap::Args;
+use clubs::edition::Edition;
+use dcbor::{CBORTaggedDecodable, Date, prelude::CBOR};
+use known_values::{DATE, HAS_RECIPIENT, SIGNED};
+use serde::Serialize;
+
+use super::{
+    DigestBinding, Linkage,
+    inspect::{self, verifier_label},
+    permits,
+};
+use crate::{
+    chain,
+    cmd::{
+        EXPECTATION_FAILED_EXIT, Silenced, VERIFICATION_FAILED_EXIT,
+        content::decrypt,
+    },
+    io::{self, EditionOrMark, PassphraseArgs, RecipientDescriptor},
+    output::{self, OutputFormat},
+    trust::{self, TrustArgs},
+};
 
 /// Verify the signature and optional provenance of an edition.
 #[derive(Debug, Args)]
 pub struct CommandArgs {
-    /// Edition UR to verify.
-    #[arg(long, value_name = "UR")]
-    pub edition: String,
-    /// Optional previous edition UR for provenance validation.
+    /// Edition UR to verify. Repeat to verify an ordered chain: every
+    /// edition must be signed by `--publisher`, name the same club, and
+    /// immediately follow the one before it.
+    #[arg(
+        long = "edition",
+        value_name = "UR",
+        required_unless_present_any = ["edition_dir", "chain_file"]
+    )]
+    pub editions: Vec<String>,
+    /// File listing edition paths, one per line and in chain order, to
+    /// verify as with repeated `--edition`. Relative paths are resolved
+    /// against the file's directory; blank lines and `#` comments are
+    /// skipped.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["editions", "previous"]
+    )]
+    pub chain_file: Option<PathBuf>,
+    /// Verify every `.ur` file in this directory instead, reporting each
+    /// file's result.
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["editions", "previous", "chain_file"]
+    )]
+    pub edition_dir: Option<PathBuf>,
+    /// With `--edition-dir`, verify the files whose names match this
+    /// pattern (`*` and `?` wildcards) instead of the `.ur` files.
+    #[arg(long, value_name = "PATTERN", requires = "edition_dir")]
+    pub glob: Option<String>,
+    /// With `--edition-dir`, verify this many files at a time.
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        requires = "edition_dir"
+    )]
+    pub jobs: usize,
+    /// Optional previous edition UR for provenance validation, or just
+    /// its bare provenance mark (`ur:provenance`), whose signature then
+    /// goes unchecked.
     #[arg(long, value_name = "UR")]
     pub previous: Option<String>,
     /// Publisher descriptor (XID document or public-keys UR) used for
-    /// signature verification.
-    #[arg(long, value_name = "UR")]
-    pub publisher: String,
+    /// signature verification. Repeat to accept any of several keys, e.g.
+    /// across a key rotation; the one that matched is reported. When
+    /// omitted, the keys pinned for the edition's club in the trust store
+    /// are used.
+    #[arg(long = "publisher", value_name = "UR")]
+    pub publishers: Vec<String>,
+    /// Require signatures from at least M distinct `--publisher`s, for
+    /// editions co-signed by several officers. Each signature counts for
+    /// at most one publisher.
+    #[arg(long, value_name = "M", requires = "publishers")]
+    pub threshold: Option<usize>,
+    /// Directory of trusted publisher XID documents named by club XID
+    /// (`<xid>.ur`, or a unique prefix of the XID hex). The club is read
+    /// from the edition before verification and its document loaded from
+    /// here, instead of passing `--publisher`.
+    #[arg(long, value_name = "DIR", conflicts_with = "publishers")]
+    pub publisher_dir: Option<PathBuf>,
+    /// Accept an edition whose provenance mark or signing date is earlier
+    /// than the previous edition's.
+    #[arg(long)]
+    pub allow_date_regression: bool,
+    /// With `--previous`, fail (exit status 4) unless the previous mark
+    /// cryptographically links to this edition's, rather than merely
+    /// having the preceding sequence number on the same chain. A mark from
+    /// another chain always fails, and chain verification always requires
+    /// the cryptographic link.
+    #[arg(long)]
+    pub require_cryptographic_link: bool,
+    /// Fail (exit status 3) if the edition's signing date (the `'date'`
+    /// signature metadata) is earlier than this RFC 3339 date.
+    #[arg(long, value_name = "DATE")]
+    pub not_before: Option<String>,
+    /// Fail (exit status 3) if the edition's signing date is later than
+    /// this RFC 3339 date.
+    #[arg(long, value_name = "DATE")]
+    pub not_after: Option<String>,
+    /// Fail when a signing date needed by `--not-before`, `--not-after`, or
+    /// the `--previous` date comparison is missing, instead of warning.
+    #[arg(long)]
+    pub require_date: bool,
+    /// Check that the provenance mark's info field holds the content digest
+    /// and report the result; a mismatch fails verification. Genesis
+    /// editions are always checked.
+    #[arg(long)]
+    pub check_digest_binding: bool,
+    /// Fail (exit status 3) when a genesis mark carries no content digest,
+    /// instead of warning.
+    #[arg(long)]
+    pub strict: bool,
+    /// Fail (exit status 3) unless the content is encrypted and a permit
+    /// or SSKR policy is attached to unlock it.
+    #[arg(long)]
+    pub require_encrypted: bool,
+    /// Fail (exit status 3) unless the edition has this many permits:
+    /// exactly `N`, or at least `N` when written `>=N`.
+    #[arg(long, value_name = "N|>=N")]
+    pub expect_permits: Option<PermitCount>,
+    /// Fail (exit status 3) unless a permit is annotated with this member
+    /// XID. Anonymous permits cannot satisfy it. Repeatable.
+    #[arg(long = "require-member", value_name = "XID")]
+    pub require_members: Vec<String>,
+    /// Also decrypt the content in memory and check that it matches the
+    /// digest its ciphertext declares and, when present, the digest bound
+    /// in the provenance mark. The plaintext is discarded. Needs
+    /// `--identity`, `--key`, or `--sskr`, as for `content decrypt`.
+    #[arg(long)]
+    pub deep: bool,
+    /// With `--deep`, private-key material that opens one of the edition's
+    /// permits (XID document or private-keys UR).
+    #[arg(long = "identity", value_name = "UR", requires = "deep")]
+    pub identities: Vec<String>,
+    /// With `--deep`, symmetric key UR that decrypts the content.
+    #[arg(long, value_name = "UR", requires = "deep")]
+    pub key: Option<String>,
+    /// With `--deep`, SSKR share URs that recover the content.
+    #[arg(long = "sskr", value_name = "UR", requires = "deep")]
+    pub shards: Vec<String>,
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
+    /// Report format. `json` prints each check and its outcome as one
+    /// object on stdout; it applies to a single edition.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// Print nothing, not even errors; only the exit status reports the
+    /// outcome.
+    #[arg(long, conflicts_with_all = ["summary", "format"])]
+    pub quiet: bool,
+    /// Print one line per check performed on a single edition, with its
+    /// outcome (`OK`, `FAILED`, or `SKIPPED`), on stderr.
+    #[arg(long)]
+    pub summary: bool,
+    #[command(flatten)]
+    pub trust: TrustArgs,
 }
 
 pub fn exec(args: CommandArgs) -> Result<()> {
-    let edition_env =
-        io::parse_envelope(&args.edition).context("failed to parse edition")?;
-    let publisher_descriptor = io::parse_recipient_descriptor(&args.publisher)
-        .context("failed to parse publisher input")?;
-    let publisher_keys = publisher_descriptor.public_keys().clone();
-
-    let inner_envelope = edition_env
-        .verify(&publisher_keys)
-        .context("failed to verify edition signature")?;
-    let edition = Edition::try_from(inner_envelope.clone())
+    if args.quiet {
+        return verify(args).map_err(|err| err.context(Silenced));
+    }
+    verify(args)
+}
+
+fn verify(args: CommandArgs) -> Result<()> {
+    let checks = EditionChecks::new(&args)?;
+
+    let single = args.edition_dir.is_none()
+        && args.chain_file.is_none()
+        && args.editions.len() == 1;
+    if args.format == OutputFormat::Json && !single {
+        return Err(VerifyFailure::Usage(
+            "--format json applies to a single edition".to_string(),
+        )
+        .into());
+    }
+    if args.summary && !single {
+        return Err(VerifyFailure::Usage(
+            "--summary applies to a single edition".to_string(),
+        )
+        .into());
+    }
+    if args.trust.trust && args.publishers.len() != 1 {
+        return Err(VerifyFailure::Usage(
+            "--trust pins a single --publisher".to_string(),
+        )
+        .into());
+    }
+
+    if let Some(dir) = args.edition_dir.as_ref() {
+        let files = io::files_matching(dir, args.glob.as_deref())?;
+        if files.is_empty() {
+            return Err(VerifyFailure::Input(format!(
+                "no edition files found in '{}'",
+                dir.display()
+            ))
+            .into());
+        }
+        // A directory may hold editions of several clubs, each verified
+        // against its own pinned keys.
+        let mut clubs: Vec<(Option<XID>, Vec<Publisher>)> = Vec::new();
+        let mut club_of = Vec::with_capacity(files.len());
+        for file in &files {
+            let club = trust::edition_club(&format!("@{}", file.display()));
+            let seen = club.as_ref().ok().and_then(|club| {
+                clubs.iter().position(|(seen, _)| *seen == Some(*club))
+            });
+            let index = match seen {
+                Some(index) => index,
+                None => {
+                    let (publishers, club) = publishers_for_club(&args, club)
+                        .with_context(|| {
+                        format!(
+                            "cannot resolve publishers for '{}'",
+                            file.display()
+                        )
+                    })?;
+                    clubs.push((club, publishers));
+                    clubs.len() - 1
+                }
+            };
+            club_of.push(index);
+        }
+        let publishers: Vec<&[Publisher]> = club_of
+            .iter()
+            .map(|&index| clubs[index].1.as_slice())
+            .collect();
+        let results = verify_files(&files, &publishers, &checks, args.jobs);
+        report_files(&files, &results, args.quiet)?;
+        for (club, publishers) in &clubs {
+            record_trust(&args, *club, publishers)?;
+        }
+        return Ok(());
+    }
+
+    let chain = match args.chain_file.as_ref() {
+        Some(path) => read_chain_file(path)?,
+        None => args
+            .editions
+            .iter()
+            .enumerate()
+            .map(|(index, spec)| {
+                (format!("--edition #{}", index + 1), spec.clone())
+            })
+            .collect(),
+    };
+    let Some((_, first)) = chain.first() else {
+        return Err(
+            VerifyFailure::Input("no editions to verify".to_string()).into()
+        );
+    };
+    let (publishers, club) = resolve_publishers(&args, first)?;
+    let spec = match chain.as_slice() {
+        [(_, spec)] if args.chain_file.is_none() => spec,
+        _ => {
+            if args.previous.is_some() {
+                return Err(VerifyFailure::Usage(
+                    "--previous applies to a single edition".to_string(),
+                )
+                .into());
+            }
+            let range = verify_chain(
+                &chain,
+                &publishers,
+                &checks,
+                args.allow_date_regression,
+            )?;
+            if !args.quiet {
+                eprintln!("Verified {range}");
+            }
+            return record_trust(&args, club, &publishers);
+        }
+    };
+    let mut report = VerifyReport::new();
+    let result = verify_edition(&args, &checks, spec, &publishers, &mut report);
+    report.verified = result.is_ok();
+    report.failure = result.as_ref().err().map(FailureReport::new);
+    if args.format == OutputFormat::Json {
+        output::print_json(&report)?;
+    }
+    if args.summary {
+        for line in report.summary_lines() {
+            eprintln!("{line}");
+        }
+    }
+    result?;
+    record_trust(&args, club, &publishers)
+}
+
+/// The `--publisher`s, checked against the keys pinned for the club of the
+/// edition at `spec`, or those pinned keys when no `--publisher` is given.
+/// With `--publisher-dir`, the club's document from that directory instead.
+/// Also returns the club, when the edition names one.
+fn resolve_publishers(
+    args: &CommandArgs,
+    spec: &str,
+) -> Result<(Vec<Publisher>, Option<XID>)> {
+    publishers_for_club(args, trust::edition_club(spec))
+}
+
+/// [`resolve_publishers`] for an edition whose club has been looked up.
+fn publishers_for_club(
+    args: &CommandArgs,
+    club: Result<XID>,
+) -> Result<(Vec<Publisher>, Option<XID>)> {
+    if let Some(dir) = &args.publisher_dir {
+        let club =
+            club.context("cannot look up the club's publisher document")?;
+        let descriptor = io::publisher_in_dir(dir, &club, &args.passphrase)?;
+        let descriptors = args.trust.resolve(club, vec![descriptor])?;
+        return Ok((
+            descriptors.iter().map(Publisher::new).collect(),
+            Some(club),
+        ));
+    }
+    let mut descriptors = Vec::with_capacity(args.publishers.len());
+    for spec in &args.publishers {
+        descriptors.push(
+            io::parse_recipient_descriptor(spec)
+                .context("failed to parse publisher input")?,
+        );
+    }
+    // An unreadable edition fails verification later, with its own error.
+    let club = match club {
+        Ok(club) => Some(club),
+        Err(err) if descriptors.is_empty() => {
+            return Err(err.context("cannot look up pinned publisher keys"));
+        }
+        Err(_) => None,
+    };
+    if let Some(club) = club {
+        descriptors = args.trust.resolve(club, descriptors)?;
+    }
+    if descriptors.is_empty() {
+        return Err(VerifyFailure::Usage(format!(
+            "no --publisher given and no publisher keys are pinned for club {}; pass --publisher, with --trust to pin it",
+            club.expect("the club is known when no --publisher is given")
+        ))
+        .into());
+    }
+    Ok((descriptors.iter().map(Publisher::new).collect(), club))
+}
+
+/// With `--trust`, pin the sole `--publisher` for `club` once its editions
+/// have verified.
+fn record_trust(
+    args: &CommandArgs,
+    club: Option<XID>,
+    publishers: &[Publisher],
+) -> Result<()> {
+    let (Some(club), [publisher]) = (club, publishers) else {
+        return Ok(());
+    };
+    if args.trust.record(club, &publisher.keys)? && !args.quiet {
+        eprintln!("Pinned the publisher keys for club {club}");
+    }
+    Ok(())
+}
+
+/// Verify a single edition, recording each check in `report` and printing
+/// the text report as it goes.
+fn verify_edition(
+    args: &CommandArgs,
+    checks: &EditionChecks,
+    spec: &str,
+    publishers: &[Publisher],
+    report: &mut VerifyReport,
+) -> Result<()> {
+    let text =
+        args.format == OutputFormat::Text && !args.summary && !args.quiet;
+    let edition_env = io::parse_envelope(spec)
+        .context("failed to parse edition")
+        .map_err(|err| report.fail("parse", err))?;
+    report.pass("parse", None);
+
+    let (inner, publisher) = match_publisher(&edition_env, publishers)
+        .map_err(|err| report.fail("signature", err))?;
+    report.publisher_match = Some(publisher.reference.clone());
+    report.pass(
+        "signature",
+        Some(format!("verified by {}", publisher.label)),
+    );
+    if text {
+        eprintln!("Signature: verified by {}", publisher.label);
+    }
+
+    match checks.check_threshold(&edition_env, publishers) {
+        Ok(Some(signers)) => {
+            let detail = describe_signers(&signers);
+            if text {
+                eprintln!("Threshold: {detail}");
+            }
+            report.pass("threshold", Some(detail));
+            report.signers = signers;
+        }
+        Ok(None) => report.skip("threshold", "--threshold not given"),
+        Err(err) => return Err(report.fail("threshold", err)),
+    }
+
+    // Checked before the edition is parsed, so a malformed permit is named
+    // rather than failing the parse.
+    let permit_count = inner.objects_for_predicate(HAS_RECIPIENT).len();
+    check_permits(&inner).map_err(|err| report.fail("permits", err))?;
+    report.pass("permits", Some(format!("{permit_count} well-formed")));
+
+    let edition = super::decode_edition(&inner)
+        .context("edition payload is not a valid club edition")
+        .map_err(|err| report.fail("edition", err))?;
+    report.club_xid = Some(edition.club_xid.to_string());
+    report.seq = Some(edition.provenance.seq());
+    report.pass("edition", None);
+
+    match publisher.club_xid {
+        Some(_) => {
+            check_club(&edition, publisher)
+                .map_err(|err| report.fail("club-xid", err))?;
+            report.pass("club-xid", Some(edition.club_xid.to_string()));
+        }
+        None => report.skip("club-xid", "publisher is bare public keys"),
+    }
+
+    match checks.check_binding(&edition) {
+        Ok(Some(binding)) => {
+            if text {
+                eprintln!("Digest binding: {}", binding.describe());
+            }
+            report.pass("digest-binding", Some(binding.describe()));
+        }
+        Ok(None) => report.skip(
+            "digest-binding",
+            "not a genesis edition and --check-digest-binding not given",
+        ),
+        Err(err) => return Err(report.fail("digest-binding", err)),
+    }
+
+    if checks.require_encrypted {
+        check_encrypted(&edition_env, &edition)
+            .map_err(|err| report.fail("encrypted", err))?;
+        report.pass("encrypted", None);
+    } else {
+        report.skip("encrypted", "--require-encrypted not given");
+    }
+
+    if checks.checks_membership() {
+        let found = checks
+            .check_membership(&edition)
+            .map_err(|err| report.fail("membership", err))?;
+        report.pass("membership", Some(found));
+    } else {
+        report.skip(
+            "membership",
+            "--expect-permits and --require-member not given",
+        );
+    }
+
+    if checks.checks_window() {
+        let detail = checks
+            .check_window(&edition_env)
+            .map_err(|err| report.fail("date-window", err))?;
+        report.pass("date-window", Some(detail));
+    } else {
+        report.skip("date-window", "--not-before and --not-after not given");
+    }
+
+    match checks.check_content(&edition) {
+        Ok(Some(detail)) => {
+            if text {
+                eprintln!("Content: {detail}");
+            }
+            report.pass("content", Some(detail));
+        }
+        Ok(None) => report.skip("content", "--deep not given"),
+        Err(err) => return Err(report.fail("content", err)),
+    }
+
+    let Some(prev_spec) = args.previous.as_ref() else {
+        report.skip("previous", "--previous not given");
+        return Ok(());
+    };
+    match check_previous(
+        prev_spec,
+        &edition_env,
+        &edition,
+        publishers,
+        args,
+        checks,
+    ) {
+        Ok(link) => {
+            let mut detail = format!("linkage: {}", link.linkage.label());
+            if link.mark_only {
+                detail.push_str(
+                    "; bare provenance mark, previous signature not checked",
+                );
+            }
+            if text {
+                eprintln!("Previous: {detail}");
+            }
+            report.previous_check = CheckStatus::Passed;
+            report.linkage = Some(link.linkage);
+            report.pass("previous", Some(detail));
+            Ok(())
+        }
+        Err(err) => {
+            report.previous_check = CheckStatus::Failed;
+            Err(report.fail("previous", err))
+        }
+    }
+}
+
+/// Check that `prev_spec` is a validly signed edition that immediately
+/// precedes `edition` and was not signed after it. A bare provenance mark
+/// is checked the same way, except that it has no signature to verify or
+/// signing date to compare.
+fn check_previous(
+    prev_spec: &str,
+    edition_env: &Envelope,
+    edition: &Edition,
+    publishers: &[Publisher],
+    args: &CommandArgs,
+    checks: &EditionChecks,
+) -> Result<PreviousLink> {
+    let parsed = io::parse_edition_or_mark(prev_spec)
+        .context("failed to parse previous edition or provenance mark")?;
+    let (previous_mark, prev_env, what) = match parsed {
+        EditionOrMark::Edition(prev_env) => {
+            let (prev_edition, _) = verify_signed(&prev_env, publishers)
+                .context("failed to verify previous edition")?;
+            (prev_edition.provenance, Some(prev_env), "previous edition")
+        }
+        EditionOrMark::Mark(mark) => {
+            (mark, None, "previous mark (a bare provenance mark)")
+        }
+    };
+    // Adjacent sequence numbers on another chain are a forgery, not a weak
+    // link.
+    if previous_mark.chain_id() != edition.provenance.chain_id() {
+        return Err(VerifyFailure::ProvenanceOrder(format!(
+            "{what} is from a different provenance chain than the verified edition"
+        ))
+        .into());
+    }
+    let Some(linkage) = Linkage::between(&previous_mark, &edition.provenance)
+    else {
+        return Err(VerifyFailure::ProvenanceOrder(format!(
+            "{what} at seq {} does not precede the verified edition at seq {}",
+            previous_mark.seq(),
+            edition.provenance.seq()
+        ))
+        .into());
+    };
+    if linkage == Linkage::SequenceOnly {
+        let message = format!(
+            "{what} has the adjacent seq, but its provenance mark does not cryptographically link to this edition's mark"
+        );
+        if args.require_cryptographic_link {
+            return Err(VerifyFailure::ProvenanceOrder(message).into());
+        }
+        checks.warn(&message);
+    }
+    let previous_date = previous_mark.date();
+    if edition.provenance.date() < previous_date && !args.allow_date_regression
+    {
+        return Err(VerifyFailure::ProvenanceOrder(format!(
+            "edition provenance date {} precedes the {what}'s date {}",
+            edition.provenance.date(),
+            previous_date
+        ))
+        .into());
+    }
+    let Some(prev_env) = prev_env else {
+        return Ok(PreviousLink { linkage, mark_only: true });
+    };
+    match (signing_date(edition_env), signing_date(&prev_env)) {
+        (Some(date), Some(previous_date)) => {
+            if date < previous_date && !args.allow_date_regression {
+                return Err(VerifyFailure::Policy(format!(
+                    "edition was signed {date}, before the previous edition's signing date {previous_date}"
+                ))
+                .into());
+            }
+        }
+        _ => checks.missing_date(
+            "the edition or the previous edition carries no signing date, so their order cannot be compared",
+        )?,
+    }
+    Ok(PreviousLink { linkage, mark_only: false })
+}
+
+/// The outcome of a passing `--previous` check.
+struct PreviousLink {
+    linkage: Linkage,
+    /// `--previous` was a bare provenance mark, so no signature was checked.
+    mark_only: bool,
+}
+
+/// The `'date'` recorded in the edition's signature metadata, if any.
+fn signing_date(edition_env: &Envelope) -> Option<Date> {
+    inspect::signature_metadata(edition_env)
+        .iter()
+        .find_map(|meta| meta.extract_object_for_predicate::<Date>(DATE).ok())
+}
+
+/// Why verification failed, so automation can branch on it: the `kind` in
+/// the `--format json` report, and the exit status.
+#[derive(Debug)]
+pub enum VerifyFailure {
+    /// Flags that cannot be combined or met.
+    Usage(String),
+    /// Nothing to verify.
+    Input(String),
+    /// No `--publisher` made a valid, authorized signature, or fewer than
+    /// `--threshold` did.
+    Signature(String),
+    /// The edition names another club than its publisher, or the club
+    /// changes along a chain.
+    ClubMismatch(String),
+    /// The previous edition or mark does not come immediately before the
+    /// edition, or a date regresses.
+    ProvenanceOrder(String),
+    /// The provenance mark does not commit to the edition content.
+    DigestBinding(String),
+    /// A permit is not a sealed message, has an invalid holder annotation,
+    /// or repeats another permit.
+    MalformedPermit(String),
+    /// With `--deep`, no content key could be recovered from the inputs.
+    KeyRecovery(String),
+    /// With `--deep`, the recovered key does not decrypt the content.
+    Decryption(String),
+    /// With `--deep`, the decrypted content does not have the digest its
+    /// ciphertext declares or its provenance mark binds.
+    ContentDigest(String),
+    /// A policy or expectation flag (`--require-encrypted`,
+    /// `--expect-permits`, `--not-before`, ...) is not met.
+    Policy(String),
+    /// Some of the editions in a directory failed.
+    EditionsFailed(String),
+}
+
+impl VerifyFailure {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Usage(_) => "usage",
+            Self::Input(_) => "input",
+            Self::Signature(_) => "signature",
+            Self::ClubMismatch(_) => "club_mismatch",
+            Self::ProvenanceOrder(_) => "provenance_order",
+            Self::DigestBinding(_) => "digest_binding",
+            Self::MalformedPermit(_) => "malformed_permit",
+            Self::KeyRecovery(_) => "key_recovery",
+            Self::Decryption(_) => "decryption",
+            Self::ContentDigest(_) => "content_digest",
+            Self::Policy(_) => "policy",
+            Self::EditionsFailed(_) => "editions_failed",
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            Self::Usage(message)
+            | Self::Input(message)
+            | Self::Signature(message)
+            | Self::ClubMismatch(message)
+            | Self::ProvenanceOrder(message)
+            | Self::DigestBinding(message)
+            | Self::MalformedPermit(message)
+            | Self::KeyRecovery(message)
+            | Self::Decryption(message)
+            | Self::ContentDigest(message)
+            | Self::Policy(message)
+            | Self::EditionsFailed(message) => message,
+        }
+    }
+
+    /// 3 for a policy failure, 4 for a failed verification, 1 otherwise.
+    pub fn exit_status(&self) -> u8 {
+        match self {
+            Self::Usage(_) | Self::Input(_) => 1,
+            Self::Policy(_) => EXPECTATION_FAILED_EXIT,
+            _ => VERIFICATION_FAILED_EXIT,
+        }
+    }
+}
+
+impl fmt::Display for VerifyFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for VerifyFailure {}
+
+/// The `failure` object of the `--format json` report.
+#[derive(Debug, Serialize)]
+struct FailureReport {
+    kind: &'static str,
+    message: String,
+}
+
+impl FailureReport {
+    /// Errors without a [`VerifyFailure`] come from unreadable input.
+    fn new(err: &anyhow::Error) -> Self {
+        Self {
+            kind: err
+                .downcast_ref::<VerifyFailure>()
+                .map_or("input", VerifyFailure::kind),
+            message: format!("{err:#}"),
+        }
+    }
+}
+
+/// The `--format json` report of verifying a single edition. Field names
+/// are snake_case, as automation consuming the report expects.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct VerifyReport {
+    verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    club_xid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<u32>,
+    /// The matching publisher's XID, or its public keys reference when it
+    /// was given as bare keys.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    publisher_match: Option<String>,
+    previous_check: CheckStatus,
+    /// Why verification failed, when it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<FailureReport>,
+    /// With `--threshold`, which publisher made each signature.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    signers: Vec<SignerMatch>,
+    /// With `--previous`, how strongly the previous edition is linked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    linkage: Option<Linkage>,
+    /// Every check run, in order; verification stops at the first failure.
+    checks: Vec<Check>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum CheckStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+impl Check {
+    fn failed(name: &'static str, detail: String) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Failed,
+            detail: Some(detail),
+        }
+    }
+}
+
+impl VerifyReport {
+    fn new() -> Self {
+        Self {
+            verified: false,
+            club_xid: None,
+            seq: None,
+            publisher_match: None,
+            previous_check: CheckStatus::Skipped,
+            failure: None,
+            signers: Vec::new(),
+            linkage: None,
+            checks: Vec::new(),
+        }
+    }
+
+    fn pass(&mut self, name: &'static str, detail: Option<String>) {
+        self.checks
+            .push(Check { name, status: CheckStatus::Passed, detail });
+    }
+
+    fn skip(&mut self, name: &'static str, reason: &str) {
+        self.checks.push(Check {
+            name,
+            status: CheckStatus::Skipped,
+            detail: Some(reason.to_string()),
+        });
+    }
+
+    /// One line per check, e.g. `signature: OK (verified by XID …)`.
+    fn summary_lines(&self) -> Vec<String> {
+        self.checks
+            .iter()
+            .map(|check| {
+                let status = match check.status {
+                    CheckStatus::Passed => "OK",
+                    CheckStatus::Failed => "FAILED",
+                    CheckStatus::Skipped => "SKIPPED",
+                };
+                match check.detail.as_ref() {
+                    Some(detail) => {
+                        format!("{}: {status} ({detail})", check.name)
+                    }
+                    None => format!("{}: {status}", check.name),
+                }
+            })
+            .collect()
+    }
+
+    /// Record `name` as failed with `err` as its detail, and hand `err`
+    /// back.
+    fn fail(
+        &mut self,
+        name: &'static str,
+        err: anyhow::Error,
+    ) -> anyhow::Error {
+        self.checks.push(Check::failed(name, format!("{err:#}")));
+        err
+    }
+}
+
+/// A `--publisher` an edition may be signed by.
+struct Publisher {
+    keys: PublicKeys,
+    /// For an XID document, each of its keys and whether it may sign.
+    document_keys: Vec<(PublicKeys, bool)>,
+    club_xid: Option<XID>,
+    /// The publisher's XID, or its public keys reference.
+    label: String,
+    /// [`Self::label`] without its kind.
+    reference: String,
+}
+
+impl Publisher {
+    fn new(descriptor: &RecipientDescriptor) -> Self {
+        Self {
+            keys: descriptor.public_keys().clone(),
+            document_keys: descriptor
+                .xid_document()
+                .map(|doc| {
+                    doc.keys()
+                        .iter()
+                        .map(|key| (key.public_keys().clone(), may_sign(key)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            club_xid: descriptor.member_xid(),
+            label: verifier_label(descriptor),
+            reference: match descriptor.member_xid() {
+                Some(xid) => xid.to_string(),
+                None => descriptor.public_keys().reference().ref_hex(),
+            },
+        }
+    }
+
+    /// The payload of `edition_env` if one of the publisher's keys signed
+    /// it. An XID document's keys are tried one by one, so the key that
+    /// signed is known and must be authorized for signing.
+    fn verify(&self, edition_env: &Envelope) -> SignatureMatch {
+        if self.document_keys.is_empty() {
+            return match edition_env.verify(&self.keys) {
+                Ok(inner) => SignatureMatch::Verified(inner),
+                Err(err) => SignatureMatch::Unmatched(anyhow!(err)),
+            };
+        }
+        let mut failure = None;
+        for (keys, may_sign) in &self.document_keys {
+            match edition_env.verify(keys) {
+                Ok(_) if !may_sign => {
+                    return SignatureMatch::Unauthorized(
+                        keys.reference().ref_hex_short(),
+                    );
+                }
+                Ok(inner) => return SignatureMatch::Verified(inner),
+                Err(err) => {
+                    failure.get_or_insert(err);
+                }
+            }
+        }
+        SignatureMatch::Unmatched(match failure {
+            Some(err) => anyhow!(err),
+            None => anyhow!("publisher XID document has no keys"),
+        })
+    }
+
+    /// Whether one of the publisher's keys made `signature` over `message`;
+    /// for an XID document, only keys authorized for signing count.
+    fn made(&self, signature: &Signature, message: &[u8]) -> bool {
+        if self.document_keys.is_empty() {
+            return self.keys.verify(signature, &message);
+        }
+        self.document_keys.iter().any(|(keys, may_sign)| {
+            *may_sign && keys.verify(signature, &message)
+        })
+    }
+}
+
+/// How an edition's signature relates to one publisher.
+enum SignatureMatch {
+    Verified(Envelope),
+    /// Signed by the key with this short reference, which the publisher
+    /// document does not allow to sign.
+    Unauthorized(String),
+    Unmatched(anyhow::Error),
+}
+
+/// Whether the XID document `key` is allowed, and not denied, to sign.
+fn may_sign(key: &Key) -> bool {
+    let signs = |privilege: &Privilege| {
+        matches!(privilege, Privilege::All | Privilege::Sign)
+    };
+    key.permissions().allow().iter().any(signs)
+        && !key.permissions().deny().iter().any(signs)
+}
+
+/// Verify `edition_env`'s signature against each of `publishers` in turn.
+/// The club XID is checked only against the publisher whose key matched.
+fn verify_signed<'a>(
+    edition_env: &Envelope,
+    publishers: &'a [Publisher],
+) -> Result<(Edition, &'a Publisher)> {
+    let (inner_envelope, publisher) = match_publisher(edition_env, publishers)?;
+    let edition = super::decode_edition(&inner_envelope)
         .context("edition payload is not a valid club edition")?;
+    check_club(&edition, publisher)?;
+    Ok((edition, publisher))
+}
 
-    if let Some(expected_xid) = publisher_descriptor.member_xid()
+/// The payload of `edition_env` and the first of `publishers` whose key
+/// signed it. A key a publisher document does not authorize for signing
+/// fails verification even though its signature is valid.
+fn match_publisher<'a>(
+    edition_env: &Envelope,
+    publishers: &'a [Publisher],
+) -> Result<(Envelope, &'a Publisher)> {
+    let mut failure = None;
+    let mut unauthorized = None;
+    for publisher in publishers {
+        match publisher.verify(edition_env) {
+            SignatureMatch::Verified(inner) => return Ok((inner, publisher)),
+            SignatureMatch::Unauthorized(key) => {
+                unauthorized.get_or_insert((key, &publisher.label));
+            }
+            SignatureMatch::Unmatched(err) => {
+                failure.get_or_insert(err);
+            }
+        }
+    }
+    if let Some((key, label)) = unauthorized {
+        return Err(VerifyFailure::Signature(format!(
+            "signature made by a key not authorized for signing (key {key} of {label} lacks the 'Sign' permission)"
+        ))
+        .into());
+    }
+    let err = failure.expect("at least one publisher is required");
+    let message = if publishers.len() > 1 {
+        format!(
+            "failed to verify edition signature against any of the {} publishers",
+            publishers.len()
+        )
+    } else {
+        "failed to verify edition signature".to_string()
+    };
+    Err(err.context(VerifyFailure::Signature(message)))
+}
+
+/// One signature on the edition wrapper and the `--publisher` that made it.
+#[derive(Debug, Serialize)]
+struct SignerMatch {
+    /// 1-based position of the signature on the wrapper.
+    signature: usize,
+    /// The matching publisher, e.g. `XID …` or `keys …`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    publisher: Option<String>,
+}
+
+/// Which of `publishers` made each signature on `edition_env`, counting
+/// each publisher for at most one signature.
+fn match_signers(
+    edition_env: &Envelope,
+    publishers: &[Publisher],
+) -> Vec<SignerMatch> {
+    let message = edition_env.subject().digest();
+    let mut used = vec![false; publishers.len()];
+    edition_env
+        .objects_for_predicate(SIGNED)
+        .into_iter()
+        .enumerate()
+        .map(|(index, object)| {
+            let (signature, _) = super::signature_parts(&object);
+            let matched = signature.and_then(|signature| {
+                let position = publishers.iter().enumerate().position(
+                    |(position, publisher)| {
+                        !used[position]
+                            && publisher.made(&signature, message.data())
+                    },
+                )?;
+                used[position] = true;
+                Some(publishers[position].label.clone())
+            });
+            SignerMatch { signature: index + 1, publisher: matched }
+        })
+        .collect()
+}
+
+/// e.g. `signature 1: XID …, signature 2: no publisher`.
+fn describe_signers(signers: &[SignerMatch]) -> String {
+    signers
+        .iter()
+        .map(|signer| {
+            format!(
+                "signature {}: {}",
+                signer.signature,
+                signer.publisher.as_deref().unwrap_or("no publisher")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Fail if `publisher` is an XID document naming another club.
+fn check_club(edition: &Edition, publisher: &Publisher) -> Result<()> {
+    if let Some(expected_xid) = publisher.club_xid
         && edition.club_xid != expected_xid
     {
-        bail!(
+        return Err(VerifyFailure::ClubMismatch(format!(
             "edition references club XID {} but publisher descriptor is {}",
-            edition.club_xid,
-            expected_xid
+            edition.club_xid, expected_xid
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// The optional checks run on each verified edition.
+#[derive(Debug, Clone, Default)]
+struct EditionChecks {
+    threshold: Option<usize>,
+    digest_binding: bool,
+    require_encrypted: bool,
+    strict: bool,
+    expect_permits: Option<PermitCount>,
+    require_members: Vec<XID>,
+    not_before: Option<Date>,
+    not_after: Option<Date>,
+    require_date: bool,
+    deep: Option<DeepInputs>,
+    quiet: bool,
+    /// Set with `--format json`, whose report is the only output.
+    json: bool,
+}
+
+/// What `--deep` recovers the content with.
+#[derive(Debug, Clone, Default)]
+struct DeepInputs {
+    key: Option<SymmetricKey>,
+    identities: Vec<PrivateKeys>,
+    /// Share specs, parsed per edition since envelopes cannot be shared
+    /// between threads.
+    shards: Vec<String>,
+}
+
+impl EditionChecks {
+    fn new(args: &CommandArgs) -> Result<Self> {
+        let require_members = args
+            .require_members
+            .iter()
+            .map(|spec| {
+                io::parse_xid_value(spec).with_context(|| {
+                    format!("failed to parse --require-member '{spec}'")
+                })
+            })
+            .collect::<Result<_>>()?;
+        let parse_bound = |spec: Option<&String>, flag: &str| {
+            spec.map(|spec| {
+                io::parse_date(spec)
+                    .with_context(|| format!("failed to parse {flag}"))
+            })
+            .transpose()
+        };
+        if let Some(threshold) = args.threshold
+            && (threshold == 0 || threshold > args.publishers.len())
+        {
+            return Err(VerifyFailure::Usage(format!(
+                "--threshold {threshold} cannot be met by the {} --publisher descriptors supplied",
+                args.publishers.len()
+            ))
+            .into());
+        }
+        let deep = if args.deep {
+            if args.identities.is_empty()
+                && args.key.is_none()
+                && args.shards.is_empty()
+            {
+                return Err(VerifyFailure::Usage(
+                    "--deep needs --identity, --key, or --sskr to recover the content".to_string(),
+                )
+                .into());
+            }
+            let key = args
+                .key
+                .as_ref()
+                .map(|spec| {
+                    io::parse_symmetric_key(spec)
+                        .context("failed to parse symmetric key input")
+                })
+                .transpose()?;
+            Some(DeepInputs {
+                key,
+                identities: decrypt::parse_private_keys(
+                    &args.identities,
+                    &args.passphrase,
+                )?,
+                shards: args.shards.clone(),
+            })
+        } else {
+            None
+        };
+        Ok(Self {
+            threshold: args.threshold,
+            digest_binding: args.check_digest_binding,
+            require_encrypted: args.require_encrypted,
+            strict: args.strict,
+            expect_permits: args.expect_permits,
+            require_members,
+            not_before: parse_bound(args.not_before.as_ref(), "--not-before")?,
+            not_after: parse_bound(args.not_after.as_ref(), "--not-after")?,
+            require_date: args.require_date,
+            deep,
+            quiet: args.quiet,
+            json: args.format == OutputFormat::Json,
+        })
+    }
+
+    fn run(
+        &self,
+        edition_env: &Envelope,
+        edition: &Edition,
+        publishers: &[Publisher],
+    ) -> Result<()> {
+        self.check_threshold(edition_env, publishers)?;
+        check_permits(&inspect::edition_payload(edition_env)?.0)?;
+        self.check_binding(edition)?;
+        if self.require_encrypted {
+            check_encrypted(edition_env, edition)?;
+        }
+        if self.checks_membership() {
+            self.check_membership(edition)?;
+        }
+        if self.checks_window() {
+            self.check_window(edition_env)?;
+        }
+        self.check_content(edition)?;
+        Ok(())
+    }
+
+    /// With `--threshold`, require that many distinct publishers to have
+    /// signed. Returns which publisher made each signature, or `None` when
+    /// the check did not apply.
+    fn check_threshold(
+        &self,
+        edition_env: &Envelope,
+        publishers: &[Publisher],
+    ) -> Result<Option<Vec<SignerMatch>>> {
+        let Some(threshold) = self.threshold else {
+            return Ok(None);
+        };
+        let signers = match_signers(edition_env, publishers);
+        let signed = signers
+            .iter()
+            .filter(|signer| signer.publisher.is_some())
+            .count();
+        if signed < threshold {
+            return Err(VerifyFailure::Signature(format!(
+                "only {signed} of the required {threshold} publishers signed the edition ({})",
+                describe_signers(&signers)
+            ))
+            .into());
+        }
+        Ok(Some(signers))
+    }
+
+    fn checks_window(&self) -> bool {
+        self.not_before.is_some() || self.not_after.is_some()
+    }
+
+    /// Check the edition's signing date against `--not-before` and
+    /// `--not-after`. Returns a description of the outcome.
+    fn check_window(&self, edition_env: &Envelope) -> Result<String> {
+        let Some(date) = signing_date(edition_env) else {
+            self.missing_date(
+                "edition carries no signing date, so its validity window cannot be checked",
+            )?;
+            return Ok("no signing date".to_string());
+        };
+        if let Some(not_before) = self.not_before.as_ref()
+            && date < *not_before
+        {
+            return Err(VerifyFailure::Policy(format!(
+                "edition was signed {date}, before --not-before {not_before}"
+            ))
+            .into());
+        }
+        if let Some(not_after) = self.not_after.as_ref()
+            && date > *not_after
+        {
+            return Err(VerifyFailure::Policy(format!(
+                "edition was signed {date}, after --not-after {not_after}"
+            ))
+            .into());
+        }
+        Ok(format!("signed {date}"))
+    }
+
+    /// Warn about a missing signing date, or with `--require-date` fail.
+    fn missing_date(&self, message: &str) -> Result<()> {
+        if self.require_date {
+            return Err(VerifyFailure::Policy(message.to_string()).into());
+        }
+        self.warn(message);
+        Ok(())
+    }
+
+    /// Print a warning, unless `--quiet` or `--format json` was given.
+    fn warn(&self, message: &str) {
+        if !self.quiet && !self.json {
+            eprintln!("WARNING: {message}");
+        }
+    }
+
+    /// Check the digest binding with `--check-digest-binding`, and always
+    /// for a genesis edition, whose mark `init` binds to the content. A
+    /// genesis mark without a binding is a warning, or with `--strict` a
+    /// failure. Returns the outcome, or `None` when the check did not
+    /// apply.
+    fn check_binding(
+        &self,
+        edition: &Edition,
+    ) -> Result<Option<DigestBinding>> {
+        let mark = &edition.provenance;
+        if !self.digest_binding && !mark.is_genesis() {
+            return Ok(None);
+        }
+        let binding = DigestBinding::check(mark, &edition.content);
+        match &binding {
+            DigestBinding::Mismatch { bound, content } => {
+                return Err(VerifyFailure::DigestBinding(format!(
+                    "provenance mark does not commit to the edition content (mark binds {}, content is {})",
+                    bound.hex(),
+                    content.hex()
+                ))
+                .into());
+            }
+            DigestBinding::Absent if mark.is_genesis() => {
+                let message = "genesis mark carries no content digest";
+                if self.strict {
+                    return Err(
+                        VerifyFailure::Policy(message.to_string()).into()
+                    );
+                }
+                self.warn(message);
+            }
+            _ => {}
+        }
+        Ok(Some(binding))
+    }
+
+    /// With `--deep`, decrypt the content and check its digest against the
+    /// one its ciphertext declares and the one the provenance mark binds,
+    /// if any. Returns a description of the outcome, or `None` when the
+    /// check did not apply.
+    fn check_content(&self, edition: &Edition) -> Result<Option<String>> {
+        let Some(deep) = self.deep.as_ref() else {
+            return Ok(None);
+        };
+        let (plaintext, source) = deep.open(edition)?;
+        // Sealed content is wrapped before it is encrypted, so the edition
+        // declares the digest of the wrapped plaintext.
+        let declared = edition.content.digest();
+        let actual = plaintext.digest();
+        if actual != declared && plaintext.wrap().digest() != declared {
+            return Err(VerifyFailure::ContentDigest(format!(
+                "decrypted content digest {} does not match the digest {} its ciphertext declares",
+                actual.hex(),
+                declared.hex()
+            ))
+            .into());
+        }
+        let binding = DigestBinding::check(&edition.provenance, &plaintext);
+        if let DigestBinding::Mismatch { bound, content } = &binding {
+            return Err(VerifyFailure::ContentDigest(format!(
+                "decrypted content digest {} does not match the digest {} bound in the provenance mark",
+                content.hex(),
+                bound.hex()
+            ))
+            .into());
+        }
+        Ok(Some(format!("{source}, {}", binding.describe())))
+    }
+
+    fn checks_membership(&self) -> bool {
+        self.expect_permits.is_some() || !self.require_members.is_empty()
+    }
+
+    /// Compare the edition's permits with `--expect-permits` and
+    /// `--require-member`, failing with what was found. Returns a
+    /// description of the permits.
+    fn check_membership(&self, edition: &Edition) -> Result<String> {
+        let holders: Vec<Option<XID>> = permits::sealed_permits(edition)
+            .into_iter()
+            .map(|(_, holder)| holder)
+            .collect();
+        let annotated: Vec<XID> = holders.iter().flatten().copied().collect();
+        let anonymous = holders.len() - annotated.len();
+        let found = format!(
+            "{} permits, {} annotated with a member XID",
+            holders.len(),
+            annotated.len()
         );
+
+        let mut unmet = Vec::new();
+        if let Some(expected) = self.expect_permits
+            && !expected.admits(holders.len())
+        {
+            unmet.push(format!(
+                "expected {expected} permits, but found {}",
+                holders.len()
+            ));
+        }
+        for member in &self.require_members {
+            if annotated.contains(member) {
+                continue;
+            }
+            let mut problem =
+                format!("expected a permit for member {member}, but found ");
+            if annotated.is_empty() {
+                problem.push_str("no annotated permits");
+            } else {
+                let listed: Vec<String> =
+                    annotated.iter().map(XID::to_string).collect();
+                problem.push_str(&format!("permits for {}", listed.join(", ")));
+            }
+            match anonymous {
+                0 => {}
+                1 => problem.push_str(
+                    " (1 unannotated permit cannot satisfy --require-member)",
+                ),
+                _ => problem.push_str(&format!(
+                    " ({anonymous} unannotated permits cannot satisfy --require-member)"
+                )),
+            }
+            unmet.push(problem);
+        }
+        if unmet.is_empty() {
+            Ok(found)
+        } else {
+            Err(VerifyFailure::Policy(unmet.join("; ")).into())
+        }
+    }
+}
+
+impl DeepInputs {
+    /// The edition's plaintext content, unwrapped as `content decrypt`
+    /// recovers it, and how it was recovered.
+    fn open(&self, edition: &Edition) -> Result<(Envelope, &'static str)> {
+        if !edition.content.is_encrypted() {
+            return Ok((unwrapped(&edition.content)?, "content is cleartext"));
+        }
+        if !self.shards.is_empty() {
+            let shares = decrypt::parse_shards(&self.shards)?;
+            let content = decrypt::join_shards(&edition.content, &shares)
+                .map_err(|err| {
+                    VerifyFailure::KeyRecovery(format!(
+                        "could not recover the content from the SSKR shares: {err:#}"
+                    ))
+                })?;
+            return Ok((content, "recovered from SSKR shares"));
+        }
+        let (key, source) = match &self.key {
+            Some(key) => (key.clone(), "decrypted with --key"),
+            None => {
+                let sealed: Vec<SealedMessage> =
+                    permits::sealed_permits(edition)
+                        .into_iter()
+                        .map(|(sealed, _)| sealed.clone())
+                        .collect();
+                let key = decrypt::recover_key_from_permits(
+                    &sealed,
+                    &self.identities,
+                )
+                .map_err(|err| {
+                    VerifyFailure::KeyRecovery(format!(
+                        "could not recover the content key: {err:#}"
+                    ))
+                })?;
+                (key, "decrypted with a permit")
+            }
+        };
+        Ok((
+            unwrapped(&decrypt_content(&edition.content, &key)?)?,
+            source,
+        ))
+    }
+}
+
+/// `content` without the wrapper sealing puts around it.
+fn unwrapped(content: &Envelope) -> Result<Envelope> {
+    if content.is_wrapped() {
+        content.try_unwrap().context("failed to unwrap the content")
+    } else {
+        Ok(content.clone())
+    }
+}
+
+/// Decrypt the subject of `content` with `key`, leaving the digest it
+/// declares for the caller to compare.
+fn decrypt_content(content: &Envelope, key: &SymmetricKey) -> Result<Envelope> {
+    let subject = content.subject();
+    let EnvelopeCase::Encrypted(message) = subject.case() else {
+        return Err(VerifyFailure::Decryption(
+            "content subject is not encrypted".to_string(),
+        )
+        .into());
+    };
+    let plaintext = key.decrypt(message).map_err(|err| {
+        VerifyFailure::Decryption(format!(
+            "failed to decrypt the content: {err}"
+        ))
+    })?;
+    CBOR::try_from_data(plaintext)
+        .ok()
+        .and_then(|cbor| Envelope::from_tagged_cbor(cbor).ok())
+        .ok_or_else(|| {
+            VerifyFailure::Decryption(
+                "decrypted content is not an envelope".to_string(),
+            )
+            .into()
+        })
+}
+
+/// An `--expect-permits` count: exactly `N`, or `>=N`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermitCount {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl PermitCount {
+    fn admits(&self, count: usize) -> bool {
+        match *self {
+            Self::Exactly(expected) => count == expected,
+            Self::AtLeast(minimum) => count >= minimum,
+        }
     }
+}
+
+impl FromStr for PermitCount {
+    type Err = String;
 
-    if let Some(prev_spec) = args.previous.as_ref() {
-        let prev_env = io::parse_envelope(prev_spec)
-            .context("failed to parse previous edition")?;
-        let prev_inner = prev_env
-            .verify(&publisher_keys)
-            .context("failed to verify previous edition signature")?;
-        let prev_edition = Edition::try_from(prev_inner)
-            .context("previous edition is not a valid club edition")?;
-        if !prev_edition.precedes(&edition) {
-            bail!("previous edition does not precede the verified edition");
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let (at_least, number) = match value.strip_prefix(">=") {
+            Some(number) => (true, number.trim()),
+            None => (false, value),
+        };
+        let count = number
+            .parse()
+            .map_err(|_| format!("expected N or >=N, got '{value}'"))?;
+        Ok(if at_least {
+            Self::AtLeast(count)
+        } else {
+            Self::Exactly(count)
+        })
+    }
+}
+
+impl fmt::Display for PermitCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exactly(count) => write!(f, "{count}"),
+            Self::AtLeast(count) => write!(f, "at least {count}"),
         }
     }
+}
 
+/// Fail if any permit of the unwrapped edition is malformed, naming each
+/// offending permit.
+fn check_permits(inner: &Envelope) -> Result<()> {
+    let problems = permits::permit_problems(inner);
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(VerifyFailure::MalformedPermit(problems.join("; ")).into())
+}
+
+/// The `--require-encrypted` policy: the content must be encrypted, and a
+/// permit or recorded SSKR policy must give some way to decrypt it.
+fn check_encrypted(edition_env: &Envelope, edition: &Edition) -> Result<()> {
+    if !edition.content.is_encrypted() {
+        let message = if edition.content.is_wrapped() {
+            "content is cleartext (wrapped, not encrypted)"
+        } else {
+            "content is cleartext"
+        };
+        return Err(VerifyFailure::Policy(message.to_string()).into());
+    }
+    if edition.permits.is_empty() {
+        let (inner, _) = inspect::edition_payload(edition_env)?;
+        if inner.assertion_with_predicate(super::SSKR_POLICY).is_err() {
+            return Err(VerifyFailure::Policy(
+                "content encrypted but no access method attached: no permits and no SSKR policy"
+                    .to_string(),
+            )
+            .into());
+        }
+    }
     Ok(())
 }
+
+/// Read a `--chain-file`: `(source, spec)` for each listed edition, in
+/// order.
+fn read_chain_file(path: &Path) -> Result<Vec<(String, String)>> {
+    let text = std::fs::read_to_string(path).with_context(|| {
+        format!("failed to read chain file '{}'", path.display())
+    })?;
+    let base = path.parent().unwrap_or(Path::new(""));
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let file = base.join(line);
+            (line.to_string(), format!("@{}", file.display()))
+        })
+        .collect())
+}
+
+/// Verify `chain`, given as `(source, spec)` pairs in order, as one
+/// contiguous run of a club's editions, failing at the first edition or
+/// consecutive pair that does not check out. Returns the verified range.
+fn verify_chain(
+    chain: &[(String, String)],
+    publishers: &[Publisher],
+    checks: &EditionChecks,
+    allow_date_regression: bool,
+) -> Result<String> {
+    let mut previous: Option<(String, Edition)> = None;
+    let mut first_seq = None;
+    for (index, (source, spec)) in chain.iter().enumerate() {
+        let label = format!("edition {} ({source})", index + 1);
+        let edition_env = io::parse_envelope(spec)
+            .with_context(|| format!("failed to parse {label}"))?;
+        let (edition, _) = verify_signed(&edition_env, publishers)
+            .with_context(|| format!("{label} failed verification"))?;
+        let mark = &edition.provenance;
+        checks
+            .run(&edition_env, &edition, publishers)
+            .with_context(|| format!("{label} failed its checks"))?;
+        if let Some((prev_label, prev)) = previous.as_ref() {
+            let prev_mark = &prev.provenance;
+            let pair = format!(
+                "between {prev_label} at seq {} and {label} at seq {}",
+                prev_mark.seq(),
+                mark.seq()
+            );
+            let problem = if prev.club_xid != edition.club_xid {
+                Some(VerifyFailure::ClubMismatch(format!(
+                    "club changes {pair} ({} then {})",
+                    prev.club_xid, edition.club_xid
+                )))
+            } else if prev_mark.chain_id() != mark.chain_id() {
+                Some(VerifyFailure::ProvenanceOrder(format!(
+                    "provenance chain changes {pair}"
+                )))
+            } else if !chain::links(prev_mark, mark) {
+                Some(VerifyFailure::ProvenanceOrder(format!(
+                    "chain breaks {pair}"
+                )))
+            } else if mark.date() < prev_mark.date() && !allow_date_regression {
+                Some(VerifyFailure::ProvenanceOrder(format!(
+                    "provenance date regresses {pair} ({} then {})",
+                    prev_mark.date(),
+                    mark.date()
+                )))
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                return Err(problem.into());
+            }
+        }
+        first_seq.get_or_insert(mark.seq());
+        previous = Some((label, edition));
+    }
+    let last = previous.expect("the chain is not empty").1;
+    Ok(format!(
+        "seq {} through {}, {} editions, chain intact",
+        first_seq.expect("the chain is not empty"),
+        last.provenance.seq(),
+        chain.len()
+    ))
+}
+
+/// Verify one edition file: its signature, club, and `checks`. Returns the
+/// label of the publisher that signed it.
+fn verify_file(
+    path: &Path,
+    publishers: &[Publisher],
+    checks: &EditionChecks,
+) -> Result<String> {
+    let edition_env = io::parse_envelope(&format!("@{}", path.display()))
+        .context("failed to parse edition")?;
+    let (edition, publisher) = verify_signed(&edition_env, publishers)?;
+    checks.run(&edition_env, &edition, publishers)?;
+    Ok(publisher.label.clone())
+}
+
+/// Verify each of `files` against the publishers at the same index, `jobs`
+/// at a time, returning the results in the same order.
+fn verify_files(
+    files: &[PathBuf],
+    publishers: &[&[Publisher]],
+    checks: &EditionChecks,
+    jobs: usize,
+) -> Vec<Result<String>> {
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<String>>>> =
+        Mutex::new(files.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, files.len().max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = files.get(index) else {
+                        break;
+                    };
+                    let result = verify_file(path, publishers[index], checks);
+                    results.lock().expect("no verifier panicked")[index] =
+                        Some(result);
+                }
+            });
+        }
+    });
+    results
+        .into_inner()
+        .expect("no verifier panicked")
+        .into_iter()
+        .map(|result| result.expect("every file is verified"))
+        .collect()
+}
+
+/// Print a pass/fail line per file and a final count, failing if any file
+/// failed.
+fn report_files(
+    files: &[PathBuf],
+    results: &[Result<String>],
+    quiet: bool,
+) -> Result<()> {
+    let width = files
+        .iter()
+        .map(|path| path.display().to_string().len())
+        .max()
+        .unwrap_or(0);
+    let mut lines = Vec::with_capacity(files.len() + 1);
+    let mut failed = 0;
+    for (path, result) in files.iter().zip(results) {
+        let name = path.display().to_string();
+        match result {
+            Ok(publisher) => lines
+                .push(format!("PASS  {name:<width$}  signed by {publisher}")),
+            Err(err) => {
+                failed += 1;
+                lines.push(format!("FAIL  {name:<width$}  {err:#}"));
+            }
+        }
+    }
+    let total = files.len();
+    lines.push(format!(
+        "Verified {} of {total} editions, {failed} failed",
+        total - failed
+    ));
+    if !quiet {
+        for line in &lines {
+            eprintln!("{line}");
+        }
+    }
+    if failed > 0 {
+        return Err(VerifyFailure::EditionsFailed(format!(
+            "{failed} of {total} editions failed verification"
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use bc_components::{
+        EncryptedMessage, Nonce, PrivateKeyBase, PrivateKeysProvider,
+        PublicKeysProvider, XIDProvider,
+    };
+    use bc_ur::{URDecodable, UREncodable};
+    use dcbor::Date;
+    use known_values::HAS_RECIPIENT;
+    use provenance_mark::{
+        ProvenanceMark, ProvenanceMarkGenerator, ProvenanceMarkResolution,
+    };
+
+    use super::*;
+    use crate::{
+        cmd::{VerificationFailed, edition::compose},
+        test_support::{
+            Club, compose_args, marks, parse, tamper, try_parse, with_mark,
+        },
+        trust::TrustStore,
+    };
+
+    /// The club's public XID document as a `--publisher`.
+    fn club_publisher(club: &Club) -> Publisher {
+        Publisher::new(
+            &io::parse_recipient_descriptor(&club.public_ur()).unwrap(),
+        )
+    }
+
+    fn kind(err: &anyhow::Error) -> &'static str {
+        FailureReport::new(err).kind
+    }
+
+    fn chain(editions: &[String]) -> Vec<(String, String)> {
+        editions
+            .iter()
+            .enumerate()
+            .map(|(index, ur)| (format!("#{}", index + 1), ur.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn reports_a_tampered_file_among_valid_ones() {
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-verify-dir-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let club = Club::new();
+        let mut editions = club.chain(3);
+        editions[1] = tamper(&editions[1]);
+        for (index, edition) in editions.iter().enumerate() {
+            fs::write(dir.join(format!("{:04}.ur", index + 1)), edition)
+                .unwrap();
+        }
+        fs::write(dir.join("notes.txt"), "not an edition").unwrap();
+
+        let files = io::files_matching(&dir, None).unwrap();
+        assert_eq!(files.len(), 3);
+        let publishers = [club_publisher(&club)];
+        let results = verify_files(
+            &files,
+            &[&publishers[..]; 3],
+            &EditionChecks { digest_binding: true, ..Default::default() },
+            2,
+        );
+        let passed: Vec<bool> = results.iter().map(Result::is_ok).collect();
+        assert_eq!(passed, [true, false, true]);
+        let err = report_files(&files, &results, true).unwrap_err();
+        assert_eq!(kind(&err), "editions_failed");
+
+        let matched = io::files_matching(&dir, Some("000?.ur")).unwrap();
+        assert_eq!(matched.len(), 3);
+        assert_eq!(io::files_matching(&dir, Some("*.txt")).unwrap().len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn chain_verification_pinpoints_the_failing_pair() {
+        let club = Club::new();
+        // Bare keys, so the club check below is the chain's own.
+        let publishers =
+            [Publisher::new(&RecipientDescriptor::from_public_keys(
+                club.keys.public_keys(),
+                None,
+            ))];
+        let marks = marks(4);
+        let editions: Vec<String> =
+            marks.iter().map(|mark| club.compose(mark)).collect();
+        let verify = |editions: &[String]| {
+            verify_chain(
+                &chain(editions),
+                &publishers,
+                &EditionChecks::default(),
+                false,
+            )
+        };
+
+        assert_eq!(
+            verify(&editions).unwrap(),
+            "seq 0 through 3, 4 editions, chain intact"
+        );
+
+        let gap = [
+            editions[0].clone(),
+            editions[1].clone(),
+            editions[3].clone(),
+        ];
+        let err = verify(&gap).unwrap_err();
+        assert_eq!(kind(&err), "provenance_order");
+        assert_eq!(
+            err.to_string(),
+            "chain breaks between edition 2 (#2) at seq 1 and edition 3 (#3) at seq 3"
+        );
+
+        // Same publisher keys, but the edition names another club.
+        let signed = Envelope::from_ur_string(&editions[2]).unwrap();
+        let inner = signed.try_unwrap().unwrap();
+        let club_assertion = inner.assertion_with_predicate("club").unwrap();
+        let other_club = Club::new().doc.xid();
+        let wrong_club = inner
+            .remove_assertion(club_assertion)
+            .add_assertion("club", other_club)
+            .wrap()
+            .add_signature(&club.keys)
+            .ur_string();
+        let mut mixed = editions.clone();
+        mixed[2] = wrong_club;
+        let err = verify(&mixed).unwrap_err();
+        assert!(err.to_string().starts_with(
+            "club changes between edition 2 (#2) at seq 1 and edition 3 (#3) at seq 2"
+        ));
+
+        let mut forged = editions.clone();
+        forged[1] = tamper(&editions[1]);
+        let err = verify(&forged).unwrap_err();
+        assert_eq!(kind(&err), "signature");
+        assert_eq!(err.to_string(), "edition 2 (#2) failed verification");
+    }
+
+    #[test]
+    fn accepts_any_of_several_publisher_keys() {
+        let club = Club::new();
+        let marks = marks(2);
+        let old = Envelope::from_ur_string(club.compose(&marks[0])).unwrap();
+        // After rotation the club's editions are signed with a new key.
+        let rotated = PrivateKeyBase::new();
+        let new = Envelope::from_ur_string(club.compose(&marks[1]))
+            .unwrap()
+            .try_unwrap()
+            .unwrap()
+            .wrap()
+            .add_signature(&rotated);
+        let rotated_keys = rotated.public_keys();
+        let rotated_label =
+            format!("keys {}", rotated_keys.reference().ref_hex());
+        let publishers = [
+            // Another club's descriptor, checked only if its key matches.
+            club_publisher(&Club::new()),
+            club_publisher(&club),
+            Publisher::new(&RecipientDescriptor::from_public_keys(
+                rotated_keys,
+                None,
+            )),
+        ];
+
+        let (_, matched) = verify_signed(&old, &publishers).unwrap();
+        assert_eq!(matched.label, format!("XID {}", club.doc.xid()));
+        let (edition, matched) = verify_signed(&new, &publishers).unwrap();
+        assert_eq!(matched.label, rotated_label);
+        assert_eq!(edition.club_xid, club.doc.xid());
+
+        let err = verify_signed(&new, &publishers[..2]).err().unwrap();
+        assert_eq!(kind(&err), "signature");
+        assert_eq!(
+            err.to_string(),
+            "failed to verify edition signature against any of the 2 publishers"
+        );
+    }
+
+    #[test]
+    fn requires_the_signing_key_to_be_authorized_for_signing() {
+        let mut club = Club::new();
+        let (encrypter, signer) =
+            (PrivateKeyBase::new(), PrivateKeyBase::new());
+        let mut encrypt_only = Key::new(encrypter.public_keys());
+        encrypt_only.add_allow(Privilege::Encrypt);
+        let mut sign_only = Key::new(signer.public_keys());
+        sign_only.add_allow(Privilege::Sign);
+        club.doc.add_key(encrypt_only).unwrap();
+        club.doc.add_key(sign_only).unwrap();
+        let edition = club.compose(&marks(1)[0]);
+        let signed_by = |keys: &PrivateKeyBase| {
+            Envelope::from_ur_string(&edition)
+                .unwrap()
+                .try_unwrap()
+                .unwrap()
+                .wrap()
+                .add_signature(keys)
+        };
+        let publishers = [club_publisher(&club)];
+
+        let (_, matched) =
+            verify_signed(&signed_by(&club.keys), &publishers).unwrap();
+        assert_eq!(matched.label, format!("XID {}", club.doc.xid()));
+        verify_signed(&signed_by(&signer), &publishers).unwrap();
+
+        // The encryption key's signature is valid, but the document does not
+        // let that key sign.
+        let forged = signed_by(&encrypter);
+        assert!(forged.verify(&encrypter.public_keys()).is_ok());
+        let err = verify_signed(&forged, &publishers).err().unwrap();
+        assert_eq!(kind(&err), "signature");
+        let message = err.to_string();
+        assert!(
+            message
+                .contains("signature made by a key not authorized for signing")
+        );
+        assert!(
+            message
+                .contains(&encrypter.public_keys().reference().ref_hex_short())
+        );
+    }
+
+    #[test]
+    fn json_report_names_every_check() {
+        use CheckStatus::*;
+
+        let club = Club::new();
+        let editions = club.chain(2);
+        let publisher = io::xid_document_ur(&club.doc, false).unwrap();
+        let args = parse::<CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &editions[1],
+            "--previous",
+            &editions[0],
+            "--publisher",
+            &publisher,
+            "--format",
+            "json",
+        ]);
+        let publishers = [club_publisher(&club)];
+        let checks = EditionChecks::new(&args).unwrap();
+        let outcomes = |report: &VerifyReport| -> Vec<(&str, CheckStatus)> {
+            report
+                .checks
+                .iter()
+                .map(|check| (check.name, check.status))
+                .collect()
+        };
+
+        let mut report = VerifyReport::new();
+        verify_edition(&args, &checks, &editions[1], &publishers, &mut report)
+            .unwrap();
+        report.verified = true;
+        let json = serde_json::to_value(&report).unwrap();
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "checks",
+                "club_xid",
+                "linkage",
+                "previous_check",
+                "publisher_match",
+                "seq",
+                "verified"
+            ]
+        );
+        assert_eq!(json["publisher_match"], club.doc.xid().to_string());
+        assert_eq!(json["previous_check"], "passed");
+        assert_eq!(json["linkage"], "cryptographic");
+        assert_eq!(json["checks"][1]["name"], "signature");
+        assert_eq!(json["checks"][1]["status"], "passed");
+        assert_eq!(
+            json["checks"][6]["detail"],
+            "not a genesis edition and --check-digest-binding not given"
+        );
+        assert_eq!(
+            outcomes(&report),
+            [
+                ("parse", Passed),
+                ("signature", Passed),
+                ("threshold", Skipped),
+                ("permits", Passed),
+                ("edition", Passed),
+                ("club-xid", Passed),
+                ("digest-binding", Skipped),
+                ("encrypted", Skipped),
+                ("membership", Skipped),
+                ("date-window", Skipped),
+                ("content", Skipped),
+                ("previous", Passed),
+            ]
+        );
+
+        let forged = tamper(&editions[1]);
+        let mut report = VerifyReport::new();
+        let err =
+            verify_edition(&args, &checks, &forged, &publishers, &mut report)
+                .unwrap_err();
+        assert_eq!(kind(&err), "signature");
+        assert_eq!(
+            outcomes(&report),
+            [("parse", Passed), ("signature", Failed)]
+        );
+        assert_eq!(report.previous_check, Skipped);
+        assert!(report.publisher_match.is_none());
+    }
+
+    #[test]
+    fn tells_cryptographic_linkage_from_adjacent_sequence_numbers() {
+        let club = Club::new();
+        let genuine_marks = marks(2);
+        // Seq 1 of another chain follows seq 0 by number only.
+        let forged_mark = &marks(2)[1];
+        let previous = club.compose(&genuine_marks[0]);
+        let genuine = club.compose(&genuine_marks[1]);
+        let forged = club.compose(forged_mark);
+        let publisher = io::xid_document_ur(&club.doc, false).unwrap();
+        let publishers = [club_publisher(&club)];
+        let verify = |edition: &str, extra: &[&str]| {
+            let mut argv = vec![
+                "clubs",
+                "--edition",
+                edition,
+                "--previous",
+                &previous,
+                "--publisher",
+                &publisher,
+                "--quiet",
+            ];
+            argv.extend(extra);
+            let args = parse::<CommandArgs>(&argv);
+            let checks = EditionChecks::new(&args).unwrap();
+            let mut report = VerifyReport::new();
+            verify_edition(&args, &checks, edition, &publishers, &mut report)
+                .map(|()| report.linkage)
+        };
+
+        assert_eq!(
+            Linkage::between(&genuine_marks[0], forged_mark),
+            Some(Linkage::SequenceOnly)
+        );
+        assert_eq!(
+            Linkage::between(&genuine_marks[0], &genuine_marks[0]),
+            None
+        );
+        assert_eq!(
+            verify(&genuine, &[]).unwrap(),
+            Some(Linkage::Cryptographic)
+        );
+        assert_eq!(
+            verify(&genuine, &["--require-cryptographic-link"]).unwrap(),
+            Some(Linkage::Cryptographic)
+        );
+        for extra in [&[][..], &["--require-cryptographic-link"]] {
+            let err = verify(&forged, extra).unwrap_err();
+            assert_eq!(kind(&err), "provenance_order");
+            assert!(err.to_string().contains("different provenance chain"));
+        }
+    }
+
+    #[test]
+    fn accepts_a_bare_provenance_mark_as_previous() {
+        let club = Club::new();
+        let provenance = marks(3);
+        let editions: Vec<String> =
+            provenance.iter().map(|mark| club.compose(mark)).collect();
+        let publisher = io::xid_document_ur(&club.doc, false).unwrap();
+        let publishers = [club_publisher(&club)];
+        let verify = |edition: &str, previous: &str| {
+            let args = parse::<CommandArgs>(&[
+                "clubs",
+                "--edition",
+                edition,
+                "--previous",
+                previous,
+                "--publisher",
+                &publisher,
+                "--quiet",
+            ]);
+            let checks = EditionChecks::new(&args).unwrap();
+            let mut report = VerifyReport::new();
+            verify_edition(&args, &checks, edition, &publishers, &mut report)
+                .map(|()| report.checks.pop().unwrap().detail.unwrap())
+        };
+
+        assert_eq!(
+            verify(&editions[1], &editions[0]).unwrap(),
+            "linkage: cryptographic"
+        );
+        assert_eq!(
+            verify(&editions[1], &provenance[0].ur_string()).unwrap(),
+            "linkage: cryptographic; bare provenance mark, previous signature not checked"
+        );
+
+        let message = format!(
+            "{:#}",
+            verify(&editions[2], &provenance[0].ur_string()).unwrap_err()
+        );
+        assert!(
+            message.contains("previous mark (a bare provenance mark) at seq 0")
+        );
+        let other_chain = marks(1)[0].ur_string();
+        let message =
+            format!("{:#}", verify(&editions[1], &other_chain).unwrap_err());
+        assert!(message.contains("different provenance chain"));
+        assert!(message.contains("bare provenance mark"));
+        let message =
+            format!("{:#}", verify(&editions[2], &editions[0]).unwrap_err());
+        assert!(message.contains("previous edition at seq 0"));
+    }
+
+    #[test]
+    fn threshold_counts_distinct_publishers_that_signed() {
+        let club = Club::new();
+        let (cosigner, absent) = (PrivateKeyBase::new(), PrivateKeyBase::new());
+        let cosigned = Envelope::from_ur_string(club.compose(&marks(1)[0]))
+            .unwrap()
+            .add_signature(&cosigner)
+            .ur_string();
+        let officers = [
+            io::xid_document_ur(&club.doc, false).unwrap(),
+            cosigner.public_keys().ur_string(),
+            absent.public_keys().ur_string(),
+        ];
+        let publishers: Vec<Publisher> = officers
+            .iter()
+            .map(|ur| {
+                Publisher::new(&io::parse_recipient_descriptor(ur).unwrap())
+            })
+            .collect();
+        let parse = |threshold: &str| {
+            let mut argv =
+                vec!["clubs", "--edition", "-", "--threshold", threshold];
+            for officer in &officers {
+                argv.extend(["--publisher", officer]);
+            }
+            parse::<CommandArgs>(&argv)
+        };
+        let verify = |threshold: &str| {
+            let args = parse(threshold);
+            let checks = EditionChecks::new(&args).unwrap();
+            let mut report = VerifyReport::new();
+            verify_edition(&args, &checks, &cosigned, &publishers, &mut report)
+                .map(|()| report)
+        };
+
+        for threshold in ["1", "2"] {
+            let report = verify(threshold).unwrap();
+            let signers: Vec<Option<&str>> = report
+                .signers
+                .iter()
+                .map(|signer| signer.publisher.as_deref())
+                .collect();
+            assert_eq!(signers.len(), 2);
+            assert!(signers.contains(&Some(publishers[0].label.as_str())));
+            assert!(signers.contains(&Some(publishers[1].label.as_str())));
+        }
+        let err = verify("3").unwrap_err();
+        assert_eq!(kind(&err), "signature");
+        assert!(
+            err.to_string()
+                .starts_with("only 2 of the required 3 publishers signed")
+        );
+
+        // More signatures than publishers can never be reached.
+        assert!(EditionChecks::new(&parse("4")).is_err());
+        assert!(EditionChecks::new(&parse("0")).is_err());
+    }
+
+    #[test]
+    fn json_report_names_the_failure_kind() {
+        let club = Club::new();
+        let editions = club.chain(3);
+        let publisher = io::xid_document_ur(&club.doc, false).unwrap();
+        let other_club = Club::new();
+        let wrong_club = other_club.compose(&marks(1)[0]);
+        let impostor = Envelope::from_ur_string(&wrong_club)
+            .unwrap()
+            .try_unwrap()
+            .unwrap()
+            .wrap()
+            .add_signature(&club.keys)
+            .ur_string();
+        let failure = |edition: &str, extra: &[&str]| {
+            let mut argv = vec![
+                "clubs",
+                "--edition",
+                edition,
+                "--publisher",
+                &publisher,
+                "--format",
+                "json",
+            ];
+            argv.extend(extra);
+            let args = parse::<CommandArgs>(&argv);
+            let checks = EditionChecks::new(&args).unwrap();
+            let publishers = [club_publisher(&club)];
+            let mut report = VerifyReport::new();
+            let err = verify_edition(
+                &args,
+                &checks,
+                edition,
+                &publishers,
+                &mut report,
+            )
+            .unwrap_err();
+            report.failure = Some(FailureReport::new(&err));
+            let json = serde_json::to_value(&report).unwrap();
+            assert_eq!(json["verified"], false);
+            assert_eq!(
+                json["failure"]["message"].as_str().unwrap(),
+                format!("{err:#}")
+            );
+            let exit =
+                err.downcast_ref::<VerifyFailure>().unwrap().exit_status();
+            (json["failure"]["kind"].as_str().unwrap().to_string(), exit)
+        };
+
+        assert_eq!(
+            failure(&tamper(&editions[1]), &[]),
+            ("signature".to_string(), 4)
+        );
+        assert_eq!(failure(&impostor, &[]), ("club_mismatch".to_string(), 4));
+        assert_eq!(
+            failure(&editions[2], &["--previous", &editions[0]]),
+            ("provenance_order".to_string(), 4)
+        );
+        assert_eq!(
+            failure(&editions[1], &["--expect-permits", "2"]),
+            ("policy".to_string(), 3)
+        );
+        assert_eq!(kind(&anyhow!("failed to parse edition")), "input");
+    }
+
+    #[test]
+    fn names_each_malformed_permit() {
+        let club = Club::new();
+        let edition = club.compose(&marks(1)[0]);
+        let inner = Envelope::from_ur_string(&edition)
+            .unwrap()
+            .try_unwrap()
+            .unwrap();
+        let permit = inner.object_for_predicate(HAS_RECIPIENT).unwrap();
+        let resign = |inner: Envelope| {
+            inner.wrap().add_signature(&club.keys).ur_string()
+        };
+        let publishers = [club_publisher(&club)];
+        let verify = |edition: &str| {
+            let args = parse::<CommandArgs>(&["clubs", "--edition", edition]);
+            let checks = EditionChecks::new(&args).unwrap();
+            let mut report = VerifyReport::new();
+            verify_edition(&args, &checks, edition, &publishers, &mut report)
+        };
+
+        let corrupted =
+            resign(inner.clone().add_assertion(HAS_RECIPIENT, "not a permit"));
+        let err = verify(&corrupted).unwrap_err();
+        assert_eq!(kind(&err), "malformed_permit");
+        let message = err.to_string();
+        assert!(message.starts_with("permit #"));
+        assert!(message.contains("is not a sealed message"));
+
+        // The same sealed message again, annotated with a holder that is not
+        // an XID.
+        let repeated = resign(inner.add_assertion(
+            HAS_RECIPIENT,
+            permit.add_assertion(known_values::HOLDER, "alice"),
+        ));
+        let message = verify(&repeated).unwrap_err().to_string();
+        assert!(message.contains("repeats the sealed message of permit #"));
+        assert!(message.contains("holder annotation that is not an XID"));
+    }
+
+    #[test]
+    fn title_and_notes_survive_signing_and_a_second_signature() {
+        let club = Club::new();
+        let edition = club.compose_with(
+            &marks(1)[0],
+            &["--title", "Issue 1", "--note", "draft"],
+        );
+        let cosigned = Envelope::from_ur_string(&edition)
+            .unwrap()
+            .add_signature_opt(
+                &PrivateKeyBase::new(),
+                None,
+                Some(
+                    SignatureMetadata::new()
+                        .with_assertion(known_values::NAME, "Issue 1"),
+                ),
+            )
+            .ur_string();
+        let publishers = [club_publisher(&club)];
+        for edition in [&edition, &cosigned] {
+            let args = parse::<CommandArgs>(&["clubs", "--edition", edition]);
+            let checks = EditionChecks::new(&args).unwrap();
+            let mut report = VerifyReport::new();
+            verify_edition(&args, &checks, edition, &publishers, &mut report)
+                .unwrap();
+
+            let signed = Envelope::from_ur_string(edition).unwrap();
+            let metadata = inspect::signature_metadata(&signed);
+            let publisher_meta = metadata
+                .iter()
+                .find(|meta| {
+                    meta.extract_optional_object_for_predicate::<String>(
+                        known_values::NOTE,
+                    )
+                    .unwrap()
+                    .is_some()
+                })
+                .unwrap();
+            assert_eq!(
+                publisher_meta
+                    .extract_object_for_predicate::<String>(known_values::NAME)
+                    .unwrap(),
+                "Issue 1"
+            );
+            assert!(signed.verify(&club.keys.public_keys()).is_ok());
+        }
+    }
+
+    #[test]
+    fn deep_verification_tells_key_decryption_and_digest_failures_apart() {
+        let club = Club::new();
+        let member = PrivateKeyBase::new();
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        generator.next(Date::now(), None::<String>);
+        let mark = generator.next(
+            Date::now(),
+            Some(Envelope::new("issue 1").digest()),
+        );
+        let permit = member.public_keys().ur_string();
+        let edition = club.compose_with(&mark, &["--permit", &permit]);
+        let identity = member.ur_string();
+        let publishers = [club_publisher(&club)];
+        let verify = |edition: &str, extra: &[&str]| {
+            let mut argv = vec!["clubs", "--edition", edition, "--deep"];
+            argv.extend(extra);
+            let args = parse::<CommandArgs>(&argv);
+            let checks = EditionChecks::new(&args)?;
+            let mut report = VerifyReport::new();
+            verify_edition(&args, &checks, edition, &publishers, &mut report)?;
+            let content = report
+                .checks
+                .into_iter()
+                .find(|check| check.name == "content")
+                .unwrap();
+            Ok::<_, anyhow::Error>(content.detail)
+        };
+
+        assert_eq!(
+            verify(&edition, &["--identity", &identity])
+                .unwrap()
+                .as_deref(),
+            Some("decrypted with a permit, bound and matching")
+        );
+        let err = verify(&edition, &[]).unwrap_err();
+        assert_eq!(kind(&err), "usage");
+        let stranger = PrivateKeyBase::new().ur_string();
+        let err = verify(&edition, &["--identity", &stranger]).unwrap_err();
+        assert_eq!(kind(&err), "key_recovery");
+        let wrong_key = SymmetricKey::new().ur_string();
+        let err = verify(&edition, &["--key", &wrong_key]).unwrap_err();
+        assert_eq!(kind(&err), "decryption");
+
+        // Re-signed with a flipped ciphertext byte, then with other content
+        // encrypted under the real key but declaring the original digest.
+        let inner = Envelope::from_ur_string(&edition)
+            .unwrap()
+            .try_unwrap()
+            .unwrap();
+        let subject = inner.subject();
+        let EnvelopeCase::Encrypted(message) = subject.case() else {
+            panic!("content is not encrypted");
+        };
+        let reseal = |message: EncryptedMessage| {
+            let content =
+                Envelope::from_untagged_cbor(message.tagged_cbor()).unwrap();
+            inner
+                .replace_subject(content)
+                .wrap()
+                .add_signature(&club.keys)
+                .ur_string()
+        };
+        let mut ciphertext = message.ciphertext().to_vec();
+        ciphertext[0] ^= 1;
+        let flipped = reseal(EncryptedMessage::new(
+            ciphertext,
+            message.aad(),
+            *message.nonce(),
+            message.authentication_tag().clone(),
+        ));
+        let err = verify(&flipped, &["--identity", &identity]).unwrap_err();
+        assert_eq!(kind(&err), "decryption");
+
+        let parsed = super::super::decode_edition(&inner).unwrap();
+        let sealed: Vec<SealedMessage> = permits::sealed_permits(&parsed)
+            .into_iter()
+            .map(|(sealed, _)| sealed.clone())
+            .collect();
+        let key = decrypt::recover_key_from_permits(
+            &sealed,
+            &[member.private_keys()],
+        )
+        .unwrap();
+        let forged = reseal(key.encrypt_with_digest(
+            Envelope::new("issue 2").tagged_cbor().to_cbor_data(),
+            inner.subject().digest(),
+            None::<Nonce>,
+        ));
+        let err = verify(&forged, &["--identity", &identity]).unwrap_err();
+        assert_eq!(kind(&err), "content_digest");
+        assert!(err.to_string().contains("its ciphertext declares"));
+    }
+
+    #[test]
+    fn deep_verification_joins_sskr_shares_and_checks_the_binding() {
+        let club = Club::new();
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        generator.next(Date::now(), None::<String>);
+        let bound = generator.next(
+            Date::now(),
+            Some(Envelope::new("issue 1").digest()),
+        );
+        let rebound = generator.next(
+            Date::now(),
+            Some(Envelope::new("issue 2").digest()),
+        );
+        let publishers = [club_publisher(&club)];
+        let verify = |mark: &ProvenanceMark, remark: bool| {
+            let composed = compose::run(compose_args(
+                &club.doc,
+                mark,
+                &["--sskr", "2of3"],
+            ))
+            .unwrap();
+            let edition = if remark {
+                with_mark(&composed.edition_ur, &club.doc, &bound)
+            } else {
+                composed.edition_ur.clone()
+            };
+            let mut argv = vec![
+                "clubs".to_string(),
+                "--edition".to_string(),
+                edition.clone(),
+                "--deep".to_string(),
+            ];
+            for (_, share) in composed.shares.iter().take(2) {
+                argv.extend(["--sskr".to_string(), share.clone()]);
+            }
+            let argv: Vec<&str> = argv.iter().map(String::as_str).collect();
+            let args = parse::<CommandArgs>(&argv);
+            let checks = EditionChecks::new(&args)?;
+            let mut report = VerifyReport::new();
+            verify_edition(
+                &args,
+                &checks,
+                &edition,
+                &publishers,
+                &mut report,
+            )?;
+            let content = report
+                .checks
+                .into_iter()
+                .find(|check| check.name == "content")
+                .unwrap();
+            Ok::<_, anyhow::Error>(content.detail)
+        };
+
+        assert_eq!(
+            verify(&bound, false).unwrap().as_deref(),
+            Some("recovered from SSKR shares, bound and matching")
+        );
+        // Composed at seq 2, so its content is "issue 2", then given the
+        // mark binding "issue 1".
+        let err = verify(&rebound, true).unwrap_err();
+        assert_eq!(kind(&err), "content_digest");
+        assert!(err.to_string().contains("bound in the provenance mark"));
+    }
+
+    #[test]
+    fn require_encrypted_tells_cleartext_from_unreachable_content() {
+        let club = Club::new();
+        let mark = &marks(1)[0];
+        let publishers = [club_publisher(&club)];
+        let check = |edition: &str| {
+            let edition_env = Envelope::from_ur_string(edition).unwrap();
+            let (edition, _) =
+                verify_signed(&edition_env, &publishers).unwrap();
+            check_encrypted(&edition_env, &edition)
+        };
+
+        let with_permit = club.compose(mark);
+        check(&with_permit).unwrap();
+        check(&club.compose_with(mark, &["--sskr", "2of3"])).unwrap();
+
+        let err = check(&club.compose_with(mark, &[])).unwrap_err();
+        assert_eq!(kind(&err), "policy");
+        assert_eq!(err.to_string(), "content is cleartext");
+
+        // Re-signed with its permit removed, nothing can unlock the content.
+        let inner = Envelope::from_ur_string(&with_permit)
+            .unwrap()
+            .try_unwrap()
+            .unwrap();
+        let unreachable = inner
+            .assertions_with_predicate(HAS_RECIPIENT)
+            .into_iter()
+            .fold(inner.clone(), |envelope, permit| {
+                envelope.remove_assertion(permit)
+            })
+            .wrap()
+            .add_signature(&club.keys)
+            .ur_string();
+        let err = check(&unreachable).unwrap_err();
+        assert_eq!(kind(&err), "policy");
+        assert_eq!(
+            err.to_string(),
+            "content encrypted but no access method attached: no permits and no SSKR policy"
+        );
+    }
+
+    #[test]
+    fn checks_the_genesis_digest_binding() {
+        let club = Club::new();
+        let publishers = [club_publisher(&club)];
+        let check = |edition: &str, requested: bool, strict: bool| {
+            let edition_env = Envelope::from_ur_string(edition).unwrap();
+            let (edition, _) =
+                verify_signed(&edition_env, &publishers).unwrap();
+            EditionChecks {
+                digest_binding: requested,
+                strict,
+                ..Default::default()
+            }
+            .check_binding(&edition)
+        };
+        // `Club::compose` publishes `issue <seq>`.
+        let digest =
+            |text: &str| Some(Envelope::new(text).digest());
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        let genesis = generator.next(Date::now(), digest("issue 0"));
+        let next = generator.next(Date::now(), digest("issue 1"));
+
+        assert_eq!(
+            check(&club.compose(&genesis), false, true).unwrap(),
+            Some(DigestBinding::Match)
+        );
+
+        let mismatched = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        )
+        .next(Date::now(), digest("swapped"));
+        let swapped =
+            with_mark(&club.compose(&genesis), &club.doc, &mismatched);
+        let err = check(&swapped, false, false).unwrap_err();
+        assert_eq!(kind(&err), "digest_binding");
+        let message = err.to_string();
+        assert!(message.contains(&digest("swapped").unwrap().hex()));
+        // The encrypted content declares the digest of the wrapped plaintext.
+        let sealed = Envelope::new("issue 0").wrap().digest();
+        assert!(message.contains(&sealed.hex()));
+
+        let unbound = club.compose(&marks(1)[0]);
+        assert_eq!(
+            check(&unbound, false, false).unwrap(),
+            Some(DigestBinding::Absent)
+        );
+        let err = check(&unbound, false, true).unwrap_err();
+        assert_eq!(kind(&err), "policy");
+
+        let later = club.compose(&next);
+        assert_eq!(check(&later, false, true).unwrap(), None);
+        assert_eq!(
+            check(&later, true, true).unwrap(),
+            Some(DigestBinding::Match)
+        );
+    }
+
+    #[test]
+    fn membership_expectations_list_what_was_found() {
+        let club = Club::new();
+        let publisher = io::xid_document_ur(&club.doc, false).unwrap();
+        let (alice, bob) = (Club::new(), Club::new());
+        let alice_doc = io::xid_document_ur(&alice.doc, false).unwrap();
+        let anonymous = PrivateKeyBase::new().public_keys().ur_string();
+        let edition = club.compose_with(
+            &marks(1)[0],
+            &["--permit", &alice_doc, "--permit", &anonymous],
+        );
+        let edition_env = Envelope::from_ur_string(&edition).unwrap();
+        let (edition, _) =
+            verify_signed(&edition_env, &[club_publisher(&club)]).unwrap();
+        let check = |extra: &[&str]| {
+            let mut argv =
+                vec!["clubs", "--edition", "-", "--publisher", &publisher];
+            argv.extend(extra);
+            let args = parse::<CommandArgs>(&argv);
+            EditionChecks::new(&args)
+                .unwrap()
+                .check_membership(&edition)
+        };
+        let (alice_xid, bob_xid) = (alice.doc.xid(), bob.doc.xid());
+
+        assert_eq!(
+            check(&[
+                "--expect-permits",
+                "2",
+                "--require-member",
+                &alice_xid.ur_string(),
+            ])
+            .unwrap(),
+            "2 permits, 1 annotated with a member XID"
+        );
+        check(&["--expect-permits", ">=1"]).unwrap();
+
+        let err = check(&["--expect-permits", ">=3"]).unwrap_err();
+        assert_eq!(kind(&err), "policy");
+        assert_eq!(err.to_string(), "expected at least 3 permits, but found 2");
+
+        // Bob may hold the anonymous permit, but nothing shows it.
+        let err = check(&[
+            "--expect-permits",
+            "12",
+            "--require-member",
+            &bob_xid.ur_string(),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "expected 12 permits, but found 2; expected a permit for member {bob_xid}, but found permits for {alice_xid} (1 unannotated permit cannot satisfy --require-member)"
+            )
+        );
+
+        assert!(
+            try_parse::<CommandArgs>(&[
+                "clubs",
+                "--edition",
+                "-",
+                "--publisher",
+                &publisher,
+                "--expect-permits",
+                "=>2",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn pins_publisher_keys_on_first_use() {
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-verify-trust-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let store = dir.join("trust.json");
+        let store_arg = store.display().to_string();
+        let club = Club::new();
+        let editions = club.chain(2);
+        let publisher = io::xid_document_ur(&club.doc, false).unwrap();
+        let verify = |extra: &[&str]| {
+            let mut argv = vec!["clubs", "--trust-store", &store_arg];
+            argv.extend(extra);
+            exec(parse::<CommandArgs>(&argv))
+        };
+
+        // Nothing is pinned yet, so a publisher must be supplied.
+        assert!(verify(&["--edition", &editions[0]]).is_err());
+        verify(&[
+            "--edition",
+            &editions[0],
+            "--publisher",
+            &publisher,
+            "--trust",
+        ])
+        .unwrap();
+        let trust = TrustStore::open(Some(&store)).unwrap();
+        let entries = trust.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, club.doc.xid());
+        verify(&["--edition", &editions[1]]).unwrap();
+
+        // An impostor re-signs an edition of the pinned club.
+        let impostor = PrivateKeyBase::new();
+        let forged = Envelope::from_ur_string(&editions[1])
+            .unwrap()
+            .try_unwrap()
+            .unwrap()
+            .wrap()
+            .add_signature(&impostor)
+            .ur_string();
+        let impostor_keys = impostor.public_keys().ur_string();
+        let err =
+            verify(&["--edition", &forged, "--publisher", &impostor_keys])
+                .unwrap_err();
+        assert!(err.is::<VerificationFailed>());
+        assert!(err.to_string().contains("CONFLICT"));
+        let err = verify(&["--edition", &forged]).unwrap_err();
+        assert_eq!(kind(&err), "signature");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn edition_dir_uses_the_keys_pinned_for_each_club() {
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-verify-pinned-dir-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let editions = dir.join("editions");
+        fs::create_dir_all(&editions).unwrap();
+        let store_arg = dir.join("trust.json").display().to_string();
+        let editions_arg = editions.display().to_string();
+        let verify = |extra: &[&str]| {
+            let mut argv =
+                vec!["clubs", "--trust-store", &store_arg, "--quiet"];
+            argv.extend(extra);
+            exec(parse::<CommandArgs>(&argv))
+        };
+
+        for (name, club) in [("a.ur", Club::new()), ("b.ur", Club::new())] {
+            let edition = club.compose(&marks(1)[0]);
+            let publisher = io::xid_document_ur(&club.doc, false).unwrap();
+            verify(&[
+                "--edition",
+                &edition,
+                "--publisher",
+                &publisher,
+                "--trust",
+            ])
+            .unwrap();
+            fs::write(editions.join(name), edition).unwrap();
+        }
+        verify(&["--edition-dir", &editions_arg]).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resolves_each_club_publisher_from_a_directory() {
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-verify-publishers-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let (first, second, stranger) = (Club::new(), Club::new(), Club::new());
+        let first_hex = hex::encode(first.doc.xid().data());
+        let second_hex = hex::encode(second.doc.xid().data());
+        let write = |name: &str, club: &Club| {
+            let ur = io::xid_document_ur(&club.doc, false).unwrap();
+            fs::write(dir.join(name), ur).unwrap();
+        };
+        write(&format!("{first_hex}.ur"), &first);
+        write(&format!("{}.ur", &second_hex[..8]), &second);
+        let dir_arg = dir.display().to_string();
+        let store_arg = dir.join("trust.json").display().to_string();
+        let verify = |edition: &str| {
+            let argv = [
+                "clubs",
+                "--edition",
+                edition,
+                "--publisher-dir",
+                &dir_arg,
+                "--trust-store",
+                &store_arg,
+                "--quiet",
+            ];
+            exec(parse::<CommandArgs>(&argv))
+        };
+
+        for club in [&first, &second] {
+            verify(&club.compose(&marks(1)[0])).unwrap();
+        }
+
+        // A club with no document in the directory names both.
+        let message = format!(
+            "{:#}",
+            verify(&stranger.compose(&marks(1)[0])).unwrap_err()
+        );
+        assert!(message.contains(&stranger.doc.xid().to_string()));
+        assert!(message.contains(&dir_arg));
+
+        // Two prefixes of the second club's XID leave its document ambiguous.
+        write(&format!("{}.ur", &second_hex[..4]), &second);
+        let message =
+            format!("{:#}", verify(&second.compose(&marks(1)[0])).unwrap_err());
+        assert!(message.contains("ambiguous"));
+        assert!(message.contains(&second.doc.xid().to_string()));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn checks_signing_dates_against_the_window_and_previous_edition() {
+        let club = Club::new();
+        let publishers = [club_publisher(&club)];
+        let editions = club.chain(2);
+        let signed_on = |edition: &str, date: &str| {
+            let metadata = SignatureMetadata::new()
+                .with_assertion(DATE, io::parse_date(date).unwrap());
+            Envelope::from_ur_string(edition)
+                .unwrap()
+                .try_unwrap()
+                .unwrap()
+                .wrap()
+                .add_signature_opt(&club.keys, None, Some(metadata))
+        };
+        let checks = |extra: &[&str]| {
+            let mut argv = vec!["clubs", "--edition", "-"];
+            argv.extend(extra);
+            let args = parse::<CommandArgs>(&argv);
+            (EditionChecks::new(&args).unwrap(), args)
+        };
+        let expectation = |result: Result<String>| {
+            let err = result.unwrap_err();
+            assert_eq!(kind(&err), "policy");
+            err.to_string()
+        };
+
+        let january = signed_on(&editions[0], "2025-01-15T00:00:00Z");
+        let (window, _) = checks(&[
+            "--not-before",
+            "2025-01-01T00:00:00Z",
+            "--not-after",
+            "2025-02-01T00:00:00Z",
+        ]);
+        assert!(
+            window
+                .check_window(&january)
+                .unwrap()
+                .starts_with("signed ")
+        );
+        let (late, _) = checks(&["--not-after", "2025-01-10T00:00:00Z"]);
+        assert!(
+            expectation(late.check_window(&january))
+                .contains("after --not-after")
+        );
+        let (early, _) = checks(&["--not-before", "2025-02-01T00:00:00Z"]);
+        assert!(
+            expectation(early.check_window(&january))
+                .contains("before --not-before")
+        );
+
+        let undated = Envelope::from_ur_string(&editions[1]).unwrap();
+        assert_eq!(window.check_window(&undated).unwrap(), "no signing date");
+        let (strict, _) =
+            checks(&["--not-before", "2025-01-01T00:00:00Z", "--require-date"]);
+        expectation(strict.check_window(&undated));
+
+        let previous =
+            signed_on(&editions[0], "2025-03-01T00:00:00Z").ur_string();
+        let after_previous = |edition: &Envelope, extra: &[&str]| {
+            let (edition_checks, args) = checks(extra);
+            let (verified, _) = verify_signed(edition, &publishers).unwrap();
+            check_previous(
+                &previous,
+                edition,
+                &verified,
+                &publishers,
+                &args,
+                &edition_checks,
+            )
+            .map(|_| String::new())
+        };
+        after_previous(&signed_on(&editions[1], "2025-04-01T00:00:00Z"), &[])
+            .unwrap();
+        let regressed = signed_on(&editions[1], "2025-02-01T00:00:00Z");
+        assert!(
+            expectation(after_previous(&regressed, &[]))
+                .contains("before the previous edition's signing date")
+        );
+        after_previous(&regressed, &["--allow-date-regression"]).unwrap();
+        after_previous(&undated, &[]).unwrap();
+        expectation(after_previous(&undated, &["--require-date"]));
+    }
+
+    #[test]
+    fn summary_lists_each_check_and_quiet_keeps_the_exit_status() {
+        let club = Club::new();
+        let editions = club.chain(2);
+        let publisher = io::xid_document_ur(&club.doc, false).unwrap();
+        let parse = |edition: &str, extra: &[&str]| {
+            let mut argv =
+                vec!["clubs", "--edition", edition, "--publisher", &publisher];
+            argv.extend(extra);
+            parse::<CommandArgs>(&argv)
+        };
+        let summary = |extra: &[&str]| {
+            let args = parse(&editions[1], extra);
+            let checks = EditionChecks::new(&args).unwrap();
+            let mut report = VerifyReport::new();
+            verify_edition(
+                &args,
+                &checks,
+                &editions[1],
+                &[club_publisher(&club)],
+                &mut report,
+            )
+            .unwrap();
+            report.summary_lines()
+        };
+        let xid = club.doc.xid();
+        let expected = |previous: &str| {
+            vec![
+                "parse: OK".to_string(),
+                format!("signature: OK (verified by XID {xid})"),
+                "threshold: SKIPPED (--threshold not given)".to_string(),
+                "permits: OK (1 well-formed)".to_string(),
+                "edition: OK".to_string(),
+                format!("club-xid: OK ({xid})"),
+                "digest-binding: SKIPPED (not a genesis edition and --check-digest-binding not given)".to_string(),
+                "encrypted: SKIPPED (--require-encrypted not given)".to_string(),
+                "membership: SKIPPED (--expect-permits and --require-member not given)".to_string(),
+                "date-window: SKIPPED (--not-before and --not-after not given)".to_string(),
+                "content: SKIPPED (--deep not given)".to_string(),
+                previous.to_string(),
+            ]
+        };
+
+        assert_eq!(
+            summary(&["--summary"]),
+            expected("previous: SKIPPED (--previous not given)")
+        );
+        assert_eq!(
+            summary(&["--summary", "--previous", &editions[0]]),
+            expected("previous: OK (linkage: cryptographic)")
+        );
+
+        let err = exec(parse(&tamper(&editions[1]), &["--quiet"])).unwrap_err();
+        assert!(err.is::<Silenced>());
+        assert_eq!(kind(&err), "signature");
+        assert!(
+            try_parse::<CommandArgs>(&[
+                "clubs",
+                "--edition",
+                "-",
+                "--quiet",
+                "--summary"
+            ])
+            .is_err()
+        );
+    }
+}
@@ -1,49 +1,897 @@
-use anyhow::{Result, anyhow, bail};
-use bc_components::{Digest, DigestProvider};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use bc_components::{Digest, PrivateKeyBase};
+use bc_envelope::prelude::*;
+use bc_ur::UREncodable;
+use bc_xid::{XIDDocument, XIDGenesisMarkOptions, XIDInceptionKeyOptions};
 use clap::Args;
+use provenance_mark::{
+    ProvenanceMark, ProvenanceMarkGenerator, ProvenanceMarkResolution,
+    ProvenanceSeed,
+};
+use serde::Serialize;
 
-use super::edition;
-use crate::io;
+use super::edition::{self, compose::Composed};
+use crate::{
+    io,
+    output::{self, OutputFormat},
+    workspace::{
+        CLUB_FILE, ClubConfig, PRIVATE_PUBLISHER_FILE, PROVENANCE_STATE_FILE,
+        PUBLIC_PUBLISHER_FILE, SHARES_DIR, Workspace, edition_stem,
+    },
+};
 
-/// Create the genesis edition for a single-publisher club.
+/// Create the genesis edition for a single-publisher club. With `--out-dir`,
+/// the results are written as a club workspace that later commands can be
+/// pointed at with `--club`. Without it, the state of a provenance chain
+/// init mints is written to `--provenance-state FILE`.
 #[derive(Debug, Args)]
+#[group(id = "init")]
 pub struct CommandArgs {
+    /// Generate a fresh publisher XID document and provenance chain instead
+    /// of supplying `--publisher` and `--provenance`. Requires `--out-dir`,
+    /// where the private keys and generator state are kept.
+    #[arg(long)]
+    pub generate_keys: bool,
+    /// Human-readable club name recorded on the generated publisher XID
+    /// document.
+    #[arg(long, value_name = "TEXT", requires = "generate_keys")]
+    pub name: Option<String>,
+    /// Club description recorded alongside `--name`.
+    #[arg(long, value_name = "TEXT", requires = "name")]
+    pub description: Option<String>,
+    /// Seed (`ur:seed` or hex) that deterministically derives the provenance
+    /// chain minted when `--provenance` is omitted and, with
+    /// `--generate-keys`, the publisher keys and hence the club XID. Fresh
+    /// randomness is used otherwise.
+    #[arg(long, value_name = "SEED")]
+    pub seed: Option<String>,
+    /// Resolution of the genesis provenance mark minted when `--provenance`
+    /// is omitted.
+    #[arg(long, value_enum, default_value_t = edition::MarkResolution::Quartile)]
+    pub resolution: edition::MarkResolution,
+    /// Initialize the workspace even if `--out-dir` is not empty, or
+    /// overwrite an existing `--provenance-state` file.
+    #[arg(long)]
+    pub force: bool,
+    /// Report format. `json` prints one object on stdout with the club XID,
+    /// each artifact's path or UR keyed by role, and next steps, instead of
+    /// emitting the bare URs.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
     #[command(flatten)]
     pub compose: edition::compose::CommandArgs,
 }
 
 pub fn exec(args: CommandArgs) -> Result<()> {
-    if args.compose.previous.is_some() {
+    let mut compose = edition::manifest::apply(args.compose)?;
+    if compose.previous.is_some() {
         bail!("genesis editions cannot specify a previous edition");
     }
 
-    let content_env =
-        io::parse_envelope(&args.compose.content).map_err(|err| {
-            anyhow!("failed to load edition content envelope: {err}")
-        })?;
+    // `--out-dir` names the workspace, which init lays out itself.
+    let workspace_dir = compose.out_dir.take();
+    if let Some(dir) = workspace_dir.as_ref() {
+        Workspace::ensure_vacant(dir, args.force)?;
+    }
+
+    let seed = match args.seed.as_ref() {
+        Some(spec) => {
+            Some(io::parse_seed(spec).context("failed to parse --seed")?)
+        }
+        None => None,
+    };
+
+    let generated = if args.generate_keys {
+        if workspace_dir.is_none() {
+            bail!(
+                "--generate-keys needs --out-dir to store the new private keys"
+            );
+        }
+        let profile = args.name.clone().map(|name| io::ClubProfile {
+            name,
+            description: args.description.clone(),
+        });
+        Some(generate_club(
+            &mut compose,
+            profile.as_ref(),
+            seed.as_deref(),
+        )?)
+    } else {
+        None
+    };
+
+    // Init reads no generator state: `--provenance-state` names the file a
+    // chain it mints is saved to when there is no workspace to hold it.
+    let state_file = compose.provenance_state.take();
+    if state_file.is_some()
+        && (workspace_dir.is_some() || compose.provenance.is_some())
+    {
+        bail!(
+            "--provenance-state names where init saves the chain it mints without --out-dir; omit it with --out-dir or --provenance"
+        );
+    }
+    let state = match compose.provenance.as_ref() {
+        Some(_) => None,
+        None => {
+            if workspace_dir.is_none()
+                && state_file.is_none()
+                && !compose.dry_run
+            {
+                bail!(
+                    "init mints a new provenance chain whose state is secret; pass --out-dir or --provenance-state FILE to save it"
+                );
+            }
+            if let Some(path) = state_file.as_ref()
+                && path.exists()
+                && !args.force
+            {
+                bail!(
+                    "'{}' already exists; pass --force to overwrite it",
+                    path.display()
+                );
+            }
+            let (mark, state) =
+                mint_genesis(&compose, seed.as_deref(), args.resolution)?;
+            compose.provenance = Some(mark);
+            Some(state)
+        }
+    };
+    if state.is_none() && seed.is_some() && !args.generate_keys {
+        bail!(
+            "--seed applies only when init mints the genesis mark or generates keys"
+        );
+    }
 
-    let provenance = io::parse_provenance_mark(&args.compose.provenance)
+    let provenance_spec = compose
+        .provenance
+        .as_deref()
+        .expect("provenance mark is supplied or minted");
+    let provenance = io::parse_provenance_mark(provenance_spec)
         .map_err(|err| anyhow!("failed to parse provenance mark: {err}"))?;
     if !provenance.is_genesis() {
         bail!("genesis editions must use a genesis provenance mark");
     }
 
-    let info_cbor = provenance.info().ok_or_else(|| {
-        anyhow!(
-            "provenance mark info field must contain the content digest for genesis editions"
+    // The genesis mark's info field must commit to the content digest.
+    (compose.bind_digest, compose.no_bind_digest) = (true, false);
+    (compose.genesis, compose.no_genesis) = (true, false);
+    compose.quiet = args.format == OutputFormat::Json;
+    let publisher_spec = compose.publisher.clone();
+    let passphrase = compose.passphrase.clone();
+    let dry_run = compose.dry_run;
+    let composed = edition::compose::run(compose)?;
+
+    // Lock private artifacts before the workspace is created, so a failed
+    // passphrase entry leaves no partial workspace behind.
+    let secret = if dry_run {
+        None
+    } else {
+        passphrase.for_writing()?
+    };
+    let state = match secret.as_deref() {
+        Some(secret) => {
+            state.map(|state| io::lock_ur(&state, secret)).transpose()?
+        }
+        None => state,
+    };
+
+    if dry_run {
+        return Ok(());
+    }
+    let Some(dir) = workspace_dir else {
+        if let (Some(path), Some(state)) = (state_file.as_ref(), state.as_ref())
+        {
+            output::write_secret(path, format!("{state}\n").as_bytes())
+                .context("failed to write --provenance-state")?;
+        }
+        return Quickstart::streamed(
+            &composed,
+            &provenance,
+            state.as_ref().and(state_file.as_deref()),
+            publisher_spec.as_deref(),
         )
-    })?;
-    let info_digest = Digest::try_from(info_cbor).map_err(|err| {
-        anyhow!("provenance mark info is not a digest: {err}")
-    })?;
-    let content_digest = content_env.digest();
-    if info_digest != content_digest {
-        bail!(
-            "provenance mark info digest {} does not match content digest {}",
-            info_digest.hex(),
-            content_digest.hex()
+        .emit(args.format);
+    };
+    let (mut private_doc, public_doc) = match generated {
+        Some(club) => (club.private_doc, club.public_doc),
+        None => {
+            let spec = publisher_spec
+                .ok_or_else(|| anyhow!("--publisher is required"))?;
+            let private_doc = io::load_from_spec(&spec)?;
+            let doc = io::parse_xid_document(&private_doc, &passphrase)
+                .context("failed to load publisher XID document")?;
+            (private_doc, io::xid_document_ur(&doc, false)?)
+        }
+    };
+
+    let config = ClubConfig::new(
+        composed.club_xid,
+        Date::now().to_string(),
+        state.is_some(),
+    );
+    if let Some(secret) = secret.as_deref()
+        && !io::is_locked_ur(&private_doc)
+    {
+        private_doc = io::lock_ur(&private_doc, secret)?;
+    }
+
+    let workspace = Workspace::create(&dir, config)?;
+    workspace.write_ur(PRIVATE_PUBLISHER_FILE, &private_doc, true)?;
+    workspace.write_ur(PUBLIC_PUBLISHER_FILE, &public_doc, false)?;
+    if let Some(state) = state.as_ref() {
+        workspace.write_ur(PROVENANCE_STATE_FILE, state, true)?;
+    }
+    let edition_path = workspace.record_edition(&composed)?;
+
+    Quickstart::workspace(
+        &workspace,
+        &composed,
+        &provenance,
+        &edition_path,
+        state.is_some(),
+    )
+    .emit(args.format)
+}
+
+/// Placeholder for the next edition's content in suggested commands.
+const NEXT_CONTENT: &str = "@<next-content.ur>";
+
+/// What init produced and how to continue: the closing summary, or the
+/// `--format json` report.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct Quickstart {
+    club_xid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workspace: Option<PathBuf>,
+    genesis_mark: String,
+    /// Artifacts keyed by role.
+    artifacts: BTreeMap<String, Artifact>,
+    next_steps: Vec<String>,
+}
+
+/// One artifact init produced: a file it wrote, or a UR it emitted.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+struct Artifact {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ur: Option<String>,
+    /// Whether the artifact holds private keys or seeds.
+    secret: bool,
+}
+
+impl Artifact {
+    fn file(path: PathBuf, secret: bool) -> Self {
+        Self { path: Some(path), ur: None, secret }
+    }
+
+    fn inline(ur: &str, secret: bool) -> Self {
+        Self { path: None, ur: Some(ur.to_owned()), secret }
+    }
+}
+
+impl Quickstart {
+    fn new(composed: &Composed, mark: &ProvenanceMark) -> Self {
+        Self {
+            club_xid: composed.club_xid.to_string(),
+            workspace: None,
+            genesis_mark: format!(
+                "{} resolution, dated {}",
+                edition::resolution_label(mark.res()),
+                mark.date()
+            ),
+            artifacts: BTreeMap::new(),
+            next_steps: Vec::new(),
+        }
+    }
+
+    /// Summary for a run that wrote a club workspace.
+    fn workspace(
+        workspace: &Workspace,
+        composed: &Composed,
+        mark: &ProvenanceMark,
+        edition_path: &Path,
+        has_state: bool,
+    ) -> Self {
+        let mut quickstart = Self::new(composed, mark);
+        let root = workspace.root();
+        quickstart.workspace = Some(root.to_path_buf());
+        let mut add = |role: &str, artifact| {
+            quickstart.artifacts.insert(role.to_owned(), artifact);
+        };
+        add(
+            "club_config",
+            Artifact::file(workspace.path(CLUB_FILE), false),
+        );
+        add("edition", Artifact::file(edition_path.to_path_buf(), false));
+        add(
+            "publisher_private",
+            Artifact::file(workspace.path(PRIVATE_PUBLISHER_FILE), true),
+        );
+        add(
+            "publisher_public",
+            Artifact::file(workspace.path(PUBLIC_PUBLISHER_FILE), false),
+        );
+        if has_state {
+            add(
+                "provenance_state",
+                Artifact::file(workspace.path(PROVENANCE_STATE_FILE), true),
+            );
+        }
+        if !composed.shares.is_empty() {
+            add(
+                "sskr_shares",
+                Artifact::file(
+                    workspace.path(SHARES_DIR).join(edition_stem(composed.seq)),
+                    true,
+                ),
+            );
+        }
+
+        let mark_source = if has_state {
+            format!(
+                "--provenance-state {}",
+                workspace.path(PROVENANCE_STATE_FILE).display()
+            )
+        } else {
+            "--provenance <next mark UR>".to_string()
+        };
+        quickstart.next_steps = vec![
+            format!(
+                "To publish the next edition: clubs edition compose --club {} --content {NEXT_CONTENT}",
+                root.display()
+            ),
+            format!(
+                "Without the workspace: clubs edition compose --publisher @{} {mark_source} --previous @{} --content {NEXT_CONTENT}",
+                workspace.path(PRIVATE_PUBLISHER_FILE).display(),
+                edition_path.display()
+            ),
+            format!(
+                "To let members verify editions, share {}",
+                workspace.path(PUBLIC_PUBLISHER_FILE).display()
+            ),
+        ];
+        quickstart
+    }
+
+    /// Summary for a run that emitted its URs instead of writing a
+    /// workspace. The minted chain's state, being secret, went to
+    /// `state_path` rather than stdout.
+    fn streamed(
+        composed: &Composed,
+        mark: &ProvenanceMark,
+        state_path: Option<&Path>,
+        publisher_spec: Option<&str>,
+    ) -> Self {
+        let mut quickstart = Self::new(composed, mark);
+        quickstart.artifacts.insert(
+            "edition".to_owned(),
+            Artifact::inline(&composed.edition_ur, false),
+        );
+        if let Some(path) = state_path {
+            quickstart.artifacts.insert(
+                "provenance_state".to_owned(),
+                Artifact::file(path.to_path_buf(), true),
+            );
+        }
+        for (name, ur) in &composed.shares {
+            quickstart
+                .artifacts
+                .insert(format!("sskr_{name}"), Artifact::inline(ur, true));
+        }
+
+        let publisher = publisher_spec
+            .filter(|spec| spec.starts_with('@'))
+            .unwrap_or("@<publisher-private.ur>");
+        let mark_source = match state_path {
+            Some(path) => format!("--provenance-state {}", path.display()),
+            None => "--provenance <next mark UR>".to_string(),
+        };
+        quickstart.next_steps = vec![
+            "Save the edition UR as genesis.ur".to_string(),
+            format!(
+                "To publish the next edition: clubs edition compose --publisher {publisher} {mark_source} --previous @genesis.ur --content {NEXT_CONTENT}"
+            ),
+        ];
+        quickstart
+    }
+
+    fn emit(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => output::print_json(self),
+            OutputFormat::Text => {
+                self.print_text();
+                Ok(())
+            }
+        }
+    }
+
+    fn print_text(&self) {
+        eprintln!("Club XID: {}", self.club_xid);
+        if let Some(dir) = self.workspace.as_ref() {
+            eprintln!("Club workspace: {}", dir.display());
+        }
+        eprintln!("Genesis mark: {}", self.genesis_mark);
+        eprintln!("Artifacts:");
+        for (role, artifact) in &self.artifacts {
+            let location = match artifact.path.as_ref() {
+                Some(path) => path.display().to_string(),
+                None => "printed on stdout".to_string(),
+            };
+            let secret = if artifact.secret {
+                " (SECRET: keep private)"
+            } else {
+                ""
+            };
+            eprintln!("  {role}: {location}{secret}");
+        }
+        eprintln!("Next steps:");
+        for step in &self.next_steps {
+            eprintln!("  {step}");
+        }
+    }
+}
+
+/// Key material minted by `--generate-keys`.
+struct GeneratedClub {
+    private_doc: String,
+    public_doc: String,
+}
+
+/// Domain separator mixed into `--seed` when deriving the publisher keys, so
+/// they are independent of the provenance chain derived from the same seed.
+const PUBLISHER_SEED_CONTEXT: &[u8] = b"clubs publisher";
+
+/// Mint a publisher XID document, optionally carrying the club profile, and
+/// point `compose` at it. With a seed the keys, and so the XID, are
+/// reproducible.
+fn generate_club(
+    compose: &mut edition::compose::CommandArgs,
+    profile: Option<&io::ClubProfile>,
+    seed: Option<&[u8]>,
+) -> Result<GeneratedClub> {
+    if compose.publisher.is_some() {
+        bail!("--generate-keys creates the publisher; omit --publisher");
+    }
+    let key_base = match seed {
+        Some(seed) => PrivateKeyBase::from_data(
+            Digest::from_image_parts(&[seed, PUBLISHER_SEED_CONTEXT]).data(),
+        ),
+        None => PrivateKeyBase::new(),
+    };
+    let mut doc = XIDDocument::new(
+        XIDInceptionKeyOptions::PrivateKeyBase(key_base),
+        XIDGenesisMarkOptions::None,
+    );
+    if let Some(profile) = profile {
+        profile.attach_to(&mut doc);
+    }
+    let private_doc = io::xid_document_ur(&doc, true)?;
+    let public_doc = io::xid_document_ur(&doc, false)?;
+    compose.publisher = Some(private_doc.clone());
+    Ok(GeneratedClub { private_doc, public_doc })
+}
+
+/// Start a provenance chain and mint its genesis mark with the content
+/// digest as info. Returns the mark UR and the generator state UR.
+fn mint_genesis(
+    compose: &edition::compose::CommandArgs,
+    seed: Option<&[u8]>,
+    resolution: edition::MarkResolution,
+) -> Result<(String, String)> {
+    let content_spec = compose
+        .content
+        .as_deref()
+        .ok_or_else(|| anyhow!("--content is required"))?;
+    let content = io::parse_envelope(content_spec)
+        .context("failed to load edition content envelope")?;
+
+    let date = edition::parse_mark_date(compose.date.as_deref())
+        .context("failed to parse --date")?;
+    let resolution = ProvenanceMarkResolution::from(resolution);
+    let mut generator = match seed {
+        Some(seed) => ProvenanceMarkGenerator::new_with_seed(
+            resolution,
+            ProvenanceSeed::from_bytes(*Digest::from_image(seed).data()),
+        ),
+        None => ProvenanceMarkGenerator::new_random(resolution),
+    };
+    let mark = edition::mint_bound_mark(&mut generator, &content, date)?;
+    Ok((mark.ur_string(), io::provenance_state_ur(&generator)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_components::{XID, XIDProvider};
+
+    use super::*;
+    use crate::test_support::{parse, register_tags};
+
+    #[test]
+    fn generated_publisher_carries_the_club_profile() {
+        register_tags();
+        let content = Envelope::new("genesis issue").ur_string();
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        let mark = generator.next(Date::now(), None::<Envelope>).ur_string();
+        let mut compose = parse::<edition::compose::CommandArgs>(&[
+            "clubs",
+            "--content",
+            &content,
+            "--provenance",
+            &mark,
+            "--genesis",
+        ]);
+        let profile = io::ClubProfile {
+            name: "Gordian Reading Club".into(),
+            description: Some("Monthly notes on self-sovereign tools".into()),
+        };
+        let club = generate_club(&mut compose, Some(&profile), None).unwrap();
+
+        let passphrase = io::PassphraseArgs::default();
+        let public =
+            io::parse_xid_document(&club.public_doc, &passphrase).unwrap();
+        assert_eq!(
+            io::ClubProfile::from_document(&public).unwrap(),
+            Some(profile)
         );
+        let private =
+            io::parse_xid_document(&club.private_doc, &passphrase).unwrap();
+        assert_eq!(public.xid(), private.xid());
+
+        let composed = edition::compose::run(compose).unwrap();
+        assert_eq!(composed.club_xid, public.xid());
+        let verifier =
+            io::parse_recipient_descriptor(&club.public_doc).unwrap();
+        io::parse_envelope(&composed.edition_ur)
+            .unwrap()
+            .verify(verifier.public_keys())
+            .unwrap();
     }
 
-    edition::compose::exec(args.compose)
+    /// Club XID and genesis chain id bootstrapped from `seed` and `content`.
+    fn bootstrap(seed: &[u8], content: &str) -> (XID, Vec<u8>) {
+        let content = Envelope::new(content).ur_string();
+        let mut compose = parse::<edition::compose::CommandArgs>(&[
+            "clubs",
+            "--content",
+            &content,
+        ]);
+        let club = generate_club(&mut compose, None, Some(seed)).unwrap();
+        let (mark, _) = mint_genesis(
+            &compose,
+            Some(seed),
+            edition::MarkResolution::Quartile,
+        )
+        .unwrap();
+        let doc = io::parse_xid_document(
+            &club.public_doc,
+            &io::PassphraseArgs::default(),
+        )
+        .unwrap();
+        let mark = io::parse_provenance_mark(&mark).unwrap();
+        (doc.xid(), mark.chain_id().to_vec())
+    }
+
+    #[test]
+    fn seed_reproduces_the_club_identity_and_chain() {
+        register_tags();
+        let seed = [7u8; 16];
+        let (xid, chain) = bootstrap(&seed, "issue one");
+        let (again_xid, again_chain) = bootstrap(&seed, "issue one, revised");
+        assert_eq!(xid, again_xid);
+        assert_eq!(chain, again_chain);
+
+        let (other_xid, other_chain) = bootstrap(&[8u8; 16], "issue one");
+        assert_ne!(xid, other_xid);
+        assert_ne!(chain, other_chain);
+    }
+
+    #[test]
+    fn genesis_marks_follow_the_requested_resolution_and_date() {
+        register_tags();
+        let content = Envelope::new("issue one").ur_string();
+        for (resolution, label) in [
+            (edition::MarkResolution::Low, "low resolution"),
+            (edition::MarkResolution::High, "high resolution"),
+        ] {
+            let compose = parse::<edition::compose::CommandArgs>(&[
+                "clubs",
+                "--content",
+                &content,
+                "--date",
+                "2025-06-01T00:00:00Z",
+            ]);
+            let (mark, _) = mint_genesis(&compose, None, resolution).unwrap();
+            let mark = io::parse_provenance_mark(&mark).unwrap();
+            assert_eq!(mark.res(), resolution.into());
+            let summary = edition::inspect::provenance_summary(&mark);
+            assert!(summary.starts_with("seq 0 "), "{summary}");
+            assert!(summary.contains(label), "{summary}");
+            assert!(summary.contains("2025-06-01"), "{summary}");
+        }
+    }
+
+    #[test]
+    fn json_quickstart_reports_the_club_the_edition_references() {
+        register_tags();
+        let content = Envelope::new("issue one").ur_string();
+        let mut compose = parse::<edition::compose::CommandArgs>(&[
+            "clubs",
+            "--content",
+            &content,
+            "--genesis",
+            "--bind-digest",
+        ]);
+        compose.quiet = true;
+        let club = generate_club(&mut compose, None, None).unwrap();
+        let (mark, state) =
+            mint_genesis(&compose, None, edition::MarkResolution::Quartile)
+                .unwrap();
+        compose.provenance = Some(mark.clone());
+        let composed = edition::compose::run(compose).unwrap();
+        let mark = io::parse_provenance_mark(&mark).unwrap();
+
+        let quickstart = Quickstart::streamed(
+            &composed,
+            &mark,
+            Some(Path::new("provenance-state.ur")),
+            Some("@publisher-private.ur"),
+        );
+        let json = serde_json::to_value(&quickstart).unwrap();
+        let edition = edition::decode_edition(
+            &io::parse_envelope(&composed.edition_ur)
+                .unwrap()
+                .try_unwrap()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(json["club_xid"], edition.club_xid.to_string());
+        assert_eq!(json["artifacts"]["edition"]["ur"], composed.edition_ur);
+        let state_artifact = &json["artifacts"]["provenance_state"];
+        assert_eq!(state_artifact["secret"], true);
+        assert_eq!(state_artifact["path"], "provenance-state.ur");
+        assert!(state_artifact.get("ur").is_none());
+        assert!(!json.to_string().contains(&state));
+        let publisher = io::parse_xid_document(
+            &club.public_doc,
+            &io::PassphraseArgs::default(),
+        )
+        .unwrap();
+        assert_eq!(edition.club_xid, publisher.xid());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn generated_secrets_are_owner_only_even_over_existing_files() {
+        register_tags();
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-init-secrets-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        // World-readable leftovers: the files themselves, and the
+        // temporary siblings the atomic writer reuses.
+        let world_readable = fs::Permissions::from_mode(0o644);
+        for name in [PRIVATE_PUBLISHER_FILE, PROVENANCE_STATE_FILE] {
+            for file in [name.to_string(), format!(".{name}.tmp")] {
+                let path = dir.join(file);
+                fs::write(&path, "stale\n").unwrap();
+                fs::set_permissions(&path, world_readable.clone()).unwrap();
+            }
+        }
+
+        let content = Envelope::new("issue one").ur_string();
+        exec(parse::<CommandArgs>(&[
+            "clubs",
+            "--generate-keys",
+            "--force",
+            "--out-dir",
+            dir.to_str().unwrap(),
+            "--content",
+            &content,
+        ]))
+        .unwrap();
+
+        for name in [PRIVATE_PUBLISHER_FILE, PROVENANCE_STATE_FILE] {
+            let metadata = fs::metadata(dir.join(name)).unwrap();
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600, "{name}");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-init-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn minted_genesis_binds_the_content_and_chains_into_a_strict_sequence() {
+        register_tags();
+        use crate::workspace::EDITIONS_DIR;
+
+        let dir = scratch_dir("minted-chain");
+        let workspace = dir.to_str().unwrap();
+        let content = Envelope::new("issue one");
+        exec(parse::<CommandArgs>(&[
+            "clubs",
+            "--generate-keys",
+            "--out-dir",
+            workspace,
+            "--content",
+            &content.ur_string(),
+        ]))
+        .unwrap();
+
+        let editions = dir.join(EDITIONS_DIR);
+        let genesis = io::parse_envelope(&format!(
+            "@{}",
+            editions.join("0001-genesis.ur").display()
+        ))
+        .unwrap();
+        let genesis =
+            edition::decode_edition(&genesis.try_unwrap().unwrap()).unwrap();
+        assert!(genesis.provenance.is_genesis());
+        assert_eq!(
+            edition::DigestBinding::check(&genesis.provenance, &content),
+            edition::DigestBinding::Match
+        );
+
+        // The next edition mints its mark from the state init saved.
+        let mut next = parse::<edition::compose::CommandArgs>(&[
+            "clubs",
+            "--club",
+            workspace,
+            "--content",
+            &Envelope::new("issue two").ur_string(),
+        ]);
+        next.quiet = true;
+        edition::compose::exec(next).unwrap();
+        assert!(editions.join("0002.ur").exists());
+
+        let public = format!("@{}", dir.join(PUBLIC_PUBLISHER_FILE).display());
+        edition::sequence::exec(parse::<edition::sequence::CommandArgs>(&[
+            "clubs",
+            "--edition-dir",
+            editions.to_str().unwrap(),
+            "--publisher",
+            &public,
+            "--strict",
+        ]))
+        .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn permit_encrypted_genesis_passes_edition_verify() {
+        register_tags();
+        let dir = scratch_dir("permit-genesis");
+        let workspace = dir.to_str().unwrap();
+        let member = crate::test_support::xid_document();
+        exec(parse::<CommandArgs>(&[
+            "clubs",
+            "--generate-keys",
+            "--out-dir",
+            workspace,
+            "--content",
+            &Envelope::new("issue one").ur_string(),
+            "--permit",
+            &crate::test_support::public_ur(&member),
+        ]))
+        .unwrap();
+
+        let genesis = dir
+            .join(crate::workspace::EDITIONS_DIR)
+            .join("0001-genesis.ur");
+        let edition = format!("@{}", genesis.display());
+        let sealed =
+            io::parse_envelope(&edition).unwrap().try_unwrap().unwrap();
+        let sealed = edition::decode_edition(&sealed).unwrap();
+        assert!(sealed.content.is_encrypted());
+        let public = format!("@{}", dir.join(PUBLIC_PUBLISHER_FILE).display());
+        edition::verify::exec(parse::<edition::verify::CommandArgs>(&[
+            "clubs",
+            "--edition",
+            &edition,
+            "--publisher",
+            &public,
+            "--strict",
+            "--quiet",
+        ]))
+        .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn minted_state_goes_to_a_file_never_stdout() {
+        let dir = scratch_dir("state-file");
+        std::fs::create_dir_all(&dir).unwrap();
+        let state_path = dir.join(PROVENANCE_STATE_FILE);
+        let publisher = crate::test_support::private_ur(
+            &crate::test_support::xid_document(),
+        );
+        let content = Envelope::new("issue one").ur_string();
+        let init = |extra: &[&str]| {
+            let mut argv =
+                vec!["clubs", "--publisher", &publisher, "--content", &content];
+            argv.extend(extra);
+            exec(parse::<CommandArgs>(&argv))
+        };
+
+        let err = init(&[]).unwrap_err();
+        assert!(err.to_string().contains("--provenance-state"), "{err}");
+
+        let state = state_path.to_str().unwrap();
+        init(&["--provenance-state", state]).unwrap();
+        let (mut generator, _) = io::parse_provenance_state(
+            &format!("@{state}"),
+            &io::PassphraseArgs::default(),
+        )
+        .unwrap();
+        assert_eq!(generator.next(Date::now(), None::<String>).seq(), 1);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&state_path).unwrap().permissions();
+            assert_eq!(mode.mode() & 0o777, 0o600);
+        }
+
+        assert!(init(&["--provenance-state", state]).is_err());
+        init(&["--provenance-state", state, "--force"]).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn passphrase_locks_the_workspace_and_a_wrong_one_is_refused() {
+        register_tags();
+        use crate::workspace::EDITIONS_DIR;
+
+        let dir = scratch_dir("locked");
+        let workspace = dir.to_str().unwrap();
+        let mut args = parse::<CommandArgs>(&[
+            "clubs",
+            "--generate-keys",
+            "--out-dir",
+            workspace,
+            "--content",
+            &Envelope::new("issue one").ur_string(),
+        ]);
+        args.compose.passphrase = io::PassphraseArgs::given("correct horse");
+        exec(args).unwrap();
+        for name in [PRIVATE_PUBLISHER_FILE, PROVENANCE_STATE_FILE] {
+            let text = std::fs::read_to_string(dir.join(name)).unwrap();
+            assert!(io::is_locked_ur(text.trim()), "{name}");
+        }
+
+        let next_content = Envelope::new("issue two").ur_string();
+        let compose_next = |passphrase: &str| {
+            let mut next = parse::<edition::compose::CommandArgs>(&[
+                "clubs",
+                "--club",
+                workspace,
+                "--content",
+                &next_content,
+            ]);
+            next.quiet = true;
+            next.passphrase = io::PassphraseArgs::given(passphrase);
+            edition::compose::exec(next)
+        };
+        let err = compose_next("battery staple").unwrap_err();
+        assert!(format!("{err:#}").contains("wrong passphrase"), "{err:#}");
+        assert!(!dir.join(EDITIONS_DIR).join("0002.ur").exists());
+        compose_next("correct horse").unwrap();
+        assert!(dir.join(EDITIONS_DIR).join("0002.ur").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -2,3 +2,67 @@ pub mod content;
 pub mod edition;
 pub mod init;
 pub mod permits;
+pub mod trust;
+
+use std::{fmt, process::ExitCode};
+
+/// Exit status for a run that worked but failed a check the caller asked
+/// for, such as `edition inspect --expect-seq` or `--strict`. Status 1 is
+/// any other error (including unparseable input) and 2 is a usage error.
+pub const EXPECTATION_FAILED_EXIT: u8 = 3;
+
+/// Exit status for an edition whose signature or provenance does not verify
+/// against the keys or editions the caller supplied.
+pub const VERIFICATION_FAILED_EXIT: u8 = 4;
+
+/// An unmet `--expect-*` check, reported with [`EXPECTATION_FAILED_EXIT`].
+#[derive(Debug)]
+pub struct ExpectationFailed(pub String);
+
+impl fmt::Display for ExpectationFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ExpectationFailed {}
+
+/// A failed signature or provenance check, reported with
+/// [`VERIFICATION_FAILED_EXIT`].
+#[derive(Debug)]
+pub struct VerificationFailed(pub String);
+
+impl fmt::Display for VerificationFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for VerificationFailed {}
+
+/// Marks the error of a command run with `--quiet`: only its exit status
+/// is reported.
+#[derive(Debug)]
+pub struct Silenced;
+
+impl fmt::Display for Silenced {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("error output suppressed by --quiet")
+    }
+}
+
+impl std::error::Error for Silenced {}
+
+/// Process exit status for a command that failed with `err`.
+pub fn exit_code(err: &anyhow::Error) -> ExitCode {
+    if let Some(failure) = err.downcast_ref::<edition::verify::VerifyFailure>()
+    {
+        ExitCode::from(failure.exit_status())
+    } else if err.is::<ExpectationFailed>() {
+        ExitCode::from(EXPECTATION_FAILED_EXIT)
+    } else if err.is::<VerificationFailed>() {
+        ExitCode::from(VERIFICATION_FAILED_EXIT)
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -1,11 +1,14 @@
 use anyhow::{Context, Result, bail};
-use bc_components::{PublicKeys, XID};
-use bc_envelope::prelude::Envelope;
 use bc_ur::UREncodable;
 use clap::Args;
-use known_values::HOLDER;
 
-use crate::io;
+use crate::{
+    io,
+    output::{
+        FragmentArgs,
+        qr::{self, QrArgs},
+    },
+};
 
 /// Derive a public-key permit from recipient materials.
 #[derive(Debug, Args)]
@@ -16,9 +19,35 @@ pub struct CommandArgs {
     /// Optional label to annotate the permit holder.
     #[arg(long, value_name = "XID")]
     pub label: Option<String>,
+    /// RFC 3339 date after which decrypters stop honoring the permit; the
+    /// compose step attaches it to this recipient's permit.
+    #[arg(long, value_name = "DATE")]
+    pub permit_expiry: Option<String>,
+    #[command(flatten)]
+    pub fragments: FragmentArgs,
+    #[command(flatten)]
+    pub qr: QrArgs,
 }
 
 pub fn exec(args: CommandArgs) -> Result<()> {
+    args.qr.format()?;
+    for (index, ur) in run(&args)?.iter().enumerate() {
+        for line in args.fragments.lines(ur)? {
+            println!("{}", line);
+        }
+        qr::emit(
+            &args.qr,
+            &args.fragments,
+            &format!("permit-{}", index + 1),
+            ur,
+        )?;
+    }
+    Ok(())
+}
+
+/// The permit UR for each of `args.recipient`, in order. A permit without
+/// a holder is anonymous; its expiry still travels with it to compose.
+pub fn run(args: &CommandArgs) -> Result<Vec<String>> {
     if args.recipient.is_empty() {
         bail!("at least one --recipient value is required");
     }
@@ -31,29 +60,70 @@ pub fn exec(args: CommandArgs) -> Result<()> {
         None => None,
     };
 
-    for recipient in &args.recipient {
-        let descriptor = io::parse_recipient_descriptor(recipient)
-            .with_context(|| {
-                format!("failed to parse recipient '{recipient}'")
-            })?;
-
-        let member_xid = override_xid.or(descriptor.member_xid());
-        let public_keys = descriptor.public_keys().clone();
-        let envelope = permit_envelope(&public_keys, member_xid);
-        println!("{}", envelope.ur_string());
-    }
+    let expires = match args.permit_expiry.as_ref() {
+        Some(spec) => Some(
+            io::parse_date(spec).context("failed to parse --permit-expiry")?,
+        ),
+        None => None,
+    };
 
-    Ok(())
+    args.recipient
+        .iter()
+        .map(|recipient| {
+            let descriptor = io::parse_recipient_descriptor(recipient)
+                .with_context(|| {
+                    format!("failed to parse recipient '{recipient}'")
+                })?;
+            let envelope = io::permit_descriptor_envelope(
+                descriptor.public_keys(),
+                override_xid.or(descriptor.member_xid()),
+                expires.as_ref().or(descriptor.expires()),
+            );
+            Ok(envelope.ur_string())
+        })
+        .collect()
 }
 
-fn permit_envelope(
-    public_keys: &PublicKeys,
-    member_xid: Option<XID>,
-) -> Envelope {
-    let mut envelope =
-        Envelope::new(public_keys.clone()).add_type("PublicKeyPermit");
-    if let Some(xid) = member_xid {
-        envelope = envelope.add_assertion(HOLDER, xid);
+#[cfg(test)]
+mod tests {
+    use bc_components::{PrivateKeyBase, PublicKeysProvider};
+
+    use super::*;
+    use crate::{
+        cmd::edition::{compose, permit_expiries},
+        test_support::{
+            compose_args, genesis_mark, parse, register_tags, xid_document,
+        },
+    };
+
+    #[test]
+    fn anonymous_permit_carries_its_expiry_into_compose() {
+        register_tags();
+        let keys = PrivateKeyBase::new().public_keys().ur_string();
+        let permits = run(&parse::<CommandArgs>(&[
+            "clubs",
+            "--recipient",
+            &keys,
+            "--permit-expiry",
+            "2999-01-01",
+        ]))
+        .unwrap();
+        let expires = io::parse_date("2999-01-01").unwrap();
+        let descriptor = io::parse_recipient_descriptor(&permits[0]).unwrap();
+        assert_eq!(descriptor.member_xid(), None);
+        assert_eq!(descriptor.expires(), Some(&expires));
+
+        let composed = compose::run(compose_args(
+            &xid_document(),
+            &genesis_mark(),
+            &["--permit", &permits[0]],
+        ))
+        .unwrap();
+        let inner = io::parse_envelope(&composed.edition_ur)
+            .unwrap()
+            .try_unwrap()
+            .unwrap();
+        let expiries = permit_expiries(&inner).unwrap();
+        assert_eq!(expiries.values().collect::<Vec<_>>(), [&expires]);
     }
-    envelope
 }
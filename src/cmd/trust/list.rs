@@ -0,0 +1,30 @@
+use anyhow::Result;
+use bc_components::ReferenceProvider;
+use bc_ur::UREncodable;
+use clap::Args;
+
+use crate::trust::TrustStoreArgs;
+
+/// List pinned publisher keys, one club per line.
+#[derive(Debug, Args)]
+pub struct CommandArgs {
+    #[command(flatten)]
+    pub store: TrustStoreArgs,
+}
+
+pub fn exec(args: CommandArgs) -> Result<()> {
+    let store = args.store.open()?;
+    let entries = store.entries()?;
+    if entries.is_empty() {
+        eprintln!("No publisher keys pinned in '{}'", store.path().display());
+    }
+    for (club, keys, pinned) in entries {
+        println!(
+            "{}  keys {}  pinned {}",
+            club.ur_string(),
+            keys.reference().ref_hex(),
+            pinned.pinned
+        );
+    }
+    Ok(())
+}
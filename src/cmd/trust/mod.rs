@@ -0,0 +1,26 @@
+pub mod list;
+pub mod remove;
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+
+#[derive(Debug, Args)]
+pub struct CommandArgs {
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    /// List the clubs whose publisher keys are pinned.
+    List(list::CommandArgs),
+    /// Forget the publisher keys pinned for a club.
+    Remove(remove::CommandArgs),
+}
+
+pub fn exec(args: CommandArgs) -> Result<()> {
+    match args.command {
+        Commands::List(args) => list::exec(args),
+        Commands::Remove(args) => remove::exec(args),
+    }
+}
@@ -0,0 +1,29 @@
+use anyhow::{Context, Result, bail};
+use clap::Args;
+
+use crate::{io, trust::TrustStoreArgs};
+
+/// Forget a club's pinned publisher keys, so the next `--trust` pins anew.
+#[derive(Debug, Args)]
+pub struct CommandArgs {
+    /// Club XID (`ur:xid`, hex, or `XID(…)`).
+    #[arg(long, value_name = "XID")]
+    pub club: String,
+    #[command(flatten)]
+    pub store: TrustStoreArgs,
+}
+
+pub fn exec(args: CommandArgs) -> Result<()> {
+    let club =
+        io::parse_xid_value(&args.club).context("failed to parse club XID")?;
+    let mut store = args.store.open()?;
+    if !store.remove(&club) {
+        bail!(
+            "no publisher keys are pinned for club {club} in '{}'",
+            store.path().display()
+        );
+    }
+    store.save()?;
+    eprintln!("Removed the publisher keys pinned for club {club}");
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{Context, Result, anyhow};
+use bc_components::{Digest, XID};
+use serde::Deserialize;
+
+use crate::io;
+
+/// Member directory file: a TOML `[members]` table mapping XIDs (`ur:xid`,
+/// hex, or `XID(…)`) to display names.
+///
+/// ```toml
+/// [members]
+/// "ur:xid/hdcx…" = "Alice"
+/// ```
+#[derive(Debug, Default)]
+pub struct MemberDirectory {
+    names: HashMap<XID, String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DirectoryFile {
+    #[serde(default)]
+    members: HashMap<String, String>,
+}
+
+impl MemberDirectory {
+    /// Read a directory file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).with_context(|| {
+            format!("failed to read member directory '{}'", path.display())
+        })?;
+        Self::parse(&text).with_context(|| {
+            format!("invalid member directory '{}'", path.display())
+        })
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let file: DirectoryFile =
+            toml::from_str(text).map_err(|err| anyhow!(err))?;
+        let mut names = HashMap::with_capacity(file.members.len());
+        for (key, name) in file.members {
+            let xid = io::parse_xid_value(&key)
+                .with_context(|| format!("invalid member XID '{key}'"))?;
+            names.insert(xid, name);
+        }
+        Ok(Self { names })
+    }
+
+    /// Display name listed for `xid`.
+    pub fn name(&self, xid: &XID) -> Option<&str> {
+        self.names.get(xid).map(String::as_str)
+    }
+
+    /// How to show a permit's holder: the listed name, an unlisted member's
+    /// XID, or the sealed permit's digest when the holder is anonymous.
+    /// Without a directory, annotated holders are shown as plain members.
+    pub fn holder_label(
+        directory: Option<&Self>,
+        holder: Option<&XID>,
+        sealed_digest: &Digest,
+    ) -> String {
+        let Some(xid) = holder else {
+            return format!(
+                "anonymous permit (sealed digest {}…)",
+                short_hex(sealed_digest.data())
+            );
+        };
+        let short = short_hex(xid.data());
+        match directory {
+            Some(directory) => match directory.name(xid) {
+                Some(name) => format!("{name} <XID {short}…>"),
+                None => format!("unknown member <XID {short}…>"),
+            },
+            None => format!("member <XID {short}…>"),
+        }
+    }
+}
+
+fn short_hex(data: &[u8]) -> String { hex::encode(&data[..4]) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xid(byte: u8) -> XID { XID::from_data([byte; 32]) }
+
+    #[test]
+    fn renders_listed_unlisted_and_anonymous_holders() {
+        let alice = xid(0x7c);
+        let directory = MemberDirectory::parse(&format!(
+            "[members]\n\"{}\" = \"Alice\"\n",
+            hex::encode(alice.data())
+        ))
+        .unwrap();
+        let digest = Digest::from_image(b"sealed permit");
+
+        assert_eq!(
+            MemberDirectory::holder_label(
+                Some(&directory),
+                Some(&alice),
+                &digest
+            ),
+            "Alice <XID 7c7c7c7c…>"
+        );
+        assert_eq!(
+            MemberDirectory::holder_label(
+                Some(&directory),
+                Some(&xid(0x11)),
+                &digest
+            ),
+            "unknown member <XID 11111111…>"
+        );
+        assert_eq!(
+            MemberDirectory::holder_label(Some(&directory), None, &digest),
+            format!(
+                "anonymous permit (sealed digest {}…)",
+                hex::encode(&digest.data()[..4])
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_xids() {
+        let err = MemberDirectory::parse("[members]\n\"nonsense\" = \"Bob\"\n")
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("nonsense"), "{err:#}");
+    }
+}
@@ -0,0 +1,129 @@
+use std::borrow::Cow;
+
+use bc_envelope::prelude::*;
+use bc_ur::bytewords::{self, Style};
+
+const ENVELOPE_PREFIX: &str = "ur:envelope/";
+
+/// The single-part UR of `envelope`, as `UREncodable::ur_string` writes it.
+/// `bc-ur` collects a string slice for every byte before joining them,
+/// which costs some twenty times the envelope's size; this holds only its
+/// CBOR and the UR text.
+pub fn envelope_ur_string(envelope: &Envelope) -> String {
+    let data = envelope.untagged_cbor().to_cbor_data();
+    let checksum = crc32fast::hash(&data).to_be_bytes();
+    let mut ur = bytewords::encode_to_minimal_bytewords(&data);
+    drop(data);
+    ur.insert_str(0, ENVELOPE_PREFIX);
+    ur.push_str(&bytewords::encode_to_minimal_bytewords(&checksum));
+    ur
+}
+
+/// The length of `envelope_ur_string(envelope)`, without spelling it out.
+pub fn envelope_ur_len(envelope: &Envelope) -> usize {
+    let data = envelope.untagged_cbor().to_cbor_data().len();
+    ENVELOPE_PREFIX.len() + 2 * (data + 4)
+}
+
+/// The payload of `text` when it is a single-part UR of `ur_type`, in
+/// either case, decoded by `bc-ur`, which also checks its checksum.
+/// Anything else is `None`, left to the general decoder to accept or
+/// explain.
+pub fn decode(text: &str, ur_type: &str) -> Option<Vec<u8>> {
+    let body = single_part_body(text, ur_type)?;
+    let body = if body.bytes().any(|byte| byte.is_ascii_uppercase()) {
+        Cow::Owned(body.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(body)
+    };
+    bytewords::decode(&body, Style::Minimal).ok()
+}
+
+/// The length of the payload `decode(text, ur_type)` returns, read from
+/// the length of `text` without decoding it. `None` when `text` is not
+/// shaped like a single-part UR of `ur_type`.
+pub fn payload_len(text: &str, ur_type: &str) -> Option<usize> {
+    let body = single_part_body(text, ur_type)?;
+    (body.len() / 2).checked_sub(4)
+}
+
+/// The bytewords of `text` when it is a single-part UR of `ur_type`: two
+/// letters for each byte of the payload and its checksum.
+fn single_part_body<'a>(text: &'a str, ur_type: &str) -> Option<&'a str> {
+    let (scheme, rest) = text.split_at_checked("ur:".len())?;
+    let (found, body) = rest.split_once('/')?;
+    let single_part = scheme.eq_ignore_ascii_case("ur:")
+        && found.eq_ignore_ascii_case(ur_type)
+        && !body.contains('/')
+        && body.len() % 2 == 0;
+    single_part.then_some(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_ur::{UR, UREncodable};
+
+    use super::*;
+    use crate::test_support::register_tags;
+
+    #[test]
+    fn matches_bc_ur_both_ways() {
+        register_tags();
+        for envelope in [
+            Envelope::new("issue one"),
+            Envelope::new(CBOR::to_byte_string([7u8; 300]))
+                .add_assertion("note", "large"),
+        ] {
+            let ur = envelope_ur_string(&envelope);
+            assert_eq!(ur, envelope.ur_string());
+            assert_eq!(envelope_ur_len(&envelope), ur.len());
+            let data = decode(&ur.to_uppercase(), "envelope").unwrap();
+            assert_eq!(data, envelope.untagged_cbor().to_cbor_data());
+        }
+    }
+
+    /// `decode` accepts exactly the single-part envelope URs `bc-ur` does,
+    /// with the same payload, and `payload_len` measures each of them.
+    #[test]
+    fn agrees_with_bc_ur_on_edge_cases() {
+        register_tags();
+        let ur = Envelope::new("issue one").ur_string();
+        let body = &ur[ENVELOPE_PREFIX.len()..];
+        let last = if ur.ends_with("ae") { "ad" } else { "ae" };
+        let mixed_case: String = ur
+            .char_indices()
+            .map(|(index, c)| {
+                if index % 3 == 0 { c.to_ascii_uppercase() } else { c }
+            })
+            .collect();
+        let cases = [
+            ur.clone(),
+            ur.to_uppercase(),
+            mixed_case,
+            format!("UR:ENVELOPE/{body}"),
+            format!("{}{last}", &ur[..ur.len() - 2]),
+            ur[..ur.len() - 1].to_string(),
+            ur[..ur.len() - 2].to_string(),
+            format!("{ur}zz"),
+            format!("{}éé", &ur[..ur.len() - 2]),
+            format!("ur:envelope/1-3/{body}"),
+            format!("ur:crypto-key/{body}"),
+            "ur:envelope/".to_string(),
+            "ur:envelope/aeadao".to_string(),
+            "ur:envelope".to_string(),
+            "envelope/lftpsplntansfwlrhdfw".to_string(),
+            String::new(),
+        ];
+        for text in &cases {
+            let expected = UR::from_ur_string(text.as_str())
+                .ok()
+                .filter(|ur| ur.ur_type_str() == "envelope")
+                .map(|ur| ur.cbor().to_cbor_data());
+            let decoded = decode(text, "envelope");
+            assert_eq!(decoded, expected, "{text}");
+            if let Some(data) = decoded {
+                assert_eq!(payload_len(text, "envelope"), Some(data.len()));
+            }
+        }
+    }
+}
@@ -1,37 +1,83 @@
+mod bytewords;
+
 use std::{
+    cell::OnceCell,
     fs,
     io::{self, Read},
     panic,
-    path::Path,
+    path::{Path, PathBuf},
 };
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
 use bc_components::{
+    EncapsulationPublicKey, EncapsulationScheme, KeyDerivationMethod,
     PrivateKeyBase, PrivateKeys, PrivateKeysProvider, PublicKeys, SSKRShare,
-    SealedMessage, SymmetricKey, XID, XIDProvider,
+    SealedMessage, Seed, SymmetricKey, XID, XIDProvider,
+};
+use bc_envelope::{Attachable, prelude::*};
+use bc_ur::{MultipartDecoder, UREncodable};
+use bc_xid::{
+    HasPermissions, Privilege, XIDDocument, XIDGeneratorOptions,
+    XIDPrivateKeyOptions, XIDSigningOptions,
 };
-use bc_envelope::prelude::*;
-use bc_xid::{HasPermissions, Privilege, XIDDocument};
+use clap::Args;
+use dcbor::Date;
 use known_values::HOLDER;
-use provenance_mark::ProvenanceMark;
+use provenance_mark::{ProvenanceMark, ProvenanceMarkGenerator};
+
+pub use bytewords::{envelope_ur_len, envelope_ur_string};
 
 /// Descriptor for a permit recipient.
 pub struct RecipientDescriptor {
     pub_keys: PublicKeys,
     xid_document: Option<XIDDocument>,
     annotated_xid: Option<XID>,
+    expires: Option<Date>,
 }
 
+/// Predicate for the expiry date attached to a permit.
+pub const PERMIT_EXPIRES: &str = "expires";
+
+/// Predicate for the key reference of the recipient an unannotated permit
+/// was sealed to, stored as a byte string.
+pub const PERMIT_RECIPIENT_KEY: &str = "recipientKey";
+
 impl RecipientDescriptor {
+    /// Create a descriptor from bare public keys and an optional holder XID.
+    pub fn from_public_keys(
+        pub_keys: PublicKeys,
+        member_xid: Option<XID>,
+    ) -> Self {
+        Self {
+            pub_keys,
+            xid_document: None,
+            annotated_xid: member_xid,
+            expires: None,
+        }
+    }
+
+    /// Create a descriptor from an XID document, using the same key choice
+    /// as `parse_recipient_descriptor`.
+    pub fn from_xid_document(doc: XIDDocument) -> Result<Self> {
+        Ok(Self {
+            pub_keys: select_public_keys(&doc)?,
+            xid_document: Some(doc),
+            annotated_xid: None,
+            expires: None,
+        })
+    }
+
     /// Returns the public keys associated with the descriptor.
     pub fn public_keys(&self) -> &PublicKeys { &self.pub_keys }
 
     /// Returns the optional XID document if one was provided.
-    #[allow(dead_code)]
     pub fn xid_document(&self) -> Option<&XIDDocument> {
         self.xid_document.as_ref()
     }
 
+    /// Returns the expiry date carried by a permit descriptor, if any.
+    pub fn expires(&self) -> Option<&Date> { self.expires.as_ref() }
+
     /// Returns the annotated member XID, if present.
     pub fn member_xid(&self) -> Option<XID> {
         if let Some(doc) = self.xid_document.as_ref() {
@@ -42,6 +88,232 @@ impl RecipientDescriptor {
     }
 }
 
+/// Passphrase source for password-locked private artifacts (publisher XID
+/// documents, identities, provenance generator state).
+#[derive(Debug, Clone, Default, Args)]
+pub struct PassphraseArgs {
+    /// Prompt for a passphrase: init locks the private artifacts it writes
+    /// with it; other commands prompt automatically for locked inputs.
+    #[arg(long)]
+    pub passphrase: bool,
+    /// Read the passphrase from this environment variable instead of
+    /// prompting.
+    #[arg(long, value_name = "VAR", conflicts_with = "passphrase")]
+    pub passphrase_env: Option<String>,
+    #[arg(skip)]
+    cached: OnceCell<String>,
+}
+
+impl PassphraseArgs {
+    /// A source that yields `passphrase` without prompting or reading the
+    /// environment, for tests.
+    #[cfg(test)]
+    pub fn given(passphrase: &str) -> Self {
+        Self {
+            cached: OnceCell::from(passphrase.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Passphrase for locking new artifacts, or `None` when neither flag was
+    /// given. Interactive entry is confirmed.
+    pub fn for_writing(&self) -> Result<Option<String>> {
+        if !self.passphrase
+            && self.passphrase_env.is_none()
+            && self.cached.get().is_none()
+        {
+            return Ok(None);
+        }
+        self.obtain(true).map(Some)
+    }
+
+    /// Passphrase for unlocking a locked input.
+    pub fn for_reading(&self) -> Result<String> { self.obtain(false) }
+
+    fn obtain(&self, confirm: bool) -> Result<String> {
+        if let Some(cached) = self.cached.get() {
+            return Ok(cached.clone());
+        }
+        let passphrase = match self.passphrase_env.as_ref() {
+            Some(var) => std::env::var(var).with_context(|| {
+                format!("passphrase environment variable '{var}' is not set")
+            })?,
+            None => {
+                let entered = rpassword::prompt_password("Passphrase: ")
+                    .context("failed to read passphrase")?;
+                if confirm
+                    && rpassword::prompt_password("Confirm passphrase: ")
+                        .context("failed to read passphrase")?
+                        != entered
+                {
+                    bail!("passphrases do not match");
+                }
+                entered
+            }
+        };
+        if passphrase.is_empty() {
+            bail!("passphrase must not be empty");
+        }
+        Ok(self.cached.get_or_init(|| passphrase).clone())
+    }
+}
+
+/// Lock the envelope in `ur` with `passphrase`, returning the locked
+/// envelope's UR.
+pub fn lock_ur(ur: &str, passphrase: &str) -> Result<String> {
+    let envelope = decode_envelope(ur)?;
+    let locked = envelope
+        .lock(KeyDerivationMethod::Argon2id, passphrase.as_bytes())
+        .with_context(|| "failed to lock envelope with passphrase")?;
+    Ok(locked.ur_string())
+}
+
+/// Whether `ur` holds a password-locked envelope.
+pub fn is_locked_ur(ur: &str) -> bool {
+    decode_envelope(ur).is_ok_and(|envelope| is_locked(&envelope))
+}
+
+fn is_locked(envelope: &Envelope) -> bool {
+    envelope.subject().is_encrypted()
+        && !envelope
+            .assertions_with_predicate(known_values::HAS_SECRET)
+            .is_empty()
+}
+
+/// If `raw` is a password-locked envelope, unlock it and return the inner
+/// envelope's UR along with the passphrase used; otherwise return `raw`.
+fn unlock_raw(
+    raw: String,
+    passphrase: &PassphraseArgs,
+) -> Result<(String, Option<String>)> {
+    let Ok(envelope) = decode_envelope(&raw) else {
+        return Ok((raw, None));
+    };
+    if !is_locked(&envelope) {
+        return Ok((raw, None));
+    }
+    let secret = passphrase.for_reading()?;
+    let unlocked = envelope.unlock(secret.as_bytes()).map_err(|_| {
+        anyhow!("failed to unlock input: wrong passphrase or corrupted data")
+    })?;
+    Ok((unlocked.ur_string(), Some(secret)))
+}
+
+/// The `.ur` files directly inside `dir`, sorted by name.
+pub fn ur_files_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| {
+        format!("failed to read directory '{}'", dir.display())
+    })? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "ur") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The publisher XID document for `club` in `dir`: the `.ur` file named by
+/// the club's XID hex, or by a prefix of it (e.g. `<xid>.ur` or the short
+/// `1a2b3c4d.ur`). Exactly one file must match.
+pub fn publisher_in_dir(
+    dir: &Path,
+    club: &XID,
+    passphrase: &PassphraseArgs,
+) -> Result<RecipientDescriptor> {
+    let hex = hex::encode(club.data());
+    let stem = |path: &PathBuf| {
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default()
+    };
+    let files = ur_files_in(dir)?;
+    let mut matches: Vec<&PathBuf> =
+        files.iter().filter(|path| stem(path) == hex).collect();
+    if matches.is_empty() {
+        matches = files
+            .iter()
+            .filter(|path| {
+                let stem = stem(path);
+                !stem.is_empty() && hex.starts_with(&stem)
+            })
+            .collect();
+    }
+    let path = match matches.as_slice() {
+        [path] => *path,
+        [] => bail!(
+            "no publisher document for club {club} in '{}'; expected {hex}.ur",
+            dir.display()
+        ),
+        _ => bail!(
+            "ambiguous publisher document for club {club} in '{}': {}",
+            dir.display(),
+            matches
+                .iter()
+                .filter_map(|path| path.file_name())
+                .map(|name| name.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let doc = parse_xid_document(&format!("@{}", path.display()), passphrase)
+        .with_context(|| {
+        format!("failed to read publisher document '{}'", path.display())
+    })?;
+    RecipientDescriptor::from_xid_document(doc)
+}
+
+/// Files directly inside `dir` whose names match `pattern` (see
+/// [`glob_matches`]), sorted by name; without a pattern, the `.ur` files.
+pub fn files_matching(
+    dir: &Path,
+    pattern: Option<&str>,
+) -> Result<Vec<PathBuf>> {
+    let Some(pattern) = pattern else {
+        return ur_files_in(dir);
+    };
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| {
+        format!("failed to read directory '{}'", dir.display())
+    })? {
+        let path = entry?.path();
+        if path.is_file()
+            && path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| glob_matches(pattern, name))
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Whether the file name `name` matches `pattern`, where `*` matches any run
+/// of characters and `?` any single character.
+pub fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // matches[j]: the pattern so far matches the first j characters.
+    let mut matches = vec![false; name.len() + 1];
+    matches[0] = true;
+    for token in pattern {
+        let mut next = vec![false; name.len() + 1];
+        for j in 0..=name.len() {
+            next[j] = match token {
+                '*' => matches[j] || (j > 0 && next[j - 1]),
+                '?' => j > 0 && matches[j - 1],
+                c => j > 0 && matches[j - 1] && name[j - 1] == c,
+            };
+        }
+        matches = next;
+    }
+    matches[name.len()]
+}
+
 /// Read input from a required CLI argument.
 pub fn load_from_spec(spec: &str) -> Result<String> {
     if spec == "-" {
@@ -58,11 +330,11 @@ pub fn load_from_spec(spec: &str) -> Result<String> {
         }
         let content = fs::read_to_string(Path::new(path))
             .with_context(|| format!("failed to read input file '{path}'"))?;
-        let trimmed = content.trim();
+        let trimmed = trim_in_place(content);
         if trimmed.is_empty() {
             bail!("input file '{path}' is empty");
         }
-        return Ok(trimmed.to_owned());
+        return Ok(trimmed);
     }
 
     let trimmed = spec.trim();
@@ -75,27 +347,130 @@ pub fn load_from_spec(spec: &str) -> Result<String> {
 fn read_stdin() -> Result<String> {
     let mut buf = String::new();
     io::stdin().read_to_string(&mut buf)?;
-    let trimmed = buf.trim();
+    let trimmed = trim_in_place(buf);
     if trimmed.is_empty() {
         bail!("no data provided on stdin");
     }
-    Ok(trimmed.to_owned())
+    Ok(trimmed)
+}
+
+/// Trim `text` without copying it, as inputs can be large.
+fn trim_in_place(mut text: String) -> String {
+    text.truncate(text.trim_end().len());
+    let leading = text.len() - text.trim_start().len();
+    text.drain(..leading);
+    text
 }
 
 fn tighten_ur(input: &str) -> String { input.split_whitespace().collect() }
 
+/// Reassemble multi-part UR fragments (whitespace or newline separated) into
+/// a single UR. Returns `None` when the input is not a multi-part UR.
+fn assemble_multipart(raw: &str) -> Result<Option<UR>> {
+    let parts: Vec<&str> = raw.split_whitespace().collect();
+    if !parts.first().is_some_and(|part| is_multipart_ur(part)) {
+        return Ok(None);
+    }
+
+    let mut decoder = MultipartDecoder::new();
+    for part in parts {
+        decoder
+            .receive(part)
+            .with_context(|| "failed to read multi-part UR fragment")?;
+        if decoder.is_complete() {
+            break;
+        }
+    }
+    let ur = decoder
+        .message()
+        .with_context(|| "failed to assemble multi-part UR")?
+        .ok_or_else(|| {
+            anyhow!("incomplete multi-part UR; more fragments are required")
+        })?;
+    Ok(Some(ur))
+}
+
+fn is_multipart_ur(part: &str) -> bool {
+    let lower = part.to_ascii_lowercase();
+    let Some(rest) = lower.strip_prefix("ur:") else {
+        return false;
+    };
+    let mut segments = rest.split('/');
+    segments.next();
+    let Some((index, count)) =
+        segments.next().and_then(|seq| seq.split_once('-'))
+    else {
+        return false;
+    };
+    let numeric =
+        |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    numeric(index) && numeric(count)
+}
+
 /// Load an Envelope, expecting a UR encoding.
 pub fn parse_envelope(spec: &str) -> Result<Envelope> {
     let raw = load_from_spec(spec)?;
     decode_envelope(&raw)
 }
 
+/// Load an Envelope like `parse_envelope`, refusing one whose CBOR is
+/// larger than `max_bytes`. A single-part UR is measured by its length
+/// before it is decoded; any other form once it is.
+pub fn parse_envelope_limited(
+    spec: &str,
+    max_bytes: Option<usize>,
+) -> Result<Envelope> {
+    let Some(limit) = max_bytes else {
+        return parse_envelope(spec);
+    };
+    let check = |size: usize| {
+        if size > limit {
+            bail!(
+                "envelope is {size} bytes, exceeding the limit of {limit} bytes"
+            );
+        }
+        Ok(())
+    };
+    let raw = load_from_spec(spec)?;
+    if let Some(size) = bytewords::payload_len(&raw, "envelope") {
+        check(size)?;
+        if let Some(data) = bytewords::decode(&raw, "envelope") {
+            // The text is twice the size of its payload; release it first.
+            drop(raw);
+            return envelope_from_payload(data);
+        }
+    }
+    let envelope = decode_envelope(&raw)?;
+    drop(raw);
+    check(envelope.untagged_cbor().to_cbor_data().len())?;
+    Ok(envelope)
+}
+
+/// The envelope in the payload of a single-part envelope UR, releasing the
+/// payload once it is parsed.
+fn envelope_from_payload(data: Vec<u8>) -> Result<Envelope> {
+    let cbor = CBOR::try_from_data(&data)
+        .with_context(|| "failed to decode Envelope CBOR")?;
+    drop(data);
+    Envelope::from_untagged_cbor(cbor)
+        .with_context(|| "failed to decode Envelope CBOR")
+}
+
 fn decode_envelope(raw: &str) -> Result<Envelope> {
     let primary = raw.trim();
     if primary.is_empty() {
         bail!("empty envelope input");
     }
 
+    if let Some(data) = bytewords::decode(primary, "envelope") {
+        return envelope_from_payload(data);
+    }
+
+    if let Some(ur) = assemble_multipart(primary)? {
+        return Envelope::from_ur(&ur)
+            .with_context(|| "failed to decode multi-part envelope UR");
+    }
+
     if let Ok(env) = Envelope::from_ur_string(primary) {
         return Ok(env);
     }
@@ -119,6 +494,60 @@ fn decode_envelope(raw: &str) -> Result<Envelope> {
         .with_context(|| "failed to decode Envelope CBOR")
 }
 
+/// Envelope type of a stored provenance mark generator.
+pub const PROVENANCE_STATE_TYPE: &str = "ProvenanceMarkGenerator";
+
+/// Encode provenance mark generator state as an envelope UR whose subject
+/// is the generator's JSON serialization (as used by `provenance` tooling).
+pub fn provenance_state_ur(
+    generator: &ProvenanceMarkGenerator,
+) -> Result<String> {
+    let json = serde_json::to_string(generator)
+        .context("failed to serialize provenance generator state")?;
+    Ok(Envelope::new(json)
+        .add_type(PROVENANCE_STATE_TYPE)
+        .ur_string())
+}
+
+/// Load provenance mark generator state written by `provenance_state_ur`,
+/// unlocking it if necessary. Also returns the passphrase that unlocked it,
+/// so the advanced state can be locked the same way.
+pub fn parse_provenance_state(
+    spec: &str,
+    passphrase: &PassphraseArgs,
+) -> Result<(ProvenanceMarkGenerator, Option<String>)> {
+    let (raw, secret) = unlock_raw(load_from_spec(spec)?, passphrase)?;
+    let envelope = decode_envelope(&raw)?;
+    if !envelope.has_type(PROVENANCE_STATE_TYPE) {
+        bail!("envelope is not provenance generator state");
+    }
+    let json: String = envelope
+        .extract_subject()
+        .context("provenance generator state subject must be text")?;
+    let generator = serde_json::from_str(&json)
+        .context("failed to decode provenance generator state")?;
+    Ok((generator, secret))
+}
+
+/// An edition envelope, or only the provenance mark of one.
+pub enum EditionOrMark {
+    Edition(Envelope),
+    Mark(ProvenanceMark),
+}
+
+/// Parse an edition envelope UR or a bare `ur:provenance` mark, told apart
+/// by UR type.
+pub fn parse_edition_or_mark(spec: &str) -> Result<EditionOrMark> {
+    let raw = load_from_spec(spec)?;
+    if tighten_ur(&raw)
+        .to_ascii_lowercase()
+        .starts_with("ur:provenance/")
+    {
+        return Ok(EditionOrMark::Mark(decode_provenance_mark(&raw)?));
+    }
+    Ok(EditionOrMark::Edition(decode_envelope(&raw)?))
+}
+
 /// Parse a provenance mark from input.
 pub fn parse_provenance_mark(spec: &str) -> Result<ProvenanceMark> {
     let raw = load_from_spec(spec)?;
@@ -135,11 +564,90 @@ fn decode_provenance_mark(raw: &str) -> Result<ProvenanceMark> {
 }
 
 /// Parse an XID document from input.
-pub fn parse_xid_document(spec: &str) -> Result<XIDDocument> {
-    let raw = load_from_spec(spec)?;
+pub fn parse_xid_document(
+    spec: &str,
+    passphrase: &PassphraseArgs,
+) -> Result<XIDDocument> {
+    let (raw, _) = unlock_raw(load_from_spec(spec)?, passphrase)?;
     decode_xid_document(&raw)
 }
 
+/// Encode an XID document as an envelope UR, optionally including its
+/// private keys. The result round-trips through `parse_xid_document`.
+pub fn xid_document_ur(
+    doc: &XIDDocument,
+    include_private_keys: bool,
+) -> Result<String> {
+    let private_keys = if include_private_keys {
+        XIDPrivateKeyOptions::Include
+    } else {
+        XIDPrivateKeyOptions::Omit
+    };
+    let envelope = doc
+        .to_envelope(
+            private_keys,
+            XIDGeneratorOptions::Omit,
+            XIDSigningOptions::None,
+        )
+        .with_context(|| "failed to encode XID document")?;
+    Ok(envelope.ur_string())
+}
+
+/// Vendor of the club profile attachment on a publisher XID document.
+pub const CLUB_PROFILE_VENDOR: &str = "com.blockchaincommons.clubs";
+/// Format of the club profile attachment payload.
+pub const CLUB_PROFILE_CONFORMS_TO: &str = "club-profile";
+
+/// Human-readable club identity carried on the publisher XID document as an
+/// attachment whose payload is `"<name>" [ 'note': "<description>" ]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClubProfile {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl ClubProfile {
+    /// Attach the profile to `doc`. Attachments sit outside the inception
+    /// key, so the XID is unchanged.
+    pub fn attach_to(&self, doc: &mut XIDDocument) {
+        let payload = Envelope::new(self.name.as_str()).add_optional_assertion(
+            known_values::NOTE,
+            self.description.clone(),
+        );
+        doc.add_attachment(
+            payload,
+            CLUB_PROFILE_VENDOR,
+            Some(CLUB_PROFILE_CONFORMS_TO),
+        );
+    }
+
+    /// Read the club profile attached to `doc`, if any.
+    pub fn from_document(doc: &XIDDocument) -> Result<Option<Self>> {
+        let envelope = doc
+            .to_envelope(
+                XIDPrivateKeyOptions::Omit,
+                XIDGeneratorOptions::Omit,
+                XIDSigningOptions::None,
+            )
+            .with_context(|| "failed to encode XID document")?;
+        let attachments = envelope.attachments_with_vendor_and_conforms_to(
+            Some(CLUB_PROFILE_VENDOR),
+            Some(CLUB_PROFILE_CONFORMS_TO),
+        )?;
+        let Some(attachment) = attachments.first() else {
+            return Ok(None);
+        };
+        let payload = attachment.attachment_payload()?;
+        let name = payload
+            .extract_subject::<String>()
+            .context("club profile name must be text")?;
+        let description = payload
+            .extract_optional_object_for_predicate::<String>(known_values::NOTE)
+            .context("club profile description must be text")?;
+        Ok(Some(Self { name, description }))
+    }
+}
+
 fn decode_xid_document(raw: &str) -> Result<XIDDocument> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
@@ -164,7 +672,7 @@ fn decode_xid_document(raw: &str) -> Result<XIDDocument> {
         "xid" => XIDDocument::from_ur(&ur)
             .with_context(|| "failed to decode XID document from UR"),
         "envelope" => {
-            let env = Envelope::from_tagged_cbor(ur.cbor())
+            let env = Envelope::from_untagged_cbor(ur.cbor())
                 .with_context(|| "failed to decode XID document envelope")?;
             XIDDocument::try_from(env)
                 .with_context(|| "failed to convert envelope to XID document")
@@ -202,6 +710,54 @@ fn decode_public_keys(raw: &str) -> Result<PublicKeys> {
 /// Parse a recipient descriptor (XID document or public keys).
 pub fn parse_recipient_descriptor(spec: &str) -> Result<RecipientDescriptor> {
     let raw = load_from_spec(spec)?;
+    decode_recipient_descriptor(&raw)
+}
+
+/// Parse a recipients manifest: one recipient descriptor UR per line, with
+/// blank lines and `#` comments ignored.
+pub fn parse_recipients_file(path: &Path) -> Result<Vec<RecipientDescriptor>> {
+    let content = fs::read_to_string(path).with_context(|| {
+        format!("failed to read recipients file '{}'", path.display())
+    })?;
+
+    let mut descriptors = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let entry = line.trim();
+        if entry.is_empty() || entry.starts_with('#') {
+            continue;
+        }
+        let descriptor =
+            decode_recipient_descriptor(entry).with_context(|| {
+                format!(
+                    "invalid recipient on line {} of '{}'",
+                    index + 1,
+                    path.display()
+                )
+            })?;
+        descriptors.push(descriptor);
+    }
+    Ok(descriptors)
+}
+
+/// Build the `PublicKeyPermit` descriptor envelope understood by
+/// `parse_recipient_descriptor`.
+pub fn permit_descriptor_envelope(
+    public_keys: &PublicKeys,
+    member_xid: Option<XID>,
+    expires: Option<&Date>,
+) -> Envelope {
+    let mut envelope =
+        Envelope::new(public_keys.clone()).add_type("PublicKeyPermit");
+    if let Some(xid) = member_xid {
+        envelope = envelope.add_assertion(HOLDER, xid);
+    }
+    if let Some(date) = expires {
+        envelope = envelope.add_assertion(PERMIT_EXPIRES, *date);
+    }
+    envelope
+}
+
+fn decode_recipient_descriptor(raw: &str) -> Result<RecipientDescriptor> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
         bail!("empty recipient descriptor");
@@ -213,15 +769,12 @@ pub fn parse_recipient_descriptor(spec: &str) -> Result<RecipientDescriptor> {
             pub_keys,
             xid_document: Some(doc),
             annotated_xid: None,
+            expires: None,
         });
     }
 
-    if let Some((pub_keys, member_xid)) = decode_public_key_permit(trimmed)? {
-        return Ok(RecipientDescriptor {
-            pub_keys,
-            xid_document: None,
-            annotated_xid: member_xid,
-        });
+    if let Some(descriptor) = decode_public_key_permit(trimmed)? {
+        return Ok(descriptor);
     }
 
     let keys = decode_public_keys(trimmed)?;
@@ -229,6 +782,7 @@ pub fn parse_recipient_descriptor(spec: &str) -> Result<RecipientDescriptor> {
         pub_keys: keys,
         xid_document: None,
         annotated_xid: None,
+        expires: None,
     })
 }
 
@@ -245,6 +799,15 @@ fn select_public_keys(doc: &XIDDocument) -> Result<PublicKeys> {
         return Ok(key.public_keys().clone());
     }
 
+    if let Some(key) = keys.iter().find(|key| {
+        key.permissions()
+            .allow()
+            .iter()
+            .any(|privilege| privilege == &Privilege::Encrypt)
+    }) {
+        return Ok(key.public_keys().clone());
+    }
+
     if let Some(key) = keys.first() {
         return Ok(key.public_keys().clone());
     }
@@ -252,9 +815,25 @@ fn select_public_keys(doc: &XIDDocument) -> Result<PublicKeys> {
     bail!("XID document does not contain any public keys");
 }
 
+/// Name the encapsulation scheme used to seal permits to `public_keys`.
+pub fn recipient_key_scheme(public_keys: &PublicKeys) -> &'static str {
+    let key: &EncapsulationPublicKey = public_keys.as_ref();
+    encapsulation_scheme_label(key.encapsulation_scheme())
+}
+
+/// Human-readable name for an encapsulation scheme.
+pub fn encapsulation_scheme_label(scheme: EncapsulationScheme) -> &'static str {
+    match scheme {
+        EncapsulationScheme::X25519 => "X25519",
+        EncapsulationScheme::MLKEM512 => "ML-KEM-512",
+        EncapsulationScheme::MLKEM768 => "ML-KEM-768",
+        EncapsulationScheme::MLKEM1024 => "ML-KEM-1024",
+    }
+}
+
 fn decode_public_key_permit(
     raw: &str,
-) -> Result<Option<(PublicKeys, Option<XID>)>> {
+) -> Result<Option<RecipientDescriptor>> {
     let Ok(envelope) = decode_envelope(raw) else {
         return Ok(None);
     };
@@ -278,17 +857,31 @@ fn decode_public_key_permit(
         None => None,
     };
 
-    let allowed = if holder.is_some() { 1 } else { 0 };
+    let expires = envelope
+        .extract_optional_object_for_predicate::<Date>(PERMIT_EXPIRES)
+        .with_context(|| "public-key permit expiry must be a date")?;
+
+    // The type assertion, then the optional holder and expiry.
+    let allowed =
+        1 + usize::from(holder.is_some()) + usize::from(expires.is_some());
     if envelope.assertions().len() > allowed {
         bail!("public-key permit contains unsupported assertions");
     }
 
-    Ok(Some((public_keys, holder)))
+    Ok(Some(RecipientDescriptor {
+        pub_keys: public_keys,
+        xid_document: None,
+        annotated_xid: holder,
+        expires,
+    }))
 }
 
 /// Parse private keys from either a UR or an XID document containing them.
-pub fn parse_private_keys(spec: &str) -> Result<PrivateKeys> {
-    let raw = load_from_spec(spec)?;
+pub fn parse_private_keys(
+    spec: &str,
+    passphrase: &PassphraseArgs,
+) -> Result<PrivateKeys> {
+    let (raw, _) = unlock_raw(load_from_spec(spec)?, passphrase)?;
     let trimmed = raw.trim();
     if trimmed.is_empty() {
         bail!("empty private keys input");
@@ -398,6 +991,17 @@ pub fn parse_xid_value(spec: &str) -> Result<XID> {
     }
 }
 
+/// Parse an RFC 3339 date or timestamp, e.g. `2025-06-30` or
+/// `2025-06-30T12:00:00Z`.
+pub fn parse_date(spec: &str) -> Result<Date> {
+    let trimmed = spec.trim();
+    if trimmed.is_empty() {
+        bail!("empty date value");
+    }
+    Date::from_string(trimmed)
+        .map_err(|err| anyhow!("invalid RFC 3339 date '{trimmed}': {err}"))
+}
+
 /// Parse a sealed message permit.
 pub fn parse_sealed_message(spec: &str) -> Result<SealedMessage> {
     let raw = load_from_spec(spec)?;
@@ -455,6 +1059,24 @@ pub fn parse_sskr_share(spec: &str) -> Result<SSKRShare> {
     }
 }
 
+/// Parse seed material from a `ur:seed` or a hex string of at least 16
+/// bytes.
+pub fn parse_seed(spec: &str) -> Result<Vec<u8>> {
+    let raw = load_from_spec(spec)?;
+    let trimmed = raw.trim();
+    if trimmed.to_ascii_lowercase().starts_with("ur:") {
+        let seed = Seed::from_ur_string(tighten_ur(trimmed))
+            .with_context(|| "failed to parse seed UR")?;
+        return Ok(seed.as_bytes().to_vec());
+    }
+    let data = hex::decode(trimmed)
+        .with_context(|| "seed must be a ur:seed or hex string")?;
+    if data.len() < 16 {
+        bail!("seed must be at least 16 bytes, got {}", data.len());
+    }
+    Ok(data)
+}
+
 /// Parse a symmetric key UR.
 pub fn parse_symmetric_key(spec: &str) -> Result<SymmetricKey> {
     let raw = load_from_spec(spec)?;
@@ -486,9 +1108,88 @@ pub fn parse_symmetric_key(spec: &str) -> Result<SymmetricKey> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::register_tags;
 
     #[test]
     fn tighten_removes_whitespace() {
         assert_eq!(tighten_ur(" ur:example / data \n"), "ur:example/data");
     }
+
+    #[test]
+    fn size_limit_refuses_oversized_inputs() {
+        register_tags();
+        let envelope = Envelope::new("x".repeat(1000));
+        let path = std::env::temp_dir()
+            .join(format!("clubs-io-limit-{}.ur", std::process::id()));
+        fs::write(&path, format!("\n{}\n", envelope.ur_string())).unwrap();
+        let spec = format!("@{}", path.display());
+
+        let err = parse_envelope_limited(&spec, Some(100)).err().unwrap();
+        assert!(
+            err.to_string().contains("exceeding the limit of 100 bytes"),
+            "{err:#}"
+        );
+        let loaded = parse_envelope_limited(&spec, Some(2000)).unwrap();
+        assert!(loaded.is_identical_to(&envelope));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn detects_multipart_urs() {
+        assert!(is_multipart_ur(
+            "ur:envelope/1-12/lpadbbcsiecyvdidatkpfeghihjtcxiabdfevlms"
+        ));
+        assert!(!is_multipart_ur("ur:envelope/lftpsplntansfwlrhdfw"));
+        assert!(!is_multipart_ur("envelope/1-12/abc"));
+    }
+
+    #[test]
+    fn parses_post_quantum_recipients() {
+        register_tags();
+        use bc_components::{SignatureScheme, keypair_opt};
+
+        let (_, public_keys) = keypair_opt(
+            SignatureScheme::MLDSA65,
+            EncapsulationScheme::MLKEM768,
+        );
+        let descriptor =
+            decode_recipient_descriptor(&public_keys.ur_string()).unwrap();
+        assert_eq!(descriptor.public_keys(), &public_keys);
+        assert_eq!(
+            recipient_key_scheme(descriptor.public_keys()),
+            "ML-KEM-768"
+        );
+    }
+
+    #[test]
+    fn locked_provenance_state_needs_the_right_passphrase() {
+        register_tags();
+        use provenance_mark::ProvenanceMarkResolution;
+
+        let generator =
+            ProvenanceMarkGenerator::new_random(ProvenanceMarkResolution::Low);
+        let state = provenance_state_ur(&generator).unwrap();
+        let right = PassphraseArgs::given("correct horse");
+        let secret = right.for_writing().unwrap().unwrap();
+        let locked = lock_ur(&state, &secret).unwrap();
+        assert!(is_locked_ur(&locked));
+        assert!(!is_locked_ur(&state));
+
+        let path = std::env::temp_dir()
+            .join(format!("clubs-locked-state-{}.ur", std::process::id()));
+        std::fs::write(&path, &locked).unwrap();
+        let spec = format!("@{}", path.display());
+
+        let wrong = PassphraseArgs::given("battery staple");
+        let err = parse_provenance_state(&spec, &wrong)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("wrong passphrase"), "{err}");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), locked);
+
+        let (restored, used) = parse_provenance_state(&spec, &right).unwrap();
+        assert_eq!(restored.next_seq(), generator.next_seq());
+        assert_eq!(used, Some(secret));
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -1,5 +1,14 @@
+mod chain;
 mod cmd;
+mod directory;
 mod io;
+mod output;
+#[cfg(test)]
+mod test_support;
+mod trust;
+mod workspace;
+
+use std::process::ExitCode;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
@@ -28,18 +37,33 @@ enum Command {
     Permits(cmd::permits::CommandArgs),
     /// Work with encrypted club content.
     Content(cmd::content::CommandArgs),
+    /// Manage publisher keys pinned on first use.
+    Trust(cmd::trust::CommandArgs),
 }
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     // Calls envelope register_tags, which calls bc_components register_tags.
     provenance_mark::register_tags();
 
     let cli = Cli::parse();
 
-    match cli.command {
+    match run(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            if !err.is::<cmd::Silenced>() {
+                eprintln!("Error: {err:?}");
+            }
+            cmd::exit_code(&err)
+        }
+    }
+}
+
+fn run(command: Command) -> Result<()> {
+    match command {
         Command::Init(args) => cmd::init::exec(args),
         Command::Edition(args) => cmd::edition::exec(args),
         Command::Permits(args) => cmd::permits::exec(args),
         Command::Content(args) => cmd::content::exec(args),
+        Command::Trust(args) => cmd::trust::exec(args),
     }
 }
@@ -0,0 +1,328 @@
+pub mod qr;
+
+use std::{
+    convert::Infallible,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use bc_ur::{MultipartEncoder, UR};
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+
+/// Options for splitting emitted URs into multi-part fragments.
+#[derive(Debug, Args)]
+pub struct FragmentArgs {
+    /// Emit URs as multi-part fragments of at most this many characters.
+    #[arg(long, value_name = "CHARS")]
+    pub max_fragment_len: Option<usize>,
+    /// Additional fountain-encoded parts to emit for redundancy.
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub extra_parts: usize,
+}
+
+impl FragmentArgs {
+    /// Returns the lines to print for `ur_string`: the UR itself, or its
+    /// multi-part fragments when `--max-fragment-len` is set.
+    pub fn lines(&self, ur_string: &str) -> Result<Vec<String>> {
+        match self.max_fragment_len {
+            Some(len) => fragment_ur(ur_string, len, self.extra_parts),
+            None => Ok(vec![ur_string.to_owned()]),
+        }
+    }
+}
+
+/// Format of a command's report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text on stderr.
+    #[default]
+    Text,
+    /// A single JSON object on stdout.
+    Json,
+}
+
+/// Print `value` to stdout as pretty-printed JSON.
+pub fn print_json(value: &impl Serialize) -> Result<()> {
+    let json = serde_json::to_string_pretty(value)
+        .context("failed to serialize JSON output")?;
+    println!("{json}");
+    Ok(())
+}
+
+/// Where a command's human-readable summary goes: `stderr` (the default),
+/// `stdout`, or any other value as a file path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SummaryTarget {
+    #[default]
+    Stderr,
+    Stdout,
+    File(PathBuf),
+    /// Drop every line; not selectable on the command line.
+    Discard,
+}
+
+impl FromStr for SummaryTarget {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value {
+            "stderr" => Self::Stderr,
+            "stdout" => Self::Stdout,
+            path => Self::File(PathBuf::from(path)),
+        })
+    }
+}
+
+/// Sends summary lines to a [`SummaryTarget`]. Streams are written as lines
+/// arrive; a file is written atomically by [`SummaryWriter::finish`].
+pub struct SummaryWriter {
+    target: SummaryTarget,
+    buffer: String,
+}
+
+impl SummaryWriter {
+    pub fn new(target: SummaryTarget) -> Self {
+        Self { target, buffer: String::new() }
+    }
+
+    pub fn line(&mut self, line: &str) {
+        match self.target {
+            SummaryTarget::Stderr => eprintln!("{line}"),
+            SummaryTarget::Stdout => println!("{line}"),
+            SummaryTarget::File(_) => {
+                self.buffer.push_str(line);
+                self.buffer.push('\n');
+            }
+            SummaryTarget::Discard => {}
+        }
+    }
+
+    /// Write the collected summary when it goes to a file.
+    pub fn finish(self) -> Result<()> {
+        match &self.target {
+            SummaryTarget::File(path) => {
+                write_atomic(path, self.buffer.as_bytes())
+            }
+            SummaryTarget::Stderr
+            | SummaryTarget::Stdout
+            | SummaryTarget::Discard => Ok(()),
+        }
+    }
+}
+
+/// Create `path` (and any missing parents) if it does not already exist.
+pub fn ensure_dir(path: &Path) -> Result<()> {
+    fs::create_dir_all(path).with_context(|| {
+        format!("failed to create output directory '{}'", path.display())
+    })
+}
+
+/// Write `contents` to `path` atomically by writing a sibling temporary file
+/// and renaming it into place, so readers never observe a partial write.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    write_atomic_with(path, contents, false)
+}
+
+/// Like `write_atomic`, but the file is readable only by its owner on
+/// platforms that support it. Use for private keys and generator state.
+pub fn write_secret(path: &Path, contents: &[u8]) -> Result<()> {
+    write_atomic_with(path, contents, true)
+}
+
+fn write_atomic_with(path: &Path, contents: &[u8], secret: bool) -> Result<()> {
+    let file_name = path.file_name().ok_or_else(|| {
+        anyhow!("output path '{}' has no file name", path.display())
+    })?;
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let temp_path =
+        parent.join(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    write_file(&temp_path, contents, secret).with_context(|| {
+        format!("failed to write temporary file '{}'", temp_path.display())
+    })?;
+    fs::rename(&temp_path, path).with_context(|| {
+        format!("failed to move output into place at '{}'", path.display())
+    })?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn write_file(
+    path: &Path,
+    contents: &[u8],
+    secret: bool,
+) -> std::io::Result<()> {
+    use std::{
+        io::Write,
+        os::unix::fs::{OpenOptionsExt, PermissionsExt},
+    };
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    if secret {
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    // The creation mode is ignored when the file already exists (a stale
+    // temporary file, say), so narrow it explicitly before writing.
+    if secret {
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents)
+}
+
+#[cfg(not(unix))]
+fn write_file(
+    path: &Path,
+    contents: &[u8],
+    _secret: bool,
+) -> std::io::Result<()> {
+    fs::write(path, contents)
+}
+
+/// Write newline-terminated lines (typically URs) to `path` atomically.
+pub fn write_lines<I, S>(path: &Path, lines: I) -> Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut buf = String::new();
+    for line in lines {
+        buf.push_str(line.as_ref());
+        buf.push('\n');
+    }
+    write_atomic(path, buf.as_bytes())
+}
+
+/// Split a UR into fountain-encoded parts of at most `max_fragment_len`
+/// characters, followed by `extra_parts` additional redundant parts. A UR
+/// that already fits is returned unchanged as the only part.
+pub fn fragment_ur(
+    ur_string: &str,
+    max_fragment_len: usize,
+    extra_parts: usize,
+) -> Result<Vec<String>> {
+    if max_fragment_len == 0 {
+        bail!("fragment length must be greater than zero");
+    }
+    if ur_string.len() <= max_fragment_len {
+        return Ok(vec![ur_string.to_owned()]);
+    }
+
+    let ur = UR::from_ur_string(ur_string)
+        .context("failed to parse UR for fragmenting")?;
+    // The encoder budgets CBOR bytes per fragment, and each byte costs two
+    // characters. Every part also spends characters on its header,
+    // sequence numbers, and checksum, so shrink the budget until the
+    // longest part fits.
+    let header = "ur:".len() + ur.ur_type_str().len() + "/1-1/".len();
+    let mut budget = max_fragment_len.saturating_sub(header) / 2;
+    loop {
+        if budget == 0 {
+            bail!(
+                "fragment length {max_fragment_len} is too short for a multi-part UR"
+            );
+        }
+        let parts = fountain_parts(&ur, budget, extra_parts)?;
+        let longest = parts.iter().map(String::len).max().unwrap_or(0);
+        if longest <= max_fragment_len {
+            return Ok(parts);
+        }
+        budget =
+            budget.saturating_sub((longest - max_fragment_len).div_ceil(2));
+    }
+}
+
+/// The fountain-encoded parts of `ur` with fragments of at most `budget`
+/// CBOR bytes, followed by `extra_parts` redundant parts.
+fn fountain_parts(
+    ur: &UR,
+    budget: usize,
+    extra_parts: usize,
+) -> Result<Vec<String>> {
+    let mut encoder = MultipartEncoder::new(ur, budget)
+        .context("failed to create multi-part UR encoder")?;
+    let count = encoder.parts_count() + extra_parts;
+    let mut parts = Vec::with_capacity(count);
+    for _ in 0..count {
+        parts.push(
+            encoder
+                .next_part()
+                .context("failed to produce multi-part UR fragment")?,
+        );
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+    use bc_ur::MultipartDecoder;
+
+    use super::*;
+
+    #[test]
+    fn fragments_reassemble_into_identical_envelope() {
+        bc_envelope::register_tags();
+        let envelope = Envelope::new("Gordian Club ".repeat(300));
+        let ur_string = envelope.ur_string();
+
+        let parts = fragment_ur(&ur_string, 200, 0).unwrap();
+        assert!(parts.len() > 1);
+
+        let mut decoder = MultipartDecoder::new();
+        for part in &parts {
+            decoder.receive(part).unwrap();
+        }
+        assert!(decoder.is_complete());
+        let ur = decoder.message().unwrap().unwrap();
+        let decoded = Envelope::from_ur(&ur).unwrap();
+        assert!(decoded.is_identical_to(&envelope));
+    }
+
+    #[test]
+    fn fragments_fit_the_character_limit() {
+        bc_envelope::register_tags();
+        let ur_string = Envelope::new("Gordian Club ".repeat(300)).ur_string();
+        for max_fragment_len in [80, 100, 200, 400, 1000] {
+            let parts = fragment_ur(&ur_string, max_fragment_len, 20).unwrap();
+            assert!(parts.len() > 20);
+            for part in &parts {
+                assert!(
+                    part.len() <= max_fragment_len,
+                    "{} > {max_fragment_len}: {part}",
+                    part.len()
+                );
+            }
+        }
+        let err = fragment_ur(&ur_string, 20, 0).err().unwrap();
+        assert!(err.to_string().contains("too short"), "{err:#}");
+    }
+
+    #[test]
+    fn summary_goes_to_the_named_destination() {
+        assert_eq!("stderr".parse(), Ok(SummaryTarget::Stderr));
+        assert_eq!("stdout".parse(), Ok(SummaryTarget::Stdout));
+        let path = std::env::temp_dir()
+            .join(format!("clubs-summary-{}.txt", std::process::id()));
+        let target: SummaryTarget = path.to_str().unwrap().parse().unwrap();
+        assert_eq!(target, SummaryTarget::File(path.clone()));
+
+        let mut writer = SummaryWriter::new(target);
+        writer.line("Club: ur:xid/example");
+        writer.line("Permits: none");
+        assert!(!path.exists());
+        writer.finish().unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "Club: ur:xid/example\nPermits: none\n"
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,199 @@
+use std::{io::Cursor, path::PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use clap::{Args, ValueEnum};
+use qrcode::{
+    QrCode,
+    render::{svg, unicode},
+};
+
+use super::{FragmentArgs, fragment_ur, write_atomic};
+
+/// Fragment length, in characters, used for QR sequences when none is
+/// requested.
+const DEFAULT_QR_FRAGMENT_LEN: usize = 400;
+
+/// Rendering formats for QR output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QrFormat {
+    /// Scalable vector graphics files.
+    Svg,
+    /// PNG image files.
+    Png,
+    /// Unicode half-block drawing on stderr.
+    Term,
+}
+
+/// Options for rendering emitted URs as QR codes.
+#[derive(Debug, Args)]
+pub struct QrArgs {
+    /// Directory to write a QR code for each emitted UR.
+    #[arg(long = "qr", value_name = "DIR")]
+    pub qr_dir: Option<PathBuf>,
+    /// QR rendering format (defaults to svg when --qr is given).
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub qr_format: Option<QrFormat>,
+}
+
+impl QrArgs {
+    /// Returns the effective format, or `None` when QR output is disabled.
+    pub fn format(&self) -> Result<Option<QrFormat>> {
+        match (self.qr_format, self.qr_dir.as_ref()) {
+            (Some(QrFormat::Term), _) => Ok(Some(QrFormat::Term)),
+            (Some(format), Some(_)) => Ok(Some(format)),
+            (Some(_), None) => {
+                bail!("--qr-format svg|png requires --qr <DIR>")
+            }
+            (None, Some(_)) => Ok(Some(QrFormat::Svg)),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+/// The QR frames for `ur_string`: each frame's file stem and the text it
+/// encodes. A UR longer than the fragment length becomes a numbered
+/// multi-part sequence.
+fn frames(
+    fragments: &FragmentArgs,
+    name: &str,
+    ur_string: &str,
+) -> Result<Vec<(String, String)>> {
+    let parts = fragment_ur(
+        ur_string,
+        fragments
+            .max_fragment_len
+            .unwrap_or(DEFAULT_QR_FRAGMENT_LEN),
+        fragments.extra_parts,
+    )?;
+    let count = parts.len();
+    Ok(parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, part)| {
+            let frame_name = if count == 1 {
+                name.to_owned()
+            } else {
+                format!("{name}-part{:03}", index + 1)
+            };
+            // Uppercase URs encode in the denser alphanumeric QR mode.
+            (frame_name, part.to_ascii_uppercase())
+        })
+        .collect())
+}
+
+/// Render `ur_string` as one QR code, or as an animated multi-part sequence
+/// when it exceeds the fragment length. `name` is the file stem used under
+/// the QR directory.
+pub fn emit(
+    args: &QrArgs,
+    fragments: &FragmentArgs,
+    name: &str,
+    ur_string: &str,
+) -> Result<()> {
+    let Some(format) = args.format()? else {
+        return Ok(());
+    };
+
+    for (frame_name, payload) in frames(fragments, name, ur_string)? {
+        let code = QrCode::new(payload.as_bytes())
+            .with_context(|| format!("failed to encode QR for {frame_name}"))?;
+
+        match format {
+            QrFormat::Term => {
+                let drawing =
+                    code.render::<unicode::Dense1x2>().quiet_zone(true).build();
+                eprintln!("{frame_name}:");
+                eprintln!("{drawing}");
+            }
+            QrFormat::Svg => {
+                let drawing = code
+                    .render::<svg::Color>()
+                    .min_dimensions(256, 256)
+                    .build();
+                write_atomic(
+                    &qr_path(args, &frame_name, "svg")?,
+                    drawing.as_bytes(),
+                )?;
+            }
+            QrFormat::Png => {
+                let image = code
+                    .render::<image::Luma<u8>>()
+                    .min_dimensions(256, 256)
+                    .build();
+                let mut buf = Vec::new();
+                image::DynamicImage::ImageLuma8(image)
+                    .write_to(
+                        &mut Cursor::new(&mut buf),
+                        image::ImageFormat::Png,
+                    )
+                    .context("failed to encode PNG")?;
+                write_atomic(&qr_path(args, &frame_name, "png")?, &buf)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn qr_path(args: &QrArgs, name: &str, extension: &str) -> Result<PathBuf> {
+    let dir = args
+        .qr_dir
+        .as_ref()
+        .ok_or_else(|| anyhow!("QR output directory is not set"))?;
+    super::ensure_dir(dir)?;
+    Ok(dir.join(format!("{name}.{extension}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::*;
+    use crate::{
+        io,
+        test_support::{
+            compose_edition, genesis_mark, register_tags, xid_document,
+        },
+    };
+
+    #[test]
+    fn composed_edition_frames_reassemble_into_the_edition() {
+        register_tags();
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-qr-frames-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let qr_dir = dir.display().to_string();
+        let content = Envelope::new("Gordian Club ".repeat(300)).ur_string();
+        let edition = compose_edition(
+            &xid_document(),
+            &genesis_mark(),
+            &[
+                "--content",
+                &content,
+                "--qr",
+                &qr_dir,
+                "--max-fragment-len",
+                "300",
+                "--extra-parts",
+                "2",
+            ],
+        );
+
+        let fragments =
+            FragmentArgs { max_fragment_len: Some(300), extra_parts: 2 };
+        let frames = frames(&fragments, "edition", &edition).unwrap();
+        assert!(frames.len() > 3);
+        for (frame_name, _) in &frames {
+            assert!(dir.join(format!("{frame_name}.svg")).is_file());
+        }
+        assert!(!dir.join("edition.svg").exists());
+
+        // Scanners hand back the lowercase UR text.
+        let scanned: Vec<String> = frames
+            .iter()
+            .map(|(_, payload)| payload.to_ascii_lowercase())
+            .collect();
+        let reassembled = io::parse_envelope(&scanned.join("\n")).unwrap();
+        assert_eq!(reassembled.ur_string(), edition);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,216 @@
+//! Fixtures shared by the command tests: publishers and their XID
+//! documents, provenance marks, composed and chained editions, and
+//! tampered copies of them.
+
+use bc_components::{PrivateKeyBase, PublicKeysProvider};
+use bc_envelope::prelude::*;
+use bc_ur::{URDecodable, UREncodable};
+use bc_xid::{XIDDocument, XIDGenesisMarkOptions, XIDInceptionKeyOptions};
+use clap::{Args, Parser};
+use dcbor::Date;
+use known_values::SIGNED;
+use provenance_mark::{
+    ProvenanceMark, ProvenanceMarkGenerator, ProvenanceMarkResolution,
+};
+
+use crate::{cmd::edition::compose, io};
+
+/// Register the CBOR tags `main` registers, so fixtures can encode URs
+/// whichever test runs first.
+pub fn register_tags() {
+    static REGISTER: std::sync::Once = std::sync::Once::new();
+    REGISTER.call_once(provenance_mark::register_tags);
+}
+
+/// One command's flags, parsed without the rest of the CLI.
+#[derive(Parser)]
+struct Cli<T: Args> {
+    #[command(flatten)]
+    args: T,
+}
+
+/// `argv`, program name first, parsed as the flags of one command.
+pub fn try_parse<T: Args>(argv: &[&str]) -> Result<T, clap::Error> {
+    register_tags();
+    Cli::<T>::try_parse_from(argv).map(|cli| cli.args)
+}
+
+/// [`try_parse`], for flags the test expects to be accepted.
+pub fn parse<T: Args>(argv: &[&str]) -> T { try_parse(argv).unwrap() }
+
+/// A publisher: its keys and the XID document holding them.
+pub struct Club {
+    pub keys: PrivateKeyBase,
+    pub doc: XIDDocument,
+}
+
+impl Club {
+    pub fn new() -> Self { Self::with_keys(PrivateKeyBase::new()) }
+
+    pub fn with_keys(keys: PrivateKeyBase) -> Self {
+        let doc = xid_document_with(&keys);
+        Self { keys, doc }
+    }
+
+    /// The XID document with its private keys, as a `--publisher`.
+    pub fn private_ur(&self) -> String { private_ur(&self.doc) }
+
+    /// The public XID document, as a verifying `--publisher`.
+    pub fn public_ur(&self) -> String { public_ur(&self.doc) }
+
+    /// An edition at `mark` with one anonymous permit.
+    pub fn compose(&self, mark: &ProvenanceMark) -> String {
+        let member = PrivateKeyBase::new().public_keys().ur_string();
+        self.compose_with(mark, &["--permit", &member])
+    }
+
+    /// An edition at `mark` composed with the `extra` flags.
+    pub fn compose_with(
+        &self,
+        mark: &ProvenanceMark,
+        extra: &[&str],
+    ) -> String {
+        compose_edition(&self.doc, mark, extra)
+    }
+
+    /// Signed editions for a fresh chain of `count` marks, each with one
+    /// anonymous permit.
+    pub fn chain(&self, count: usize) -> Vec<String> {
+        marks(count).iter().map(|mark| self.compose(mark)).collect()
+    }
+}
+
+/// A new XID document holding fresh private keys.
+pub fn xid_document() -> XIDDocument {
+    xid_document_with(&PrivateKeyBase::new())
+}
+
+/// A new XID document holding `keys`.
+pub fn xid_document_with(keys: &PrivateKeyBase) -> XIDDocument {
+    register_tags();
+    XIDDocument::new(
+        XIDInceptionKeyOptions::PrivateKeyBase(keys.clone()),
+        XIDGenesisMarkOptions::None,
+    )
+}
+
+/// `doc` without its private keys, as a recipient or verifier UR.
+pub fn public_ur(doc: &XIDDocument) -> String {
+    io::xid_document_ur(doc, false).unwrap()
+}
+
+/// `doc` with its private keys, as a publisher or identity UR.
+pub fn private_ur(doc: &XIDDocument) -> String {
+    io::xid_document_ur(doc, true).unwrap()
+}
+
+/// The first `count` marks of a fresh chain, genesis first.
+pub fn marks(count: usize) -> Vec<ProvenanceMark> {
+    register_tags();
+    let mut generator =
+        ProvenanceMarkGenerator::new_random(ProvenanceMarkResolution::Quartile);
+    (0..count)
+        .map(|_| generator.next(Date::now(), None::<String>))
+        .collect()
+}
+
+/// The genesis mark of a fresh chain.
+pub fn genesis_mark() -> ProvenanceMark { marks(1).remove(0) }
+
+/// Compose flags for an edition of `publisher` at `mark`, followed by
+/// `extra`. Unless `extra` says otherwise, the content is the text
+/// `issue <seq>` and a genesis mark gets `--genesis`. Emitted URs stay off
+/// stdout.
+pub fn compose_args(
+    publisher: &XIDDocument,
+    mark: &ProvenanceMark,
+    extra: &[&str],
+) -> compose::CommandArgs {
+    let signer = private_ur(publisher);
+    let content = Envelope::new(format!("issue {}", mark.seq())).ur_string();
+    let mark_ur = mark.ur_string();
+    let mut argv =
+        vec!["clubs", "--publisher", &signer, "--provenance", &mark_ur];
+    if !extra.contains(&"--content") {
+        argv.extend(["--content", &content]);
+    }
+    if mark.is_genesis() && !extra.contains(&"--genesis") {
+        argv.push("--genesis");
+    }
+    argv.extend(extra);
+    let mut args: compose::CommandArgs = parse(&argv);
+    args.quiet = true;
+    args
+}
+
+/// The UR of the edition [`compose_args`] describes.
+pub fn compose_edition(
+    publisher: &XIDDocument,
+    mark: &ProvenanceMark,
+    extra: &[&str],
+) -> String {
+    compose::run(compose_args(publisher, mark, extra))
+        .unwrap()
+        .edition_ur
+}
+
+/// [`compose_edition`] with a permit for each of `recipients`.
+pub fn compose_for(
+    publisher: &XIDDocument,
+    mark: &ProvenanceMark,
+    recipients: &[&XIDDocument],
+    extra: &[&str],
+) -> String {
+    let recipients: Vec<String> =
+        recipients.iter().map(|doc| public_ur(doc)).collect();
+    let mut argv: Vec<&str> = recipients
+        .iter()
+        .flat_map(|recipient| ["--permit", recipient.as_str()])
+        .collect();
+    argv.extend(extra);
+    compose_edition(publisher, mark, &argv)
+}
+
+/// Alter the signed payload of `edition` with a `note` assertion but keep
+/// its signatures.
+pub fn tamper(edition: &str) -> String {
+    tamper_with(edition, |inner| inner.add_assertion("note", "altered"))
+}
+
+/// Alter the signed payload of `edition` with `alter` but keep its
+/// signatures.
+pub fn tamper_with(
+    edition: &str,
+    alter: impl FnOnce(Envelope) -> Envelope,
+) -> String {
+    let signed = Envelope::from_ur_string(edition).unwrap();
+    let altered = alter(signed.try_unwrap().unwrap()).wrap();
+    signed
+        .objects_for_predicate(SIGNED)
+        .into_iter()
+        .fold(altered, |envelope, signature| {
+            envelope.add_assertion(SIGNED, signature)
+        })
+        .ur_string()
+}
+
+/// `edition` at `mark` instead, re-signed by `publisher`: an edition whose
+/// mark need not bind its content, which `compose` refuses to make.
+pub fn with_mark(
+    edition: &str,
+    publisher: &XIDDocument,
+    mark: &ProvenanceMark,
+) -> String {
+    let signed = Envelope::from_ur_string(edition).unwrap();
+    let inner = signed.try_unwrap().unwrap();
+    let provenance = inner
+        .assertion_with_predicate(known_values::PROVENANCE)
+        .unwrap();
+    let (keys, _) = compose::select_signing_keys(publisher, None).unwrap();
+    inner
+        .remove_assertion(provenance)
+        .add_assertion(known_values::PROVENANCE, mark.clone())
+        .wrap()
+        .add_signature(&keys)
+        .ur_string()
+}
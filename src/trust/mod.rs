@@ -0,0 +1,245 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use bc_components::{PublicKeys, ReferenceProvider, XID};
+use bc_ur::{URDecodable, UREncodable};
+use clap::Args;
+use dcbor::Date;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cmd::{
+        VerificationFailed,
+        edition::{decode_edition, inspect},
+    },
+    io::{self, RecipientDescriptor},
+    output,
+};
+
+/// Trust store location under the user's data directory.
+const DEFAULT_STORE: &str = "clubs/trust.json";
+
+/// Options for the trust-on-first-use publisher key store.
+#[derive(Debug, Args)]
+pub struct TrustArgs {
+    /// Pin the `--publisher` keys for the edition's club in the trust
+    /// store once the edition verifies, unless keys are already pinned.
+    #[arg(long, requires = "publishers")]
+    pub trust: bool,
+    #[command(flatten)]
+    pub store: TrustStoreArgs,
+}
+
+/// The `--trust-store` option, shared with the `trust` commands.
+#[derive(Debug, Args)]
+pub struct TrustStoreArgs {
+    /// Trust store file instead of `~/.local/share/clubs/trust.json`.
+    #[arg(long, value_name = "PATH")]
+    pub trust_store: Option<PathBuf>,
+}
+
+impl TrustStoreArgs {
+    pub fn open(&self) -> Result<TrustStore> {
+        TrustStore::open(self.trust_store.as_deref())
+    }
+}
+
+impl TrustArgs {
+    pub fn open(&self) -> Result<TrustStore> { self.store.open() }
+
+    /// The publishers to verify an edition of `club` against: `given`, once
+    /// checked against the keys pinned for the club, or the pinned keys when
+    /// none are given. Empty when neither is available.
+    pub fn resolve(
+        &self,
+        club: XID,
+        given: Vec<RecipientDescriptor>,
+    ) -> Result<Vec<RecipientDescriptor>> {
+        let store = self.open()?;
+        if given.is_empty() {
+            return Ok(store
+                .pinned(&club)?
+                .map(|keys| {
+                    RecipientDescriptor::from_public_keys(keys, Some(club))
+                })
+                .into_iter()
+                .collect());
+        }
+        for descriptor in &given {
+            store.check(&club, descriptor.public_keys())?;
+        }
+        Ok(given)
+    }
+
+    /// With `--trust`, pin `keys` for `club` unless keys are pinned already.
+    /// Returns whether they were newly pinned.
+    pub fn record(&self, club: XID, keys: &PublicKeys) -> Result<bool> {
+        if !self.trust {
+            return Ok(false);
+        }
+        let mut store = self.open()?;
+        if store.pinned(&club)?.is_some() {
+            store.check(&club, keys)?;
+            return Ok(false);
+        }
+        store.pin(club, keys);
+        store.save()?;
+        Ok(true)
+    }
+}
+
+/// Publisher keys pinned per club XID on first use, so later editions can
+/// be verified without `--publisher` and a substituted key is caught.
+///
+/// ```json
+/// { "clubs": { "ur:xid/hdcx…": { "keys": "ur:crypto-pubkeys/…", "pinned": "2025-01-01" } } }
+/// ```
+#[derive(Debug)]
+pub struct TrustStore {
+    path: PathBuf,
+    clubs: BTreeMap<String, PinnedKeys>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TrustFile {
+    #[serde(default)]
+    clubs: BTreeMap<String, PinnedKeys>,
+}
+
+/// The publisher keys pinned for one club.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PinnedKeys {
+    /// Public keys UR.
+    pub keys: String,
+    /// When the keys were pinned.
+    pub pinned: String,
+}
+
+impl TrustStore {
+    /// `$XDG_DATA_HOME/clubs/trust.json`, falling back to
+    /// `~/.local/share/clubs/trust.json`; `None` without either variable.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(data) = std::env::var_os("XDG_DATA_HOME")
+            && !data.is_empty()
+        {
+            return Some(PathBuf::from(data).join(DEFAULT_STORE));
+        }
+        let home = std::env::var_os("HOME").filter(|home| !home.is_empty())?;
+        Some(PathBuf::from(home).join(".local/share").join(DEFAULT_STORE))
+    }
+
+    /// Read the store at `path` (or the default location); a missing file,
+    /// or no default location, is an empty store.
+    pub fn open(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => Self::default_path().unwrap_or_default(),
+        };
+        if path.as_os_str().is_empty() || !path.exists() {
+            return Ok(Self { path, clubs: BTreeMap::new() });
+        }
+        let text = fs::read_to_string(&path).with_context(|| {
+            format!("failed to read trust store '{}'", path.display())
+        })?;
+        let file: TrustFile =
+            serde_json::from_str(&text).with_context(|| {
+                format!("invalid trust store '{}'", path.display())
+            })?;
+        Ok(Self { path, clubs: file.clubs })
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// Pinned clubs and their keys, in XID order.
+    pub fn entries(&self) -> Result<Vec<(XID, PublicKeys, &PinnedKeys)>> {
+        self.clubs
+            .iter()
+            .map(|(club, pinned)| {
+                let xid = io::parse_xid_value(club).with_context(|| {
+                    format!("invalid club XID '{club}' in trust store")
+                })?;
+                Ok((xid, decode_keys(club, pinned)?, pinned))
+            })
+            .collect()
+    }
+
+    /// The keys pinned for `club`, if any.
+    pub fn pinned(&self, club: &XID) -> Result<Option<PublicKeys>> {
+        let key = club.ur_string();
+        self.clubs
+            .get(&key)
+            .map(|pinned| decode_keys(&key, pinned))
+            .transpose()
+    }
+
+    /// Fail loudly if other keys than `keys` are pinned for `club`.
+    pub fn check(&self, club: &XID, keys: &PublicKeys) -> Result<()> {
+        let Some(pinned) = self.pinned(club)? else {
+            return Ok(());
+        };
+        if pinned.reference() != keys.reference() {
+            return Err(VerificationFailed(format!(
+                "publisher keys {} CONFLICT with keys {} pinned for club {club} in '{}'; if the club really changed keys, run `clubs trust remove --club {}` and pin the new keys with --trust",
+                keys.reference().ref_hex(),
+                pinned.reference().ref_hex(),
+                self.path.display(),
+                club.ur_string()
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    pub fn pin(&mut self, club: XID, keys: &PublicKeys) {
+        self.clubs.insert(
+            club.ur_string(),
+            PinnedKeys {
+                keys: keys.ur_string(),
+                pinned: Date::now().to_string(),
+            },
+        );
+    }
+
+    /// Forget the keys pinned for `club`; returns whether any were.
+    pub fn remove(&mut self, club: &XID) -> bool {
+        self.clubs.remove(&club.ur_string()).is_some()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if self.path.as_os_str().is_empty() {
+            bail!(
+                "neither XDG_DATA_HOME nor HOME is set; pass --trust-store to locate the trust store"
+            );
+        }
+        if let Some(parent) = self.path.parent() {
+            output::ensure_dir(parent)?;
+        }
+        let file = TrustFile { clubs: self.clubs.clone() };
+        let json = serde_json::to_string_pretty(&file)
+            .context("failed to serialize trust store")?;
+        output::write_atomic(&self.path, format!("{json}\n").as_bytes())
+    }
+}
+
+fn decode_keys(club: &str, pinned: &PinnedKeys) -> Result<PublicKeys> {
+    PublicKeys::from_ur_string(&pinned.keys).with_context(|| {
+        format!("invalid keys pinned for club '{club}' in trust store")
+    })
+}
+
+/// The club an edition names, read without verifying its signature so its
+/// pinned keys can be looked up.
+pub fn edition_club(spec: &str) -> Result<XID> {
+    let envelope =
+        io::parse_envelope(spec).context("failed to parse edition")?;
+    let (payload, _) = inspect::edition_payload(&envelope)?;
+    let edition = decode_edition(&payload)
+        .context("edition payload is not a valid club edition")?;
+    Ok(edition.club_xid)
+}
@@ -0,0 +1,477 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use bc_components::{XID, XIDProvider};
+use bc_envelope::prelude::*;
+use provenance_mark::ProvenanceMarkGenerator;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cmd::edition::{compose::Composed, decode_edition},
+    io, output,
+};
+
+/// Club configuration file at the root of a workspace.
+pub const CLUB_FILE: &str = "club.toml";
+/// Publisher XID document including private keys.
+pub const PRIVATE_PUBLISHER_FILE: &str = "publisher-private.ur";
+/// Publisher XID document without private keys, safe to distribute.
+pub const PUBLIC_PUBLISHER_FILE: &str = "publisher-public.ur";
+/// Provenance mark generator state for minting the next edition's mark.
+pub const PROVENANCE_STATE_FILE: &str = "provenance-state.ur";
+/// Directory holding one UR file per published edition.
+pub const EDITIONS_DIR: &str = "editions";
+/// Directory holding the current recipients manifest.
+pub const PERMITS_DIR: &str = "permits";
+/// Directory holding SSKR shares, grouped by edition.
+pub const SHARES_DIR: &str = "shares";
+
+/// Names `init --generate-keys --out-dir` used before workspaces had a
+/// `club.toml`; `Workspace::open` migrates them.
+const LEGACY_PUBLIC_PUBLISHER_FILE: &str = "publisher.ur";
+const LEGACY_GENERATOR_FILE: &str = "generator.json";
+const LEGACY_EDITION_FILE: &str = "edition.ur";
+
+/// Contents of `club.toml`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ClubConfig {
+    pub club: ClubInfo,
+    #[serde(default)]
+    pub defaults: Defaults,
+}
+
+/// Identity of the club a workspace belongs to.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ClubInfo {
+    pub xid: String,
+    pub created: String,
+}
+
+/// Settings later commands pick up when pointed at the workspace. Paths are
+/// relative to the workspace root.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Defaults {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance_state: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipients: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sskr: Vec<String>,
+    /// Issue the publisher a permit on every edition (`--self-permit`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub self_permit: bool,
+}
+
+impl ClubConfig {
+    /// Configuration for a freshly initialized workspace.
+    pub fn new(club_xid: XID, created: String, has_state: bool) -> Self {
+        Self {
+            club: ClubInfo { xid: club_xid.to_string(), created },
+            defaults: Defaults {
+                publisher: Some(PRIVATE_PUBLISHER_FILE.into()),
+                provenance_state: has_state
+                    .then(|| PROVENANCE_STATE_FILE.into()),
+                recipients: Some(
+                    Path::new(PERMITS_DIR)
+                        .join(crate::cmd::edition::compose::RECIPIENTS_FILE),
+                ),
+                sskr: Vec::new(),
+                self_permit: false,
+            },
+        }
+    }
+}
+
+/// A club workspace directory created by `clubs init --out-dir`.
+pub struct Workspace {
+    root: PathBuf,
+    config: ClubConfig,
+}
+
+impl Workspace {
+    /// Fail unless `root` is missing or empty, or `force` is set.
+    pub fn ensure_vacant(root: &Path, force: bool) -> Result<()> {
+        if force || !root.exists() {
+            return Ok(());
+        }
+        let mut entries = fs::read_dir(root).with_context(|| {
+            format!("failed to read directory '{}'", root.display())
+        })?;
+        if entries.next().is_some() {
+            bail!(
+                "'{}' is not empty; pass --force to initialize a club there anyway",
+                root.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Create the workspace layout and write `club.toml`. Callers check
+    /// `ensure_vacant` first, before doing any work the refusal would waste.
+    pub fn create(root: &Path, config: ClubConfig) -> Result<Self> {
+        output::ensure_dir(&root.join(EDITIONS_DIR))?;
+        let text = toml::to_string_pretty(&config)
+            .context("failed to serialize club configuration")?;
+        output::write_atomic(&root.join(CLUB_FILE), text.as_bytes())?;
+        Ok(Self { root: root.to_path_buf(), config })
+    }
+
+    /// Open an existing workspace by reading its `club.toml`, migrating an
+    /// older `init --generate-keys` output directory first.
+    pub fn open(root: &Path) -> Result<Self> {
+        let path = root.join(CLUB_FILE);
+        if !path.exists() && root.join(LEGACY_PUBLIC_PUBLISHER_FILE).exists() {
+            return Self::migrate(root);
+        }
+        let text = fs::read_to_string(&path).with_context(|| {
+            format!("failed to read club configuration '{}'", path.display())
+        })?;
+        let config = toml::from_str(&text)
+            .map_err(|err| anyhow!(err))
+            .with_context(|| {
+                format!("invalid club configuration '{}'", path.display())
+            })?;
+        Ok(Self { root: root.to_path_buf(), config })
+    }
+
+    pub fn config(&self) -> &ClubConfig { &self.config }
+
+    pub fn root(&self) -> &Path { &self.root }
+
+    /// Resolve a workspace-relative path.
+    pub fn path(&self, relative: impl AsRef<Path>) -> PathBuf {
+        self.root.join(relative)
+    }
+
+    /// Write a UR artifact; key-bearing artifacts are owner-readable only.
+    pub fn write_ur(&self, name: &str, ur: &str, secret: bool) -> Result<()> {
+        let contents = format!("{ur}\n");
+        if secret {
+            output::write_secret(&self.path(name), contents.as_bytes())
+        } else {
+            output::write_atomic(&self.path(name), contents.as_bytes())
+        }
+    }
+
+    /// Lay out a directory written by `init --generate-keys --out-dir`
+    /// before workspaces existed: rename the public publisher document,
+    /// convert the JSON generator state to `provenance-state.ur`, file the
+    /// edition and recipients manifest, and write `club.toml`.
+    fn migrate(root: &Path) -> Result<Self> {
+        let legacy_public = root.join(LEGACY_PUBLIC_PUBLISHER_FILE);
+        let public = fs::read_to_string(&legacy_public).with_context(|| {
+            format!("failed to read '{}'", legacy_public.display())
+        })?;
+        let club_xid =
+            io::parse_xid_document(&public, &io::PassphraseArgs::default())
+                .with_context(|| {
+                    format!(
+                        "failed to load publisher XID document '{}'",
+                        legacy_public.display()
+                    )
+                })?
+                .xid();
+
+        let legacy_generator = root.join(LEGACY_GENERATOR_FILE);
+        let has_state = legacy_generator.exists();
+        if has_state {
+            let json =
+                fs::read_to_string(&legacy_generator).with_context(|| {
+                    format!("failed to read '{}'", legacy_generator.display())
+                })?;
+            let generator: ProvenanceMarkGenerator =
+                serde_json::from_str(&json).with_context(|| {
+                    format!(
+                        "failed to decode provenance generator state '{}'",
+                        legacy_generator.display()
+                    )
+                })?;
+            let state = io::provenance_state_ur(&generator)?;
+            output::write_secret(
+                &root.join(PROVENANCE_STATE_FILE),
+                format!("{state}\n").as_bytes(),
+            )?;
+            fs::remove_file(&legacy_generator).with_context(|| {
+                format!("failed to remove '{}'", legacy_generator.display())
+            })?;
+        }
+
+        let workspace = Self::create(
+            root,
+            ClubConfig::new(club_xid, Date::now().to_string(), has_state),
+        )?;
+        let moves =
+            [
+                (legacy_public, workspace.path(PUBLIC_PUBLISHER_FILE)),
+                (
+                    root.join(crate::cmd::edition::compose::RECIPIENTS_FILE),
+                    workspace.path(
+                        workspace.config.defaults.recipients.as_ref().expect(
+                            "new workspaces name a recipients manifest",
+                        ),
+                    ),
+                ),
+            ];
+        let edition = root.join(LEGACY_EDITION_FILE);
+        let edition_move = if edition.exists() {
+            let stem = edition_stem(edition_seq(&edition)?);
+            Some((
+                edition,
+                workspace.path(EDITIONS_DIR).join(format!("{stem}.ur")),
+            ))
+        } else {
+            None
+        };
+        for (from, to) in moves.into_iter().chain(edition_move) {
+            if !from.exists() {
+                continue;
+            }
+            if let Some(parent) = to.parent() {
+                output::ensure_dir(parent)?;
+            }
+            fs::rename(&from, &to).with_context(|| {
+                format!(
+                    "failed to move '{}' to '{}'",
+                    from.display(),
+                    to.display()
+                )
+            })?;
+        }
+        eprintln!(
+            "note: migrated '{}' to the club workspace layout",
+            root.display()
+        );
+        Ok(workspace)
+    }
+
+    /// The edition file with the highest provenance sequence number.
+    pub fn latest_edition(&self) -> Result<Option<PathBuf>> {
+        let dir = self.path(EDITIONS_DIR);
+        if !dir.exists() {
+            return Ok(None);
+        }
+        let mut latest: Option<(u32, PathBuf)> = None;
+        for path in io::ur_files_in(&dir)? {
+            let seq = edition_seq(&path)?;
+            if latest.as_ref().is_none_or(|(best, _)| seq > *best) {
+                latest = Some((seq, path));
+            }
+        }
+        Ok(latest.map(|(_, path)| path))
+    }
+
+    /// File an edition (and its shares, permit manifest, and recipients
+    /// manifest) under the workspace, refusing to overwrite an existing
+    /// edition file.
+    pub fn record_edition(&self, composed: &Composed) -> Result<PathBuf> {
+        let stem = edition_stem(composed.seq);
+        let path = self.path(EDITIONS_DIR).join(format!("{stem}.ur"));
+        if path.exists() {
+            bail!("edition file '{}' already exists", path.display());
+        }
+        output::ensure_dir(&self.path(EDITIONS_DIR))?;
+        output::write_lines(&path, [&composed.edition_ur])?;
+
+        if !composed.shares.is_empty() {
+            let dir = self.path(SHARES_DIR).join(&stem);
+            output::ensure_dir(&dir)?;
+            for (name, ur) in &composed.shares {
+                output::write_secret(
+                    &dir.join(format!("{name}.ur")),
+                    format!("{ur}\n").as_bytes(),
+                )?;
+            }
+        }
+
+        if !composed.permits.is_empty() {
+            output::ensure_dir(&self.path(PERMITS_DIR))?;
+            let json = serde_json::to_string_pretty(&composed.permits)
+                .context("failed to serialize permit manifest")?;
+            output::write_atomic(
+                &self.path(PERMITS_DIR).join(format!("{stem}.json")),
+                format!("{json}\n").as_bytes(),
+            )?;
+        }
+
+        if !composed.recipients.is_empty()
+            && let Some(manifest) = self.config.defaults.recipients.as_ref()
+        {
+            let manifest = self.path(manifest);
+            if let Some(parent) = manifest.parent() {
+                output::ensure_dir(parent)?;
+            }
+            output::write_lines(&manifest, &composed.recipients)?;
+        }
+        Ok(path)
+    }
+}
+
+/// Provenance sequence number of the edition stored at `path`.
+fn edition_seq(path: &Path) -> Result<u32> {
+    let edition = io::parse_envelope(&format!("@{}", path.display()))
+        .and_then(|envelope| Ok(envelope.try_unwrap()?))
+        .and_then(|inner| decode_edition(&inner))
+        .with_context(|| {
+            format!("failed to read edition file '{}'", path.display())
+        })?;
+    Ok(edition.provenance.seq())
+}
+
+/// File stem for the edition at provenance `seq`: `0001-genesis`, `0002`, …
+pub fn edition_stem(seq: u32) -> String {
+    if seq == 0 {
+        "0001-genesis".to_string()
+    } else {
+        format!("{:04}", seq + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_xid::XIDDocument;
+    use provenance_mark::{ProvenanceMark, ProvenanceMarkResolution};
+
+    use super::*;
+    use crate::{
+        cmd::edition::compose::{self, RECIPIENTS_FILE},
+        test_support::{
+            compose_args, marks, private_ur, public_ur, xid_document,
+        },
+    };
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("clubs-workspace-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// A real edition of `publisher` at `mark`, with a permit for `member`.
+    fn composed(
+        publisher: &XIDDocument,
+        member: &XIDDocument,
+        mark: &ProvenanceMark,
+    ) -> Composed {
+        let member = public_ur(member);
+        compose::run(compose_args(publisher, mark, &["--permit", &member]))
+            .unwrap()
+    }
+
+    #[test]
+    fn workspace_round_trips_and_tracks_editions() {
+        let dir = scratch_dir("round-trip");
+        let (publisher, member) = (xid_document(), xid_document());
+        let xid = publisher.xid();
+        let config = ClubConfig::new(xid, "2025-01-01".into(), true);
+        let workspace = Workspace::create(&dir, config).unwrap();
+        // Seq 9999 files as `10000.ur`, which sorts before `9999.ur` by name.
+        let marks = marks(10_000);
+        for seq in [0, 1, 9998, 9999] {
+            workspace
+                .record_edition(&composed(&publisher, &member, &marks[seq]))
+                .unwrap();
+        }
+        assert!(
+            workspace
+                .record_edition(&composed(&publisher, &member, &marks[1]))
+                .is_err()
+        );
+
+        let reopened = Workspace::open(&dir).unwrap();
+        assert_eq!(reopened.config().club.xid, xid.to_string());
+        assert_eq!(
+            reopened.config().defaults.provenance_state,
+            Some(PathBuf::from(PROVENANCE_STATE_FILE))
+        );
+        assert_eq!(
+            reopened.latest_edition().unwrap(),
+            Some(dir.join(EDITIONS_DIR).join("10000.ur"))
+        );
+        for stem in ["0001-genesis", "0002", "9999"] {
+            assert!(dir.join(EDITIONS_DIR).join(format!("{stem}.ur")).exists());
+        }
+        let manifest: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(dir.join(PERMITS_DIR).join("0002.json"))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest.as_array().unwrap().len(), 1);
+        let recipients = reopened
+            .path(reopened.config().defaults.recipients.as_ref().unwrap());
+        assert_eq!(fs::read_to_string(recipients).unwrap().lines().count(), 1);
+
+        assert!(Workspace::ensure_vacant(&dir, false).is_err());
+        assert!(Workspace::ensure_vacant(&dir, true).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn legacy_generate_keys_output_is_migrated_on_open() {
+        let dir = scratch_dir("legacy");
+        fs::create_dir_all(&dir).unwrap();
+        let (publisher, member) = (xid_document(), xid_document());
+        let mut generator = ProvenanceMarkGenerator::new_random(
+            ProvenanceMarkResolution::Quartile,
+        );
+        let genesis = generator.next(Date::now(), None::<String>);
+        let edition = composed(&publisher, &member, &genesis);
+        let write = |name: &str, contents: String| {
+            fs::write(dir.join(name), contents).unwrap();
+        };
+        write(
+            PRIVATE_PUBLISHER_FILE,
+            format!("{}\n", private_ur(&publisher)),
+        );
+        write(
+            LEGACY_PUBLIC_PUBLISHER_FILE,
+            format!("{}\n", public_ur(&publisher)),
+        );
+        write(
+            LEGACY_GENERATOR_FILE,
+            serde_json::to_string_pretty(&generator).unwrap(),
+        );
+        write(LEGACY_EDITION_FILE, format!("{}\n", edition.edition_ur));
+        write(RECIPIENTS_FILE, edition.recipients.join("\n"));
+
+        let workspace = Workspace::open(&dir).unwrap();
+        assert_eq!(workspace.config().club.xid, publisher.xid().to_string());
+        for legacy in [
+            LEGACY_PUBLIC_PUBLISHER_FILE,
+            LEGACY_GENERATOR_FILE,
+            LEGACY_EDITION_FILE,
+            RECIPIENTS_FILE,
+        ] {
+            assert!(!dir.join(legacy).exists(), "{legacy}");
+        }
+        assert!(dir.join(CLUB_FILE).exists());
+        assert!(workspace.path(PUBLIC_PUBLISHER_FILE).exists());
+        assert_eq!(
+            workspace.latest_edition().unwrap(),
+            Some(dir.join(EDITIONS_DIR).join("0001-genesis.ur"))
+        );
+        assert!(
+            workspace
+                .path(workspace.config().defaults.recipients.as_ref().unwrap())
+                .exists()
+        );
+        let state =
+            format!("@{}", workspace.path(PROVENANCE_STATE_FILE).display());
+        let (mut migrated, _) =
+            io::parse_provenance_state(&state, &io::PassphraseArgs::default())
+                .unwrap();
+        let next = migrated.next(Date::now(), None::<String>);
+        assert!(genesis.precedes(&next));
+
+        // A second open reads the club.toml the migration wrote.
+        assert!(Workspace::open(&dir).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}